@@ -0,0 +1,29 @@
+/*
+ * The C API of the ray-commands library, built as a shared library by
+ * `cargo build --release`. Plans go in and results come out as JSON; see
+ * src/ffi.rs. Every string returned is to be freed by ray_free_string.
+ */
+
+#ifndef RAY_COMMANDS_H
+#define RAY_COMMANDS_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* {"operations": [...]} of a plan, without changing any file */
+char *ray_plan(const char *request);
+
+/* {"records": [...], "renamed": bool}, or with an "error", of applying a plan */
+char *ray_apply(const char *program, const char *request);
+
+/* {"operations": [...]} of the renames undone of the latest run of program */
+char *ray_undo(const char *program);
+
+void ray_free_string(char *text);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* RAY_COMMANDS_H */
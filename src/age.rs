@@ -0,0 +1,29 @@
+//! Ages of files like `30m`, `12h` or `1d12h`, for the options and rules
+//! picking files by how long ago they were last modified.
+
+use crate::i18n::{tr, Message};
+use regex::Regex;
+use std::time::Duration;
+
+/// parse an age like `30m`, `12h` or `1d12h`
+pub fn parse_age(text: &str) -> Result<Duration, String> {
+    let invalid = || tr(Message::ExpectedAge, &[]);
+    if !Regex::new(r"^(?:\d+[dhms])+$").unwrap().is_match(text) {
+        return Err(invalid());
+    }
+    let mut seconds: u64 = 0;
+    for caps in Regex::new(r"(\d+)([dhms])").unwrap().captures_iter(text) {
+        let amount: u64 = caps[1].parse().map_err(|_| invalid())?;
+        let unit: u64 = match &caps[2] {
+            "d" => 86_400,
+            "h" => 3_600,
+            "m" => 60,
+            _ => 1,
+        };
+        seconds = amount
+            .checked_mul(unit)
+            .and_then(|s| seconds.checked_add(s))
+            .ok_or_else(invalid)?;
+    }
+    Ok(Duration::from_secs(seconds))
+}
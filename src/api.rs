@@ -0,0 +1,44 @@
+//! The stable API of the library, for programs wrapping the tools such as
+//! graphical front ends: build a [`Plan`] of renames, naming files by a
+//! [`FileNameTemplate`] if need be, list its [`Operation`]s, [`apply`] it and
+//! [`undo`] it. [`plan_names`] plans names by time from [`FileSnapshot`]s
+//! without reading the disk, as for testing the naming itself, and a plan
+//! given a [`MemoryFilesystem`] plans and renames in memory alone.
+//!
+//! The items of this module follow semantic versioning: a release changing any
+//! of them incompatibly bumps the major version, or the minor one before 1.0.
+//! The other modules serve the command-line tools and may change in any
+//! release.
+
+use crate::{output::Report, restore::undo_latest_quietly};
+
+pub use crate::{
+    conflict::ConflictStrategy,
+    error::{Error, Result},
+    failure::FailurePolicy,
+    filesystem::{DiskFilesystem, Filesystem, MemoryFilesystem, ReadOnly, Stat},
+    naming::{derive_name, derive_names, plan_names, FileSnapshot, NamingOptions},
+    pipe::{PipeRecord, PipeStatus},
+    plan::{RenamePlan as Plan, RenameRecord as Operation},
+    preset::TimeFormats,
+    template::{Template as FileNameTemplate, TemplateError},
+    time_format::{TimeFormat, TimeSource},
+};
+
+/// the operations of `plan`, a rename, an overwrite or a skip per file
+pub fn operations(plan: &Plan) -> Vec<Operation> {
+    plan.records()
+}
+
+/// rename as `plan` plans without asking or printing on standard output,
+/// journaling the renames under `program` for [`undo`]; return the record of
+/// what became of each file, and whether anything was renamed or why the run
+/// stopped
+pub fn apply(plan: Plan, program: &str) -> (Vec<PipeRecord>, Result<bool>) {
+    plan.run_piped(program, true)
+}
+
+/// undo the latest run of `program`, returning the renames undone
+pub fn undo(program: &str) -> Result<Vec<Operation>> {
+    undo_latest_quietly(program)
+}
@@ -0,0 +1,167 @@
+//! Backups of the files a rename replaces, by `--backup[=CONTROL]` and
+//! `--suffix` as with GNU mv: `name~`, or numbered as `name.~1~`, `name.~2~`,
+//! ... beside the file, instead of moving it to the trash.
+
+use crate::{
+    i18n::{tr, Message},
+    recycle::move_to_trash,
+};
+use clap::{Arg, ArgMatches};
+use std::{
+    env,
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+pub const BACKUP_CONTROL_NAMES: [&str; 8] = [
+    "none", "off", "numbered", "t", "existing", "nil", "simple", "never",
+];
+
+const BACKUP_HELP_MESSAGE: &str = r#"Keep a backup of each file replaced by --on-conflict overwrite beside
+it, instead of moving it to the trash; CONTROL, $VERSION_CONTROL by
+default, is one of:
+  none, off:     no backups, the trash
+  numbered, t:   numbered backups, e.g. name.~1~
+  existing, nil: numbered if the file has numbered backups, else simple
+  simple, never: simple backups, e.g. name~"#;
+
+const SUFFIX_HELP_MESSAGE: &str =
+    "The suffix of simple backups, $SIMPLE_BACKUP_SUFFIX or ~ by default";
+
+/// the backups of this run, and the suffix of simple ones
+static BACKUP_POLICY: OnceLock<(BackupControl, String)> = OnceLock::new();
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackupControl {
+    #[default]
+    None,
+    Numbered,
+    Existing,
+    Simple,
+}
+
+impl BackupControl {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "none" | "off" => Self::None,
+            "numbered" | "t" => Self::Numbered,
+            "existing" | "nil" => Self::Existing,
+            "simple" | "never" => Self::Simple,
+            _ => panic!("Unknown backup control: {name}"),
+        }
+    }
+}
+
+/// the `--backup` and `--suffix` options of the tools overwriting files
+pub fn get_backup_args() -> [Arg; 2] {
+    [
+        Arg::new("backup")
+            .long("backup")
+            .help(BACKUP_HELP_MESSAGE)
+            .value_name("CONTROL")
+            .value_parser(BACKUP_CONTROL_NAMES)
+            .num_args(0..=1)
+            .require_equals(true),
+        Arg::new("backup_suffix")
+            .short('S')
+            .long("suffix")
+            .help(SUFFIX_HELP_MESSAGE)
+            .value_name("SUFFIX"),
+    ]
+}
+
+/// back up the files replaced in this run by `--backup` and `--suffix`; as
+/// with GNU mv, `--backup` without CONTROL and `--suffix` alone make the
+/// backups of `$VERSION_CONTROL`, or else `existing` ones
+pub fn set_backup_policy(matches: &ArgMatches) {
+    let suffix: Option<&String> = matches.get_one::<String>("backup_suffix");
+    let control: BackupControl = match matches.get_one::<String>("backup") {
+        Some(name) => BackupControl::from(name),
+        None if matches.contains_id("backup") || suffix.is_some() => {
+            get_env_control().unwrap_or(BackupControl::Existing)
+        }
+        None => BackupControl::None,
+    };
+    let suffix: String = suffix
+        .cloned()
+        .or_else(|| env::var("SIMPLE_BACKUP_SUFFIX").ok())
+        .filter(|s| !s.is_empty() && !s.contains(['/', '\\']))
+        .unwrap_or_else(|| "~".to_string());
+    let _ = BACKUP_POLICY.set((control, suffix));
+}
+
+/// the backups named by `$VERSION_CONTROL`, when it names any
+fn get_env_control() -> Option<BackupControl> {
+    let name: String = env::var("VERSION_CONTROL").ok()?;
+    BACKUP_CONTROL_NAMES
+        .contains(&name.as_str())
+        .then(|| BackupControl::from(&name))
+}
+
+/// whether the files replaced in this run are backed up, by `--backup`,
+/// rather than moved to the trash
+pub fn is_backed_up() -> bool {
+    get_backup_policy().0 != BackupControl::None
+}
+
+fn get_backup_policy() -> (BackupControl, &'static str) {
+    BACKUP_POLICY
+        .get()
+        .map(|(control, suffix)| (*control, suffix.as_str()))
+        .unwrap_or((BackupControl::None, "~"))
+}
+
+/// move the file at `target` out of the way of a rename replacing it: to its
+/// backup by `--backup`, or else to the trash, where it stays recoverable;
+/// return the path of the backup, none for the trash
+pub fn set_aside(target: &Path) -> io::Result<Option<PathBuf>> {
+    let (control, suffix) = get_backup_policy();
+    if control == BackupControl::None {
+        return move_to_trash(target).map(|()| None);
+    }
+    let backup: PathBuf = get_backup_path(target, control, suffix)?;
+    fs::rename(target, &backup)?;
+    Ok(Some(backup))
+}
+
+/// the path of the backup of `target`, replacing an older simple backup
+fn get_backup_path(target: &Path, control: BackupControl, suffix: &str) -> io::Result<PathBuf> {
+    let name: OsString = target
+        .file_name()
+        .ok_or_else(|| io::Error::other(tr(Message::NoFileName, &[&target.display()])))?
+        .to_os_string();
+    let last: Option<u64> = find_last_number(target, &name)?;
+    let is_numbered: bool = match control {
+        BackupControl::Numbered => true,
+        BackupControl::Existing => last.is_some(),
+        _ => false,
+    };
+    let mut backup_name: OsString = name;
+    match is_numbered {
+        true => backup_name.push(format!(".~{}~", last.unwrap_or(0) + 1)),
+        false => backup_name.push(suffix),
+    }
+    Ok(target.with_file_name(backup_name))
+}
+
+/// the highest number of the numbered backups of the file `name` at `target`
+fn find_last_number(target: &Path, name: &OsString) -> io::Result<Option<u64>> {
+    let dir: &Path = match target.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let prefix: String = format!("{}.~", name.to_string_lossy());
+    let mut last: Option<u64> = None;
+    for entry in fs::read_dir(dir)? {
+        let entry_name: OsString = entry?.file_name();
+        let number: Option<u64> = entry_name
+            .to_str()
+            .and_then(|n| n.strip_prefix(&prefix))
+            .and_then(|n| n.strip_suffix('~'))
+            .and_then(|n| n.parse().ok());
+        last = last.max(number);
+    }
+    Ok(last)
+}
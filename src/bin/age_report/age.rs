@@ -0,0 +1,93 @@
+use ray_commands::{
+    output::Report,
+    size::format_size,
+    table::{Alignment, Table},
+    walk::{walk_files, WalkEntry},
+};
+use serde::Serialize;
+use std::{
+    io,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// the bucket names with the age in days below which a file falls in the bucket
+const BUCKETS: [(&str, Option<u64>); 5] = [
+    ("today", Some(1)),
+    ("this week", Some(7)),
+    ("this month", Some(30)),
+    ("this year", Some(365)),
+    ("older", None),
+];
+
+#[derive(Clone, Serialize)]
+pub struct AgeBucket {
+    pub name: String,
+    pub count: usize,
+    pub size: u64,
+}
+
+pub struct AgeReport {
+    /// from the most to the least recent
+    pub buckets: Vec<AgeBucket>,
+}
+
+impl AgeReport {
+    /// bucket the files under `root` by their age at `now`;
+    /// files modified in the future count as modified today
+    pub fn scan(root: &Path, now: SystemTime) -> io::Result<Self> {
+        let mut buckets: Vec<AgeBucket> = BUCKETS
+            .iter()
+            .map(|(name, _)| AgeBucket {
+                name: name.to_string(),
+                count: 0,
+                size: 0,
+            })
+            .collect();
+        let entries: Vec<WalkEntry> = walk_files(root)?;
+        for entry in entries.iter().filter(|e| e.metadata.is_file()) {
+            let age: Duration = match entry.metadata.modified() {
+                Ok(modified) => now.duration_since(modified).unwrap_or_default(),
+                Err(_) => continue,
+            };
+            let days: u64 = age.as_secs() / SECONDS_PER_DAY;
+            let index: usize = BUCKETS
+                .iter()
+                .position(|(_, limit)| limit.is_none_or(|l| days < l))
+                .unwrap();
+            buckets[index].count += 1;
+            buckets[index].size += entry.metadata.len();
+        }
+        Ok(Self { buckets })
+    }
+}
+
+impl Report for AgeReport {
+    type Record = AgeBucket;
+
+    fn records(&self) -> Vec<AgeBucket> {
+        self.buckets.clone()
+    }
+
+    /// print the `bucket files size` table with a total row
+    fn print_table(&self) {
+        let total: AgeBucket = AgeBucket {
+            name: String::from("total"),
+            count: self.buckets.iter().map(|b| b.count).sum(),
+            size: self.buckets.iter().map(|b| b.size).sum(),
+        };
+        let mut table: Table = Table::new(&["age", "files", "size"])
+            .align(1, Alignment::Right)
+            .align(2, Alignment::Right);
+        self.buckets.iter().chain([&total]).for_each(|b| {
+            table.add_row(vec![
+                b.name.clone(),
+                b.count.to_string(),
+                format_size(b.size),
+            ])
+        });
+        table.print();
+    }
+}
@@ -0,0 +1,33 @@
+use clap::{crate_version, value_parser, Arg, Command};
+use ray_commands::{clock::get_now_arg, i18n::get_lang_arg, output::get_output_arg};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "age_report";
+
+const SHORT_DESCRIPTION: &str =
+    "Count the files under a path and their total size by modification age,\nto help decide what to keep.";
+
+const BUCKETS_HELP_MESSAGE: &str = r#"The files are bucketed by the time since their last modification:
+  today:      less than 1 day
+  this week:  less than 7 days
+  this month: less than 30 days
+  this year:  less than 365 days
+  older:      365 days or more"#;
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .after_help(BUCKETS_HELP_MESSAGE)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_output_arg())
+        .arg(
+            Arg::new("path")
+                .help("The directory to examine, including its subdirectories")
+                .value_parser(value_parser!(PathBuf))
+                .required(true),
+        )
+}
@@ -0,0 +1,34 @@
+use age::AgeReport;
+use ray_commands::{
+    clock::set_now,
+    clock::system_now,
+    i18n::{set_language, tr, Message},
+    output::{get_output_format, print_report, OutputFormat},
+};
+use std::{path::PathBuf, process};
+
+mod age;
+pub mod cli;
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    let path: PathBuf = matches.get_one::<PathBuf>("path").unwrap().clone();
+    let output: OutputFormat = get_output_format(&matches);
+
+    let report: AgeReport = match AgeReport::scan(&path, system_now()) {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToRead, &[&path.display(), &err]));
+            process::exit(1);
+        }
+    };
+
+    print_report(&report, output);
+}
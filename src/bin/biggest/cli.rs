@@ -0,0 +1,42 @@
+use clap::{crate_version, value_parser, Arg, Command};
+use ray_commands::{clock::get_now_arg, i18n::get_lang_arg, output::get_output_arg};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "biggest";
+
+const SHORT_DESCRIPTION: &str =
+    "Report the largest files and heaviest directories under a path\nto find what is eating the disk.";
+
+const MIN_SIZE_HELP_MESSAGE: &str = r#"Ignore files smaller than this size, e.g. 100M or 1.5GiB;
+K, M, G, T are binary (1024) and KB, MB, GB, TB decimal (1000)"#;
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_output_arg())
+        .arg(
+            Arg::new("number")
+                .short('n')
+                .long("number")
+                .help("The number of files and of directories to report")
+                .value_parser(value_parser!(usize))
+                .default_value("10"),
+        )
+        .arg(
+            Arg::new("min_size")
+                .long("min-size")
+                .help(MIN_SIZE_HELP_MESSAGE)
+                .default_value("0"),
+        )
+        .arg(
+            Arg::new("path")
+                .help("The directory to examine")
+                .value_parser(value_parser!(PathBuf))
+                .required(true),
+        )
+}
@@ -0,0 +1,43 @@
+use ray_commands::{
+    clock::set_now,
+    i18n::{set_language, tr, Message},
+    output::{get_output_format, print_report, OutputFormat},
+    size::parse_size,
+};
+use std::{path::PathBuf, process};
+use usage::DiskUsage;
+
+pub mod cli;
+mod usage;
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    let path: PathBuf = matches.get_one::<PathBuf>("path").unwrap().clone();
+    let number: usize = *matches.get_one::<usize>("number").unwrap();
+    let output: OutputFormat = get_output_format(&matches);
+    let min_size_text: &str = matches.get_one::<String>("min_size").unwrap();
+    let min_size: u64 = match parse_size(min_size_text) {
+        Some(size) => size,
+        None => {
+            eprintln!("{}", tr(Message::InvalidSize, &[&min_size_text]));
+            process::exit(2);
+        }
+    };
+
+    let usage: DiskUsage = match DiskUsage::scan(&path, number, min_size) {
+        Ok(usage) => usage,
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToRead, &[&path.display(), &err]));
+            process::exit(1);
+        }
+    };
+
+    print_report(&usage, output);
+}
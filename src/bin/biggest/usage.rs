@@ -0,0 +1,114 @@
+use ray_commands::{
+    i18n::{tr, Message},
+    output::Report,
+    paths::resolve,
+    size::format_size,
+    table::{Alignment, Table},
+    walk::{walk_files, WalkEntry},
+};
+use serde::Serialize;
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+pub struct SizeEntry {
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Serialize)]
+pub struct SizeRecord {
+    /// "file" or "directory"
+    kind: &'static str,
+    path: String,
+    size: u64,
+}
+
+pub struct DiskUsage {
+    /// the largest files, largest first
+    pub files: Vec<SizeEntry>,
+    /// the subdirectories with the largest total size of the files within, largest first
+    pub directories: Vec<SizeEntry>,
+}
+
+impl DiskUsage {
+    /// the `number` largest files and directories under `root`,
+    /// counting only files of at least `min_size` bytes
+    pub fn scan(root: &Path, number: usize, min_size: u64) -> io::Result<Self> {
+        let entries: Vec<WalkEntry> = walk_files(root)?;
+        let mut files: Vec<(PathBuf, u64)> = vec![];
+        let mut directories: HashMap<PathBuf, u64> = HashMap::new();
+        for entry in entries {
+            let size: u64 = entry.metadata.len();
+            if !entry.metadata.is_file() || size < min_size {
+                continue;
+            }
+            // every directory above the file, except `root` itself
+            for ancestor in entry.relative.ancestors().skip(1) {
+                if !ancestor.as_os_str().is_empty() {
+                    *directories.entry(ancestor.to_path_buf()).or_default() += size;
+                }
+            }
+            files.push((entry.relative, size));
+        }
+
+        Ok(Self {
+            files: get_top(root, files, number),
+            directories: get_top(root, directories.into_iter().collect(), number),
+        })
+    }
+}
+
+impl Report for DiskUsage {
+    type Record = SizeRecord;
+
+    fn records(&self) -> Vec<SizeRecord> {
+        let files = self.files.iter().map(|e| ("file", e));
+        let directories = self.directories.iter().map(|e| ("directory", e));
+        files
+            .chain(directories)
+            .map(|(kind, e)| SizeRecord {
+                kind,
+                path: e.path.clone(),
+                size: e.size,
+            })
+            .collect()
+    }
+
+    fn print_table(&self) {
+        println!("{}", tr(Message::LargestFiles, &[]));
+        print_table(&self.files);
+        println!();
+        println!("{}", tr(Message::HeaviestDirectories, &[]));
+        print_table(&self.directories);
+    }
+}
+
+/// the `number` largest entries, ties broken by path
+fn get_top(root: &Path, mut sizes: Vec<(PathBuf, u64)>, number: usize) -> Vec<SizeEntry> {
+    sizes.sort_by(|(pa, sa), (pb, sb)| Reverse(sa).cmp(&Reverse(sb)).then(pa.cmp(pb)));
+    sizes
+        .into_iter()
+        .take(number)
+        .map(|(relative, size)| SizeEntry {
+            path: resolve(root, &relative).display().to_string(),
+            size,
+        })
+        .collect()
+}
+
+/// print the `size path` table, with sizes right-aligned
+fn print_table(entries: &[SizeEntry]) {
+    if entries.is_empty() {
+        println!("  (none)");
+        return;
+    }
+    let mut table: Table = Table::new(&["size", "path"]).align(0, Alignment::Right);
+    entries
+        .iter()
+        .for_each(|e| table.add_row(vec![format_size(e.size), e.path.clone()]));
+    table.print();
+}
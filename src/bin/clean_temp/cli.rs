@@ -0,0 +1,72 @@
+use super::leftovers::KIND_NAMES;
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::{
+    age::parse_age,
+    clock::get_now_arg,
+    guard::get_guard_args,
+    i18n::get_lang_arg,
+    output::get_output_arg,
+    prompt::get_yes_arg,
+    sort::get_sort_arg,
+    walk::{get_content_args, get_no_ignore_arg, get_recursion_args, get_size_args, get_type_arg},
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "clean_temp";
+
+const SHORT_DESCRIPTION: &str =
+    "Remove editor swap files, backups, temporary and partial files\nand empty leftovers that have not been touched for a while,\nby moving them to the trash.";
+
+const KEEP_HELP_MESSAGE: &str = r#"A kind of leftover to leave alone, repeatable:
+  swap:    Vim swap files (.name.swp) and Emacs locks (.#name)
+  backup:  name~ and #name#
+  temp:    *.tmp and *.temp
+  partial: *.partial, *.part and *.crdownload
+  empty:   files of zero bytes"#;
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_yes_arg())
+        .arg(get_output_arg())
+        .args(get_guard_args())
+        .arg(get_no_ignore_arg())
+        .args(get_recursion_args())
+        .args(get_size_args())
+        .arg(get_sort_arg())
+        .args(get_content_args())
+        .arg(get_type_arg())
+        .arg(
+            Arg::new("older_than")
+                .long("older-than")
+                .help("Only remove files not modified for this long, e.g. 12h or 7d")
+                .value_parser(parse_age)
+                .default_value("1d"),
+        )
+        .arg(
+            Arg::new("keep")
+                .short('k')
+                .long("keep")
+                .help(KEEP_HELP_MESSAGE)
+                .value_parser(KIND_NAMES)
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("dry_run")
+                .short('n')
+                .long("dry-run")
+                .help("Only print the files that would be removed")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("dir")
+                .help("The directory tree to clean")
+                .value_parser(value_parser!(PathBuf))
+                .required(true),
+        )
+}
@@ -0,0 +1,185 @@
+use ray_commands::{
+    clock::system_now,
+    i18n::{tr, Message},
+    output::Report,
+    prompt::wait_accepting_prompt,
+    recycle::move_to_trash,
+    size::format_size,
+    table::{Alignment, Table},
+    walk::{walk_files, WalkEntry},
+};
+use regex::Regex;
+use serde::Serialize;
+use std::{
+    io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+pub const KIND_NAMES: [&str; 5] = ["swap", "backup", "temp", "partial", "empty"];
+
+/// the name patterns of each kind of leftover, except empty files
+const KIND_PATTERNS: [(LeftoverKind, &str); 4] = [
+    // Vim swap files and Emacs lock links
+    (LeftoverKind::Swap, r"^(?:\..+\.sw[a-p]|\.#.+)$"),
+    // Emacs, Vim and nano backups and Emacs auto-saves
+    (LeftoverKind::Backup, r"^(?:.+~|#.+#)$"),
+    (LeftoverKind::Temp, r"(?i)\.te?mp$"),
+    // interrupted downloads and copies
+    (LeftoverKind::Partial, r"(?i)\.(?:partial|part|crdownload)$"),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LeftoverKind {
+    Swap,
+    Backup,
+    Temp,
+    Partial,
+    Empty,
+}
+
+impl LeftoverKind {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "swap" => Self::Swap,
+            "backup" => Self::Backup,
+            "temp" => Self::Temp,
+            "partial" => Self::Partial,
+            "empty" => Self::Empty,
+            _ => panic!("Unknown kind of leftover: {name}"),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Swap => "swap",
+            Self::Backup => "backup",
+            Self::Temp => "temp",
+            Self::Partial => "partial",
+            Self::Empty => "empty",
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct LeftoverRecord {
+    path: String,
+    kind: &'static str,
+    size: u64,
+}
+
+pub struct CleanPlan {
+    /// leftover files with their kind and size
+    leftovers: Vec<(PathBuf, LeftoverKind, u64)>,
+}
+
+impl CleanPlan {
+    /// the leftovers of `kinds` below `root` not modified for `min_age`
+    pub fn new(root: &Path, kinds: &[LeftoverKind], min_age: Duration) -> io::Result<Self> {
+        let patterns: Vec<(LeftoverKind, Regex)> = KIND_PATTERNS
+            .iter()
+            .filter(|(kind, _)| kinds.contains(kind))
+            .map(|(kind, pattern)| (*kind, Regex::new(pattern).unwrap()))
+            .collect();
+        let now: SystemTime = system_now();
+
+        let entries: Vec<WalkEntry> = walk_files(root)?;
+        let mut leftovers: Vec<(PathBuf, LeftoverKind, u64)> = vec![];
+        for entry in entries {
+            let is_old: bool = entry
+                .metadata
+                .modified()
+                .ok()
+                .and_then(|m| now.duration_since(m).ok())
+                .is_some_and(|age| age >= min_age);
+            if !is_old {
+                continue;
+            }
+            let name: String = entry
+                .path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned();
+            let kind: Option<LeftoverKind> = patterns
+                .iter()
+                .find(|(_, pattern)| pattern.is_match(&name))
+                .map(|(kind, _)| *kind)
+                .or_else(|| {
+                    let is_empty: bool = entry.metadata.is_file() && entry.metadata.len() == 0;
+                    (is_empty && kinds.contains(&LeftoverKind::Empty))
+                        .then_some(LeftoverKind::Empty)
+                });
+            if let Some(kind) = kind {
+                leftovers.push((entry.path, kind, entry.metadata.len()));
+            }
+        }
+        Ok(Self { leftovers })
+    }
+
+    /// the leftovers to remove
+    pub fn file_count(&self) -> usize {
+        self.leftovers.len()
+    }
+
+    /// print the leftovers, or that there are none; return whether there are any
+    pub fn preview(&self) -> bool {
+        if self.leftovers.is_empty() {
+            println!("{}", tr(Message::NoLeftovers, &[]));
+            return false;
+        }
+        self.print_table();
+        true
+    }
+
+    /// confirm and move the leftovers previewed to the trash, the only step
+    /// removing any; return whether all of them were removed
+    pub fn apply(&self) -> bool {
+        if !wait_accepting_prompt(&tr(Message::AcceptRemoval, &[])) {
+            println!("{}", tr(Message::NothingDone, &[]));
+            return true;
+        }
+        let mut has_failed: bool = false;
+        for (path, _, _) in &self.leftovers {
+            if let Err(err) = move_to_trash(path) {
+                eprintln!("{}", tr(Message::FailedToRemove, &[&path.display(), &err]));
+                has_failed = true;
+            }
+        }
+        !has_failed
+    }
+}
+
+impl Report for CleanPlan {
+    type Record = LeftoverRecord;
+
+    fn records(&self) -> Vec<LeftoverRecord> {
+        self.leftovers
+            .iter()
+            .map(|(p, kind, size)| LeftoverRecord {
+                path: p.display().to_string(),
+                kind: kind.name(),
+                size: *size,
+            })
+            .collect()
+    }
+
+    /// print the `path kind size` table and the total size
+    fn print_table(&self) {
+        let mut table: Table = Table::new(&["path", "kind", "size"]).align(2, Alignment::Right);
+        self.leftovers.iter().for_each(|(p, kind, size)| {
+            table.add_row(vec![
+                p.display().to_string(),
+                kind.name().to_string(),
+                format_size(*size),
+            ])
+        });
+        table.print();
+        let total: u64 = self.leftovers.iter().map(|(_, _, s)| s).sum();
+        println!(
+            "{} leftover(s), {} in total",
+            self.leftovers.len(),
+            format_size(total)
+        );
+    }
+}
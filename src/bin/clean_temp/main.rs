@@ -0,0 +1,80 @@
+use leftovers::{CleanPlan, LeftoverKind, KIND_NAMES};
+use ray_commands::{
+    clock::set_now,
+    guard::{check_batch_size, check_paths, set_guard},
+    i18n::{set_language, tr, Message},
+    output::{get_output_format, print_report, OutputFormat},
+    prompt::set_assume_yes,
+    sort::set_sort_order,
+    walk::{
+        set_content_filter, set_entry_types, set_ignore_rules, set_recursion_limits,
+        set_size_limits,
+    },
+};
+use std::{path::PathBuf, process, time::Duration};
+
+pub mod cli;
+mod leftovers;
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    set_assume_yes(&matches);
+    set_guard(&matches);
+    set_ignore_rules(&matches);
+    set_recursion_limits(&matches);
+    set_size_limits(&matches);
+    set_sort_order(&matches);
+    set_content_filter(&matches);
+    set_entry_types(&matches);
+    let dir: PathBuf = matches.get_one::<PathBuf>("dir").unwrap().clone();
+    if let Err(err) = check_paths(&[&dir]) {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
+    let min_age: Duration = *matches.get_one::<Duration>("older_than").unwrap();
+    let kept: Vec<&String> = matches
+        .get_many::<String>("keep")
+        .unwrap_or_default()
+        .collect();
+    let kinds: Vec<LeftoverKind> = KIND_NAMES
+        .iter()
+        .filter(|name| !kept.iter().any(|k| k == *name))
+        .map(|name| LeftoverKind::from(name))
+        .collect();
+    let dry_run: bool = matches.get_flag("dry_run");
+    let output: OutputFormat = get_output_format(&matches);
+
+    let plan: CleanPlan = match CleanPlan::new(&dir, &kinds, min_age) {
+        Ok(plan) => plan,
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToRead, &[&dir.display(), &err]));
+            process::exit(1);
+        }
+    };
+    if !output.is_table() {
+        print_report(&plan, output);
+        return;
+    }
+    if let Err(err) = check_batch_size(plan.file_count()) {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
+    if !plan.preview() {
+        return;
+    }
+    // a dry run stops at the preview, never reaching the removal
+    if dry_run {
+        println!("{}", tr(Message::NothingDone, &[]));
+        return;
+    }
+    if !plan.apply() {
+        process::exit(1);
+    }
+}
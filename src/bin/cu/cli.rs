@@ -0,0 +1,37 @@
+use super::TOOLS;
+use clap::{crate_version, value_parser, Arg, Command};
+use clap_complete::Shell;
+
+pub const PROGRAM_NAME: &str = "cu";
+
+const SHORT_DESCRIPTION: &str =
+    "All command utilities in one binary, e.g. `cu rename-mod-time *.jpg`.\nLinked or copied under the name of a tool, it runs as that tool.";
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .subcommand_required(true)
+        .subcommands(
+            TOOLS
+                .iter()
+                .map(|(name, parser, _, _)| parser().name(get_subcommand_name(name))),
+        )
+        .subcommand(
+            Command::new("completions")
+                .about("Print the shell completion script of cu")
+                .arg(
+                    Arg::new("shell")
+                        .help("The shell to complete in")
+                        .value_parser(value_parser!(Shell))
+                        .required(true),
+                ),
+        )
+}
+
+/// the subcommand of a tool, e.g. `rename-mod-time` for `rename_mod_time`
+pub fn get_subcommand_name(program: &str) -> String {
+    program.replace('_', "-")
+}
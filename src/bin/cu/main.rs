@@ -0,0 +1,240 @@
+use clap::{ArgMatches, Command};
+use clap_complete::Shell;
+use std::{env, io, path::Path};
+
+mod cli;
+
+#[path = "../age_report/main.rs"]
+mod age_report;
+#[path = "../biggest/main.rs"]
+mod biggest;
+#[path = "../clean_temp/main.rs"]
+mod clean_temp;
+#[path = "../diff_trees/main.rs"]
+mod diff_trees;
+#[path = "../doc_rename/main.rs"]
+mod doc_rename;
+#[path = "../exif_shift/main.rs"]
+mod exif_shift;
+#[path = "../export_names/main.rs"]
+mod export_names;
+#[path = "../fix_encoding/main.rs"]
+mod fix_encoding;
+#[path = "../fix_perms/main.rs"]
+mod fix_perms;
+#[path = "../image_dups/main.rs"]
+mod image_dups;
+#[path = "../link_farm/main.rs"]
+mod link_farm;
+#[path = "../normalize_screenshots/main.rs"]
+mod normalize_screenshots;
+#[path = "../normalize_unicode/main.rs"]
+mod normalize_unicode;
+#[path = "../organize_downloads/main.rs"]
+mod organize_downloads;
+#[path = "../path_audit/main.rs"]
+mod path_audit;
+#[path = "../pdf_rename/main.rs"]
+mod pdf_rename;
+#[path = "../rename_from_csv/main.rs"]
+mod rename_from_csv;
+#[path = "../rename_mod_time/main.rs"]
+mod rename_mod_time;
+#[path = "../restore_names/main.rs"]
+mod restore_names;
+#[path = "../safe_mv/main.rs"]
+mod safe_mv;
+#[path = "../seq_check/main.rs"]
+mod seq_check;
+#[path = "../shuffle_names/main.rs"]
+mod shuffle_names;
+#[path = "../tag_rename/main.rs"]
+mod tag_rename;
+#[path = "../trash/main.rs"]
+mod trash;
+#[path = "../xattr_tool/main.rs"]
+mod xattr_tool;
+
+/// the name of a tool, its parser, its entry point as a separate binary and
+/// its entry point on parsed arguments
+pub type Tool = (&'static str, fn() -> Command, fn(), fn(ArgMatches));
+
+pub const TOOLS: [Tool; 25] = [
+    (
+        age_report::cli::PROGRAM_NAME,
+        age_report::cli::get_cli_parser,
+        age_report::main,
+        age_report::run,
+    ),
+    (
+        biggest::cli::PROGRAM_NAME,
+        biggest::cli::get_cli_parser,
+        biggest::main,
+        biggest::run,
+    ),
+    (
+        clean_temp::cli::PROGRAM_NAME,
+        clean_temp::cli::get_cli_parser,
+        clean_temp::main,
+        clean_temp::run,
+    ),
+    (
+        diff_trees::cli::PROGRAM_NAME,
+        diff_trees::cli::get_cli_parser,
+        diff_trees::main,
+        diff_trees::run,
+    ),
+    (
+        doc_rename::cli::PROGRAM_NAME,
+        doc_rename::cli::get_cli_parser,
+        doc_rename::main,
+        doc_rename::run,
+    ),
+    (
+        exif_shift::cli::PROGRAM_NAME,
+        exif_shift::cli::get_cli_parser,
+        exif_shift::main,
+        exif_shift::run,
+    ),
+    (
+        export_names::cli::PROGRAM_NAME,
+        export_names::cli::get_cli_parser,
+        export_names::main,
+        export_names::run,
+    ),
+    (
+        fix_encoding::cli::PROGRAM_NAME,
+        fix_encoding::cli::get_cli_parser,
+        fix_encoding::main,
+        fix_encoding::run,
+    ),
+    (
+        fix_perms::cli::PROGRAM_NAME,
+        fix_perms::cli::get_cli_parser,
+        fix_perms::main,
+        fix_perms::run,
+    ),
+    (
+        image_dups::cli::PROGRAM_NAME,
+        image_dups::cli::get_cli_parser,
+        image_dups::main,
+        image_dups::run,
+    ),
+    (
+        link_farm::cli::PROGRAM_NAME,
+        link_farm::cli::get_cli_parser,
+        link_farm::main,
+        link_farm::run,
+    ),
+    (
+        normalize_screenshots::cli::PROGRAM_NAME,
+        normalize_screenshots::cli::get_cli_parser,
+        normalize_screenshots::main,
+        normalize_screenshots::run,
+    ),
+    (
+        normalize_unicode::cli::PROGRAM_NAME,
+        normalize_unicode::cli::get_cli_parser,
+        normalize_unicode::main,
+        normalize_unicode::run,
+    ),
+    (
+        organize_downloads::cli::PROGRAM_NAME,
+        organize_downloads::cli::get_cli_parser,
+        organize_downloads::main,
+        organize_downloads::run,
+    ),
+    (
+        path_audit::cli::PROGRAM_NAME,
+        path_audit::cli::get_cli_parser,
+        path_audit::main,
+        path_audit::run,
+    ),
+    (
+        pdf_rename::cli::PROGRAM_NAME,
+        pdf_rename::cli::get_cli_parser,
+        pdf_rename::main,
+        pdf_rename::run,
+    ),
+    (
+        rename_from_csv::cli::PROGRAM_NAME,
+        rename_from_csv::cli::get_cli_parser,
+        rename_from_csv::main,
+        rename_from_csv::run,
+    ),
+    (
+        rename_mod_time::cli::PROGRAM_NAME,
+        rename_mod_time::cli::get_cli_parser,
+        rename_mod_time::main,
+        rename_mod_time::run,
+    ),
+    (
+        restore_names::cli::PROGRAM_NAME,
+        restore_names::cli::get_cli_parser,
+        restore_names::main,
+        restore_names::run,
+    ),
+    (
+        safe_mv::cli::PROGRAM_NAME,
+        safe_mv::cli::get_cli_parser,
+        safe_mv::main,
+        safe_mv::run,
+    ),
+    (
+        seq_check::cli::PROGRAM_NAME,
+        seq_check::cli::get_cli_parser,
+        seq_check::main,
+        seq_check::run,
+    ),
+    (
+        shuffle_names::cli::PROGRAM_NAME,
+        shuffle_names::cli::get_cli_parser,
+        shuffle_names::main,
+        shuffle_names::run,
+    ),
+    (
+        tag_rename::cli::PROGRAM_NAME,
+        tag_rename::cli::get_cli_parser,
+        tag_rename::main,
+        tag_rename::run,
+    ),
+    (
+        trash::cli::PROGRAM_NAME,
+        trash::cli::get_cli_parser,
+        trash::main,
+        trash::run,
+    ),
+    (
+        xattr_tool::cli::PROGRAM_NAME,
+        xattr_tool::cli::get_cli_parser,
+        xattr_tool::main,
+        xattr_tool::run,
+    ),
+];
+
+fn main() {
+    // called through a link named after a tool, busybox-style
+    let called_name: Option<String> = env::args_os().next().and_then(|a| {
+        Path::new(&a)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+    });
+    if let Some(tool) = TOOLS.iter().find(|t| called_name.as_deref() == Some(t.0)) {
+        return tool.2();
+    }
+
+    // load command-line arguments
+    let mut parser: Command = cli::get_cli_parser();
+    let matches: ArgMatches = parser.clone().get_matches();
+    let (subcommand, sub_matches) = matches.subcommand().unwrap();
+    if subcommand == "completions" {
+        let shell: Shell = *sub_matches.get_one::<Shell>("shell").unwrap();
+        clap_complete::generate(shell, &mut parser, cli::PROGRAM_NAME, &mut io::stdout());
+        return;
+    }
+    let tool: &Tool = TOOLS
+        .iter()
+        .find(|t| cli::get_subcommand_name(t.0) == subcommand)
+        .unwrap();
+    tool.3(sub_matches.clone());
+}
@@ -0,0 +1,50 @@
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::{
+    clock::get_now_arg, hash::get_no_hash_cache_arg, i18n::get_lang_arg, jobs::get_jobs_arg,
+    output::get_output_arg,
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "diff_trees";
+
+const SHORT_DESCRIPTION: &str =
+    "Compare two directory trees by name, size, modification time\nand optionally content hash.";
+
+const LONG_DESCRIPTION: &str = r#"Compare two directory trees by name, size, modification time
+and optionally content hash.
+
+Files only in A are reported as removed, files only in B as added.
+A removed file whose content matches an added file is reported as renamed.
+Exit status is 0 when the trees are identical and 1 otherwise."#;
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .long_about(LONG_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_no_hash_cache_arg())
+        .arg(get_jobs_arg())
+        .arg(get_output_arg())
+        .arg(
+            Arg::new("hash")
+                .long("hash")
+                .help("Compare the content hash of files present in both trees\ninstead of their modification time")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("tree_a")
+                .help("The path to the old directory tree")
+                .value_parser(value_parser!(PathBuf))
+                .required(true),
+        )
+        .arg(
+            Arg::new("tree_b")
+                .help("The path to the new directory tree")
+                .value_parser(value_parser!(PathBuf))
+                .required(true),
+        )
+}
@@ -0,0 +1,43 @@
+use ray_commands::{
+    clock::set_now,
+    hash::set_hash_cache,
+    i18n::{set_language, tr, Message},
+    jobs::set_jobs,
+    output::{get_output_format, print_report, OutputFormat},
+};
+use std::{path::PathBuf, process};
+use tree_diff::TreeDiff;
+
+pub mod cli;
+mod tree_diff;
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    set_hash_cache(&matches);
+    set_jobs(&matches);
+    let tree_a: PathBuf = matches.get_one::<PathBuf>("tree_a").unwrap().clone();
+    let tree_b: PathBuf = matches.get_one::<PathBuf>("tree_b").unwrap().clone();
+    let use_hash: bool = matches.get_flag("hash");
+    let output: OutputFormat = get_output_format(&matches);
+
+    let diff: TreeDiff = match TreeDiff::compare(&tree_a, &tree_b, use_hash) {
+        Ok(diff) => diff,
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToCompareTrees, &[&err]));
+            process::exit(2);
+        }
+    };
+
+    print_report(&diff, output);
+
+    if !diff.is_empty() {
+        process::exit(1);
+    }
+}
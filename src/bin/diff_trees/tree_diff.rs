@@ -0,0 +1,210 @@
+use ray_commands::{hash::hash_file_cached, output::Report, walk::walk_files};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+pub struct TreeDiff {
+    added: Vec<String>,
+    removed: Vec<String>,
+    modified: Vec<ModifiedFile>,
+    renamed: Vec<RenamedFile>,
+}
+
+struct ModifiedFile {
+    path: String,
+    /// what differs: "size", "content" or "mtime"
+    reason: &'static str,
+}
+
+struct RenamedFile {
+    from: String,
+    to: String,
+}
+
+#[derive(Serialize)]
+pub struct ChangeRecord {
+    /// "removed", "added", "modified" or "renamed"
+    change: &'static str,
+    path: String,
+    /// the path in the second tree of a renamed file
+    new_path: Option<String>,
+    /// what differs in a modified file
+    reason: Option<&'static str>,
+}
+
+struct FileState {
+    path: PathBuf,
+    size: u64,
+    mtime: Option<SystemTime>,
+}
+
+impl TreeDiff {
+    pub fn compare(tree_a: &Path, tree_b: &Path, use_hash: bool) -> io::Result<Self> {
+        let files_a: BTreeMap<String, FileState> = Self::load_tree(tree_a)?;
+        let files_b: BTreeMap<String, FileState> = Self::load_tree(tree_b)?;
+
+        let mut removed: Vec<String> = Vec::new();
+        let mut modified: Vec<ModifiedFile> = Vec::new();
+        for (name, a) in &files_a {
+            match files_b.get(name) {
+                None => removed.push(name.clone()),
+                Some(b) => {
+                    if let Some(reason) = Self::get_modification_reason(a, b, use_hash)? {
+                        modified.push(ModifiedFile {
+                            path: name.clone(),
+                            reason,
+                        });
+                    }
+                }
+            }
+        }
+        let mut added: Vec<String> = files_b
+            .keys()
+            .filter(|name| !files_a.contains_key(*name))
+            .cloned()
+            .collect();
+
+        let renamed: Vec<RenamedFile> =
+            Self::detect_renames(&files_a, &files_b, &mut removed, &mut added)?;
+
+        Ok(Self {
+            added,
+            removed,
+            modified,
+            renamed,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && self.renamed.is_empty()
+    }
+
+    fn load_tree(root: &Path) -> io::Result<BTreeMap<String, FileState>> {
+        Ok(walk_files(root)?
+            .into_iter()
+            .map(|e| {
+                (
+                    e.relative.to_string_lossy().into_owned(),
+                    FileState {
+                        path: e.path,
+                        size: e.metadata.len(),
+                        mtime: e.metadata.modified().ok(),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    fn get_modification_reason(
+        a: &FileState,
+        b: &FileState,
+        use_hash: bool,
+    ) -> io::Result<Option<&'static str>> {
+        if a.size != b.size {
+            return Ok(Some("size"));
+        }
+        if use_hash {
+            if hash_file_cached(&a.path)? != hash_file_cached(&b.path)? {
+                return Ok(Some("content"));
+            }
+            return Ok(None);
+        }
+        if a.mtime != b.mtime {
+            return Ok(Some("mtime"));
+        }
+        Ok(None)
+    }
+
+    /// pair removed and added files with identical content,
+    /// taking the pairs out of `removed` and `added`
+    fn detect_renames(
+        files_a: &BTreeMap<String, FileState>,
+        files_b: &BTreeMap<String, FileState>,
+        removed: &mut Vec<String>,
+        added: &mut Vec<String>,
+    ) -> io::Result<Vec<RenamedFile>> {
+        let mut renamed: Vec<RenamedFile> = Vec::new();
+        let mut added_hashes: BTreeMap<String, String> = BTreeMap::new();
+        for name in added.iter() {
+            // only files sharing a size with some removed file can be renamed copies
+            let size: u64 = files_b[name].size;
+            if removed.iter().any(|r| files_a[r].size == size) {
+                added_hashes.insert(name.clone(), hash_file_cached(&files_b[name].path)?);
+            }
+        }
+
+        let mut still_removed: Vec<String> = Vec::new();
+        for name in removed.drain(..) {
+            let a: &FileState = &files_a[&name];
+            let candidates: Vec<&String> = added_hashes
+                .keys()
+                .filter(|n| files_b[*n].size == a.size)
+                .collect();
+            if candidates.is_empty() {
+                still_removed.push(name);
+                continue;
+            }
+            let hash: String = hash_file_cached(&a.path)?;
+            match candidates.into_iter().find(|n| added_hashes[*n] == hash) {
+                Some(target) => {
+                    let target: String = target.clone();
+                    added_hashes.remove(&target);
+                    added.retain(|n| *n != target);
+                    renamed.push(RenamedFile {
+                        from: name,
+                        to: target,
+                    });
+                }
+                None => still_removed.push(name),
+            }
+        }
+        *removed = still_removed;
+        Ok(renamed)
+    }
+}
+
+impl Report for TreeDiff {
+    type Record = ChangeRecord;
+
+    fn records(&self) -> Vec<ChangeRecord> {
+        let record = |change: &'static str, path: &String| ChangeRecord {
+            change,
+            path: path.clone(),
+            new_path: None,
+            reason: None,
+        };
+        let removed = self.removed.iter().map(|p| record("removed", p));
+        let added = self.added.iter().map(|p| record("added", p));
+        let modified = self.modified.iter().map(|m| ChangeRecord {
+            reason: Some(m.reason),
+            ..record("modified", &m.path)
+        });
+        let renamed = self.renamed.iter().map(|r| ChangeRecord {
+            new_path: Some(r.to.clone()),
+            ..record("renamed", &r.from)
+        });
+        removed
+            .chain(added)
+            .chain(modified)
+            .chain(renamed)
+            .collect()
+    }
+
+    fn print_table(&self) {
+        self.removed.iter().for_each(|p| println!("- {p}"));
+        self.added.iter().for_each(|p| println!("+ {p}"));
+        self.modified
+            .iter()
+            .for_each(|m| println!("M {} ({})", m.path, m.reason));
+        self.renamed
+            .iter()
+            .for_each(|r| println!("R {} -> {}", r.from, r.to));
+    }
+}
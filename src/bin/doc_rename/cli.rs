@@ -0,0 +1,136 @@
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::conflict::{CONFLICT_HELP_MESSAGE, CONFLICT_STRATEGY_NAMES};
+use ray_commands::{
+    backup::get_backup_args,
+    clock::get_now_arg,
+    failure::get_on_error_arg,
+    guard::get_guard_args,
+    hardlinks::get_hardlinks_arg,
+    hooks::get_hook_args,
+    i18n::get_lang_arg,
+    jobs::get_jobs_arg,
+    journal::get_journal_in_dir_arg,
+    name_command::get_name_command_args,
+    notify::get_notify_arg,
+    output::{get_output_arg, get_print0_arg},
+    pipe::{get_files_from_arg, get_pipe_args},
+    plan::{get_confirm_every_arg, get_explain_arg, get_resume_arg},
+    portable::get_portable_arg,
+    prompt::get_yes_arg,
+    report::get_report_args,
+    retry::get_retry_args,
+    serve::get_serve_args,
+    sort::get_sort_arg,
+    stats::get_stats_arg,
+    verify::get_verify_arg,
+    walk::{get_content_args, get_size_args},
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "doc_rename";
+
+const SHORT_DESCRIPTION: &str =
+    "Rename office documents (docx, xlsx, pptx, odt, ods, odp)\nafter the title, author and date in their properties,\nand with --ocr scanned images and PDFs after their text.";
+
+const OCR_HELP_MESSAGE: &str = r#"Also rename scanned images and PDFs by the text recognized in them,
+through tesseract unless --ocr-command: {title} is the first line of
+words and {date} the first date, or else the old name and the
+modification time"#;
+
+const OCR_COMMAND_HELP_MESSAGE: &str = r#"The command recognizing the text of an image, given its path as the
+last argument and printing the text, instead of tesseract"#;
+
+const TEMPLATE_HELP_MESSAGE: &str = r#"The new path, relative to the directory of each document.
+Placeholders: {title} {subject} {author} {date} (created, YYYY-MM-DD)
+              {year} {month} {name} (the old name) {ext}
+              {parent} (the name of its directory), {parent:N} (its Nth
+              word split at spaces, _ - and ., from the end if negative)
+Filters after | transform a value: lower, upper, slug, trunc:N,
+e.g. {title|slug|trunc:40}.
+Documents missing a property used by the template are skipped."#;
+
+const DEFAULT_TEMPLATE: &str = "{date} {title}.{ext}";
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_yes_arg())
+        .arg(get_output_arg())
+        .args(get_guard_args())
+        .args(get_retry_args())
+        .arg(get_stats_arg())
+        .arg(get_notify_arg())
+        .args(get_pipe_args())
+        .arg(get_print0_arg())
+        .arg(get_files_from_arg())
+        .args(get_serve_args())
+        .args(get_hook_args())
+        .arg(get_on_error_arg())
+        .arg(get_explain_arg())
+        .arg(get_verify_arg())
+        .arg(get_portable_arg())
+        .arg(get_confirm_every_arg())
+        .args(get_report_args())
+        .args(get_size_args())
+        .arg(get_sort_arg())
+        .arg(get_hardlinks_arg())
+        .args(get_content_args())
+        .args(get_name_command_args())
+        .arg(
+            Arg::new("template")
+                .short('t')
+                .long("template")
+                .help(TEMPLATE_HELP_MESSAGE)
+                .default_value(DEFAULT_TEMPLATE),
+        )
+        .arg(
+            Arg::new("ocr")
+                .long("ocr")
+                .help(OCR_HELP_MESSAGE)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("ocr_command")
+                .long("ocr-command")
+                .help(OCR_COMMAND_HELP_MESSAGE)
+                .value_name("COMMAND")
+                .requires("ocr"),
+        )
+        .arg(
+            Arg::new("on_conflict")
+                .long("on-conflict")
+                .help(CONFLICT_HELP_MESSAGE)
+                .value_parser(CONFLICT_STRATEGY_NAMES)
+                .default_value("skip"),
+        )
+        .args(get_backup_args())
+        .arg(
+            Arg::new("undo")
+                .long("undo")
+                .help("Undo the latest renaming done by this command")
+                .action(ArgAction::SetTrue)
+                .exclusive(true),
+        )
+        .arg(get_resume_arg())
+        .arg(get_jobs_arg())
+        .arg(get_journal_in_dir_arg())
+        .arg(
+            Arg::new("input_paths")
+                .help("The path(s) to the document(s)")
+                .value_parser(value_parser!(PathBuf))
+                .required_unless_present_any([
+                    "undo",
+                    "resume",
+                    "pipe",
+                    "serve",
+                    "serve_socket",
+                    "files_from",
+                ])
+                .action(ArgAction::Append),
+        )
+}
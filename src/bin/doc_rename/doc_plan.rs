@@ -0,0 +1,102 @@
+use super::{doc_props::DocProperties, ocr::Ocr};
+use chrono::{DateTime, NaiveDate};
+use ray_commands::{
+    conflict::ConflictStrategy,
+    i18n::{tr, Message},
+    paths::resolve,
+    plan::RenamePlan,
+    sanitize::sanitize_component,
+    template::{lookup_parent, Template},
+};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+pub fn plan_renames(
+    input_paths: &[PathBuf],
+    template: &Template,
+    conflict: ConflictStrategy,
+    ocr: Option<&Ocr>,
+) -> RenamePlan {
+    let mut plan: RenamePlan = RenamePlan::with_conflict(conflict);
+    for path in input_paths {
+        let target: Result<PathBuf, String> =
+            get_new_path(path, template, ocr).map(|(target, step)| {
+                plan.explain(path, step);
+                target
+            });
+        plan.add(path.clone(), target);
+    }
+    plan
+}
+
+/// the rendered template, relative to the directory of the document, and the
+/// properties it was rendered from
+fn get_new_path(
+    path: &Path,
+    template: &Template,
+    ocr: Option<&Ocr>,
+) -> Result<(PathBuf, String), String> {
+    let (properties, found_in): (DocProperties, Message) = match (DocProperties::read(path), ocr) {
+        (Ok(Some(properties)), _) => (properties, Message::ExplainFromProperties),
+        (Ok(None), Some(ocr)) if Ocr::is_scan(path) => {
+            (ocr.read(path)?, Message::ExplainFromRecognizedText)
+        }
+        (Ok(None), Some(_)) => return Err(tr(Message::NotOfficeDocumentNorScan, &[])),
+        (Ok(None), None) => return Err(tr(Message::NotOfficeDocument, &[])),
+        (Err(err), _) => return Err(err.to_string()),
+    };
+    let values: HashMap<&str, String> = get_property_values(path, properties);
+
+    let lookup = |key: &str| {
+        values
+            .get(key)
+            .cloned()
+            .or_else(|| lookup_parent(key, path))
+    };
+    let relative: String = template.render(lookup).map_err(|e| e.to_string())?;
+    let step: String = tr(found_in, &[&template.describe(lookup)]);
+    let dir: &Path = path.parent().unwrap_or(Path::new("."));
+    Ok((resolve(dir, &relative), step))
+}
+
+/// the template values of a document, each usable as a path component
+fn get_property_values<'a>(path: &Path, properties: DocProperties) -> HashMap<&'a str, String> {
+    let mut values: HashMap<&str, String> = HashMap::new();
+    let mut insert = |key: &'a str, value: Option<String>| {
+        if let Some(value) = value.map(|v| sanitize_component(&v)) {
+            if !value.is_empty() {
+                values.insert(key, value);
+            }
+        }
+    };
+    let date: Option<NaiveDate> = properties
+        .created
+        .as_deref()
+        .or(properties.modified.as_deref())
+        .and_then(parse_date);
+    insert("title", properties.title);
+    insert("subject", properties.subject);
+    insert("author", properties.author);
+    insert("date", date.map(|d| d.format("%Y-%m-%d").to_string()));
+    insert("year", date.map(|d| d.format("%Y").to_string()));
+    insert("month", date.map(|d| d.format("%m").to_string()));
+    insert(
+        "name",
+        path.file_stem().map(|s| s.to_string_lossy().into_owned()),
+    );
+    insert(
+        "ext",
+        path.extension().map(|e| e.to_string_lossy().into_owned()),
+    );
+    values
+}
+
+/// the date of an ISO 8601 timestamp, with or without a time zone
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    DateTime::parse_from_rfc3339(text)
+        .map(|t| t.date_naive())
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(text.get(..10)?, "%Y-%m-%d").ok())
+}
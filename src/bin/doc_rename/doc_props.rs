@@ -0,0 +1,98 @@
+use quick_xml::{events::Event, Reader};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+use zip::ZipArchive;
+
+/// where the document properties are kept, per container format
+// reference: ECMA-376 Part 2, 11 Core Properties; OpenDocument 1.3 Part 3, 4.2 meta.xml
+const OOXML_CORE_PROPERTIES: &str = "docProps/core.xml";
+const ODF_META: &str = "meta.xml";
+
+/// elements of both formats, with the property each holds; earlier elements win
+const PROPERTY_ELEMENTS: [(&str, &str); 9] = [
+    ("dc:title", "title"),
+    ("dc:subject", "subject"),
+    // the author in OOXML, but the last editor in ODF
+    ("meta:initial-creator", "author"),
+    ("dc:creator", "author"),
+    ("cp:lastModifiedBy", "author"),
+    ("dcterms:created", "created"),
+    ("meta:creation-date", "created"),
+    ("dcterms:modified", "modified"),
+    ("dc:date", "modified"),
+];
+
+#[derive(Default)]
+pub struct DocProperties {
+    pub title: Option<String>,
+    pub subject: Option<String>,
+    pub author: Option<String>,
+    /// ISO 8601 date and time
+    pub created: Option<String>,
+    pub modified: Option<String>,
+}
+
+impl DocProperties {
+    /// the properties of an Office Open XML (docx, xlsx, pptx) or OpenDocument
+    /// (odt, ods, odp) file, or `None` if it is neither
+    pub fn read(path: &Path) -> io::Result<Option<Self>> {
+        let mut archive: ZipArchive<File> = match ZipArchive::new(File::open(path)?) {
+            Ok(archive) => archive,
+            Err(_) => return Ok(None),
+        };
+        let Some(member) = [OOXML_CORE_PROPERTIES, ODF_META]
+            .into_iter()
+            .find(|m| archive.index_for_name(m).is_some())
+        else {
+            return Ok(None);
+        };
+        let mut xml: String = String::new();
+        archive
+            .by_name(member)
+            .map_err(io::Error::other)?
+            .read_to_string(&mut xml)?;
+        Ok(Some(Self::parse(&xml)?))
+    }
+
+    fn parse(xml: &str) -> io::Result<Self> {
+        // each property with the rank of the element it was taken from
+        let mut values: HashMap<&str, (usize, String)> = HashMap::new();
+        let mut reader: Reader<&[u8]> = Reader::from_str(xml);
+        reader.config_mut().trim_text(true);
+        let mut element: Option<(usize, &str)> = None;
+        loop {
+            match reader.read_event().map_err(io::Error::other)? {
+                Event::Start(start) => {
+                    element = PROPERTY_ELEMENTS
+                        .iter()
+                        .position(|(name, _)| name.as_bytes() == start.name().as_ref())
+                        .map(|i| (i, PROPERTY_ELEMENTS[i].1));
+                }
+                Event::Text(text) => {
+                    if let Some((rank, property)) = element {
+                        let text: String = text.unescape().map_err(io::Error::other)?.into_owned();
+                        let is_better: bool = values.get(property).is_none_or(|(r, _)| rank < *r);
+                        if is_better && !text.is_empty() {
+                            values.insert(property, (rank, text));
+                        }
+                    }
+                }
+                Event::End(_) => element = None,
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        let mut take = |property: &str| values.remove(property).map(|(_, v)| v);
+        Ok(Self {
+            title: take("title"),
+            subject: take("subject"),
+            author: take("author"),
+            created: take("created"),
+            modified: take("modified"),
+        })
+    }
+}
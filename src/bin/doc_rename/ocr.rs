@@ -0,0 +1,232 @@
+use super::doc_props::DocProperties;
+use chrono::{DateTime, Local, NaiveDate};
+use clap::ArgMatches;
+use lopdf::Document;
+use ray_commands::i18n::{tr, Message};
+use regex::Regex;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{self, Command, Output, Stdio},
+    sync::OnceLock,
+};
+
+/// the extensions of the scans read by `--ocr`
+const SCAN_EXTENSIONS: [&str; 9] = [
+    "bmp", "gif", "jpeg", "jpg", "pdf", "png", "tif", "tiff", "webp",
+];
+
+/// the least letters of a line of text to be taken for a title
+const MIN_TITLE_LETTERS: usize = 3;
+
+/// the most characters of a title, OCR running lines together at times
+const MAX_TITLE_CHARS: usize = 80;
+
+/// the text recognition of scans by `--ocr`, through tesseract or the command
+/// of `--ocr-command`
+pub struct Ocr {
+    command: Option<String>,
+}
+
+/// what the text of a scan tells
+struct ScanText {
+    title: Option<String>,
+    date: Option<NaiveDate>,
+}
+
+impl Ocr {
+    pub fn from(matches: &ArgMatches) -> Option<Self> {
+        matches.get_flag("ocr").then(|| Self {
+            command: matches.get_one::<String>("ocr_command").cloned(),
+        })
+    }
+
+    /// whether `path` is a scan to read, by its extension
+    pub fn is_scan(path: &Path) -> bool {
+        path.extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .is_some_and(|e| SCAN_EXTENSIONS.contains(&e.as_str()))
+    }
+
+    /// the properties of the scan at `path` by its text, its date by the
+    /// modification time and its title by its name when the text has none
+    pub fn read(&self, path: &Path) -> Result<DocProperties, String> {
+        let text: ScanText = ScanText::parse(&self.read_text(path)?);
+        let date: Option<String> = match text.date {
+            Some(date) => Some(date.format("%Y-%m-%d").to_string()),
+            None => fs::metadata(path)
+                .and_then(|m| m.modified())
+                .ok()
+                .map(|t| DateTime::<Local>::from(t).to_rfc3339()),
+        };
+        Ok(DocProperties {
+            title: text
+                .title
+                .or_else(|| path.file_stem().map(|s| s.to_string_lossy().into_owned())),
+            created: date,
+            ..DocProperties::default()
+        })
+    }
+
+    /// the text of an image, or of the first page of a PDF: its own text if
+    /// any, or else that of its scanned image
+    fn read_text(&self, path: &Path) -> Result<String, String> {
+        let is_pdf: bool = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("pdf"));
+        if !is_pdf {
+            return self.recognize(path);
+        }
+        let document: Document = Document::load(path).map_err(|e| e.to_string())?;
+        let text: String = document.extract_text(&[1]).unwrap_or_default();
+        if text.chars().any(char::is_alphanumeric) {
+            return Ok(text);
+        }
+        let page = *document
+            .get_pages()
+            .get(&1)
+            .ok_or_else(|| tr(Message::PdfWithoutPages, &[]))?;
+        let images = document.get_page_images(page).map_err(|e| e.to_string())?;
+        // a JPEG stream is a JPEG file as is, as scanners mostly write them
+        let image = images
+            .iter()
+            .find(|i| {
+                i.filters
+                    .as_ref()
+                    .is_some_and(|f| f.iter().any(|f| f == "DCTDecode"))
+            })
+            .ok_or_else(|| tr(Message::PdfWithoutScan, &[]))?;
+        let temporary: PathBuf = env::temp_dir().join(format!("doc_rename-{}.jpg", process::id()));
+        fs::write(&temporary, image.content).map_err(|e| e.to_string())?;
+        let text: Result<String, String> = self.recognize(&temporary);
+        let _ = fs::remove_file(&temporary);
+        text
+    }
+
+    /// the text recognized in the image at `path`
+    fn recognize(&self, path: &Path) -> Result<String, String> {
+        let mut command: Command = match &self.command {
+            Some(command) => get_file_command(command, path),
+            None => {
+                let mut tesseract: Command = Command::new("tesseract");
+                tesseract.arg(path).arg("stdout");
+                tesseract
+            }
+        };
+        let output: Output = command
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| tr(Message::OcrNotRun, &[&e]))?;
+        if !output.status.success() {
+            let stderr: String = String::from_utf8_lossy(&output.stderr).into_owned();
+            return Err(match stderr.lines().next() {
+                Some(line) => tr(Message::OcrFailed, &[&line]),
+                None => tr(Message::OcrFailed, &[&output.status]),
+            });
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+impl ScanText {
+    /// the first date and the first line of words in `text`
+    fn parse(text: &str) -> Self {
+        let title: Option<String> = text
+            .lines()
+            .map(|l| l.split_whitespace().collect::<Vec<&str>>().join(" "))
+            .find(|l| l.chars().filter(|c| c.is_alphabetic()).count() >= MIN_TITLE_LETTERS)
+            .map(|l| {
+                l.chars()
+                    .take(MAX_TITLE_CHARS)
+                    .collect::<String>()
+                    .trim()
+                    .to_string()
+            });
+        Self {
+            title,
+            date: find_date(text),
+        }
+    }
+}
+
+/// the first date written in `text`, as 2024-03-15, 15.03.2024, 03/15/2024,
+/// 15 March 2024 or March 15, 2024
+fn find_date(text: &str) -> Option<NaiveDate> {
+    static PATTERNS: OnceLock<[Regex; 4]> = OnceLock::new();
+    let [iso, numeric, day_month, month_day] = PATTERNS.get_or_init(|| {
+        let month: &str = "(jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*\\.?";
+        [
+            Regex::new(r"\b(\d{4})[-./](\d{1,2})[-./](\d{1,2})\b").unwrap(),
+            Regex::new(r"\b(\d{1,2})([./-])(\d{1,2})[./-](\d{4})\b").unwrap(),
+            Regex::new(&format!(r"(?i)\b(\d{{1,2}})\.? {month},? (\d{{4}})\b")).unwrap(),
+            Regex::new(&format!(
+                r"(?i)\b{month} (\d{{1,2}})(?:st|nd|rd|th)?,? (\d{{4}})\b"
+            ))
+            .unwrap(),
+        ]
+    });
+    let number = |text: &str| text.parse::<u32>().unwrap_or(0);
+    let month_number = |name: &str| {
+        let months = [
+            "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+        ];
+        months
+            .iter()
+            .position(|m| name.eq_ignore_ascii_case(m))
+            .map_or(0, |i| i as u32 + 1)
+    };
+    let date = |year: u32, month: u32, day: u32| {
+        NaiveDate::from_ymd_opt(year as i32, month, day).filter(|_| (1900..=2100).contains(&year))
+    };
+    let mut dates: Vec<(usize, NaiveDate)> = Vec::new();
+    for c in iso.captures_iter(text) {
+        dates.extend(
+            date(number(&c[1]), number(&c[2]), number(&c[3]))
+                .map(|d| (c.get(0).unwrap().start(), d)),
+        );
+    }
+    for c in numeric.captures_iter(text) {
+        let (first, second, year) = (number(&c[1]), number(&c[3]), number(&c[4]));
+        // day first, as in most of the world, but month first with slashes
+        // unless that is no date
+        let found: Option<NaiveDate> = match &c[2] {
+            "/" => date(year, first, second).or_else(|| date(year, second, first)),
+            _ => date(year, second, first),
+        };
+        dates.extend(found.map(|d| (c.get(0).unwrap().start(), d)));
+    }
+    for c in day_month.captures_iter(text) {
+        dates.extend(
+            date(number(&c[3]), month_number(&c[2]), number(&c[1]))
+                .map(|d| (c.get(0).unwrap().start(), d)),
+        );
+    }
+    for c in month_day.captures_iter(text) {
+        dates.extend(
+            date(number(&c[3]), month_number(&c[1]), number(&c[2]))
+                .map(|d| (c.get(0).unwrap().start(), d)),
+        );
+    }
+    dates
+        .into_iter()
+        .min_by_key(|(start, _)| *start)
+        .map(|(_, d)| d)
+}
+
+#[cfg(unix)]
+fn get_file_command(command: &str, path: &Path) -> Command {
+    let mut shell: Command = Command::new("sh");
+    shell
+        .arg("-c")
+        .arg(format!("{command} \"$1\""))
+        .arg("sh")
+        .arg(path);
+    shell
+}
+
+#[cfg(windows)]
+fn get_file_command(command: &str, path: &Path) -> Command {
+    let mut shell: Command = Command::new("cmd");
+    shell.arg("/C").arg(command).arg(path);
+    shell
+}
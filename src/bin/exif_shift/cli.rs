@@ -0,0 +1,52 @@
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::{
+    clock::get_now_arg,
+    hardlinks::get_hardlinks_arg,
+    i18n::get_lang_arg,
+    output::get_output_arg,
+    prompt::get_yes_arg,
+    sort::get_sort_arg,
+    walk::{get_content_args, get_size_args},
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "exif_shift";
+
+const SHORT_DESCRIPTION: &str =
+    "Shift the EXIF date tags of photos, e.g. to correct a camera\nwhose clock was wrong.";
+
+const OFFSET_HELP_MESSAGE: &str = r#"The time to add, as a sign followed by amounts of
+days (d), hours (h), minutes (m) and seconds (s), e.g.
+  +1h30m: one and a half hours later
+  -2d:    two days earlier
+Shifting by the opposite offset reverts a shift."#;
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_yes_arg())
+        .arg(get_output_arg())
+        .args(get_size_args())
+        .arg(get_sort_arg())
+        .arg(get_hardlinks_arg())
+        .args(get_content_args())
+        .arg(
+            Arg::new("offset")
+                .long("offset")
+                .help(OFFSET_HELP_MESSAGE)
+                .allow_hyphen_values(true)
+                .required(true),
+        )
+        .arg(
+            Arg::new("input_paths")
+                .help("The path(s) to the JPEG or TIFF-based photo(s)")
+                .value_parser(value_parser!(PathBuf))
+                .required(true)
+                .action(ArgAction::Append),
+        )
+}
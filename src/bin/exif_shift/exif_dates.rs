@@ -0,0 +1,81 @@
+use chrono::NaiveDateTime;
+use ray_commands::exif::{Entry, Exif, TAG_EXIF_IFD};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Seek, SeekFrom, Write},
+    path::Path,
+    time::SystemTime,
+};
+
+// reference: https://www.cipa.jp/std/documents/e/DC-008-2012_E.pdf
+const TAG_DATE_TIME: u16 = 0x0132;
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+const TAG_DATE_TIME_DIGITIZED: u16 = 0x9004;
+
+/// "YYYY:MM:DD HH:MM:SS", stored with a trailing NUL
+const DATE_FORMAT: &str = "%Y:%m:%d %H:%M:%S";
+const DATE_LENGTH: usize = 19;
+
+/// a date tag and where its text is in the file
+pub struct DateField {
+    pub tag: u16,
+    pub offset: u64,
+    pub value: NaiveDateTime,
+}
+
+impl DateField {
+    /// the date the photo was taken if recorded, else the first date found
+    pub fn primary(fields: &[DateField]) -> Option<&DateField> {
+        fields
+            .iter()
+            .find(|f| f.tag == TAG_DATE_TIME_ORIGINAL)
+            .or(fields.first())
+    }
+}
+
+/// the valid date fields of a JPEG or TIFF-based file, empty when it has no EXIF
+pub fn read_date_fields(path: &Path) -> io::Result<Vec<DateField>> {
+    let Some(exif) = Exif::read_whole(path)? else {
+        return Ok(vec![]);
+    };
+    let Some(ifd0) = exif.get_ifd0() else {
+        return Ok(vec![]);
+    };
+    let mut fields: Vec<DateField> = read_date_field(&exif, ifd0, TAG_DATE_TIME)
+        .into_iter()
+        .collect();
+    if let Some(exif_ifd) = exif.find_ifd(ifd0, TAG_EXIF_IFD) {
+        for tag in [TAG_DATE_TIME_ORIGINAL, TAG_DATE_TIME_DIGITIZED] {
+            fields.extend(read_date_field(&exif, exif_ifd, tag));
+        }
+    }
+    Ok(fields)
+}
+
+/// overwrite the date fields in place, keeping the modification time of the file
+pub fn write_date_fields(path: &Path, fields: &[DateField]) -> io::Result<()> {
+    let modified: SystemTime = fs::metadata(path)?.modified()?;
+    let mut file: File = OpenOptions::new().write(true).open(path)?;
+    for field in fields {
+        file.seek(SeekFrom::Start(field.offset))?;
+        file.write_all(field.value.format(DATE_FORMAT).to_string().as_bytes())?;
+    }
+    file.set_modified(modified)
+}
+
+/// the date field with `tag` in the IFD at `ifd`, with its offset in the file
+fn read_date_field(exif: &Exif, ifd: usize, tag: u16) -> Option<DateField> {
+    let entry: Entry = exif.find_entry(ifd, tag)?;
+    // longer than 4 bytes, so the value is stored at an offset
+    if entry.count as usize <= 4 {
+        return None;
+    }
+    let text: String = exif.read_ascii(&entry)?;
+    let value: NaiveDateTime =
+        NaiveDateTime::parse_from_str(text.get(..DATE_LENGTH)?, DATE_FORMAT).ok()?;
+    Some(DateField {
+        tag,
+        offset: (exif.start() + entry.value as usize) as u64,
+        value,
+    })
+}
@@ -0,0 +1,56 @@
+use chrono::TimeDelta;
+use ray_commands::{
+    clock::set_now,
+    hardlinks::set_hard_link_policy,
+    i18n::set_language,
+    output::{get_output_format, print_report, OutputFormat},
+    prompt::set_assume_yes,
+    sort::set_sort_order,
+    walk::{filter_paths, set_content_filter, set_size_limits},
+};
+use shift::ShiftPlan;
+use std::{path::PathBuf, process};
+use time_offset::parse_offset;
+
+pub mod cli;
+mod exif_dates;
+mod shift;
+mod time_offset;
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    set_assume_yes(&matches);
+    set_size_limits(&matches);
+    set_sort_order(&matches);
+    set_hard_link_policy(&matches);
+    set_content_filter(&matches);
+    let offset: TimeDelta = match parse_offset(matches.get_one::<String>("offset").unwrap()) {
+        Ok(offset) => offset,
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(2);
+        }
+    };
+    let input_paths: Vec<PathBuf> = filter_paths(
+        matches
+            .get_many::<PathBuf>("input_paths")
+            .unwrap()
+            .cloned()
+            .collect(),
+    );
+    let output: OutputFormat = get_output_format(&matches);
+
+    let plan: ShiftPlan = ShiftPlan::new(&input_paths, offset);
+    if !output.is_table() {
+        print_report(&plan, output);
+        return;
+    }
+    plan.apply(true);
+}
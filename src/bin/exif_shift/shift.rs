@@ -0,0 +1,143 @@
+use super::exif_dates::{read_date_fields, write_date_fields, DateField};
+use chrono::TimeDelta;
+use console::Color;
+use ray_commands::{
+    i18n::{tr, Message},
+    output::Report,
+    prompt::wait_accepting_prompt,
+    table::Table,
+};
+use serde::Serialize;
+use std::path::PathBuf;
+
+const DISPLAY_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Serialize)]
+pub struct ShiftRecord {
+    /// "shift" or "skip"
+    action: &'static str,
+    path: String,
+    /// the primary date of the file, before and after the shift
+    old: Option<String>,
+    new: Option<String>,
+    /// why a skipped file cannot be shifted
+    reason: Option<String>,
+}
+
+#[derive(Default)]
+pub struct ShiftPlan {
+    /// the files with their date fields already shifted
+    shifts: Vec<(PathBuf, Vec<DateField>)>,
+    /// files that cannot be shifted, with the reason
+    skipped: Vec<(PathBuf, String)>,
+    offset: TimeDelta,
+}
+
+impl ShiftPlan {
+    pub fn new(input_paths: &[PathBuf], offset: TimeDelta) -> Self {
+        let mut plan: Self = Self {
+            offset,
+            ..Self::default()
+        };
+        for path in input_paths {
+            let fields: Vec<DateField> = match read_date_fields(path) {
+                Ok(fields) if fields.is_empty() => {
+                    plan.skipped
+                        .push((path.clone(), tr(Message::NoExifDate, &[])));
+                    continue;
+                }
+                Ok(fields) => fields,
+                Err(err) => {
+                    plan.skipped.push((path.clone(), err.to_string()));
+                    continue;
+                }
+            };
+            let shifted: Option<Vec<DateField>> = fields
+                .into_iter()
+                .map(|f| {
+                    f.value
+                        .checked_add_signed(offset)
+                        .map(|value| DateField { value, ..f })
+                })
+                .collect();
+            match shifted {
+                Some(shifted) => plan.shifts.push((path.clone(), shifted)),
+                None => plan
+                    .skipped
+                    .push((path.clone(), tr(Message::ShiftedOutOfRange, &[]))),
+            }
+        }
+        plan
+    }
+
+    /// preview, confirm and rewrite the dates; return whether anything was written
+    pub fn apply(&self, to_print_prompt: bool) -> bool {
+        self.skipped.iter().for_each(|(p, reason)| {
+            eprintln!("{}", tr(Message::Skipping, &[&p.display(), reason]))
+        });
+        if self.shifts.is_empty() {
+            println!("{}", tr(Message::NothingToShift, &[]));
+            return false;
+        }
+
+        self.print_table();
+        if to_print_prompt {
+            let to_shift: bool = wait_accepting_prompt(&tr(Message::AcceptShift, &[]));
+            if !to_shift {
+                println!("{}", tr(Message::NothingDone, &[]));
+                return false;
+            }
+        }
+
+        self.shifts.iter().for_each(|(path, fields)| {
+            if let Err(err) = write_date_fields(path, fields) {
+                eprintln!("{}", tr(Message::FailedToWrite, &[&path.display(), &err]));
+            }
+        });
+        true
+    }
+
+    /// the primary date of the file shifted to `fields`, before and after the shift
+    fn get_primary_dates(&self, fields: &[DateField]) -> (String, String) {
+        let new = DateField::primary(fields).unwrap().value;
+        (
+            (new - self.offset).format(DISPLAY_FORMAT).to_string(),
+            new.format(DISPLAY_FORMAT).to_string(),
+        )
+    }
+}
+
+impl Report for ShiftPlan {
+    type Record = ShiftRecord;
+
+    fn records(&self) -> Vec<ShiftRecord> {
+        let skipped = self.skipped.iter().map(|(path, reason)| ShiftRecord {
+            action: "skip",
+            path: path.display().to_string(),
+            old: None,
+            new: None,
+            reason: Some(reason.clone()),
+        });
+        let shifts = self.shifts.iter().map(|(path, fields)| {
+            let (old, new) = self.get_primary_dates(fields);
+            ShiftRecord {
+                action: "shift",
+                path: path.display().to_string(),
+                old: Some(old),
+                new: Some(new),
+                reason: None,
+            }
+        });
+        skipped.chain(shifts).collect()
+    }
+
+    /// print the `file old new` table of the primary date of each file
+    fn print_table(&self) {
+        let mut table: Table = Table::new(&["file", "old", "new"]).color(2, Color::Green);
+        self.shifts.iter().for_each(|(path, fields)| {
+            let (old, new) = self.get_primary_dates(fields);
+            table.add_row(vec![path.display().to_string(), old, new])
+        });
+        table.print();
+    }
+}
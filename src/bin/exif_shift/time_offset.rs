@@ -0,0 +1,30 @@
+use chrono::TimeDelta;
+use ray_commands::i18n::{tr, Message};
+use regex::Regex;
+
+/// parse an offset like `+1h30m` or `-2d` into the time to add
+pub fn parse_offset(text: &str) -> Result<TimeDelta, String> {
+    let invalid = || tr(Message::InvalidOffset, &[&text]);
+    let whole: Regex = Regex::new(r"^([+-]?)((?:\d+[dhms])+)$").unwrap();
+    let part: Regex = Regex::new(r"(\d+)([dhms])").unwrap();
+
+    let caps = whole.captures(text).ok_or_else(invalid)?;
+    let mut seconds: i64 = 0;
+    for part_caps in part.captures_iter(&caps[2]) {
+        let amount: i64 = part_caps[1].parse().map_err(|_| invalid())?;
+        let unit: i64 = match &part_caps[2] {
+            "d" => 86_400,
+            "h" => 3_600,
+            "m" => 60,
+            _ => 1,
+        };
+        seconds = amount
+            .checked_mul(unit)
+            .and_then(|s| seconds.checked_add(s))
+            .ok_or_else(invalid)?;
+    }
+    if &caps[1] == "-" {
+        seconds = -seconds;
+    }
+    TimeDelta::try_seconds(seconds).ok_or_else(invalid)
+}
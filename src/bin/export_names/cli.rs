@@ -0,0 +1,70 @@
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::{
+    clock::get_now_arg,
+    hash::get_no_hash_cache_arg,
+    i18n::get_lang_arg,
+    jobs::get_jobs_arg,
+    sort::get_sort_arg,
+    walk::{get_content_args, get_no_ignore_arg, get_recursion_args, get_size_args, get_type_arg},
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "export_names";
+
+const SHORT_DESCRIPTION: &str = "Export the file names of a directory, with size, modification time\nand content hash, for editing in a spreadsheet.";
+
+const FORMAT_HELP_MESSAGE: &str = r#"The output format, guessed from the output file extension by default:
+  csv:  old,new,size,mtime,hash; edit "new" and feed it to rename_from_csv
+  tsv:  the same, tab-separated
+  json: a manifest for restore_names"#;
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_no_hash_cache_arg())
+        .arg(get_jobs_arg())
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .help(FORMAT_HELP_MESSAGE)
+                .value_parser(["csv", "tsv", "json"])
+                .required(false),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .help("The file to write to, instead of the standard output")
+                .required(false),
+        )
+        .arg(
+            Arg::new("recursive")
+                .short('r')
+                .long("recursive")
+                .help("Include the files in subdirectories")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(get_no_ignore_arg())
+        .args(get_recursion_args())
+        .args(get_size_args())
+        .arg(get_sort_arg())
+        .args(get_content_args())
+        .arg(get_type_arg())
+        .arg(
+            Arg::new("no_hash")
+                .long("no-hash")
+                .help("Skip hashing the file content")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("dir")
+                .help("The directory to export")
+                .value_parser(value_parser!(PathBuf))
+                .required(true),
+        )
+}
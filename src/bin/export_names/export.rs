@@ -0,0 +1,111 @@
+use chrono::{DateTime, Local, SecondsFormat};
+use ray_commands::{
+    hash::hash_file_cached,
+    manifest::{write_manifest, ManifestEntry},
+    mapping::{write_mapping, MappingRow},
+    walk::{list_files, walk_files, WalkEntry},
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Copy)]
+pub enum ExportFormat {
+    Csv,
+    Tsv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "csv" => Self::Csv,
+            "tsv" => Self::Tsv,
+            "json" => Self::Json,
+            _ => panic!("Unknown export format: {name}"),
+        }
+    }
+
+    /// from the extension of the output file, CSV for the standard output
+    pub fn guess(output: Option<&Path>) -> Self {
+        match output
+            .and_then(|p| p.extension())
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .as_deref()
+        {
+            Some("tsv") => Self::Tsv,
+            Some("json") => Self::Json,
+            _ => Self::Csv,
+        }
+    }
+}
+
+pub struct NameExport {
+    entries: Vec<ManifestEntry>,
+}
+
+impl NameExport {
+    /// `output` is left out of the export when it lies inside `dir`
+    pub fn scan(
+        dir: &Path,
+        recursive: bool,
+        to_hash: bool,
+        output: Option<&Path>,
+    ) -> io::Result<Self> {
+        let output: Option<PathBuf> = output.and_then(|p| fs::canonicalize(p).ok());
+        let files: Vec<WalkEntry> = if recursive {
+            walk_files(dir)?
+        } else {
+            list_files(dir)?
+        };
+
+        let mut entries: Vec<ManifestEntry> = Vec::new();
+        for file in files {
+            if !file.metadata.is_file() {
+                continue;
+            }
+            if output.is_some() && fs::canonicalize(&file.path).ok() == output {
+                continue;
+            }
+            let mtime: Option<String> = file.metadata.modified().ok().map(|t| {
+                let chrono_time: DateTime<Local> = t.into();
+                chrono_time.to_rfc3339_opts(SecondsFormat::Secs, false)
+            });
+            let hash: Option<String> = if to_hash {
+                Some(hash_file_cached(&file.path)?)
+            } else {
+                None
+            };
+            entries.push(ManifestEntry {
+                name: file.relative.to_string_lossy().into_owned(),
+                size: file.metadata.len(),
+                mtime,
+                hash,
+            });
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn write<W: io::Write>(&self, writer: W, format: ExportFormat) -> io::Result<()> {
+        match format {
+            ExportFormat::Json => write_manifest(writer, &self.entries),
+            ExportFormat::Csv => write_mapping(writer, b',', &self.get_mapping_rows()),
+            ExportFormat::Tsv => write_mapping(writer, b'\t', &self.get_mapping_rows()),
+        }
+    }
+
+    /// one row per file, with the new name to be edited starting as the current one
+    fn get_mapping_rows(&self) -> Vec<MappingRow> {
+        self.entries
+            .iter()
+            .map(|e| MappingRow {
+                old: e.name.clone(),
+                new: e.name.clone(),
+                size: Some(e.size),
+                mtime: e.mtime.clone(),
+                hash: e.hash.clone(),
+            })
+            .collect()
+    }
+}
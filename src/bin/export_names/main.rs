@@ -0,0 +1,60 @@
+use export::{ExportFormat, NameExport};
+use ray_commands::{
+    clock::set_now,
+    hash::set_hash_cache,
+    i18n::{set_language, tr, Message},
+    jobs::set_jobs,
+    sort::set_sort_order,
+    walk::{
+        set_content_filter, set_entry_types, set_ignore_rules, set_recursion_limits,
+        set_size_limits,
+    },
+};
+use std::{fs::File, io, path::PathBuf, process};
+
+pub mod cli;
+mod export;
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    set_hash_cache(&matches);
+    set_jobs(&matches);
+    set_ignore_rules(&matches);
+    set_recursion_limits(&matches);
+    set_size_limits(&matches);
+    set_sort_order(&matches);
+    set_content_filter(&matches);
+    set_entry_types(&matches);
+    let dir: PathBuf = matches.get_one::<PathBuf>("dir").unwrap().clone();
+    let output: Option<PathBuf> = matches.get_one::<String>("output").map(PathBuf::from);
+    let format: ExportFormat = match matches.get_one::<String>("format") {
+        Some(name) => ExportFormat::from(name),
+        None => ExportFormat::guess(output.as_deref()),
+    };
+    let recursive: bool = matches.get_flag("recursive");
+    let to_hash: bool = !matches.get_flag("no_hash");
+
+    let export: NameExport = match NameExport::scan(&dir, recursive, to_hash, output.as_deref()) {
+        Ok(export) => export,
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToRead, &[&dir.display(), &err]));
+            process::exit(1);
+        }
+    };
+
+    let result: io::Result<()> = match &output {
+        Some(path) => File::create(path).and_then(|f| export.write(f, format)),
+        None => export.write(io::stdout().lock(), format),
+    };
+    if let Err(err) = result {
+        eprintln!("{}", tr(Message::FailedToWriteExport, &[&err]));
+        process::exit(1);
+    }
+}
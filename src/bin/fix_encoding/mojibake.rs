@@ -0,0 +1,110 @@
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, BIG5, GBK, SHIFT_JIS, UTF_8, WINDOWS_1252};
+use ray_commands::{
+    conflict::ConflictStrategy,
+    i18n::{tr, Message},
+    plan::RenamePlan,
+    walk::WalkEntry,
+};
+use std::{cmp::Reverse, ffi::OsStr, path::PathBuf};
+
+#[derive(Clone, Copy)]
+pub enum SourceEncoding {
+    Auto,
+    Fixed(&'static Encoding),
+}
+
+impl SourceEncoding {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "auto" => Self::Auto,
+            "utf-8" => Self::Fixed(UTF_8),
+            "gbk" => Self::Fixed(GBK),
+            "big5" => Self::Fixed(BIG5),
+            "shift_jis" => Self::Fixed(SHIFT_JIS),
+            _ => panic!("Unknown encoding: {name}"),
+        }
+    }
+
+    /// plan renaming the entries with garbled names, deepest first
+    /// so that each rename happens before the directory holding it is renamed
+    pub fn plan_renames(
+        &self,
+        mut entries: Vec<WalkEntry>,
+        conflict: ConflictStrategy,
+    ) -> RenamePlan {
+        entries.sort_by_key(|e| Reverse(e.relative.components().count()));
+        let mut plan: RenamePlan = RenamePlan::with_conflict(conflict);
+        for entry in entries {
+            let Some(name) = entry.path.file_name() else {
+                continue;
+            };
+            if let Some(repaired) = self.repair(name) {
+                let target: Result<PathBuf, String> = repaired.map(|(n, step)| {
+                    plan.explain(&entry.path, step);
+                    entry.path.with_file_name(n)
+                });
+                plan.add(entry.path, target);
+            }
+        }
+        plan
+    }
+
+    /// the readable name of a garbled `name` with how it was decoded, `None`
+    /// when it does not look garbled, or the reason it cannot be decoded
+    fn repair(&self, name: &OsStr) -> Option<Result<(String, String), String>> {
+        let bytes: Vec<u8> = get_original_bytes(name)?;
+        if bytes.is_ascii() {
+            return None;
+        }
+        let (encoding, found_by): (&Encoding, Message) = match self {
+            Self::Fixed(encoding) => (encoding, Message::ExplainDecodedAs),
+            Self::Auto => {
+                let mut detector: EncodingDetector = EncodingDetector::new();
+                detector.feed(&bytes, true);
+                match detector.guess(None, true) {
+                    // Western text, already decoded right
+                    encoding if encoding == WINDOWS_1252 => return None,
+                    encoding => (encoding, Message::ExplainDetectedAs),
+                }
+            }
+        };
+        Some(
+            encoding
+                .decode_without_bom_handling_and_without_replacement(&bytes)
+                .map(|n| (n.into_owned(), tr(found_by, &[&encoding.name()])))
+                .ok_or_else(|| tr(Message::NotValidEncoding, &[&encoding.name()])),
+        )
+    }
+}
+
+/// the bytes of `name` before it was mis-decoded as Latin-1 or Windows-1252,
+/// or its raw bytes when it is not UTF-8 at all, as on old Linux archives;
+/// `None` when the name has characters neither encoding can produce
+fn get_original_bytes(name: &OsStr) -> Option<Vec<u8>> {
+    let Some(text) = name.to_str() else {
+        return get_raw_bytes(name);
+    };
+    text.chars()
+        .map(|c| match c as u32 {
+            code @ 0..=0xFF => Some(code as u8),
+            _ => {
+                let mut buffer: [u8; 4] = [0; 4];
+                let (bytes, _, has_unmappable) = WINDOWS_1252.encode(c.encode_utf8(&mut buffer));
+                (!has_unmappable && bytes.len() == 1).then(|| bytes[0])
+            }
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+fn get_raw_bytes(name: &OsStr) -> Option<Vec<u8>> {
+    use std::os::unix::ffi::OsStrExt;
+    Some(name.as_bytes().to_vec())
+}
+
+// names on other platforms are always Unicode
+#[cfg(not(unix))]
+fn get_raw_bytes(_name: &OsStr) -> Option<Vec<u8>> {
+    None
+}
@@ -0,0 +1,71 @@
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::{
+    clock::get_now_arg,
+    guard::get_guard_args,
+    i18n::{get_lang_arg, tr, Message},
+    output::get_output_arg,
+    prompt::get_yes_arg,
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "fix_perms";
+
+const SHORT_DESCRIPTION: &str =
+    "Normalize the permissions of a directory tree, e.g. after copying it\nfrom FAT media where every file became executable.";
+
+const EXEC_MODE_HELP_MESSAGE: &str =
+    "The mode of executable files, i.e. scripts starting with #!\nand ELF or Mach-O binaries";
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_yes_arg())
+        .arg(get_output_arg())
+        .args(get_guard_args())
+        .arg(
+            Arg::new("dir_mode")
+                .long("dir-mode")
+                .help("The octal mode of directories")
+                .value_parser(parse_mode)
+                .default_value("755"),
+        )
+        .arg(
+            Arg::new("file_mode")
+                .long("file-mode")
+                .help("The octal mode of other files")
+                .value_parser(parse_mode)
+                .default_value("644"),
+        )
+        .arg(
+            Arg::new("exec_mode")
+                .long("exec-mode")
+                .help(EXEC_MODE_HELP_MESSAGE)
+                .value_parser(parse_mode)
+                .default_value("755"),
+        )
+        .arg(
+            Arg::new("dry_run")
+                .short('n')
+                .long("dry-run")
+                .help("Only print the changes that would be made")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("dir")
+                .help("The directory tree to fix, itself included")
+                .value_parser(value_parser!(PathBuf))
+                .required(true),
+        )
+}
+
+fn parse_mode(text: &str) -> Result<u32, String> {
+    match u32::from_str_radix(text, 8) {
+        Ok(mode) if mode <= 0o7777 => Ok(mode),
+        _ => Err(tr(Message::ExpectedOctalMode, &[])),
+    }
+}
@@ -0,0 +1,62 @@
+use permissions::{PermissionPlan, PermissionPolicy};
+use ray_commands::{
+    clock::set_now,
+    guard::{check_batch_size, check_paths, set_guard},
+    i18n::{set_language, tr, Message},
+    output::{get_output_format, print_report, OutputFormat},
+    prompt::set_assume_yes,
+};
+use std::{path::PathBuf, process};
+
+pub mod cli;
+mod permissions;
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    set_assume_yes(&matches);
+    set_guard(&matches);
+    let dir: PathBuf = matches.get_one::<PathBuf>("dir").unwrap().clone();
+    if let Err(err) = check_paths(&[&dir]) {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
+    let policy: PermissionPolicy = PermissionPolicy {
+        dir_mode: *matches.get_one::<u32>("dir_mode").unwrap(),
+        file_mode: *matches.get_one::<u32>("file_mode").unwrap(),
+        exec_mode: *matches.get_one::<u32>("exec_mode").unwrap(),
+    };
+    let dry_run: bool = matches.get_flag("dry_run");
+    let output: OutputFormat = get_output_format(&matches);
+
+    let plan: PermissionPlan = match PermissionPlan::new(&dir, &policy) {
+        Ok(plan) => plan,
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToRead, &[&dir.display(), &err]));
+            process::exit(1);
+        }
+    };
+    if !output.is_table() {
+        print_report(&plan, output);
+        return;
+    }
+    if let Err(err) = check_batch_size(plan.file_count()) {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
+    if !plan.preview() {
+        return;
+    }
+    // a dry run stops at the preview, never reaching the changes
+    if dry_run {
+        println!("{}", tr(Message::NothingDone, &[]));
+        return;
+    }
+    plan.apply();
+}
@@ -0,0 +1,169 @@
+use console::Color;
+use ray_commands::{
+    i18n::{tr, Message},
+    output::Report,
+    prompt::wait_accepting_prompt,
+    table::Table,
+    walk::{walk_entries, WalkEntry},
+};
+use serde::Serialize;
+use std::{
+    fs::{self, File},
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// permission bits, leaving out the file type
+const MODE_MASK: u32 = 0o7777;
+
+/// leading bytes of files that can be executed
+const EXECUTABLE_MAGICS: [&[u8]; 6] = [
+    b"#!",
+    b"\x7FELF",
+    b"\xFE\xED\xFA\xCE", // Mach-O 32-bit
+    b"\xFE\xED\xFA\xCF", // Mach-O 64-bit
+    b"\xCF\xFA\xED\xFE", // Mach-O 64-bit, little-endian
+    b"\xCA\xFE\xBA\xBE", // Mach-O universal
+];
+
+pub struct PermissionPolicy {
+    pub dir_mode: u32,
+    pub file_mode: u32,
+    pub exec_mode: u32,
+}
+
+#[derive(Serialize)]
+pub struct ModeRecord {
+    path: String,
+    /// the modes in octal, e.g. "0644"
+    old: String,
+    new: String,
+}
+
+pub struct PermissionPlan {
+    /// paths with their current and new modes
+    changes: Vec<(PathBuf, u32, u32)>,
+}
+
+impl PermissionPlan {
+    /// the mode changes bringing `root` and everything below it in line with `policy`;
+    /// symbolic links are left alone
+    pub fn new(root: &Path, policy: &PermissionPolicy) -> io::Result<Self> {
+        let mut paths: Vec<(PathBuf, fs::Metadata)> =
+            vec![(root.to_path_buf(), fs::symlink_metadata(root)?)];
+        let entries: Vec<WalkEntry> = walk_entries(root)?;
+        paths.extend(entries.into_iter().map(|e| (e.path, e.metadata)));
+
+        let mut changes: Vec<(PathBuf, u32, u32)> = vec![];
+        for (path, metadata) in paths {
+            let new_mode: u32 = if metadata.is_dir() {
+                policy.dir_mode
+            } else if metadata.is_file() && is_executable(&path)? {
+                policy.exec_mode
+            } else if metadata.is_file() {
+                policy.file_mode
+            } else {
+                continue;
+            };
+            let old_mode: u32 = get_mode(&metadata) & MODE_MASK;
+            if old_mode != new_mode {
+                changes.push((path, old_mode, new_mode));
+            }
+        }
+        Ok(Self { changes })
+    }
+
+    /// the files and directories to change
+    pub fn file_count(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// print the changes, or that there are none; return whether there are any
+    pub fn preview(&self) -> bool {
+        if self.changes.is_empty() {
+            println!("{}", tr(Message::PermissionsFollowPolicy, &[]));
+            return false;
+        }
+        self.print_table();
+        true
+    }
+
+    /// confirm and change the modes previewed, the only step changing any;
+    /// return whether anything was changed
+    pub fn apply(&self) -> bool {
+        if !wait_accepting_prompt(&tr(Message::AcceptPermissionChanges, &[])) {
+            println!("{}", tr(Message::NothingDone, &[]));
+            return false;
+        }
+        // children first, so that a stricter directory mode cannot lock them out
+        self.changes.iter().rev().for_each(|(path, _, mode)| {
+            if let Err(err) = set_mode(path, *mode) {
+                eprintln!("{}", tr(Message::FailedToChange, &[&path.display(), &err]));
+            }
+        });
+        true
+    }
+}
+
+impl Report for PermissionPlan {
+    type Record = ModeRecord;
+
+    fn records(&self) -> Vec<ModeRecord> {
+        self.changes
+            .iter()
+            .map(|(p, old, new)| ModeRecord {
+                path: p.display().to_string(),
+                old: format!("{old:04o}"),
+                new: format!("{new:04o}"),
+            })
+            .collect()
+    }
+
+    /// print the `path old new` table of modes
+    fn print_table(&self) {
+        let mut table: Table = Table::new(&["path", "old", "new"]).color(2, Color::Green);
+        self.changes.iter().for_each(|(p, old, new)| {
+            table.add_row(vec![
+                p.display().to_string(),
+                format!("{old:04o}"),
+                format!("{new:04o}"),
+            ])
+        });
+        table.print();
+    }
+}
+
+fn is_executable(path: &Path) -> io::Result<bool> {
+    let mut head: Vec<u8> = Vec::with_capacity(4);
+    File::open(path)?.take(4).read_to_end(&mut head)?;
+    Ok(EXECUTABLE_MAGICS.iter().any(|m| head.starts_with(m)))
+}
+
+#[cfg(unix)]
+fn get_mode(metadata: &fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode()
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+}
+
+// Windows has no mode bits; only the read-only flag maps onto them
+#[cfg(not(unix))]
+fn get_mode(metadata: &fs::Metadata) -> u32 {
+    if metadata.permissions().readonly() {
+        0o555
+    } else {
+        0o755
+    }
+}
+
+#[cfg(not(unix))]
+fn set_mode(path: &Path, mode: u32) -> io::Result<()> {
+    let mut permissions: fs::Permissions = fs::metadata(path)?.permissions();
+    permissions.set_readonly(mode & 0o200 == 0);
+    fs::set_permissions(path, permissions)
+}
@@ -0,0 +1,74 @@
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::{
+    clock::get_now_arg,
+    i18n::get_lang_arg,
+    output::get_output_arg,
+    pipe::get_pipe_arg,
+    prompt::get_yes_arg,
+    sort::get_sort_arg,
+    walk::{get_content_args, get_no_ignore_arg, get_recursion_args, get_size_args, get_type_arg},
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "image_dups";
+
+const SHORT_DESCRIPTION: &str =
+    "Group visually identical images, such as bursts and re-saved copies,\nby their perceptual hash.";
+
+const KEEP_HELP_MESSAGE: &str = r#"Which image of a group to keep:
+  largest:  the most pixels, then the largest file
+  earliest: the oldest modification time"#;
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_yes_arg())
+        .arg(get_output_arg())
+        .arg(get_pipe_arg())
+        .arg(
+            Arg::new("threshold")
+                .short('t')
+                .long("threshold")
+                .help("The maximum number of differing bits (out of 64)\nbetween the hashes of images in a group")
+                .value_parser(value_parser!(u32).range(0..=64))
+                .default_value("4"),
+        )
+        .arg(
+            Arg::new("keep")
+                .long("keep")
+                .help(KEEP_HELP_MESSAGE)
+                .value_parser(["largest", "earliest"])
+                .default_value("largest"),
+        )
+        .arg(
+            Arg::new("move_to")
+                .long("move-to")
+                .help("Move every image but the kept one of each group to this directory\nfor review")
+                .value_parser(value_parser!(PathBuf))
+                .required(false),
+        )
+        .arg(
+            Arg::new("recursive")
+                .short('r')
+                .long("recursive")
+                .help("Include the images in subdirectories")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(get_no_ignore_arg())
+        .args(get_recursion_args())
+        .args(get_size_args())
+        .arg(get_sort_arg())
+        .args(get_content_args())
+        .arg(get_type_arg())
+        .arg(
+            Arg::new("dir")
+                .help("The directory holding the images")
+                .value_parser(value_parser!(PathBuf))
+                .required(true),
+        )
+}
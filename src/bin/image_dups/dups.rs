@@ -0,0 +1,249 @@
+use image::{imageops::FilterType, DynamicImage, GrayImage};
+use ray_commands::{
+    i18n::{tr, Message},
+    output::Report,
+    paths::get_free_path,
+    pipe::{PipeRecord, PipeStatus},
+    preview::print_renaming_table,
+    prompt::wait_accepting_prompt,
+    size::format_size,
+    walk::{list_files, walk_files, WalkEntry},
+};
+use serde::Serialize;
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+const IMAGE_EXTENSIONS: [&str; 9] = [
+    "bmp", "gif", "jfif", "jpeg", "jpg", "png", "tif", "tiff", "webp",
+];
+
+#[derive(Clone, Copy)]
+pub enum KeepPolicy {
+    Largest,
+    Earliest,
+}
+
+impl KeepPolicy {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "largest" => Self::Largest,
+            "earliest" => Self::Earliest,
+            _ => panic!("Unknown keep policy: {name}"),
+        }
+    }
+}
+
+struct ImageInfo {
+    path: PathBuf,
+    size: u64,
+    mtime: SystemTime,
+    width: u32,
+    height: u32,
+    /// 64-bit difference hash
+    hash: u64,
+}
+
+#[derive(Serialize)]
+pub struct ImageRecord {
+    /// the number of the group, from 1
+    group: usize,
+    /// "keep" for the kept image of the group, "duplicate" for the others
+    role: &'static str,
+    path: String,
+    width: u32,
+    height: u32,
+    size: u64,
+}
+
+pub struct ImageGroups {
+    /// the kept image comes first in each group
+    groups: Vec<Vec<ImageInfo>>,
+}
+
+impl ImageGroups {
+    pub fn scan(dir: &Path, recursive: bool, threshold: u32, keep: KeepPolicy) -> io::Result<Self> {
+        let files: Vec<WalkEntry> = if recursive {
+            walk_files(dir)?
+        } else {
+            list_files(dir)?
+        };
+
+        let mut images: Vec<ImageInfo> = Vec::new();
+        for file in files
+            .into_iter()
+            .filter(|f| f.metadata.is_file() && is_image(&f.path))
+        {
+            match image::open(&file.path) {
+                Ok(img) => images.push(ImageInfo {
+                    size: file.metadata.len(),
+                    mtime: file.metadata.modified()?,
+                    width: img.width(),
+                    height: img.height(),
+                    hash: get_difference_hash(&img),
+                    path: file.path,
+                }),
+                Err(err) => eprintln!("{}", tr(Message::Skipping, &[&file.path.display(), &err])),
+            }
+        }
+
+        let mut groups: Vec<Vec<ImageInfo>> = Self::group_similar(images, threshold);
+        groups.iter_mut().for_each(|g| match keep {
+            KeepPolicy::Largest => {
+                g.sort_by_key(|i| std::cmp::Reverse((i.width as u64 * i.height as u64, i.size)))
+            }
+            KeepPolicy::Earliest => g.sort_by_key(|i| i.mtime),
+        });
+        Ok(Self { groups })
+    }
+
+    pub fn print_groups(&self) {
+        if self.groups.is_empty() {
+            println!("{}", tr(Message::NoSimilarImages, &[]));
+            return;
+        }
+        for (n, group) in self.groups.iter().enumerate() {
+            println!("{}", tr(Message::SimilarGroup, &[&(n + 1)]));
+            for (i, image) in group.iter().enumerate() {
+                println!(
+                    "  {} {} ({}x{}, {})",
+                    match i {
+                        0 => tr(Message::KeptImage, &[]),
+                        _ => tr(Message::DuplicateImage, &[]),
+                    },
+                    image.path.display(),
+                    image.width,
+                    image.height,
+                    format_size(image.size)
+                );
+            }
+        }
+    }
+
+    pub fn move_duplicates(&self, review_dir: &Path, to_print_prompt: bool) {
+        let mut taken: HashSet<PathBuf> = HashSet::new();
+        let moves: Vec<(PathBuf, PathBuf)> = self
+            .groups
+            .iter()
+            .flat_map(|g| g.iter().skip(1))
+            .map(|i| {
+                let target: PathBuf = review_dir.join(i.path.file_name().unwrap());
+                (i.path.clone(), get_free_path(&target, &mut taken))
+            })
+            .collect();
+        if moves.is_empty() {
+            return;
+        }
+
+        let operations: Vec<(String, String)> = moves
+            .iter()
+            .map(|(o, n)| (o.display().to_string(), n.display().to_string()))
+            .collect();
+        print_renaming_table(&operations);
+        if to_print_prompt {
+            let to_move: bool = wait_accepting_prompt(&tr(Message::AcceptMovingDuplicates, &[]));
+            if !to_move {
+                println!("{}", tr(Message::NothingDone, &[]));
+                return;
+            }
+        }
+
+        fs::create_dir_all(review_dir).unwrap();
+        moves
+            .iter()
+            .for_each(|(source, target)| fs::rename(source, target).unwrap())
+    }
+
+    /// the records of the duplicates for `--pipe`, each with the kept image it
+    /// is similar to, leaving the kept images out of the pipeline
+    pub fn pipe_records(&self) -> Vec<PipeRecord> {
+        self.groups
+            .iter()
+            .flat_map(|group| {
+                let kept: &Path = &group[0].path;
+                group.iter().skip(1).map(move |image| {
+                    PipeRecord::new(&image.path, PipeStatus::Listed)
+                        .with_reason(tr(Message::SimilarTo, &[&kept.display()]))
+                })
+            })
+            .collect()
+    }
+
+    /// single-linkage clustering: an image joins a group when it is
+    /// within `threshold` bits of any image already in it
+    fn group_similar(images: Vec<ImageInfo>, threshold: u32) -> Vec<Vec<ImageInfo>> {
+        let mut parent: Vec<usize> = (0..images.len()).collect();
+        fn find(parent: &mut [usize], i: usize) -> usize {
+            let mut root: usize = i;
+            while parent[root] != root {
+                root = parent[root];
+            }
+            parent[i] = root;
+            root
+        }
+        for i in 0..images.len() {
+            for j in (i + 1)..images.len() {
+                if (images[i].hash ^ images[j].hash).count_ones() <= threshold {
+                    let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                    parent[a] = b;
+                }
+            }
+        }
+
+        let mut groups: Vec<Vec<ImageInfo>> = (0..images.len()).map(|_| Vec::new()).collect();
+        for (i, image) in images.into_iter().enumerate() {
+            let root: usize = find(&mut parent, i);
+            groups[root].push(image);
+        }
+        groups.into_iter().filter(|g| g.len() > 1).collect()
+    }
+}
+
+impl Report for ImageGroups {
+    type Record = ImageRecord;
+
+    fn records(&self) -> Vec<ImageRecord> {
+        self.groups
+            .iter()
+            .enumerate()
+            .flat_map(|(n, group)| {
+                group.iter().enumerate().map(move |(i, image)| ImageRecord {
+                    group: n + 1,
+                    role: if i == 0 { "keep" } else { "duplicate" },
+                    path: image.path.display().to_string(),
+                    width: image.width,
+                    height: image.height,
+                    size: image.size,
+                })
+            })
+            .collect()
+    }
+
+    fn print_table(&self) {
+        self.print_groups();
+    }
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.as_str()))
+}
+
+/// compare each pixel with its right neighbour in a 9x8 grayscale thumbnail
+fn get_difference_hash(img: &DynamicImage) -> u64 {
+    let thumbnail: GrayImage = img.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash: u64 = 0;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if thumbnail.get_pixel(x, y)[0] < thumbnail.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
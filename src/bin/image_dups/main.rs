@@ -0,0 +1,65 @@
+use dups::{ImageGroups, KeepPolicy};
+use ray_commands::{
+    clock::set_now,
+    i18n::{set_language, tr, Message},
+    output::{get_output_format, print_report, OutputFormat},
+    pipe::Pipe,
+    prompt::set_assume_yes,
+    sort::set_sort_order,
+    walk::{
+        set_content_filter, set_entry_types, set_ignore_rules, set_recursion_limits,
+        set_size_limits,
+    },
+};
+use std::{path::PathBuf, process};
+
+pub mod cli;
+mod dups;
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    set_assume_yes(&matches);
+    set_ignore_rules(&matches);
+    set_recursion_limits(&matches);
+    set_size_limits(&matches);
+    set_sort_order(&matches);
+    set_content_filter(&matches);
+    set_entry_types(&matches);
+    let dir: PathBuf = matches.get_one::<PathBuf>("dir").unwrap().clone();
+    let recursive: bool = matches.get_flag("recursive");
+    let threshold: u32 = *matches.get_one::<u32>("threshold").unwrap();
+    let keep: KeepPolicy = KeepPolicy::from(matches.get_one::<String>("keep").unwrap());
+    let move_to: Option<PathBuf> = matches.get_one::<PathBuf>("move_to").cloned();
+    let output: OutputFormat = get_output_format(&matches);
+
+    let groups: ImageGroups = match ImageGroups::scan(&dir, recursive, threshold, keep) {
+        Ok(groups) => groups,
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToRead, &[&dir.display(), &err]));
+            process::exit(1);
+        }
+    };
+    // reading no records, the pipe of a tool taking a directory cannot fail
+    if let Ok(Some(mut pipe)) = Pipe::from(&matches) {
+        if let Err(err) = pipe.write(&groups.pipe_records()) {
+            eprintln!("{}", tr(Message::FailedToWriteRecords, &[&err]));
+            process::exit(1);
+        }
+        return;
+    }
+    if !output.is_table() {
+        print_report(&groups, output);
+        return;
+    }
+    groups.print_groups();
+    if let Some(review_dir) = move_to {
+        groups.move_duplicates(&review_dir, true);
+    }
+}
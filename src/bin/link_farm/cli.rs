@@ -0,0 +1,64 @@
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::{
+    clock::get_now_arg,
+    i18n::get_lang_arg,
+    notify::get_notify_arg,
+    output::get_output_arg,
+    sort::get_sort_arg,
+    walk::{get_content_args, get_no_ignore_arg, get_recursion_args, get_size_args, get_type_arg},
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "link_farm";
+
+const SHORT_DESCRIPTION: &str =
+    "Build a directory of symbolic links organizing the files of SRC\nalong a different axis, without moving them.";
+
+const BY_HELP_MESSAGE: &str = r#"How to group the links:
+  date: DEST/<year>/<year-month-day> of the modification time
+  ext:  DEST/<lowercase extension>
+  size: DEST/<size range>"#;
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_output_arg())
+        .arg(get_notify_arg())
+        .arg(get_no_ignore_arg())
+        .args(get_recursion_args())
+        .args(get_size_args())
+        .arg(get_sort_arg())
+        .args(get_content_args())
+        .arg(get_type_arg())
+        .arg(
+            Arg::new("by")
+                .long("by")
+                .help(BY_HELP_MESSAGE)
+                .value_parser(["date", "ext", "size"])
+                .default_value("date"),
+        )
+        .arg(
+            Arg::new("dry_run")
+                .short('n')
+                .long("dry-run")
+                .help("Only print the links that would be created")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("source")
+                .help("The directory tree to organize")
+                .value_parser(value_parser!(PathBuf))
+                .required(true),
+        )
+        .arg(
+            Arg::new("destination")
+                .help("The directory to create the links in")
+                .value_parser(value_parser!(PathBuf))
+                .required(true),
+        )
+}
@@ -0,0 +1,208 @@
+use chrono::{DateTime, Local};
+use ray_commands::{
+    i18n::{tr, Message},
+    output::Report,
+    walk::{walk_files, WalkEntry},
+};
+use serde::Serialize;
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+const SIZE_BUCKETS: [(u64, &str); 5] = [
+    (1, "empty"),
+    (1 << 20, "under_1MiB"),
+    (10 << 20, "1MiB-10MiB"),
+    (100 << 20, "10MiB-100MiB"),
+    (1 << 30, "100MiB-1GiB"),
+];
+const LARGEST_SIZE_BUCKET: &str = "over_1GiB";
+const NO_EXTENSION_GROUP: &str = "no_extension";
+
+#[derive(Clone, Copy)]
+pub enum GroupBy {
+    Date,
+    Extension,
+    Size,
+}
+
+impl GroupBy {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "date" => Self::Date,
+            "ext" => Self::Extension,
+            "size" => Self::Size,
+            _ => panic!("Unknown grouping: {name}"),
+        }
+    }
+
+    /// the directory of the group of `entry`, failing when its modification
+    /// time, grouped by, cannot be read
+    fn get_group_dir(&self, entry: &WalkEntry) -> io::Result<PathBuf> {
+        Ok(match self {
+            Self::Date => {
+                let mtime: DateTime<Local> = entry.metadata.modified()?.into();
+                PathBuf::from(mtime.format("%Y").to_string())
+                    .join(mtime.format("%Y-%m-%d").to_string())
+            }
+            Self::Extension => PathBuf::from(
+                entry
+                    .path
+                    .extension()
+                    .map(|e| e.to_string_lossy().to_lowercase())
+                    .unwrap_or(String::from(NO_EXTENSION_GROUP)),
+            ),
+            Self::Size => {
+                let size: u64 = entry.metadata.len();
+                PathBuf::from(
+                    SIZE_BUCKETS
+                        .iter()
+                        .find(|(limit, _)| size < *limit)
+                        .map(|(_, name)| *name)
+                        .unwrap_or(LARGEST_SIZE_BUCKET),
+                )
+            }
+        })
+    }
+}
+
+struct Link {
+    /// the link to create
+    link: PathBuf,
+    /// absolute path of the file the link points to
+    target: PathBuf,
+}
+
+#[derive(Serialize)]
+pub struct LinkRecord {
+    link: String,
+    target: String,
+}
+
+pub struct LinkFarm {
+    links: Vec<Link>,
+}
+
+impl LinkFarm {
+    pub fn plan(source: &Path, destination: &Path, group_by: GroupBy) -> io::Result<Self> {
+        let source: PathBuf = fs::canonicalize(source)?;
+        // skip the farm itself when it is built inside the source tree
+        let farm_root: Option<PathBuf> = fs::canonicalize(destination).ok();
+
+        let mut taken: HashSet<PathBuf> = HashSet::new();
+        let mut links: Vec<Link> = Vec::new();
+        for entry in walk_files(&source)? {
+            if farm_root
+                .as_ref()
+                .is_some_and(|r| entry.path.starts_with(r))
+            {
+                continue;
+            }
+            if !entry.metadata.is_file() {
+                continue;
+            }
+            let link: io::Result<PathBuf> = group_by.get_group_dir(&entry).and_then(|dir| {
+                Self::get_free_link_path(&destination.join(dir), &entry.path, &mut taken)
+            });
+            let link: PathBuf = match link {
+                Ok(link) => link,
+                Err(err) => {
+                    eprintln!("{}", tr(Message::Skipping, &[&entry.path.display(), &err]));
+                    continue;
+                }
+            };
+            links.push(Link {
+                link,
+                target: entry.path,
+            });
+        }
+        Ok(Self { links })
+    }
+
+    pub fn print_links(&self) {
+        self.links
+            .iter()
+            .for_each(|l| println!("{} -> {}", l.link.display(), l.target.display()));
+    }
+
+    /// create the links not already in place; return the links in place
+    pub fn create_links(&self) -> io::Result<usize> {
+        for l in &self.links {
+            // keep links from a previous run of the same farm
+            if fs::read_link(&l.link).is_ok_and(|t| t == l.target) {
+                continue;
+            }
+            if let Some(dir) = l.link.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            symlink(&l.target, &l.link)?;
+        }
+        Ok(self.links.len())
+    }
+
+    /// `group_dir/<file name>`, or `group_dir/<stem>_<n>.<ext>` when the name is taken
+    /// by another file of this plan or by something other than a link to `target`;
+    /// failing for a target without a file name
+    fn get_free_link_path(
+        group_dir: &Path,
+        target: &Path,
+        taken: &mut HashSet<PathBuf>,
+    ) -> io::Result<PathBuf> {
+        let file_name: String = target
+            .file_name()
+            .ok_or_else(|| io::Error::other(tr(Message::NoFileName, &[&target.display()])))?
+            .to_string_lossy()
+            .into_owned();
+        let stem: String = target
+            .file_stem()
+            .map_or(file_name.clone(), |s| s.to_string_lossy().into_owned());
+        let ext: Option<String> = target.extension().map(|e| e.to_string_lossy().into_owned());
+        let mut counter: usize = 0;
+        loop {
+            let name: String = match (counter, &ext) {
+                (0, _) => file_name.clone(),
+                (n, Some(ext)) => format!("{stem}_{n}.{ext}"),
+                (n, None) => format!("{stem}_{n}"),
+            };
+            let link: PathBuf = group_dir.join(name);
+            let is_free: bool = match fs::symlink_metadata(&link) {
+                Err(_) => true,
+                Ok(_) => fs::read_link(&link).is_ok_and(|t| t == target),
+            };
+            if is_free && taken.insert(link.clone()) {
+                return Ok(link);
+            }
+            counter += 1;
+        }
+    }
+}
+
+impl Report for LinkFarm {
+    type Record = LinkRecord;
+
+    fn records(&self) -> Vec<LinkRecord> {
+        self.links
+            .iter()
+            .map(|l| LinkRecord {
+                link: l.link.display().to_string(),
+                target: l.target.display().to_string(),
+            })
+            .collect()
+    }
+
+    fn print_table(&self) {
+        self.print_links();
+    }
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(target, link)
+}
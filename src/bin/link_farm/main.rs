@@ -0,0 +1,70 @@
+use cli::PROGRAM_NAME;
+use farm::{GroupBy, LinkFarm};
+use ray_commands::{
+    clock::set_now,
+    i18n::{set_language, tr, Message},
+    notify::{notify_finished, set_notify},
+    output::{get_output_format, print_report, OutputFormat},
+    sort::set_sort_order,
+    walk::{
+        set_content_filter, set_entry_types, set_ignore_rules, set_recursion_limits,
+        set_size_limits,
+    },
+};
+use std::{path::PathBuf, process};
+
+pub mod cli;
+mod farm;
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    set_notify(&matches);
+    set_ignore_rules(&matches);
+    set_recursion_limits(&matches);
+    set_size_limits(&matches);
+    set_sort_order(&matches);
+    set_content_filter(&matches);
+    set_entry_types(&matches);
+    let source: PathBuf = matches.get_one::<PathBuf>("source").unwrap().clone();
+    let destination: PathBuf = matches.get_one::<PathBuf>("destination").unwrap().clone();
+    let group_by: GroupBy = GroupBy::from(matches.get_one::<String>("by").unwrap().as_str());
+    let dry_run: bool = matches.get_flag("dry_run");
+    let output: OutputFormat = get_output_format(&matches);
+
+    let farm: LinkFarm = match LinkFarm::plan(&source, &destination, group_by) {
+        Ok(farm) => farm,
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToRead, &[&source.display(), &err]));
+            process::exit(1);
+        }
+    };
+    if !output.is_table() {
+        print_report(&farm, output);
+        return;
+    }
+    farm.print_links();
+    if dry_run {
+        println!("{}", tr(Message::NothingDone, &[]));
+        return;
+    }
+    match farm.create_links() {
+        Ok(count) => {
+            let summary: String = tr(Message::LinksInPlace, &[&count]);
+            println!("{summary}");
+            notify_finished(PROGRAM_NAME, &summary, false);
+        }
+        Err(err) => {
+            let summary: String = tr(Message::FailedToCreateLinks, &[&err]);
+            eprintln!("{summary}");
+            notify_finished(PROGRAM_NAME, &summary, true);
+            process::exit(1);
+        }
+    }
+}
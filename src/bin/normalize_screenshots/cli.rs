@@ -0,0 +1,114 @@
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::conflict::{CONFLICT_HELP_MESSAGE, CONFLICT_STRATEGY_NAMES};
+use ray_commands::{
+    backup::get_backup_args,
+    clock::get_now_arg,
+    failure::get_on_error_arg,
+    guard::get_guard_args,
+    hardlinks::get_hardlinks_arg,
+    hooks::get_hook_args,
+    i18n::get_lang_arg,
+    jobs::get_jobs_arg,
+    journal::get_journal_in_dir_arg,
+    notify::get_notify_arg,
+    output::{get_output_arg, get_print0_arg},
+    pipe::{get_files_from_arg, get_pipe_args},
+    plan::{get_confirm_every_arg, get_explain_arg, get_resume_arg},
+    portable::get_portable_arg,
+    preset::{get_offset_arg, get_preset_arg},
+    prompt::get_yes_arg,
+    report::get_report_args,
+    retry::get_retry_args,
+    serve::get_serve_args,
+    sort::get_sort_arg,
+    stats::get_stats_arg,
+    time_format::{get_strict_arg, get_week_start_arg, TIME_TOKENS_HELP_MESSAGE},
+    verify::get_verify_arg,
+    walk::{get_content_args, get_size_args},
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "normalize_screenshots";
+
+const SHORT_DESCRIPTION: &str =
+    "Rename screenshots named by any OS or language convention\nto one consistent timestamp format.";
+
+const FORMAT_HELP_MESSAGE: &str = r#"The format of date and time following Rust chrono's format:
+https://docs.rs/chrono/latest/chrono/format/strftime/index.html
+"#;
+
+const DEFAULT_TIME_FORMAT: &str = "Screenshot_%Y-%m-%d_%H-%M-%S";
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_yes_arg())
+        .arg(get_output_arg())
+        .args(get_guard_args())
+        .args(get_retry_args())
+        .arg(get_stats_arg())
+        .arg(get_notify_arg())
+        .args(get_pipe_args())
+        .arg(get_print0_arg())
+        .arg(get_files_from_arg())
+        .args(get_serve_args())
+        .args(get_hook_args())
+        .arg(get_on_error_arg())
+        .arg(get_explain_arg())
+        .arg(get_verify_arg())
+        .arg(get_portable_arg())
+        .arg(get_confirm_every_arg())
+        .args(get_report_args())
+        .args(get_size_args())
+        .arg(get_sort_arg())
+        .arg(get_hardlinks_arg())
+        .args(get_content_args())
+        .arg(
+            Arg::new("format")
+                .short('f')
+                .help(format!("{FORMAT_HELP_MESSAGE}{TIME_TOKENS_HELP_MESSAGE}"))
+                .default_value(DEFAULT_TIME_FORMAT)
+                .required(false),
+        )
+        .arg(get_preset_arg())
+        .arg(get_offset_arg())
+        .arg(get_week_start_arg())
+        .arg(get_strict_arg())
+        .arg(
+            Arg::new("on_conflict")
+                .long("on-conflict")
+                .help(CONFLICT_HELP_MESSAGE)
+                .value_parser(CONFLICT_STRATEGY_NAMES)
+                .default_value("suffix"),
+        )
+        .args(get_backup_args())
+        .arg(
+            Arg::new("undo")
+                .long("undo")
+                .help("Undo the latest renaming done by this command")
+                .action(ArgAction::SetTrue)
+                .exclusive(true),
+        )
+        .arg(get_resume_arg())
+        .arg(get_jobs_arg())
+        .arg(get_journal_in_dir_arg())
+        .arg(
+            Arg::new("input_paths")
+                .help("The path(s) to the screenshot(s); other files are skipped")
+                .value_parser(value_parser!(PathBuf))
+                .required_unless_present_any([
+                    "undo",
+                    "resume",
+                    "pipe",
+                    "serve",
+                    "serve_socket",
+                    "files_from",
+                ])
+                .action(ArgAction::Append),
+        )
+}
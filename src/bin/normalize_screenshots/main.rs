@@ -0,0 +1,140 @@
+use ray_commands::{
+    backup::set_backup_policy,
+    clock::set_now,
+    conflict::ConflictStrategy,
+    failure::FailurePolicy,
+    guard::{check_paths, set_guard},
+    hardlinks::set_hard_link_policy,
+    hooks::Hooks,
+    i18n::{set_language, tr, Message},
+    jobs::set_jobs,
+    journal::set_sidecar_journal,
+    notify::set_notify,
+    output::{get_output_format, print_report, set_print0, OutputFormat},
+    pipe::{get_input_paths, Pipe},
+    plan::{get_confirm_every, resume_latest, RenamePlan},
+    portable::set_portable,
+    preset::get_time_format,
+    prompt::set_assume_yes,
+    report::Reports,
+    restore::undo_latest,
+    retry::set_retry_policy,
+    serve::{is_serving, serve},
+    sort::set_sort_order,
+    stats::set_stats_format,
+    time_format::TimeFormat,
+    walk::{filter_paths, set_content_filter, set_size_limits},
+};
+use screenshot_name::ScreenshotNameParser;
+use std::{path::PathBuf, process};
+
+pub mod cli;
+mod screenshot_name;
+
+const PROGRAM_NAME: &str = "normalize_screenshots";
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    set_assume_yes(&matches);
+    set_print0(&matches);
+    set_guard(&matches);
+    set_retry_policy(&matches);
+    set_portable(&matches);
+    set_jobs(&matches);
+    set_sidecar_journal(&matches);
+    set_backup_policy(&matches);
+    set_stats_format(&matches);
+    set_notify(&matches);
+    set_size_limits(&matches);
+    set_sort_order(&matches);
+    set_hard_link_policy(&matches);
+    set_content_filter(&matches);
+    if matches.get_flag("undo") {
+        if let Err(err) = undo_latest(PROGRAM_NAME) {
+            eprintln!("{}", tr(Message::FailedToUndo, &[&err]));
+            process::exit(err.exit_code());
+        }
+        return;
+    }
+    if matches.get_flag("resume") {
+        if let Err(err) = resume_latest(PROGRAM_NAME) {
+            eprintln!("{}", tr(Message::FailedToResume, &[&err]));
+            process::exit(err.exit_code());
+        }
+        return;
+    }
+    let time_format: TimeFormat = match get_time_format(&matches) {
+        Ok(format) => format,
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(2);
+        }
+    };
+    let conflict: ConflictStrategy =
+        ConflictStrategy::from(matches.get_one::<String>("on_conflict").unwrap());
+    let output: OutputFormat = get_output_format(&matches);
+    let hooks: Hooks = Hooks::from(&matches);
+    let failure: FailurePolicy =
+        FailurePolicy::from(matches.get_one::<String>("on_error").unwrap());
+
+    let parser: ScreenshotNameParser = ScreenshotNameParser::new();
+    // the plan of a batch of paths, those given or those of each request by --serve
+    let make_plan = |input_paths: &[PathBuf]| -> RenamePlan {
+        parser
+            .plan_renames(input_paths, &time_format, conflict)
+            .with_hooks(hooks.clone())
+            .with_failure_policy(failure)
+            .with_explanations(matches.get_flag("explain"))
+            .with_verification(matches.get_flag("verify"))
+            .with_confirm_every(get_confirm_every(&matches))
+            .with_reports(Reports::from(&matches))
+    };
+    if is_serving(&matches) {
+        if let Err(err) = serve(PROGRAM_NAME, &matches, make_plan) {
+            eprintln!("{err}");
+            process::exit(err.exit_code());
+        }
+        return;
+    }
+    let mut pipe: Option<Pipe> = match Pipe::from(&matches) {
+        Ok(pipe) => pipe,
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToReadRecords, &[&err]));
+            process::exit(2);
+        }
+    };
+    let input_paths: Vec<PathBuf> = match get_input_paths(&matches, pipe.as_ref()) {
+        Ok(paths) => filter_paths(paths),
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToReadPaths, &[&err]));
+            process::exit(2);
+        }
+    };
+    if let Err(err) = check_paths(&input_paths) {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
+    let plan: RenamePlan = make_plan(&input_paths);
+    if let Some(pipe) = &mut pipe {
+        if let Err(err) = plan.apply_piped(PROGRAM_NAME, pipe) {
+            eprintln!("{err}");
+            process::exit(err.exit_code());
+        }
+        return;
+    }
+    if !output.is_table() {
+        print_report(&plan, output);
+        return;
+    }
+    if let Err(err) = plan.apply(PROGRAM_NAME, true) {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
+}
@@ -0,0 +1,163 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use ray_commands::{
+    conflict::ConflictStrategy,
+    i18n::{tr, Message},
+    paths::get_free_path,
+    plan::RenamePlan,
+    time_format::TimeFormat,
+};
+use regex::{Captures, Regex};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// the words screenshot tools of different OSes and languages start names with,
+/// e.g. "Screenshot 2024-05-01 at 10.00.00", "Screen Shot 2020-01-01 at 9.05.03 PM",
+/// "Screenshot from 2024-05-01 10-00-00", "Screenshot_20240501-100000",
+/// "Capture d’écran 2024-05-01 à 10.00.00" and "截屏2024-05-01 下午3.05.03"
+const PREFIX_PATTERN: &str = r"(?i)^(?:screen ?shot|capture d['’]écran|bildschirmfoto|captura de pantalla|schermata|schermafbeelding|skärmavbild|zrzut ekranu|снимок экрана|スクリーンショット|屏幕截图|截屏|截图|螢幕截圖|截圖|스크린샷)";
+
+/// the date and time following the prefix, with separators optional and
+/// the 12-hour marker either before (CJK) or after (English) the time
+const TIMESTAMP_PATTERN: &str = r"^\D*?(?P<year>\d{4})-?(?P<month>\d{2})-?(?P<day>\d{2})\D*?(?P<meridiem_before>上午|下午|午前|午後|오전|오후)?\s*(?P<hour>\d{1,2})[.:\-]?(?P<minute>\d{2})[.:\-]?(?P<second>\d{2})(?:\s*(?P<meridiem_after>[AaPp]\.?\s?[Mm]\.?))?";
+
+const PM_MARKERS: [&str; 3] = ["下午", "午後", "오후"];
+
+pub struct ScreenshotNameParser {
+    prefix: Regex,
+    timestamp: Regex,
+}
+
+impl ScreenshotNameParser {
+    pub fn new() -> Self {
+        Self {
+            prefix: Regex::new(PREFIX_PATTERN).unwrap(),
+            timestamp: Regex::new(TIMESTAMP_PATTERN).unwrap(),
+        }
+    }
+
+    /// the plan of the screenshots among `input_paths`; taken names are numbered
+    /// here by `suffix`, the default, and else left to the plan to resolve by
+    /// `conflict`
+    pub fn plan_renames(
+        &self,
+        input_paths: &[PathBuf],
+        time_format: &TimeFormat,
+        conflict: ConflictStrategy,
+    ) -> RenamePlan {
+        let mut plan: RenamePlan = RenamePlan::with_conflict(conflict);
+        let mut taken: HashSet<PathBuf> = HashSet::new();
+        for path in input_paths {
+            let target: Result<PathBuf, String> = self
+                .get_new_name(path, time_format)
+                .map(|(name, source)| {
+                    plan.explain(path, source);
+                    path.with_file_name(name)
+                })
+                .map(|target| {
+                    if target == *path || is_numbered_variant(path, &target) {
+                        return path.clone();
+                    }
+                    if conflict != ConflictStrategy::Suffix {
+                        return target;
+                    }
+                    let free: PathBuf = get_free_path(&target, &mut taken);
+                    if free != target {
+                        plan.explain(path, tr(Message::ExplainNumberedSameTime, &[]));
+                    }
+                    free
+                });
+            plan.add(path.clone(), target);
+        }
+        plan
+    }
+
+    /// the normalized file name of the screenshot, keeping its extension,
+    /// and where its date and time came from
+    fn get_new_name(
+        &self,
+        path: &Path,
+        time_format: &TimeFormat,
+    ) -> Result<(String, String), String> {
+        let stem: String = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .ok_or_else(|| tr(Message::NotAFile, &[]))?;
+        let rest: &str = match self.prefix.find(&stem) {
+            Some(m) => &stem[m.end()..],
+            None => return Err(tr(Message::NotScreenshotName, &[])),
+        };
+        let (time, source): (NaiveDateTime, String) = match self.parse_timestamp(rest) {
+            Some(time) => (time, tr(Message::ExplainTimeFromName, &[])),
+            None => {
+                let modified: DateTime<Local> = get_mod_time(path)?;
+                let offset: String = modified.format("%:z").to_string();
+                let source: String = tr(Message::ExplainNoTimeInName, &[&offset]);
+                (modified.naive_local(), source)
+            }
+        };
+
+        // a time skipped by a change to daylight saving time is named as it reads
+        let new_stem: String = match time.and_local_timezone(Local).earliest() {
+            Some(local_time) => time_format.format(&local_time),
+            None => time_format.format(&time.and_utc()),
+        }
+        .map_err(|e| e.to_string())?;
+        let name: String = match path.extension() {
+            Some(ext) => format!("{new_stem}.{}", ext.to_string_lossy()),
+            None => new_stem,
+        };
+        Ok((name, source))
+    }
+
+    /// the local date and time written in the name, if any and valid
+    fn parse_timestamp(&self, text: &str) -> Option<NaiveDateTime> {
+        let caps: Captures = self.timestamp.captures(text)?;
+        let number = |key: &str| caps[key].parse::<u32>().ok();
+        let date: NaiveDate =
+            NaiveDate::from_ymd_opt(number("year")? as i32, number("month")?, number("day")?)?;
+
+        let mut hour: u32 = number("hour")?;
+        let meridiem: Option<&str> = caps
+            .name("meridiem_before")
+            .or(caps.name("meridiem_after"))
+            .map(|m| m.as_str());
+        if let Some(meridiem) = meridiem {
+            let is_pm: bool =
+                PM_MARKERS.contains(&meridiem) || meridiem.to_lowercase().starts_with('p');
+            hour = match (hour, is_pm) {
+                (12, false) => 0,
+                (1..=11, true) => hour + 12,
+                (0..=12, _) => hour,
+                _ => return None,
+            };
+        }
+        let time: NaiveTime = NaiveTime::from_hms_opt(hour, number("minute")?, number("second")?)?;
+        Some(date.and_time(time))
+    }
+}
+
+fn get_mod_time(path: &Path) -> Result<DateTime<Local>, String> {
+    let modified: SystemTime = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map_err(|e| e.to_string())?;
+    Ok(modified.into())
+}
+
+/// whether `path` is already `target` disambiguated as `<stem>_<n>.<ext>`,
+/// so that normalizing twice leaves the numbered names alone
+fn is_numbered_variant(path: &Path, target: &Path) -> bool {
+    let (Some(stem), Some(target_stem)) = (path.file_stem(), target.file_stem()) else {
+        return false;
+    };
+    let (stem, target_stem) = (stem.to_string_lossy(), target_stem.to_string_lossy());
+    path.parent() == target.parent()
+        && path.extension() == target.extension()
+        && stem
+            .strip_prefix(target_stem.as_ref())
+            .and_then(|s| s.strip_prefix('_'))
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
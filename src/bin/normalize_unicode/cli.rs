@@ -0,0 +1,103 @@
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::conflict::{CONFLICT_HELP_MESSAGE, CONFLICT_STRATEGY_NAMES};
+use ray_commands::{
+    backup::get_backup_args,
+    clock::get_now_arg,
+    failure::get_on_error_arg,
+    guard::get_guard_args,
+    hooks::get_hook_args,
+    i18n::get_lang_arg,
+    jobs::get_jobs_arg,
+    journal::get_journal_in_dir_arg,
+    notify::get_notify_arg,
+    output::{get_output_arg, get_print0_arg},
+    pipe::get_pipe_args,
+    plan::{get_confirm_every_arg, get_explain_arg, get_resume_arg},
+    portable::get_portable_arg,
+    prompt::get_yes_arg,
+    report::get_report_args,
+    retry::get_retry_args,
+    sort::get_sort_arg,
+    stats::get_stats_arg,
+    verify::get_verify_arg,
+    walk::{get_content_args, get_no_ignore_arg, get_recursion_args, get_size_args, get_type_arg},
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "normalize_unicode";
+
+const SHORT_DESCRIPTION: &str =
+    "Rename files and directories to one Unicode normalization form, so that\ntrees moved between macOS and Linux stop having the same name twice.";
+
+const FORM_HELP_MESSAGE: &str = r#"The normalization form of the new names:
+  nfc: composed, e.g. "é" as one code point (Linux, Windows)
+  nfd: decomposed, e.g. "e" followed by a combining accent (older macOS)"#;
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_yes_arg())
+        .arg(get_output_arg())
+        .args(get_guard_args())
+        .args(get_retry_args())
+        .arg(get_stats_arg())
+        .arg(get_notify_arg())
+        .args(get_pipe_args())
+        .arg(get_print0_arg())
+        .args(get_hook_args())
+        .arg(get_on_error_arg())
+        .arg(get_explain_arg())
+        .arg(get_verify_arg())
+        .arg(get_portable_arg())
+        .arg(get_confirm_every_arg())
+        .args(get_report_args())
+        .arg(
+            Arg::new("form")
+                .long("form")
+                .help(FORM_HELP_MESSAGE)
+                .value_parser(["nfc", "nfd"])
+                .default_value("nfc"),
+        )
+        .arg(
+            Arg::new("on_conflict")
+                .long("on-conflict")
+                .help(CONFLICT_HELP_MESSAGE)
+                .value_parser(CONFLICT_STRATEGY_NAMES)
+                .default_value("skip"),
+        )
+        .args(get_backup_args())
+        .arg(
+            Arg::new("recursive")
+                .short('r')
+                .long("recursive")
+                .help("Include the files and directories in subdirectories")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(get_no_ignore_arg())
+        .args(get_recursion_args())
+        .args(get_size_args())
+        .arg(get_sort_arg())
+        .args(get_content_args())
+        .arg(get_type_arg())
+        .arg(
+            Arg::new("undo")
+                .long("undo")
+                .help("Undo the latest renaming done by this command")
+                .action(ArgAction::SetTrue)
+                .exclusive(true),
+        )
+        .arg(get_resume_arg())
+        .arg(get_jobs_arg())
+        .arg(get_journal_in_dir_arg())
+        .arg(
+            Arg::new("dir")
+                .help("The directory whose entries to rename")
+                .value_parser(value_parser!(PathBuf))
+                .required_unless_present_any(["undo", "resume"]),
+        )
+}
@@ -0,0 +1,131 @@
+use normal_form::NormalForm;
+use ray_commands::{
+    backup::set_backup_policy,
+    clock::set_now,
+    conflict::ConflictStrategy,
+    failure::FailurePolicy,
+    guard::{check_paths, set_guard},
+    hooks::Hooks,
+    i18n::{set_language, tr, Message},
+    jobs::set_jobs,
+    journal::set_sidecar_journal,
+    notify::set_notify,
+    output::{get_output_format, print_report, set_print0, OutputFormat},
+    pipe::Pipe,
+    plan::{get_confirm_every, resume_latest, RenamePlan},
+    portable::set_portable,
+    prompt::set_assume_yes,
+    report::Reports,
+    restore::undo_latest,
+    retry::set_retry_policy,
+    sort::set_sort_order,
+    stats::set_stats_format,
+    walk::{
+        list_entries, set_content_filter, set_entry_types, set_ignore_rules, set_recursion_limits,
+        set_size_limits, walk_entries, WalkEntry,
+    },
+};
+use std::{path::PathBuf, process};
+
+pub mod cli;
+mod normal_form;
+
+const PROGRAM_NAME: &str = "normalize_unicode";
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    set_assume_yes(&matches);
+    set_print0(&matches);
+    set_guard(&matches);
+    set_retry_policy(&matches);
+    set_portable(&matches);
+    set_jobs(&matches);
+    set_sidecar_journal(&matches);
+    set_backup_policy(&matches);
+    set_stats_format(&matches);
+    set_notify(&matches);
+    set_ignore_rules(&matches);
+    set_recursion_limits(&matches);
+    set_size_limits(&matches);
+    set_sort_order(&matches);
+    set_content_filter(&matches);
+    set_entry_types(&matches);
+    if matches.get_flag("undo") {
+        if let Err(err) = undo_latest(PROGRAM_NAME) {
+            eprintln!("{}", tr(Message::FailedToUndo, &[&err]));
+            process::exit(err.exit_code());
+        }
+        return;
+    }
+    if matches.get_flag("resume") {
+        if let Err(err) = resume_latest(PROGRAM_NAME) {
+            eprintln!("{}", tr(Message::FailedToResume, &[&err]));
+            process::exit(err.exit_code());
+        }
+        return;
+    }
+    let dir: PathBuf = matches.get_one::<PathBuf>("dir").unwrap().clone();
+    if let Err(err) = check_paths(&[&dir]) {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
+    let recursive: bool = matches.get_flag("recursive");
+    let form: NormalForm = NormalForm::from(matches.get_one::<String>("form").unwrap());
+    let conflict: ConflictStrategy =
+        ConflictStrategy::from(matches.get_one::<String>("on_conflict").unwrap());
+    let output: OutputFormat = get_output_format(&matches);
+    let hooks: Hooks = Hooks::from(&matches);
+    let failure: FailurePolicy =
+        FailurePolicy::from(matches.get_one::<String>("on_error").unwrap());
+
+    let listed = if recursive {
+        walk_entries(&dir)
+    } else {
+        list_entries(&dir)
+    };
+    let entries: Vec<WalkEntry> = match listed {
+        Ok(entries) => entries,
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToRead, &[&dir.display(), &err]));
+            process::exit(1);
+        }
+    };
+
+    let plan: RenamePlan = form
+        .plan_renames(entries, conflict)
+        .with_hooks(hooks)
+        .with_failure_policy(failure)
+        .with_explanations(matches.get_flag("explain"))
+        .with_verification(matches.get_flag("verify"))
+        .with_confirm_every(get_confirm_every(&matches))
+        .with_reports(Reports::from(&matches));
+    let mut pipe: Option<Pipe> = match Pipe::from(&matches) {
+        Ok(pipe) => pipe,
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToReadRecords, &[&err]));
+            process::exit(2);
+        }
+    };
+    if let Some(pipe) = &mut pipe {
+        if let Err(err) = plan.apply_piped(PROGRAM_NAME, pipe) {
+            eprintln!("{err}");
+            process::exit(err.exit_code());
+        }
+        return;
+    }
+    if !output.is_table() {
+        print_report(&plan, output);
+        return;
+    }
+    if let Err(err) = plan.apply(PROGRAM_NAME, true) {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
+}
@@ -0,0 +1,65 @@
+use ray_commands::{
+    conflict::ConflictStrategy,
+    i18n::{tr, Message},
+    plan::RenamePlan,
+    walk::WalkEntry,
+};
+use std::path::PathBuf;
+use unicode_normalization::UnicodeNormalization;
+
+#[derive(Clone, Copy)]
+pub enum NormalForm {
+    Nfc,
+    Nfd,
+}
+
+impl NormalForm {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "nfc" => Self::Nfc,
+            "nfd" => Self::Nfd,
+            _ => panic!("Unknown normalization form: {name}"),
+        }
+    }
+
+    pub fn normalize(&self, text: &str) -> String {
+        match self {
+            Self::Nfc => text.nfc().collect(),
+            Self::Nfd => text.nfd().collect(),
+        }
+    }
+
+    /// how a name is changed to this form, for `--explain`
+    fn explain(&self) -> String {
+        match self {
+            Self::Nfc => tr(Message::ExplainComposed, &[]),
+            Self::Nfd => tr(Message::ExplainDecomposed, &[]),
+        }
+    }
+
+    /// plan renaming the entries whose names are not in this form, deepest first
+    /// so that each rename happens before the directory holding it is renamed
+    pub fn plan_renames(
+        &self,
+        mut entries: Vec<WalkEntry>,
+        conflict: ConflictStrategy,
+    ) -> RenamePlan {
+        entries.sort_by_key(|e| std::cmp::Reverse(e.relative.components().count()));
+        let mut plan: RenamePlan = RenamePlan::with_conflict(conflict);
+        for entry in entries {
+            let target: Result<PathBuf, String> =
+                match entry.path.file_name().and_then(|n| n.to_str()) {
+                    Some(name) => {
+                        let normalized: String = self.normalize(name);
+                        if normalized != name {
+                            plan.explain(&entry.path, self.explain());
+                        }
+                        Ok(entry.path.with_file_name(normalized))
+                    }
+                    None => Err(tr(Message::NameNotUtf8, &[])),
+                };
+            plan.add(entry.path, target);
+        }
+        plan
+    }
+}
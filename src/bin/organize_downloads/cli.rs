@@ -0,0 +1,130 @@
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::conflict::{CONFLICT_HELP_MESSAGE, CONFLICT_STRATEGY_NAMES};
+use ray_commands::{
+    backup::get_backup_args,
+    clock::get_now_arg,
+    failure::get_on_error_arg,
+    guard::get_guard_args,
+    hooks::get_hook_args,
+    i18n::get_lang_arg,
+    jobs::get_jobs_arg,
+    journal::get_journal_in_dir_arg,
+    notify::get_notify_arg,
+    output::{get_output_arg, get_print0_arg},
+    pipe::get_pipe_args,
+    plan::{get_confirm_every_arg, get_explain_arg, get_resume_arg},
+    portable::get_portable_arg,
+    prompt::get_yes_arg,
+    report::get_report_args,
+    retry::get_retry_args,
+    stats::get_stats_arg,
+    verify::get_verify_arg,
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "organize_downloads";
+
+const SHORT_DESCRIPTION: &str =
+    "Sort the files of a Downloads directory into destinations by extension\nor name pattern, once or continuously.";
+
+const RULES_HELP_MESSAGE: &str = r#"A TOML file of rules, or a YAML one ending in .yaml, the first
+matching rule applying, e.g.
+  [[rule]]
+  glob = "*.tmp"
+  skip = true
+  [[rule]]
+  pattern = "^Invoice"
+  destination = "~/Documents/Invoices"
+  [[rule]]
+  mime = "image/*"
+  min_age = "7d"
+  destination = "Pictures"
+  template = "{year}-{month}-{day} {name|slug}.{ext|lower}"
+A rule matches the files meeting all of its conditions: extensions,
+pattern (a regular expression), glob, mime, min_size, max_size, min_age
+and max_age; it moves them to its destination, inside the organized
+directory unless starting with ~/, renames them by its template, with
+{name} {ext} {year} {month} {day}, {parent} (the name of its directory)
+or {parent:N} (its Nth word), {line1} (the first line of a text file),
+{head:N} (its first N characters), {location} (the city a photo was
+taken in), {country}, {camera} (the camera it was taken with) or {lens},
+or skips them.
+Without it, files go to Documents, Pictures, Music, Videos,
+Archives and Installers by extension."#;
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_yes_arg())
+        .arg(get_output_arg())
+        .args(get_guard_args())
+        .args(get_retry_args())
+        .arg(get_stats_arg())
+        .arg(get_notify_arg())
+        .args(get_pipe_args())
+        .arg(get_print0_arg())
+        .args(get_hook_args())
+        .arg(get_on_error_arg())
+        .arg(get_explain_arg())
+        .arg(get_verify_arg())
+        .arg(get_portable_arg())
+        .arg(get_confirm_every_arg())
+        .args(get_report_args())
+        .arg(
+            Arg::new("rules")
+                .long("rules")
+                .help(RULES_HELP_MESSAGE)
+                .value_parser(value_parser!(PathBuf)),
+        )
+        .arg(
+            Arg::new("on_conflict")
+                .long("on-conflict")
+                .help(CONFLICT_HELP_MESSAGE)
+                .value_parser(CONFLICT_STRATEGY_NAMES)
+                .default_value("suffix"),
+        )
+        .args(get_backup_args())
+        .arg(
+            Arg::new("dry_run")
+                .short('n')
+                .long("dry-run")
+                .help("Only print the moves that would be made")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("watch"),
+        )
+        .arg(
+            Arg::new("watch")
+                .short('w')
+                .long("watch")
+                .help("Keep running, moving new files without asking once their download settled")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("interval")
+                .long("interval")
+                .help("The seconds between two checks in watch mode")
+                .value_parser(value_parser!(u64).range(1..))
+                .default_value("5"),
+        )
+        .arg(
+            Arg::new("undo")
+                .long("undo")
+                .help("Undo the latest moves done by this command")
+                .action(ArgAction::SetTrue)
+                .exclusive(true),
+        )
+        .arg(get_resume_arg())
+        .arg(get_jobs_arg())
+        .arg(get_journal_in_dir_arg())
+        .arg(
+            Arg::new("dir")
+                .help("The directory to organize")
+                .value_parser(value_parser!(PathBuf))
+                .required_unless_present_any(["undo", "resume"]),
+        )
+}
@@ -0,0 +1,158 @@
+use organize::plan_moves;
+use ray_commands::{
+    backup::set_backup_policy,
+    clock::set_now,
+    conflict::ConflictStrategy,
+    failure::FailurePolicy,
+    guard::{check_paths, set_guard},
+    hooks::Hooks,
+    i18n::{set_language, tr, Message},
+    jobs::set_jobs,
+    journal::set_sidecar_journal,
+    notify::set_notify,
+    output::{get_output_format, print_report, set_print0, OutputFormat},
+    pipe::Pipe,
+    plan::{get_confirm_every, resume_latest, RenamePlan},
+    portable::set_portable,
+    prompt::set_assume_yes,
+    report::Reports,
+    restore::undo_latest,
+    retry::set_retry_policy,
+    stats::set_stats_format,
+};
+use rules::Rules;
+use std::{path::PathBuf, process, thread, time::Duration};
+
+pub mod cli;
+mod organize;
+mod rules;
+
+const PROGRAM_NAME: &str = "organize_downloads";
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    set_assume_yes(&matches);
+    set_print0(&matches);
+    set_guard(&matches);
+    set_retry_policy(&matches);
+    set_portable(&matches);
+    set_jobs(&matches);
+    set_sidecar_journal(&matches);
+    set_backup_policy(&matches);
+    set_stats_format(&matches);
+    set_notify(&matches);
+    if matches.get_flag("undo") {
+        if let Err(err) = undo_latest(PROGRAM_NAME) {
+            eprintln!("{}", tr(Message::FailedToUndo, &[&err]));
+            process::exit(err.exit_code());
+        }
+        return;
+    }
+    if matches.get_flag("resume") {
+        if let Err(err) = resume_latest(PROGRAM_NAME) {
+            eprintln!("{}", tr(Message::FailedToResume, &[&err]));
+            process::exit(err.exit_code());
+        }
+        return;
+    }
+    let dir: PathBuf = matches.get_one::<PathBuf>("dir").unwrap().clone();
+    if let Err(err) = check_paths(&[&dir]) {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
+    let conflict: ConflictStrategy =
+        ConflictStrategy::from(matches.get_one::<String>("on_conflict").unwrap());
+    let dry_run: bool = matches.get_flag("dry_run");
+    let watch: bool = matches.get_flag("watch");
+    let output: OutputFormat = get_output_format(&matches);
+    let hooks: Hooks = Hooks::from(&matches);
+    let failure: FailurePolicy =
+        FailurePolicy::from(matches.get_one::<String>("on_error").unwrap());
+    let interval: Duration = Duration::from_secs(*matches.get_one::<u64>("interval").unwrap());
+    let rules: Rules = match matches.get_one::<PathBuf>("rules") {
+        Some(path) => match Rules::load(path) {
+            Ok(rules) => rules,
+            Err(err) => {
+                eprintln!(
+                    "{}",
+                    tr(Message::InvalidRulesFile, &[&path.display(), &err])
+                );
+                process::exit(2);
+            }
+        },
+        None => Rules::default(),
+    };
+
+    let mut pipe: Option<Pipe> = match Pipe::from(&matches) {
+        Ok(pipe) => pipe,
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToReadRecords, &[&err]));
+            process::exit(2);
+        }
+    };
+
+    // in watch mode, a file modified within the interval may still be downloading
+    let min_age: Duration = if watch { interval } else { Duration::ZERO };
+    loop {
+        let plan: RenamePlan = match plan_moves(&dir, &rules, conflict, min_age) {
+            Ok(plan) => plan
+                .with_hooks(hooks.clone())
+                .with_failure_policy(failure)
+                .with_explanations(matches.get_flag("explain"))
+                .with_verification(matches.get_flag("verify"))
+                .with_confirm_every(get_confirm_every(&matches))
+                .with_reports(Reports::from(&matches)),
+            Err(err) => {
+                eprintln!("{}", tr(Message::FailedToRead, &[&dir.display(), &err]));
+                process::exit(1);
+            }
+        };
+        if let Some(pipe) = &mut pipe {
+            // in watch mode, a record per file moved or failing, round after round
+            if !watch {
+                if let Err(err) = plan.apply_piped(PROGRAM_NAME, pipe) {
+                    eprintln!("{err}");
+                    process::exit(err.exit_code());
+                }
+                return;
+            }
+            if !plan.is_empty() {
+                if let Err(err) = plan.apply_piped(PROGRAM_NAME, pipe) {
+                    eprintln!("{err}");
+                }
+            }
+            thread::sleep(interval);
+            continue;
+        }
+        if !output.is_table() {
+            print_report(&plan, output);
+            return;
+        }
+        if dry_run {
+            plan.print_preview();
+            println!("{}", tr(Message::NothingDone, &[]));
+            return;
+        }
+        if !watch {
+            if let Err(err) = plan.apply(PROGRAM_NAME, true) {
+                eprintln!("{err}");
+                process::exit(err.exit_code());
+            }
+            return;
+        }
+        // a file failing to move is tried again on the next round
+        if !plan.is_empty() {
+            if let Err(err) = plan.apply(PROGRAM_NAME, false) {
+                eprintln!("{err}");
+            }
+        }
+        thread::sleep(interval);
+    }
+}
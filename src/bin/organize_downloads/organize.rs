@@ -0,0 +1,57 @@
+use super::rules::Rules;
+use ray_commands::{
+    clock::system_now,
+    conflict::ConflictStrategy,
+    i18n::{tr, Message},
+    plan::RenamePlan,
+    platform::is_hidden,
+    walk::{list_files, WalkEntry},
+};
+use std::{
+    io,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+/// extensions of files browsers are still downloading
+const IN_PROGRESS_EXTENSIONS: [&str; 5] = ["crdownload", "part", "partial", "download", "tmp"];
+
+/// plan moving the files directly inside `dir` to the destination and name of
+/// their rule; hidden files, unfinished downloads, files matching no rule or a
+/// skipping one, and files modified less than `min_age` ago stay
+pub fn plan_moves(
+    dir: &Path,
+    rules: &Rules,
+    conflict: ConflictStrategy,
+    min_age: Duration,
+) -> io::Result<RenamePlan> {
+    let mut plan: RenamePlan = RenamePlan::with_conflict(conflict);
+    let now: SystemTime = system_now();
+    let entries: Vec<WalkEntry> = list_files(dir)?;
+    for entry in entries {
+        let name: String = entry.relative.to_string_lossy().into_owned();
+        let extension: String = entry
+            .relative
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let age: Duration = entry
+            .metadata
+            .modified()
+            .ok()
+            .and_then(|m| now.duration_since(m).ok())
+            .unwrap_or_default();
+        if is_hidden(&entry.path, &entry.metadata)
+            || IN_PROGRESS_EXTENSIONS.contains(&extension.as_str())
+            || age < min_age
+        {
+            continue;
+        }
+        if let Some((number, target)) = rules.find_target(dir, &entry.path, &name, &entry.metadata)
+        {
+            plan.explain(&entry.path, tr(Message::ExplainRule, &[&number]));
+            plan.add(entry.path, target);
+        }
+    }
+    Ok(plan)
+}
@@ -0,0 +1,285 @@
+use chrono::{DateTime, Local};
+use ray_commands::{
+    age::parse_age,
+    clock::system_now,
+    content::{get_mime_type, matches_mime},
+    dest_map::{expand_destination, glob_to_regex},
+    i18n::{tr, Message},
+    size::parse_size,
+    template::{lookup_file, Template},
+};
+use regex::Regex;
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    fs::{self, Metadata},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// the rules used without a rules file, into subdirectories of the organized directory
+const DEFAULT_RULES: [(&str, &[&str]); 6] = [
+    (
+        "Documents",
+        &[
+            "pdf", "doc", "docx", "odt", "rtf", "txt", "md", "xls", "xlsx", "ods", "csv", "ppt",
+            "pptx", "odp", "epub",
+        ],
+    ),
+    (
+        "Pictures",
+        &[
+            "jpg", "jpeg", "png", "gif", "webp", "heic", "svg", "bmp", "tif", "tiff",
+        ],
+    ),
+    (
+        "Music",
+        &["mp3", "flac", "ogg", "opus", "m4a", "wav", "aac"],
+    ),
+    ("Videos", &["mp4", "mkv", "mov", "avi", "webm"]),
+    (
+        "Archives",
+        &["zip", "tar", "gz", "tgz", "bz2", "xz", "zst", "7z", "rar"],
+    ),
+    (
+        "Installers",
+        &[
+            "exe", "msi", "dmg", "pkg", "deb", "rpm", "appimage", "apk", "iso",
+        ],
+    ),
+];
+
+/// a rules file: `[[rule]]` tables in TOML, or a `rule` list in YAML, the first
+/// matching rule applying
+#[derive(Deserialize)]
+struct RulesFile {
+    #[serde(rename = "rule")]
+    rules: Vec<RuleEntry>,
+}
+
+#[derive(Deserialize)]
+struct RuleEntry {
+    /// extensions without the dot, matched case-insensitively
+    #[serde(default)]
+    extensions: Vec<String>,
+    /// regular expression matched against the whole file name
+    pattern: Option<String>,
+    /// shell pattern matched against the whole file name, e.g. `IMG_*.jpg`
+    glob: Option<String>,
+    /// MIME type told from the content, e.g. `image/png` or `image/*`
+    mime: Option<String>,
+    /// sizes such as `10K` or `1.5 GiB`, as by `--min-size`
+    min_size: Option<String>,
+    max_size: Option<String>,
+    /// times since the last modification such as `12h` or `7d`
+    min_age: Option<String>,
+    max_age: Option<String>,
+    /// relative to the organized directory, or starting with `~/` for the home
+    /// directory; the organized directory itself by default
+    destination: Option<String>,
+    /// the new file name, with {name} (the old name without its extension),
+    /// {ext}, {year}, {month} and {day} of the modification time, and {parent}
+    /// or {parent:N}, the name of its directory or a word of it, and {line1} or
+    /// {head:N}, the first line or characters of a text file, {location} or
+    /// {country}, where a photo was taken, and {camera} or {lens}, with what
+    template: Option<String>,
+    /// leave the matching files where they are, as they are
+    #[serde(default)]
+    skip: bool,
+}
+
+struct Rule {
+    extensions: Vec<String>,
+    pattern: Option<Regex>,
+    glob: Option<Regex>,
+    mime: Option<String>,
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    min_age: Option<Duration>,
+    max_age: Option<Duration>,
+    action: Action,
+}
+
+/// what becomes of the files matching a rule
+enum Action {
+    Move {
+        destination: Option<String>,
+        template: Option<Template>,
+    },
+    Skip,
+}
+
+impl Rule {
+    fn parse(entry: RuleEntry) -> Result<Self, String> {
+        let size = |text: Option<String>| -> Result<Option<u64>, String> {
+            text.map(|t| parse_size(&t).ok_or_else(|| tr(Message::InvalidSize, &[&t])))
+                .transpose()
+        };
+        let age = |text: Option<String>| text.map(|t| parse_age(&t)).transpose();
+        let action: Action = match (entry.skip, entry.destination, entry.template) {
+            (true, None, None) => Action::Skip,
+            (true, _, _) => return Err(tr(Message::SkipRuleMoving, &[])),
+            (false, None, None) => {
+                return Err(tr(Message::RuleWithoutAction, &[]));
+            }
+            (false, destination, template) => Action::Move {
+                destination,
+                template: template
+                    .map(|t| {
+                        Template::parse(&t).map_err(|e| tr(Message::InvalidRuleTemplate, &[&t, &e]))
+                    })
+                    .transpose()?,
+            },
+        };
+        Ok(Self {
+            extensions: entry.extensions.iter().map(|e| e.to_lowercase()).collect(),
+            pattern: entry
+                .pattern
+                .map(|p| Regex::new(&p).map_err(|e| e.to_string()))
+                .transpose()?,
+            glob: entry
+                .glob
+                .map(|g| Regex::new(&glob_to_regex(&g)).map_err(|e| e.to_string()))
+                .transpose()?,
+            mime: entry.mime,
+            min_size: size(entry.min_size)?,
+            max_size: size(entry.max_size)?,
+            min_age: age(entry.min_age)?,
+            max_age: age(entry.max_age)?,
+            action,
+        })
+    }
+
+    /// whether the file `name` at `path` meets every condition of the rule;
+    /// the content is read last, only for a rule asking for a MIME type
+    fn matches(&self, path: &Path, name: &str, metadata: &Metadata) -> bool {
+        let extension: Option<String> = Path::new(name)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase());
+        let age: Duration = metadata
+            .modified()
+            .ok()
+            .and_then(|m| system_now().duration_since(m).ok())
+            .unwrap_or_default();
+        (self.extensions.is_empty()
+            || extension
+                .as_ref()
+                .is_some_and(|e| self.extensions.contains(e)))
+            && self.pattern.as_ref().is_none_or(|p| p.is_match(name))
+            && self.glob.as_ref().is_none_or(|g| g.is_match(name))
+            && self.min_size.is_none_or(|min| metadata.len() >= min)
+            && self.max_size.is_none_or(|max| metadata.len() <= max)
+            && self.min_age.is_none_or(|min| age >= min)
+            && self.max_age.is_none_or(|max| age <= max)
+            && self
+                .mime
+                .as_ref()
+                .is_none_or(|pattern| get_mime_type(path).is_some_and(|m| matches_mime(pattern, m)))
+    }
+}
+
+pub struct Rules(Vec<Rule>);
+
+impl Rules {
+    /// the rules of the TOML file at `path`, or of the YAML one for a `.yaml`
+    /// or `.yml` file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text: String = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let is_yaml: bool = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("yaml") || e.eq_ignore_ascii_case("yml"));
+        let file: RulesFile = match is_yaml {
+            true => serde_yaml_ng::from_str(&text).map_err(|e| e.to_string())?,
+            false => toml::from_str(&text).map_err(|e| e.to_string())?,
+        };
+        let mut rules: Vec<Rule> = vec![];
+        for (number, entry) in file.rules.into_iter().enumerate() {
+            rules.push(
+                Rule::parse(entry).map_err(|e| tr(Message::RuleFailed, &[&(number + 1), &e]))?,
+            );
+        }
+        Ok(Self(rules))
+    }
+
+    pub fn default() -> Self {
+        Self(
+            DEFAULT_RULES
+                .iter()
+                .map(|(destination, extensions)| Rule {
+                    extensions: extensions.iter().map(|e| e.to_string()).collect(),
+                    pattern: None,
+                    glob: None,
+                    mime: None,
+                    min_size: None,
+                    max_size: None,
+                    min_age: None,
+                    max_age: None,
+                    action: Action::Move {
+                        destination: Some(destination.to_string()),
+                        template: None,
+                    },
+                })
+                .collect(),
+        )
+    }
+
+    /// the new path of the file `name` at `path` by the first rule it matches,
+    /// with `dir` the organized directory, and the number of that rule; none
+    /// for a file matching no rule or a skipping one, and an error for a
+    /// template missing a value; a rule with no conditions matches any file
+    pub fn find_target(
+        &self,
+        dir: &Path,
+        path: &Path,
+        name: &str,
+        metadata: &Metadata,
+    ) -> Option<(usize, Result<PathBuf, String>)> {
+        let (index, rule) = self
+            .0
+            .iter()
+            .enumerate()
+            .find(|(_, rule)| rule.matches(path, name, metadata))?;
+        let Action::Move {
+            destination,
+            template,
+        } = &rule.action
+        else {
+            return None;
+        };
+        let destination: PathBuf = match destination {
+            Some(destination) => expand_destination(dir, destination),
+            None => dir.to_path_buf(),
+        };
+        let new_name: Result<String, String> = match template {
+            Some(template) => render_name(template, path, name, metadata),
+            None => Ok(name.to_string()),
+        };
+        Some((index + 1, new_name.map(|n| destination.join(n))))
+    }
+}
+
+/// the name of the file `name` at `path` by `template`
+fn render_name(
+    template: &Template,
+    path: &Path,
+    name: &str,
+    metadata: &Metadata,
+) -> Result<String, String> {
+    let name_path: &Path = Path::new(name);
+    let mut values: HashMap<&str, String> = HashMap::new();
+    if let Some(stem) = name_path.file_stem() {
+        values.insert("name", stem.to_string_lossy().into_owned());
+    }
+    if let Some(extension) = name_path.extension() {
+        values.insert("ext", extension.to_string_lossy().into_owned());
+    }
+    if let Ok(modified) = metadata.modified() {
+        let modified: DateTime<Local> = modified.into();
+        values.insert("year", modified.format("%Y").to_string());
+        values.insert("month", modified.format("%m").to_string());
+        values.insert("day", modified.format("%d").to_string());
+    }
+    template
+        .render(|key| values.get(key).cloned().or_else(|| lookup_file(key, path)))
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,203 @@
+use super::limits::Limits;
+use ray_commands::{
+    i18n::{tr, Message},
+    output::Report,
+    sanitize::{FORBIDDEN_CHARS, RESERVED_NAMES},
+    walk::{walk_entries, WalkEntry},
+};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+};
+use unicode_normalization::UnicodeNormalization;
+
+#[derive(Clone, Serialize)]
+pub struct Problem {
+    /// path relative to the audited directory
+    path: String,
+    target: &'static str,
+    kind: &'static str,
+    detail: String,
+}
+
+pub struct AuditReport {
+    problems: Vec<Problem>,
+}
+
+impl AuditReport {
+    /// check every path below `root` against each of `targets`, counting
+    /// `prefix_length` more characters in front of every path for the directory
+    /// the tree will be copied into
+    pub fn scan(root: &Path, targets: &[&Limits], prefix_length: usize) -> io::Result<Self> {
+        let entries: Vec<WalkEntry> = walk_entries(root)?;
+        let mut problems: Vec<Problem> = vec![];
+        for entry in &entries {
+            let path: String = entry.relative.to_string_lossy().into_owned();
+            let name: String = entry
+                .relative
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned();
+            let levels: usize = entry.relative.components().count();
+            let depth: usize = if entry.metadata.is_dir() {
+                levels
+            } else {
+                levels - 1
+            };
+            for target in targets {
+                let mut report = |kind: &'static str, detail: String| {
+                    problems.push(Problem {
+                        path: path.clone(),
+                        target: target.name,
+                        kind,
+                        detail,
+                    })
+                };
+                let path_length: usize = prefix_length + target.unit.measure(&path);
+                if path_length > target.max_path {
+                    report(
+                        "long_path",
+                        tr(
+                            Message::PathExceeds,
+                            &[&path_length, &target.unit.name(), &target.max_path],
+                        ),
+                    );
+                }
+                let name_length: usize = target.unit.measure(&name);
+                if name_length > target.max_component {
+                    report(
+                        "long_name",
+                        tr(
+                            Message::NameExceeds,
+                            &[&name_length, &target.unit.name(), &target.max_component],
+                        ),
+                    );
+                }
+                if let Some(max_depth) = target.max_depth.filter(|m| depth > *m) {
+                    report(
+                        "deep_path",
+                        tr(Message::DepthExceeds, &[&depth, &max_depth]),
+                    );
+                }
+                if target.windows_names {
+                    check_windows_name(&name)
+                        .into_iter()
+                        .for_each(|d| report("bad_name", d));
+                }
+                if target.iso9660_names {
+                    check_iso9660_name(&name)
+                        .into_iter()
+                        .for_each(|d| report("bad_name", d));
+                }
+            }
+        }
+        for target in targets.iter().filter(|t| t.case_insensitive) {
+            for (path, other) in find_case_collisions(&entries) {
+                problems.push(Problem {
+                    path,
+                    target: target.name,
+                    kind: "case_collision",
+                    detail: tr(Message::SameNameIgnoringCase, &[&other]),
+                });
+            }
+        }
+        problems.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(Self { problems })
+    }
+
+    pub fn has_problems(&self) -> bool {
+        !self.problems.is_empty()
+    }
+}
+
+impl Report for AuditReport {
+    type Record = Problem;
+
+    fn records(&self) -> Vec<Problem> {
+        self.problems.clone()
+    }
+
+    fn print_table(&self) {
+        self.problems
+            .iter()
+            .for_each(|p| println!("{}: {} ({})", p.path, p.detail, p.target));
+        let paths: usize = self
+            .problems
+            .iter()
+            .map(|p| &p.path)
+            .collect::<std::collections::HashSet<_>>()
+            .len();
+        println!(
+            "{}",
+            tr(Message::ProblemsFound, &[&self.problems.len(), &paths])
+        );
+    }
+}
+
+fn check_windows_name(name: &str) -> Vec<String> {
+    let mut details: Vec<String> = vec![];
+    let forbidden: String = name
+        .chars()
+        .filter(|c| FORBIDDEN_CHARS.contains(c) || c.is_control())
+        .map(|c| c.escape_default().to_string())
+        .collect();
+    if !forbidden.is_empty() {
+        details.push(tr(Message::ForbiddenChars, &[&forbidden]));
+    }
+    let stem: &str = name.split('.').next().unwrap_or("");
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        details.push(tr(Message::DeviceName, &[&stem]));
+    }
+    if name.ends_with(['.', ' ']) {
+        details.push(tr(Message::TrailingDotOrSpace, &[]));
+    }
+    details
+}
+
+fn check_iso9660_name(name: &str) -> Vec<String> {
+    let mut details: Vec<String> = vec![];
+    let others: String = name
+        .chars()
+        .filter(|c| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '.'))
+        .map(|c| c.escape_default().to_string())
+        .collect();
+    if !others.is_empty() {
+        details.push(tr(Message::NotIso9660Chars, &[&others]));
+    }
+    if name.matches('.').count() > 1 {
+        details.push(tr(Message::MoreThanOneDot, &[]));
+    }
+    details
+}
+
+/// pairs of paths in the same directory whose names only differ in case
+/// or Unicode normalization, each path with the first one it collides with
+fn find_case_collisions(entries: &[WalkEntry]) -> Vec<(String, String)> {
+    let mut seen: HashMap<(PathBuf, String), String> = HashMap::new();
+    let mut collisions: Vec<(String, String)> = vec![];
+    for entry in entries {
+        let parent: PathBuf = entry
+            .relative
+            .parent()
+            .unwrap_or(Path::new(""))
+            .to_path_buf();
+        let name: String = entry
+            .relative
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .into_owned();
+        let key: String = name.nfc().collect::<String>().to_lowercase();
+        let path: String = entry.relative.to_string_lossy().into_owned();
+        match seen.get(&(parent.clone(), key.clone())) {
+            Some(other) => collisions.push((path, other.clone())),
+            None => {
+                seen.insert((parent, key), path);
+            }
+        }
+    }
+    collisions
+}
@@ -0,0 +1,48 @@
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::{clock::get_now_arg, i18n::get_lang_arg, output::get_output_arg};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "path_audit";
+
+const SHORT_DESCRIPTION: &str =
+    "Report paths too long, too deep or with names a platform or disc format\nrejects, before copying or burning a tree.";
+
+const TARGET_HELP_MESSAGE: &str = r#"The platform or medium to check for, repeatable:
+  windows: 259-character paths, forbidden characters and names, case
+  macos:   1023-character paths, case
+  linux:   4095-byte paths, 255-byte names
+  iso9660: plain CD names: 31 characters of A-Z, 0-9 and _, depth 8
+  joliet:  CD names of 64 characters, depth 8
+  udf:     DVD and Blu-ray, 1023-character paths"#;
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_output_arg())
+        .arg(
+            Arg::new("target")
+                .short('t')
+                .long("target")
+                .help(TARGET_HELP_MESSAGE)
+                .value_parser(["windows", "macos", "linux", "iso9660", "joliet", "udf"])
+                .action(ArgAction::Append)
+                .default_value("windows"),
+        )
+        .arg(
+            Arg::new("prefix")
+                .long("prefix")
+                .help("The directory the tree will be copied into, whose length\ncounts towards every path, e.g. D:\\Backup\\")
+                .default_value(""),
+        )
+        .arg(
+            Arg::new("dir")
+                .help("The directory tree to audit")
+                .value_parser(value_parser!(PathBuf))
+                .required(true),
+        )
+}
@@ -0,0 +1,111 @@
+/// how a platform counts the length of names
+#[derive(Clone, Copy)]
+pub enum LengthUnit {
+    Bytes,
+    Utf16,
+}
+
+/// the limits of a platform or medium a tree may be copied to
+pub struct Limits {
+    pub name: &'static str,
+    pub unit: LengthUnit,
+    /// longest relative path, separators included
+    pub max_path: usize,
+    pub max_component: usize,
+    /// deepest directory nesting, the root being at depth 0
+    pub max_depth: Option<usize>,
+    /// Windows forbidden characters, reserved names and trailing dots and spaces
+    pub windows_names: bool,
+    /// names differing only in case collide
+    pub case_insensitive: bool,
+    /// only `A-Z`, `0-9`, `_` and one `.` allowed, as in plain ISO 9660
+    pub iso9660_names: bool,
+}
+
+// references: https://learn.microsoft.com/en-us/windows/win32/fileio/maximum-file-path-limitation
+// ECMA-119 (ISO 9660) 6.8.2, Joliet specification 1995, OSTA UDF 2.60 2.3.4.5
+pub const TARGETS: [Limits; 6] = [
+    Limits {
+        name: "windows",
+        unit: LengthUnit::Utf16,
+        max_path: 259,
+        max_component: 255,
+        max_depth: None,
+        windows_names: true,
+        case_insensitive: true,
+        iso9660_names: false,
+    },
+    Limits {
+        name: "macos",
+        unit: LengthUnit::Utf16,
+        max_path: 1023,
+        max_component: 255,
+        max_depth: None,
+        windows_names: false,
+        case_insensitive: true,
+        iso9660_names: false,
+    },
+    Limits {
+        name: "linux",
+        unit: LengthUnit::Bytes,
+        max_path: 4095,
+        max_component: 255,
+        max_depth: None,
+        windows_names: false,
+        case_insensitive: false,
+        iso9660_names: false,
+    },
+    Limits {
+        name: "iso9660",
+        unit: LengthUnit::Bytes,
+        max_path: 255,
+        max_component: 31,
+        max_depth: Some(8),
+        windows_names: false,
+        case_insensitive: true,
+        iso9660_names: true,
+    },
+    Limits {
+        name: "joliet",
+        unit: LengthUnit::Utf16,
+        max_path: 240,
+        max_component: 64,
+        max_depth: Some(8),
+        windows_names: true,
+        case_insensitive: true,
+        iso9660_names: false,
+    },
+    Limits {
+        name: "udf",
+        unit: LengthUnit::Utf16,
+        max_path: 1023,
+        max_component: 255,
+        max_depth: None,
+        windows_names: false,
+        case_insensitive: false,
+        iso9660_names: false,
+    },
+];
+
+pub fn find_target(name: &str) -> &'static Limits {
+    TARGETS
+        .iter()
+        .find(|t| t.name == name)
+        .unwrap_or_else(|| panic!("Unknown target: {name}"))
+}
+
+impl LengthUnit {
+    pub fn measure(&self, text: &str) -> usize {
+        match self {
+            Self::Bytes => text.len(),
+            Self::Utf16 => text.encode_utf16().count(),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Bytes => "bytes",
+            Self::Utf16 => "UTF-16 units",
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use audit::AuditReport;
+use limits::{find_target, Limits};
+use ray_commands::{
+    clock::set_now,
+    i18n::{set_language, tr, Message},
+    output::{get_output_format, print_report, OutputFormat},
+};
+use std::{path::PathBuf, process};
+
+mod audit;
+pub mod cli;
+mod limits;
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    let dir: PathBuf = matches.get_one::<PathBuf>("dir").unwrap().clone();
+    let targets: Vec<&Limits> = matches
+        .get_many::<String>("target")
+        .unwrap()
+        .map(|t| find_target(t))
+        .collect();
+    let prefix: &str = matches.get_one::<String>("prefix").unwrap();
+    let output: OutputFormat = get_output_format(&matches);
+
+    let report: AuditReport = match AuditReport::scan(&dir, &targets, prefix.chars().count()) {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToRead, &[&dir.display(), &err]));
+            process::exit(2);
+        }
+    };
+
+    print_report(&report, output);
+
+    if report.has_problems() {
+        process::exit(1);
+    }
+}
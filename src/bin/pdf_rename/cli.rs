@@ -0,0 +1,106 @@
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::{
+    clock::get_now_arg,
+    failure::get_on_error_arg,
+    guard::get_guard_args,
+    hardlinks::get_hardlinks_arg,
+    hooks::get_hook_args,
+    i18n::get_lang_arg,
+    jobs::get_jobs_arg,
+    journal::get_journal_in_dir_arg,
+    name_command::get_name_command_args,
+    notify::get_notify_arg,
+    output::{get_output_arg, get_print0_arg},
+    pipe::{get_files_from_arg, get_pipe_args},
+    plan::{get_confirm_every_arg, get_explain_arg, get_resume_arg},
+    portable::get_portable_arg,
+    prompt::get_yes_arg,
+    report::get_report_args,
+    retry::get_retry_args,
+    serve::get_serve_args,
+    sort::get_sort_arg,
+    stats::get_stats_arg,
+    verify::get_verify_arg,
+    walk::{get_content_args, get_size_args},
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "pdf_rename";
+
+const SHORT_DESCRIPTION: &str =
+    "Rename PDF files after the title in their metadata,\nor the first heading-like line of their text.";
+
+const SOURCE_HELP_MESSAGE: &str = r#"Where to take the new name from:
+  auto:  the Title metadata, else the first heading-like line of text
+  title: the Title metadata only
+  text:  the first heading-like line of text only"#;
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_yes_arg())
+        .arg(get_output_arg())
+        .args(get_guard_args())
+        .args(get_retry_args())
+        .arg(get_stats_arg())
+        .arg(get_notify_arg())
+        .args(get_pipe_args())
+        .arg(get_print0_arg())
+        .arg(get_files_from_arg())
+        .args(get_serve_args())
+        .args(get_hook_args())
+        .arg(get_on_error_arg())
+        .arg(get_explain_arg())
+        .arg(get_verify_arg())
+        .arg(get_portable_arg())
+        .arg(get_confirm_every_arg())
+        .args(get_report_args())
+        .args(get_size_args())
+        .arg(get_sort_arg())
+        .arg(get_hardlinks_arg())
+        .args(get_content_args())
+        .args(get_name_command_args())
+        .arg(
+            Arg::new("source")
+                .long("source")
+                .help(SOURCE_HELP_MESSAGE)
+                .value_parser(["auto", "title", "text"])
+                .default_value("auto"),
+        )
+        .arg(
+            Arg::new("max_length")
+                .long("max-length")
+                .help("The maximum number of characters of the new name, excluding \".pdf\"")
+                .value_parser(value_parser!(usize))
+                .default_value("80"),
+        )
+        .arg(
+            Arg::new("undo")
+                .long("undo")
+                .help("Undo the latest renaming done by this command")
+                .action(ArgAction::SetTrue)
+                .exclusive(true),
+        )
+        .arg(get_resume_arg())
+        .arg(get_jobs_arg())
+        .arg(get_journal_in_dir_arg())
+        .arg(
+            Arg::new("input_paths")
+                .help("The path(s) to the PDF file(s)")
+                .value_parser(value_parser!(PathBuf))
+                .required_unless_present_any([
+                    "undo",
+                    "resume",
+                    "pipe",
+                    "serve",
+                    "serve_socket",
+                    "files_from",
+                ])
+                .action(ArgAction::Append),
+        )
+}
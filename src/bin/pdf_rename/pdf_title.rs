@@ -0,0 +1,111 @@
+use lopdf::Document;
+use ray_commands::{
+    i18n::{tr, Message},
+    sanitize::sanitize_component,
+};
+use std::path::Path;
+
+/// prefixes office suites put in front of the title when printing to PDF
+const GENERATOR_PREFIXES: [&str; 3] = [
+    "Microsoft Word - ",
+    "Microsoft PowerPoint - ",
+    "Microsoft Excel - ",
+];
+
+/// titles that say nothing about the document
+const PLACEHOLDER_TITLES: [&str; 4] = ["untitled", "untitled document", "title", "document"];
+
+/// a heading is expected within the first lines of the first page
+const HEADING_SEARCH_LINES: usize = 20;
+const MIN_HEADING_LETTERS: usize = 3;
+const MAX_HEADING_LENGTH: usize = 120;
+
+#[derive(Clone, Copy)]
+pub enum TitleSource {
+    Auto,
+    Title,
+    Text,
+}
+
+impl TitleSource {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "auto" => Self::Auto,
+            "title" => Self::Title,
+            "text" => Self::Text,
+            _ => panic!("Unknown title source: {name}"),
+        }
+    }
+}
+
+/// the sanitized new file stem of the PDF, at most `max_length` characters long,
+/// and the steps of deriving it, for `--explain`
+pub fn get_title(
+    path: &Path,
+    source: TitleSource,
+    max_length: usize,
+) -> Result<(String, Vec<String>), String> {
+    let metadata = || -> Result<Option<(String, Message)>, String> {
+        Ok(read_metadata_title(path)?.map(|t| (t, Message::ExplainTitleMetadata)))
+    };
+    let heading = || -> Result<Option<(String, Message)>, String> {
+        Ok(read_first_heading(path)?.map(|t| (t, Message::ExplainFirstHeading)))
+    };
+    let found: Option<(String, Message)> = match source {
+        TitleSource::Title => metadata()?,
+        TitleSource::Text => heading()?,
+        TitleSource::Auto => match metadata()? {
+            Some(found) => Some(found),
+            None => heading()?,
+        },
+    };
+    let (title, found_in) = found.ok_or_else(|| tr(Message::NoTitleFound, &[]))?;
+    let mut steps: Vec<String> = vec![tr(found_in, &[])];
+    if title.chars().count() > max_length {
+        steps.push(tr(Message::ExplainTruncated, &[&max_length]));
+    }
+    let truncated: String = title.chars().take(max_length).collect();
+    Ok((sanitize_component(&truncated), steps))
+}
+
+fn read_metadata_title(path: &Path) -> Result<Option<String>, String> {
+    let title: Option<String> = Document::load_metadata(path)
+        .map_err(|e| e.to_string())?
+        .title;
+    Ok(title.and_then(|t| clean_title(&t)))
+}
+
+fn read_first_heading(path: &Path) -> Result<Option<String>, String> {
+    let document: Document = Document::load(path).map_err(|e| e.to_string())?;
+    let text: String = document.extract_text(&[1]).map_err(|e| e.to_string())?;
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .take(HEADING_SEARCH_LINES)
+        .find(|l| {
+            l.chars().count() <= MAX_HEADING_LENGTH
+                && l.chars().filter(|c| c.is_alphabetic()).count() >= MIN_HEADING_LETTERS
+        })
+        .and_then(clean_title))
+}
+
+/// drop generator prefixes, file extensions and placeholder titles;
+/// `None` when nothing meaningful is left
+fn clean_title(title: &str) -> Option<String> {
+    let mut title: &str = title.trim();
+    for prefix in GENERATOR_PREFIXES {
+        title = title.strip_prefix(prefix).unwrap_or(title);
+    }
+    // a title that is just the source file name, e.g. "report.docx"
+    if let Some((stem, ext)) = title.rsplit_once('.') {
+        if (2..=4).contains(&ext.len()) && ext.chars().all(|c| c.is_ascii_alphanumeric()) {
+            title = stem;
+        }
+    }
+    let title: String = sanitize_component(title);
+    if title.is_empty() || PLACEHOLDER_TITLES.contains(&title.to_lowercase().as_str()) {
+        return None;
+    }
+    Some(title)
+}
@@ -0,0 +1,87 @@
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::conflict::{CONFLICT_HELP_MESSAGE, CONFLICT_STRATEGY_NAMES};
+use ray_commands::{
+    backup::get_backup_args,
+    clock::get_now_arg,
+    failure::get_on_error_arg,
+    guard::get_guard_args,
+    hooks::get_hook_args,
+    i18n::get_lang_arg,
+    jobs::get_jobs_arg,
+    journal::get_journal_in_dir_arg,
+    notify::get_notify_arg,
+    output::get_output_arg,
+    plan::{get_confirm_every_arg, get_resume_arg},
+    portable::get_portable_arg,
+    prompt::get_yes_arg,
+    report::get_report_args,
+    retry::get_retry_args,
+    stats::get_stats_arg,
+    verify::get_verify_arg,
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "rename_from_csv";
+
+const SHORT_DESCRIPTION: &str = "Rename files following an old,new mapping in a CSV or TSV file.";
+
+const MAPPING_HELP_MESSAGE: &str = r#"The path to the mapping, with a header row naming the "old" and "new" columns.
+Files ending in .tsv are read as tab-separated, others as comma-separated.
+Extra columns such as size, mtime and hash are ignored."#;
+
+const ON_CONFLICT_HELP_MESSAGE: &str = r#"
+By default, a new name taken by a file the mapping does not rename
+makes the mapping invalid."#;
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_yes_arg())
+        .arg(get_output_arg())
+        .args(get_guard_args())
+        .args(get_retry_args())
+        .arg(get_stats_arg())
+        .arg(get_notify_arg())
+        .args(get_hook_args())
+        .arg(get_on_error_arg())
+        .arg(get_verify_arg())
+        .arg(get_portable_arg())
+        .arg(get_confirm_every_arg())
+        .args(get_report_args())
+        .arg(
+            Arg::new("on_conflict")
+                .long("on-conflict")
+                .help(format!("{CONFLICT_HELP_MESSAGE}{ON_CONFLICT_HELP_MESSAGE}"))
+                .value_parser(CONFLICT_STRATEGY_NAMES),
+        )
+        .args(get_backup_args())
+        .arg(
+            Arg::new("dir")
+                .short('d')
+                .long("dir")
+                .help("The directory the names in the mapping are relative to")
+                .value_parser(value_parser!(PathBuf))
+                .default_value("."),
+        )
+        .arg(
+            Arg::new("undo")
+                .long("undo")
+                .help("Undo the latest renaming done by this command")
+                .action(ArgAction::SetTrue)
+                .exclusive(true),
+        )
+        .arg(get_resume_arg())
+        .arg(get_jobs_arg())
+        .arg(get_journal_in_dir_arg())
+        .arg(
+            Arg::new("mapping")
+                .help(MAPPING_HELP_MESSAGE)
+                .value_parser(value_parser!(PathBuf))
+                .required_unless_present_any(["undo", "resume"]),
+        )
+}
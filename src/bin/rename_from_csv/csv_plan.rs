@@ -0,0 +1,71 @@
+use ray_commands::{
+    conflict::ConflictStrategy,
+    filesystem::ReadOnly,
+    i18n::{tr, Message},
+    mapping::MappingRow,
+    paths::resolve,
+    plan::RenamePlan,
+};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+/// validate the whole mapping against the files in `reader`, returning every
+/// problem found, or else the plan of its renames, chains and cycles among them
+/// ordered when applying; an existing target not moved away by the mapping is
+/// a problem, unless `conflict` resolves it
+pub fn plan_mapping(
+    rows: Vec<MappingRow>,
+    dir: &Path,
+    conflict: Option<ConflictStrategy>,
+    reader: ReadOnly,
+) -> Result<RenamePlan, Vec<String>> {
+    let mut problems: Vec<String> = Vec::new();
+    let mut sources: HashSet<PathBuf> = HashSet::new();
+    let mut targets: HashSet<PathBuf> = HashSet::new();
+    // the targets by their lower case paths, the same file where case is ignored
+    let mut folded_targets: HashMap<String, &str> = HashMap::new();
+    let mut renames: Vec<(PathBuf, PathBuf)> = Vec::new();
+
+    // the header is line 1
+    for (line, row) in rows.iter().enumerate().map(|(i, r)| (i + 2, r)) {
+        if row.old.is_empty() || row.new.is_empty() {
+            problems.push(tr(Message::MappingEmptyName, &[&line]));
+            continue;
+        }
+        let source: PathBuf = resolve(dir, &row.old);
+        let target: PathBuf = resolve(dir, &row.new);
+        if !sources.insert(source.clone()) {
+            problems.push(tr(Message::MappingListedTwice, &[&line, &row.old]));
+        }
+        let folded: String = target.to_string_lossy().to_lowercase();
+        if !targets.insert(target.clone()) {
+            problems.push(tr(Message::MappingSharedTarget, &[&line, &row.new]));
+        } else if let Some(other) = folded_targets.get(folded.as_str()) {
+            problems.push(tr(Message::MappingCaseOnly, &[&line, &row.new, other]));
+        }
+        folded_targets.entry(folded).or_insert(&row.new);
+        if !reader.exists(&source) {
+            problems.push(tr(Message::MappingMissing, &[&line, &row.old]));
+        }
+        renames.push((source, target));
+    }
+
+    // an existing target is only fine when the mapping moves it away first
+    if conflict.is_none() {
+        let unique_targets: BTreeSet<&PathBuf> = renames.iter().map(|(_, t)| t).collect();
+        for target in unique_targets {
+            if reader.exists(target) && !sources.contains(target) {
+                problems.push(tr(Message::AlreadyExists, &[&target.display()]));
+            }
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+    let mut plan: RenamePlan = RenamePlan::with_conflict(conflict.unwrap_or_default());
+    plan.add_batch(renames.into_iter().map(|(s, t)| (s, Ok(t))).collect());
+    Ok(plan)
+}
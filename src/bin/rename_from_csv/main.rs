@@ -0,0 +1,102 @@
+use ray_commands::mapping::{read_mapping, MappingRow};
+use ray_commands::output::{get_output_format, print_report, OutputFormat};
+use ray_commands::retry::set_retry_policy;
+use ray_commands::{
+    backup::set_backup_policy,
+    clock::set_now,
+    conflict::ConflictStrategy,
+    failure::FailurePolicy,
+    filesystem::{DiskFilesystem, ReadOnly},
+    guard::{check_paths, set_guard},
+    hooks::Hooks,
+    i18n::{set_language, tr, Message},
+    jobs::set_jobs,
+    journal::set_sidecar_journal,
+    notify::set_notify,
+    plan::{get_confirm_every, resume_latest, RenamePlan},
+    portable::set_portable,
+    prompt::set_assume_yes,
+    report::Reports,
+    restore::undo_latest,
+    stats::set_stats_format,
+};
+use std::{path::PathBuf, process};
+
+pub mod cli;
+mod csv_plan;
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    set_assume_yes(&matches);
+    set_guard(&matches);
+    set_retry_policy(&matches);
+    set_portable(&matches);
+    set_jobs(&matches);
+    set_sidecar_journal(&matches);
+    set_backup_policy(&matches);
+    set_stats_format(&matches);
+    set_notify(&matches);
+    if matches.get_flag("undo") {
+        if let Err(err) = undo_latest(cli::PROGRAM_NAME) {
+            eprintln!("{}", tr(Message::FailedToUndo, &[&err]));
+            process::exit(err.exit_code());
+        }
+        return;
+    }
+    if matches.get_flag("resume") {
+        if let Err(err) = resume_latest(cli::PROGRAM_NAME) {
+            eprintln!("{}", tr(Message::FailedToResume, &[&err]));
+            process::exit(err.exit_code());
+        }
+        return;
+    }
+    let mapping: PathBuf = matches.get_one::<PathBuf>("mapping").unwrap().clone();
+    let dir: PathBuf = matches.get_one::<PathBuf>("dir").unwrap().clone();
+    if let Err(err) = check_paths(&[&dir]) {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
+    let output: OutputFormat = get_output_format(&matches);
+    let conflict: Option<ConflictStrategy> = matches
+        .get_one::<String>("on_conflict")
+        .map(|name| ConflictStrategy::from(name));
+    let failure: FailurePolicy =
+        FailurePolicy::from(matches.get_one::<String>("on_error").unwrap());
+
+    let rows: Vec<MappingRow> = match read_mapping(&mapping) {
+        Ok(rows) => rows,
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToRead, &[&mapping.display(), &err]));
+            process::exit(1);
+        }
+    };
+    let reader: ReadOnly = ReadOnly::new(&DiskFilesystem);
+    let plan: RenamePlan = match csv_plan::plan_mapping(rows, &dir, conflict, reader) {
+        Ok(plan) => plan
+            .with_hooks(Hooks::from(&matches))
+            .with_failure_policy(failure)
+            .with_verification(matches.get_flag("verify"))
+            .with_confirm_every(get_confirm_every(&matches))
+            .with_reports(Reports::from(&matches)),
+        Err(problems) => {
+            problems.iter().for_each(|p| eprintln!("{p}"));
+            eprintln!("{}", tr(Message::MappingInvalid, &[]));
+            process::exit(1);
+        }
+    };
+    if !output.is_table() {
+        print_report(&plan, output);
+        return;
+    }
+    if let Err(err) = plan.apply(cli::PROGRAM_NAME, true) {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
+}
@@ -1,15 +1,69 @@
-use clap::{Arg, ArgAction, Command, crate_version};
+use super::rotation::parse_rotation;
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::conflict::{CONFLICT_HELP_MESSAGE, CONFLICT_STRATEGY_NAMES};
+use ray_commands::{
+    backup::get_backup_args,
+    clock::get_now_arg,
+    dest_map::DestMap,
+    failure::get_on_error_arg,
+    guard::get_guard_args,
+    hardlinks::get_hardlinks_arg,
+    hooks::get_hook_args,
+    i18n::{get_lang_arg, tr, Message},
+    jobs::get_jobs_arg,
+    output::{get_output_arg, get_print0_arg},
+    pipe::{get_files_from_arg, get_pipe_args},
+    plan::{get_confirm_every_arg, get_explain_arg, get_resume_arg},
+    portable::get_portable_arg,
+    preset::{get_format_for_arg, get_offset_arg, get_preset_arg, get_relative_to_arg},
+    prompt::get_yes_arg,
+    report::get_report_args,
+    retry::get_retry_args,
+    sort::get_sort_arg,
+    stats::get_stats_arg,
+    time_format::{
+        get_sessionize_arg, get_strict_arg, get_time_source_arg, get_week_start_arg,
+        TIME_TOKENS_HELP_MESSAGE,
+    },
+    verify::get_verify_arg,
+    walk::{get_content_args, get_size_args},
+};
+use std::path::{Path, PathBuf};
 
-const PROGRAM_NAME: &'static str = "rename_mod_time";
+pub const PROGRAM_NAME: &str = "rename_mod_time";
 
-const SHORT_DESCRIPTION: &'static str =
+const SHORT_DESCRIPTION: &str =
     "Rename files with their own modification date and time\nin a specific format.";
 
-const FORMAT_HELP_MESSAGE: &'static str = r#"The format of date and time following Rust chrono's format:
+const FORMAT_HELP_MESSAGE: &str = r#"The format of date and time following Rust chrono's format:
 https://docs.rs/chrono/latest/chrono/format/strftime/index.html
+{name} stands for the old name, without its extension.
 "#;
 
-const DEFAULT_TIME_FORMAT: &'static str = "%y-%m-%d_%H-%M-%S";
+const CHUNK_SIZE_HELP_MESSAGE: &str = r#"Plan, preview, confirm and rename this many files at a time, holding
+only one chunk in memory, for very many files; all at once by default.
+A file whose new name is that of a file of a later chunk waits for it"#;
+
+const ROTATE_HELP_MESSAGE: &str = r#"Rotate backups: the files given the same name by the format, as by
+--preset weekly or monthly, are a group, and the files of all but the
+N newest groups are moved to the trash instead of being renamed; the
+moves are confirmed apart from the renames, and journaled with them for
+--undo"#;
+
+const DEST_MAP_HELP_MESSAGE: &str = r#"Move the renamed files to other directories by the rules of a TOML
+file, the first matching applying, e.g.
+  [[rule]]
+  extensions = ["jpg", "png"]
+  destination = "~/Pictures/Camera"
+  [[rule]]
+  glob = "*.pdf"
+  destination = "~/Documents"
+a rule matching files by their extensions, a shell pattern or both, and
+its destination, created when missing, relative to the directory of the
+file unless starting with ~/; the files matching no rule stay where they
+are, and a name taken in a destination is resolved by --on-conflict"#;
+
+const DEFAULT_TIME_FORMAT: &str = "%y-%m-%d_%H-%M-%S";
 
 pub fn get_cli_parser() -> Command {
     Command::new(PROGRAM_NAME)
@@ -17,17 +71,96 @@ pub fn get_cli_parser() -> Command {
         .about(SHORT_DESCRIPTION)
         .next_line_help(true)
         .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_yes_arg())
+        .arg(get_output_arg())
+        .args(get_guard_args())
+        .args(get_retry_args())
+        .arg(get_stats_arg())
+        .args(get_size_args())
+        .arg(get_sort_arg())
+        .arg(get_hardlinks_arg())
+        .args(get_content_args())
+        .args(get_hook_args())
+        .arg(get_on_error_arg())
+        .arg(get_explain_arg())
+        .arg(get_verify_arg())
+        .arg(get_portable_arg())
+        .arg(get_confirm_every_arg())
+        .args(get_report_args())
+        .args(get_pipe_args())
+        .arg(get_print0_arg())
+        .arg(get_files_from_arg())
         .arg(
             Arg::new("format")
                 .short('f')
-                .help(FORMAT_HELP_MESSAGE)
+                .help(format!("{FORMAT_HELP_MESSAGE}{TIME_TOKENS_HELP_MESSAGE}"))
                 .default_value(DEFAULT_TIME_FORMAT)
                 .required(false),
         )
+        .arg(get_preset_arg())
+        .arg(get_offset_arg())
+        .arg(get_relative_to_arg())
+        .arg(get_format_for_arg())
+        .arg(get_time_source_arg())
+        .arg(get_week_start_arg())
+        .arg(get_sessionize_arg())
+        .arg(get_strict_arg())
+        .arg(
+            Arg::new("on_conflict")
+                .long("on-conflict")
+                .help(CONFLICT_HELP_MESSAGE)
+                .value_parser(CONFLICT_STRATEGY_NAMES)
+                .default_value("skip"),
+        )
+        .args(get_backup_args())
+        .arg(
+            Arg::new("undo")
+                .long("undo")
+                .help("Undo the latest renaming done by this command")
+                .action(ArgAction::SetTrue)
+                .exclusive(true),
+        )
+        .arg(get_resume_arg())
+        .arg(get_jobs_arg())
+        .arg(
+            Arg::new("dest_map")
+                .long("dest-map")
+                .help(DEST_MAP_HELP_MESSAGE)
+                .value_name("FILE")
+                .value_parser(|path: &str| {
+                    DestMap::load(Path::new(path)).map_err(|e| format!("{path}: {e}"))
+                }),
+        )
+        .arg(
+            Arg::new("rotate")
+                .long("rotate")
+                .help(ROTATE_HELP_MESSAGE)
+                .value_name("keep=N")
+                .value_parser(parse_rotation)
+                .conflicts_with_all(["pipe", "chunk_size"]),
+        )
+        .arg(
+            Arg::new("chunk_size")
+                .long("chunk-size")
+                .help(CHUNK_SIZE_HELP_MESSAGE)
+                .value_parser(parse_chunk_size)
+                // sessions span all the files, not a chunk of them
+                .conflicts_with("sessionize"),
+        )
         .arg(
             Arg::new("input_paths")
                 .help("The path(s) to the input file(s)")
-                .required(true)
+                .value_parser(value_parser!(PathBuf))
+                .required_unless_present_any(["undo", "resume", "pipe", "files_from"])
                 .action(ArgAction::Append),
         )
-}
\ No newline at end of file
+}
+
+fn parse_chunk_size(text: &str) -> Result<usize, String> {
+    match text.parse::<usize>() {
+        Ok(size) if size > 0 => Ok(size),
+        _ => Err(tr(Message::InvalidChunkSize, &[&text])),
+    }
+}
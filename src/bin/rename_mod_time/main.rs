@@ -1,14 +1,128 @@
+use ray_commands::output::{get_output_format, set_print0, OutputFormat};
+use ray_commands::pipe::{get_input_paths, Pipe};
+use ray_commands::plan::{get_confirm_every, resume_latest};
+use ray_commands::preset::{get_time_formats, TimeFormats};
+use ray_commands::report::Reports;
+use ray_commands::retry::set_retry_policy;
+use ray_commands::walk::{filter_paths, set_content_filter, set_size_limits};
+use ray_commands::{
+    backup::set_backup_policy,
+    clock::set_now,
+    conflict::ConflictStrategy,
+    dest_map::DestMap,
+    failure::FailurePolicy,
+    guard::{check_paths, set_guard},
+    hardlinks::set_hard_link_policy,
+    hooks::Hooks,
+    i18n::{set_language, tr, Message},
+    jobs::set_jobs,
+    portable::set_portable,
+    prompt::set_assume_yes,
+    restore::undo_latest,
+    sort::set_sort_order,
+    stats::set_stats_format,
+    time_format::set_time_source,
+};
 use ray_file::RayFileList;
+use rotation::Rotation;
+use std::{path::PathBuf, process, time::Duration};
 
-mod cli;
+pub mod cli;
 mod ray_file;
+mod rotation;
 
-fn main() {
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
     // load command-line arguments
-    let matches: clap::ArgMatches = cli::get_cli_parser().get_matches();
-    let input_paths: Vec<String> = matches.get_many::<String>("input_paths").unwrap().cloned().collect();
-    let time_format: String = matches.get_one::<String>("format").unwrap().clone();
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    set_assume_yes(&matches);
+    set_print0(&matches);
+    set_guard(&matches);
+    set_retry_policy(&matches);
+    set_portable(&matches);
+    set_jobs(&matches);
+    set_backup_policy(&matches);
+    set_stats_format(&matches);
+    set_size_limits(&matches);
+    set_sort_order(&matches);
+    set_hard_link_policy(&matches);
+    set_content_filter(&matches);
+    set_time_source(&matches);
+    if matches.get_flag("undo") {
+        if let Err(err) = undo_latest(cli::PROGRAM_NAME) {
+            eprintln!("{}", tr(Message::FailedToUndo, &[&err]));
+            process::exit(err.exit_code());
+        }
+        return;
+    }
+    if matches.get_flag("resume") {
+        if let Err(err) = resume_latest(cli::PROGRAM_NAME) {
+            eprintln!("{}", tr(Message::FailedToResume, &[&err]));
+            process::exit(err.exit_code());
+        }
+        return;
+    }
+    let mut pipe: Option<Pipe> = match Pipe::from(&matches) {
+        Ok(pipe) => pipe,
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToReadRecords, &[&err]));
+            process::exit(2);
+        }
+    };
+    let input_paths: Vec<PathBuf> = match get_input_paths(&matches, pipe.as_ref()) {
+        Ok(paths) => filter_paths(paths),
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToReadPaths, &[&err]));
+            process::exit(2);
+        }
+    };
+    if let Err(err) = check_paths(&input_paths) {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
+    let time_formats: TimeFormats = match get_time_formats(&matches) {
+        Ok(format) => format,
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(2);
+        }
+    };
+    let output: OutputFormat = get_output_format(&matches);
+    let conflict: ConflictStrategy =
+        ConflictStrategy::from(matches.get_one::<String>("on_conflict").unwrap());
+    let failure: FailurePolicy =
+        FailurePolicy::from(matches.get_one::<String>("on_error").unwrap());
 
-    let file_list = RayFileList::from(&input_paths, time_format);
-    file_list.rename_with_modification_time(true);
+    let file_list: RayFileList = RayFileList::from(&input_paths, time_formats)
+        .with_conflict(conflict)
+        .with_hooks(Hooks::from(&matches))
+        .with_failure_policy(failure)
+        .with_explanations(matches.get_flag("explain"))
+        .with_verification(matches.get_flag("verify"))
+        .with_confirm_every(get_confirm_every(&matches))
+        .with_reports(Reports::from(&matches))
+        .with_session_gap(matches.get_one::<Duration>("sessionize").copied())
+        .with_dest_map(matches.get_one::<DestMap>("dest_map").cloned())
+        .with_rotation(matches.get_one::<Rotation>("rotate").copied())
+        .with_chunk_size(matches.get_one::<usize>("chunk_size").copied());
+    if let Some(pipe) = &mut pipe {
+        if let Err(err) = file_list.rename_with_modification_time(false, Some(pipe)) {
+            eprintln!("{err}");
+            process::exit(err.exit_code());
+        }
+        return;
+    }
+    if !output.is_table() {
+        file_list.print_records(output);
+        return;
+    }
+    if let Err(err) = file_list.rename_with_modification_time(true, None) {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
 }
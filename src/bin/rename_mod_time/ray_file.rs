@@ -1,205 +1,279 @@
+use super::{cli::PROGRAM_NAME, rotation::Rotation};
 use chrono::{DateTime, Local};
-use regex::Regex;
+use ray_commands::{
+    conflict::ConflictStrategy,
+    dest_map::DestMap,
+    error::{self, Error},
+    failure::FailurePolicy,
+    filesystem::{DiskFilesystem, ReadOnly},
+    guard::check_batch_size,
+    hooks::Hooks,
+    naming::{derive_names, plan_names, read_existing, FileSnapshot, NamingOptions},
+    output::{OutputFormat, RecordStream, Report},
+    pipe::Pipe,
+    plan::{apply_chunks, RenamePlan},
+    preset::TimeFormats,
+    report::Reports,
+    time_format::{get_time_source, TimeSource},
+};
 use std::{
-    cmp::max,
-    fs,
-    io::{self, Write}, // Write for flush
-    iter::zip,
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::Duration,
 };
 
-// following Unicode standard 15.1.0
-// reference: https://en.wikipedia.org/w/index.php?title=CJK_Unified_Ideographs&direction=next&oldid=1203587452
-const CHINESE_UNICODE_RANGE: [[u32; 2]; 6] = [
-    [0x04_E00, 0x09_FFF], // unified basic chars
-    [0x03_400, 0x04_DBF], // extension A
-    [0x20_000, 0x2A_6DF], // extension B
-    [0x2A_700, 0x2E_E5F], // extensions C, D, E, F, I
-    [0x30_000, 0x32_3AF], // extensions G, H
-    [0x0F_900, 0x0F_AFF], // round-trip compatibility
-                          // [0x03_300, 0x03_3FF], // non-unified chars for legacy systems
-                          // [0x0F_E30, 0x0E_F4F], // non-unified chars for legacy systems
-                          // [0x0F_900, 0x0F_AFF], // non-unified chars for legacy systems
-                          // [0x2F_800, 0x2F_A1F], // non-unified chars for legacy systems
-];
-
+/// the input files, planned and renamed a chunk at a time, so that the files
+/// of only one chunk are held at once however many there are
 pub struct RayFileList {
-    file_list: Vec<RayFile>,
-    time_format: String,
-    max_len_input: usize,
-    max_len_output: usize,
+    paths: Vec<PathBuf>,
+    naming: NamingOptions,
+    /// the rotation by `--rotate`, and the files it moves to the trash
+    /// instead of renaming
+    rotation: Option<Rotation>,
+    pruned: Vec<PathBuf>,
+    /// what to do with a new name taken by a file outside the chunk
+    conflict: ConflictStrategy,
+    failure: FailurePolicy,
+    hooks: Hooks,
+    is_explained: bool,
+    chunk_size: usize,
+    /// the renames of a chunk shown and confirmed at a time, the whole chunk when none
+    confirm_every: Option<usize>,
+    /// the reports of the run to write, by `--report` and `--report-html`
+    reports: Reports,
+    /// whether the renames are checked once done, by `--verify`
+    is_verified: bool,
 }
 
 impl RayFileList {
-    pub fn from(input_file_list: &Vec<String>, time_format: String) -> Self {
-        let file_list: Vec<RayFile> = input_file_list
-            .iter()
-            .map(|f| RayFile::from(f.clone()))
-            .collect();
-
-        let max_len_input: usize = max(3, file_list.iter().map(|f| f.full_len()).max().unwrap());
-
-        let time_str_len: usize = Local::now().format(&time_format).to_string().len();
-        let max_ext_len: usize = file_list.iter().map(|f| f.ext_len()).max().unwrap();
-        let max_len_output: usize = max(3, time_str_len + 1 + max_ext_len);
-
+    pub fn from(input_file_list: &[PathBuf], time_formats: TimeFormats) -> Self {
         Self {
-            file_list,
-            time_format,
-            max_len_input,
-            max_len_output,
+            paths: input_file_list.to_vec(),
+            naming: NamingOptions {
+                time_formats,
+                time_source: get_time_source(),
+                session_gap: None,
+                dest_map: None,
+            },
+            rotation: None,
+            pruned: Vec::new(),
+            conflict: ConflictStrategy::default(),
+            failure: FailurePolicy::default(),
+            hooks: Hooks::default(),
+            is_explained: false,
+            chunk_size: usize::MAX,
+            confirm_every: None,
+            reports: Reports::default(),
+            is_verified: false,
         }
     }
 
-    pub fn rename_with_modification_time(&self, to_print_prompt: bool) {
-        let new_file_list: Vec<RayFile> = self.get_renamed_file_list();
+    /// a list resolving new names taken by existing files with `conflict`
+    pub fn with_conflict(mut self, conflict: ConflictStrategy) -> Self {
+        self.conflict = conflict;
+        self
+    }
 
-        self.print_renaming_header();
-        self.print_renaming_operations(&new_file_list);
-        if to_print_prompt {
-            let to_rename: bool = self.wait_accepting_prompt();
-            if !to_rename {
-                println!("Nothing done.");
-                return;
-            }
-        }
+    /// a list running `hooks` around the renames of each chunk
+    pub fn with_hooks(mut self, hooks: Hooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
 
-        // rename files
-        zip(&self.file_list, new_file_list).for_each(|(old_file, new_file)| {
-            fs::rename(old_file.to_string(), new_file.to_string()).unwrap()
-        })
-    }
-
-    fn get_renamed_file_list(&self) -> Vec<RayFile> {
-        self.file_list
-            .iter()
-            .map(|f: &RayFile| f.clone().get_renamed_instance(&self.time_format))
-            .collect()
-    }
-
-    /// return whether to rename or not
-    fn wait_accepting_prompt(&self) -> bool {
-        let yes_regex: Regex = Regex::new("^[yY]?$").unwrap();
-        let no_regex: Regex = Regex::new("^[nN]$").unwrap();
-        loop {
-            print!("Accept the above renaming? [Y/n] ");
-            io::stdout().flush().unwrap();
-            let mut buffer: String = String::new();
-            io::stdin().read_line(&mut buffer).unwrap();
-            if yes_regex.is_match(&buffer.trim()) {
-                return true;
-            }
-            if no_regex.is_match(&buffer.trim()) {
-                return false;
-            }
-        }
+    /// a list skipping or asking about the files failing to rename by `failure`
+    pub fn with_failure_policy(mut self, failure: FailurePolicy) -> Self {
+        self.failure = failure;
+        self
     }
 
-    fn print_renaming_header(&self) {
-        println!(
-            "{:^wi$} {:^wo$}",
-            "old",
-            "new",
-            wi = self.max_len_input,
-            wo = self.max_len_output
-        );
+    /// a list showing how each new name was derived in its preview and records
+    pub fn with_explanations(mut self, is_explained: bool) -> Self {
+        self.is_explained = is_explained;
+        self
     }
 
-    fn print_renaming_operations(&self, new_list: &Vec<RayFile>) {
-        zip(&self.file_list, new_list).for_each(|(o, n)| {
-            println!(
-                "{:w$} {}",
-                o.to_string(),
-                n.to_string(),
-                w = self.max_len_input - o.get_chinese_length_offset_value()
-            )
-        });
+    /// a list numbering its files by sessions, a new one starting after
+    /// `session_gap` without a file, by `--sessionize`
+    pub fn with_session_gap(mut self, session_gap: Option<Duration>) -> Self {
+        self.naming.session_gap = session_gap;
+        self
     }
-}
 
-#[derive(Clone)]
-pub struct RayFile {
-    /// excluding the extension (f_ext)
-    f_name: String,
-    f_ext: String,
-}
+    /// a list moving its files to the directories of `dest_map` as they are
+    /// renamed, by `--dest-map`
+    pub fn with_dest_map(mut self, dest_map: Option<DestMap>) -> Self {
+        self.naming.dest_map = dest_map;
+        self
+    }
 
-impl RayFile {
-    pub fn from(f_full_name: String) -> Self {
-        if f_full_name.contains("/") {
-            todo!("file path in different directory. Please remove all slashes.")
+    /// a list moving to the trash the files that `rotation` prunes instead of
+    /// renaming them, along with the renames of its first chunk; given after
+    /// the options naming the files, which make the groups of the rotation
+    pub fn with_rotation(mut self, rotation: Option<Rotation>) -> Self {
+        if let Some(rotation) = rotation {
+            self.pruned = rotation.find_pruned(&self.paths, &self.naming);
+            let is_pruned: HashSet<&PathBuf> = self.pruned.iter().collect();
+            self.paths.retain(|p| !is_pruned.contains(p));
         }
+        self.rotation = rotation;
+        self
+    }
 
-        if f_full_name.starts_with(".") {
-            return Self {
-                f_name: f_full_name,
-                f_ext: String::from(""),
-            };
-        }
+    /// a list previewed, confirmed and renamed `chunk_size` files at a time,
+    /// or all at once without one
+    pub fn with_chunk_size(mut self, chunk_size: Option<usize>) -> Self {
+        self.chunk_size = chunk_size.unwrap_or(usize::MAX);
+        self
+    }
 
-        let ext_dot_position: usize = f_full_name.rfind(".").unwrap();
+    /// a list shown and confirmed in pages of `page_size` renames, each renamed
+    /// or skipped on its own, instead of a chunk at once
+    pub fn with_confirm_every(mut self, page_size: Option<usize>) -> Self {
+        self.confirm_every = page_size;
+        self
+    }
 
-        RayFile {
-            f_name: f_full_name.get(..ext_dot_position).unwrap().to_string(),
-            f_ext: f_full_name
-                .get((ext_dot_position + 1)..)
-                .unwrap()
-                .to_string(),
-        }
+    /// a list writing `reports` of its run
+    pub fn with_reports(mut self, reports: Reports) -> Self {
+        self.reports = reports;
+        self
     }
 
-    fn get_renamed_instance(&self, time_format: &String) -> Self {
-        // reference: https://doc.rust-lang.org/1.76.0/std/fs/struct.Metadata.html#method.modified
-        let metadata: fs::Metadata = fs::metadata(&self.to_string()).unwrap();
-        match metadata.modified() {
-            Err(err) => panic!("Not supported on this platform.\n{err:?}"),
-            Ok(system_time) => {
-                let chrono_time: DateTime<Local> = system_time.into();
-                Self {
-                    f_name: chrono_time.format(time_format).to_string(),
-                    f_ext: self.f_ext.clone(),
-                }
-            }
-        }
+    /// a list checking that its renames hold on disk once done, failing if not
+    pub fn with_verification(mut self, is_verified: bool) -> Self {
+        self.is_verified = is_verified;
+        self
     }
 
-    fn get_chinese_length_offset_value(&self) -> usize {
-        self.f_name
-            .chars()
-            .map(|c| {
-                if c.is_ascii() {
-                    0
-                } else if CHINESE_UNICODE_RANGE
-                    .iter()
-                    .any(|r| c as u32 >= r[0] && c as u32 <= r[1])
-                {
-                    1
-                } else {
-                    0 // unknown
+    /// the plans of the chunks in turn, each made only when reached; a file
+    /// whose new name is that of a file of a later chunk waits for that chunk,
+    /// so that chains and cycles across chunks are ordered as one
+    fn plans(&self) -> impl Iterator<Item = RenamePlan> + '_ {
+        // the chunk of each file, when there are several
+        let chunk_of: HashMap<&Path, usize> = match self.paths.len() > self.chunk_size {
+            true => self
+                .paths
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (p.as_path(), i / self.chunk_size))
+                .collect(),
+            false => HashMap::new(),
+        };
+        let mut waiting: Vec<PathBuf> = Vec::new();
+        self.paths
+            .chunks(self.chunk_size)
+            .enumerate()
+            .map(move |(index, chunk)| {
+                let paths: Vec<PathBuf> = waiting.drain(..).chain(chunk.iter().cloned()).collect();
+                let is_later = |target: &Path| chunk_of.get(target).is_some_and(|c| *c > index);
+                let (mut plan, later) = self.plan(&paths, is_later);
+                waiting = later;
+                if index == 0 {
+                    self.add_pruned(&mut plan);
                 }
+                plan
             })
-            .sum()
     }
 
-    fn full_len(&self) -> usize {
-        self.f_name.len()
-            + if self.f_ext.is_empty() {
-                0
-            } else {
-                1 + self.f_ext.len()
+    /// the plan renaming `paths` by their times, read once into snapshots, the
+    /// new names taken by files outside the plan resolved by the conflict
+    /// strategy; a file whose metadata cannot be read is skipped. The files
+    /// whose new names are those of files of later chunks, by `is_later`, are
+    /// left out and returned
+    fn plan(
+        &self,
+        paths: &[PathBuf],
+        is_later: impl Fn(&Path) -> bool,
+    ) -> (RenamePlan, Vec<PathBuf>) {
+        let mut snapshots: Vec<FileSnapshot> = Vec::with_capacity(paths.len());
+        let mut unreadable: Vec<(PathBuf, String)> = Vec::new();
+        for path in paths {
+            match FileSnapshot::read(path) {
+                Ok(snapshot) => snapshots.push(snapshot),
+                Err(err) => {
+                    let reason: String = Error::from_io(err, path).to_string();
+                    unreadable.push((path.clone(), reason));
+                }
+            }
+        }
+        let mut later: Vec<PathBuf> = Vec::new();
+        if self.paths.len() > self.chunk_size {
+            let (names, _) = derive_names(&snapshots, &self.naming);
+            let mut names = names.into_iter();
+            snapshots.retain(|s| match names.next() {
+                Some(Ok(name)) if is_later(&name) => {
+                    later.push(s.path.clone());
+                    false
+                }
+                _ => true,
+            });
+        }
+        let existing: HashSet<PathBuf> =
+            read_existing(&snapshots, &self.naming, ReadOnly::new(&DiskFilesystem));
+        let mut plan: RenamePlan = plan_names(&snapshots, existing, &self.naming, self.conflict)
+            .with_hooks(self.hooks.clone())
+            .with_failure_policy(self.failure)
+            .with_explanations(self.is_explained)
+            .with_verification(self.is_verified)
+            .with_confirm_every(self.confirm_every)
+            .with_reports(self.reports.clone());
+        for (path, reason) in unreadable {
+            plan.add(path, Err(reason));
+        }
+        if self.is_explained {
+            for snapshot in &snapshots {
+                if let Some(explanation) = explain(snapshot, self.naming.time_source) {
+                    plan.explain(&snapshot.path, explanation);
+                }
             }
+        }
+        (plan, later)
     }
 
-    fn ext_len(&self) -> usize {
-        self.f_ext.len()
+    /// add the moves of the files pruned by the rotation to the trash to `plan`
+    fn add_pruned(&self, plan: &mut RenamePlan) {
+        let Some(rotation) = self.rotation else {
+            return;
+        };
+        for path in &self.pruned {
+            if self.is_explained {
+                plan.explain(path, rotation.explain());
+            }
+            plan.trash(path.clone());
+        }
     }
-}
 
-impl std::fmt::Display for RayFile {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.f_ext.is_empty() {
-            write!(f, "{}", self.f_name)
-        } else {
-            write!(f, "{}.{}", self.f_name, self.f_ext)
+    /// refuse a batch too large, then preview, confirm and rename each chunk in
+    /// turn, or each page of it by `--confirm-every`, journaling the renames of
+    /// the whole run for `--undo` and `--resume`, and the files pruned by the
+    /// rotation moving to the trash with the first chunk; stop at a chunk not
+    /// accepted, at Ctrl-C or at a rename failing unless `--on-error` skips
+    /// it. With `pipe`, plan or, by `--apply`, rename without asking, writing
+    /// a record of each file chunk by chunk instead of the preview
+    pub fn rename_with_modification_time(
+        &self,
+        to_print_prompt: bool,
+        pipe: Option<&mut Pipe>,
+    ) -> error::Result<bool> {
+        if pipe.as_ref().is_none_or(|p| p.is_applied()) {
+            check_batch_size(self.paths.len() + self.pruned.len())?;
         }
+        apply_chunks(PROGRAM_NAME, self.plans(), to_print_prompt, pipe)
     }
+
+    /// print the renames chunk by chunk as records in `output`, not a table
+    pub fn print_records(&self, output: OutputFormat) {
+        let mut stream: RecordStream = RecordStream::new(output);
+        for plan in self.plans() {
+            stream.print(&plan.records());
+        }
+        stream.finish();
+    }
+}
+
+/// where the new name of `snapshot` came from, with the UTC offset of the time
+/// in it
+fn explain(snapshot: &FileSnapshot, source: TimeSource) -> Option<String> {
+    let time: DateTime<Local> = snapshot.time(source).ok()?.into();
+    Some(source.explain(&time.format("%:z").to_string()))
 }
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Local};
+use ray_commands::{
+    i18n::{tr, Message},
+    naming::{derive_name, FileSnapshot, NamingOptions},
+};
+use std::{cmp::Reverse, collections::HashMap, path::PathBuf};
+
+/// the rotation of backups by `--rotate keep=N`: the files sharing a new name,
+/// as a weekly or monthly format gives them, are a group, and those of all but
+/// the `keep` newest groups go to the trash instead of being renamed
+#[derive(Clone, Copy)]
+pub struct Rotation {
+    keep: usize,
+}
+
+/// parse the `keep=N` of `--rotate`
+pub fn parse_rotation(text: &str) -> Result<Rotation, String> {
+    match text.strip_prefix("keep=").map(str::parse::<usize>) {
+        Some(Ok(keep)) if keep > 0 => Ok(Rotation { keep }),
+        _ => Err(tr(Message::InvalidRotation, &[&text])),
+    }
+}
+
+impl Rotation {
+    /// the files of `paths` in the groups beyond the newest ones, a group
+    /// being as new as its newest file; a file not named is in none
+    pub fn find_pruned(&self, paths: &[PathBuf], naming: &NamingOptions) -> Vec<PathBuf> {
+        let mut groups: HashMap<PathBuf, (DateTime<Local>, Vec<PathBuf>)> = HashMap::new();
+        for path in paths {
+            let Ok(snapshot) = FileSnapshot::read(path) else {
+                continue;
+            };
+            let (Ok(time), Ok(name)) = (
+                snapshot.time(naming.time_source),
+                derive_name(&snapshot, naming),
+            ) else {
+                continue;
+            };
+            let time: DateTime<Local> = time.into();
+            let group = groups.entry(name).or_insert((time, Vec::new()));
+            group.0 = group.0.max(time);
+            group.1.push(path.clone());
+        }
+        let mut groups: Vec<(DateTime<Local>, Vec<PathBuf>)> = groups.into_values().collect();
+        groups.sort_by_key(|g| Reverse(g.0));
+        let mut pruned: Vec<PathBuf> = groups
+            .into_iter()
+            .skip(self.keep)
+            .flat_map(|(_, paths)| paths)
+            .collect();
+        pruned.sort();
+        pruned
+    }
+
+    /// how a pruned file comes to be moved to the trash, for `--explain`
+    pub fn explain(&self) -> String {
+        match self.keep {
+            1 => tr(Message::ExplainBeyondNewestGroup, &[]),
+            keep => tr(Message::ExplainBeyondNewestGroups, &[&keep]),
+        }
+    }
+}
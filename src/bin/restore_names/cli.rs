@@ -0,0 +1,61 @@
+use clap::{crate_version, value_parser, Arg, Command};
+use ray_commands::{
+    backup::get_backup_args, clock::get_now_arg, hash::get_no_hash_cache_arg, i18n::get_lang_arg,
+    jobs::get_jobs_arg, output::get_output_arg, prompt::get_yes_arg, retry::get_retry_args,
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "restore_names";
+
+const SHORT_DESCRIPTION: &str =
+    "Rename files back to the names recorded in a journal,\nCSV mapping or JSON manifest.";
+
+const FORMAT_HELP_MESSAGE: &str = r#"The format of the record, guessed from its extension by default:
+  journal: JSON lines of {"from", "to"} renames (.jsonl, or the
+           .cu-journal.json kept inside a directory, its names relative
+           to that directory whatever --dir)
+  csv:     old,new mapping, renaming "new" back to "old" (.csv, .tsv)
+  json:    manifest of names with sizes and hashes (.json)"#;
+
+const BACKUP_HELP_MESSAGE: &str = r#"Restore a name taken by another file too, keeping that file beside it
+as a backup, instead of skipping the restoration; CONTROL is as for
+the renaming tools, $VERSION_CONTROL or existing by default"#;
+
+pub fn get_cli_parser() -> Command {
+    let [backup, suffix] = get_backup_args();
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_no_hash_cache_arg())
+        .arg(get_jobs_arg())
+        .arg(get_yes_arg())
+        .arg(get_output_arg())
+        .args(get_retry_args())
+        .arg(backup.help(BACKUP_HELP_MESSAGE))
+        .arg(suffix)
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .help(FORMAT_HELP_MESSAGE)
+                .value_parser(["journal", "csv", "json"])
+                .required(false),
+        )
+        .arg(
+            Arg::new("dir")
+                .short('d')
+                .long("dir")
+                .help("The directory the recorded names are relative to")
+                .value_parser(value_parser!(PathBuf))
+                .default_value("."),
+        )
+        .arg(
+            Arg::new("record")
+                .help("The path to the journal, mapping or manifest")
+                .value_parser(value_parser!(PathBuf))
+                .required(true),
+        )
+}
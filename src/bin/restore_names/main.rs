@@ -0,0 +1,53 @@
+use ray_commands::hash::set_hash_cache;
+use ray_commands::jobs::set_jobs;
+use ray_commands::output::{get_output_format, print_report, OutputFormat};
+use ray_commands::prompt::set_assume_yes;
+use ray_commands::restore::{RecordFormat, RestorePlan};
+use ray_commands::retry::set_retry_policy;
+use ray_commands::{
+    backup::set_backup_policy,
+    clock::set_now,
+    i18n::{set_language, tr, Message},
+};
+use std::{path::PathBuf, process};
+
+pub mod cli;
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    set_hash_cache(&matches);
+    set_jobs(&matches);
+    set_assume_yes(&matches);
+    set_retry_policy(&matches);
+    set_backup_policy(&matches);
+    let record: PathBuf = matches.get_one::<PathBuf>("record").unwrap().clone();
+    let dir: PathBuf = matches.get_one::<PathBuf>("dir").unwrap().clone();
+    let format: RecordFormat = match matches.get_one::<String>("format") {
+        Some(name) => RecordFormat::from(name),
+        None => RecordFormat::guess(&record),
+    };
+    let output: OutputFormat = get_output_format(&matches);
+
+    let plan: RestorePlan = match RestorePlan::load(&record, format, &dir) {
+        Ok(plan) => plan,
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToRead, &[&record.display(), &err]));
+            process::exit(err.exit_code());
+        }
+    };
+    if !output.is_table() {
+        print_report(&plan, output);
+        return;
+    }
+    if let Err(err) = plan.restore_names(true) {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
+}
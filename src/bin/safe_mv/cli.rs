@@ -0,0 +1,122 @@
+use super::preserve::ATTRIBUTE_NAMES;
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::conflict::{CONFLICT_HELP_MESSAGE, CONFLICT_STRATEGY_NAMES};
+use ray_commands::{
+    backup::get_backup_args, clock::get_now_arg, guard::get_guard_args,
+    hash::get_no_hash_cache_arg, i18n::get_lang_arg, jobs::get_jobs_arg, notify::get_notify_arg,
+    output::get_output_arg, portable::get_portable_arg, retry::get_retry_args,
+    stats::get_stats_arg, verify::get_verify_arg,
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "safe_mv";
+
+const SHORT_DESCRIPTION: &str =
+    "Move files and directories, verifying the checksum of every copy across\ndevices before deleting its source, and resuming interrupted copies.";
+
+const COPY_HELP_MESSAGE: &str = r#"Copy the files instead, leaving the sources in place; on the same
+btrfs, XFS or APFS volume, the copies are clones made at once and
+sharing the data of their sources until changed"#;
+
+const SKIP_DUPLICATES_HELP_MESSAGE: &str = r#"With --copy, leave out the files whose content is already at the
+destination, by hash, or copied by the same run, reporting them as
+duplicates, e.g. photos imported again from a card not wiped"#;
+
+const PRESERVE_HELP_MESSAGE: &str = r#"What a file copied across devices or by --copy keeps of its source,
+so that it looks as if it were renamed; comma-separated:
+  mode:       the permissions
+  timestamps: the access and modification times
+  tags:       the Finder tags, on macOS
+  xattr:      the extended attributes, Finder tags included
+  all:        all of them"#;
+
+const STRIP_ADS_HELP_MESSAGE: &str = r#"On Windows, leave the alternate data streams of a file behind when
+copying it, e.g. the Zone.Identifier marking a download, rather than
+keeping them"#;
+
+const DESTINATION_HELP_MESSAGE: &str = r#"The directory to move into, or the new path of a single source;
+running the same command again resumes an interrupted move"#;
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_output_arg())
+        .args(get_guard_args())
+        .args(get_retry_args())
+        .arg(get_jobs_arg())
+        .arg(get_stats_arg())
+        .arg(get_notify_arg())
+        .arg(
+            Arg::new("on_conflict")
+                .long("on-conflict")
+                .help(CONFLICT_HELP_MESSAGE)
+                .value_parser(CONFLICT_STRATEGY_NAMES)
+                .default_value("skip"),
+        )
+        .args(get_backup_args())
+        .arg(
+            Arg::new("copy")
+                .long("copy")
+                .help(COPY_HELP_MESSAGE)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("skip_duplicates")
+                .long("skip-duplicates")
+                .help(SKIP_DUPLICATES_HELP_MESSAGE)
+                .requires("copy")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(get_no_hash_cache_arg())
+        .arg(
+            Arg::new("preserve")
+                .long("preserve")
+                .help(PRESERVE_HELP_MESSAGE)
+                .value_name("ATTRIBUTES")
+                .value_parser(ATTRIBUTE_NAMES)
+                .value_delimiter(',')
+                .default_value("mode,timestamps,tags"),
+        )
+        .arg(
+            Arg::new("no_preserve")
+                .long("no-preserve")
+                .help("What a copy does not keep of its source, as for --preserve")
+                .value_name("ATTRIBUTES")
+                .value_parser(ATTRIBUTE_NAMES)
+                .value_delimiter(','),
+        )
+        .arg(
+            Arg::new("strip_ads")
+                .long("strip-ads")
+                .help(STRIP_ADS_HELP_MESSAGE)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(get_verify_arg())
+        .arg(get_portable_arg())
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .help("Print each file moved or copied, and how: renamed, cloned or copied")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("input_paths")
+                .help("The path(s) to the file(s) or directory(ies) to move")
+                .value_parser(value_parser!(PathBuf))
+                .required(true)
+                .num_args(1..)
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("destination")
+                .help(DESTINATION_HELP_MESSAGE)
+                .value_parser(value_parser!(PathBuf))
+                .required(true),
+        )
+}
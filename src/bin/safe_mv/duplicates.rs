@@ -0,0 +1,88 @@
+use ray_commands::{
+    hash::hash_file_cached,
+    walk::{walk_files, WalkEntry},
+};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// the files at the destination of a copy by size, hashed only once a source
+/// of their size comes, to tell the sources already there by `--skip-duplicates`
+pub struct Duplicates {
+    by_size: HashMap<u64, Vec<KnownFile>>,
+}
+
+/// a file at the destination, or a source to be copied there
+struct KnownFile {
+    path: PathBuf,
+    /// the file holding its content, the source of one yet to be copied
+    content: PathBuf,
+    hash: Option<String>,
+}
+
+impl Duplicates {
+    /// the files below the directory `destination`, or the file itself
+    pub fn scan(destination: &Path) -> io::Result<Self> {
+        let mut by_size: HashMap<u64, Vec<KnownFile>> = HashMap::new();
+        let files: Vec<(PathBuf, fs::Metadata)> = match fs::metadata(destination) {
+            Ok(metadata) if metadata.is_dir() => walk_files(destination)?
+                .into_iter()
+                .filter(|e| e.metadata.is_file() && !is_partial(e))
+                .map(|e| (e.path, e.metadata))
+                .collect(),
+            Ok(metadata) => vec![(destination.to_path_buf(), metadata)],
+            Err(err) if err.kind() == io::ErrorKind::NotFound => vec![],
+            Err(err) => return Err(err),
+        };
+        for (path, metadata) in files {
+            by_size.entry(metadata.len()).or_default().push(KnownFile {
+                content: path.clone(),
+                path,
+                hash: None,
+            });
+        }
+        Ok(Self { by_size })
+    }
+
+    /// the file at the destination with the content of `source`, if any; a
+    /// source with none is added as if already copied to `target`, so that
+    /// copies among the sources are told too
+    pub fn find(&mut self, source: &Path, target: &Path) -> io::Result<Option<PathBuf>> {
+        let metadata: fs::Metadata = fs::metadata(source)?;
+        if !metadata.is_file() {
+            return Ok(None);
+        }
+        let files: &mut Vec<KnownFile> = self.by_size.entry(metadata.len()).or_default();
+        // a source of a size new to the destination is hashed only if another comes
+        let hash: Option<String> = match files.is_empty() {
+            true => None,
+            false => Some(hash_file_cached(source)?),
+        };
+        for file in files.iter_mut() {
+            // a file unreadable at the destination matches no source
+            let file_hash: &String = file
+                .hash
+                .get_or_insert_with(|| hash_file_cached(&file.content).unwrap_or_default());
+            if hash.as_ref() == Some(file_hash) {
+                return Ok(Some(file.path.clone()));
+            }
+        }
+        files.push(KnownFile {
+            path: target.to_path_buf(),
+            content: source.to_path_buf(),
+            hash,
+        });
+        Ok(None)
+    }
+}
+
+/// whether the entry is the partial file of an interrupted copy
+fn is_partial(entry: &WalkEntry) -> bool {
+    entry
+        .path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .is_some_and(|n| n.starts_with('.') && n.ends_with(".part"))
+}
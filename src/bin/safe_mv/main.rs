@@ -0,0 +1,87 @@
+use mover::MovePlan;
+use preserve::Preserved;
+use ray_commands::{
+    backup::set_backup_policy,
+    clock::set_now,
+    conflict::ConflictStrategy,
+    error,
+    filesystem::{DiskFilesystem, ReadOnly},
+    guard::{check_batch_size, check_paths, set_guard},
+    hash::set_hash_cache,
+    i18n::{set_language, tr, Message},
+    jobs::set_jobs,
+    notify::set_notify,
+    output::{get_output_format, print_report, OutputFormat},
+    portable::{check_portable, set_portable},
+    retry::set_retry_policy,
+    stats::set_stats_format,
+};
+use std::{path::PathBuf, process};
+
+pub mod cli;
+mod duplicates;
+mod mover;
+mod preserve;
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    set_guard(&matches);
+    set_retry_policy(&matches);
+    set_portable(&matches);
+    set_jobs(&matches);
+    set_backup_policy(&matches);
+    set_stats_format(&matches);
+    set_notify(&matches);
+    set_hash_cache(&matches);
+    let input_paths: Vec<PathBuf> = matches
+        .get_many::<PathBuf>("input_paths")
+        .unwrap()
+        .cloned()
+        .collect();
+    let destination: PathBuf = matches.get_one::<PathBuf>("destination").unwrap().clone();
+    if let Err(err) = check_paths(&input_paths).and_then(|()| check_paths(&[&destination])) {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
+    let conflict: ConflictStrategy =
+        ConflictStrategy::from(matches.get_one::<String>("on_conflict").unwrap());
+    let output: OutputFormat = get_output_format(&matches);
+
+    if input_paths.len() > 1 && !destination.is_dir() {
+        eprintln!("{}", tr(Message::NotADirectory, &[&destination.display()]));
+        process::exit(2);
+    }
+    let is_deduplicated: bool = matches.get_flag("skip_duplicates");
+    let plan: MovePlan = match MovePlan::new(&input_paths, &destination, conflict, is_deduplicated)
+    {
+        Ok(plan) => plan
+            .with_copy(matches.get_flag("copy"))
+            .with_preserved(Preserved::from(&matches))
+            .with_verification(matches.get_flag("verify"))
+            .with_verbose(matches.get_flag("verbose")),
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToReadSources, &[&err]));
+            process::exit(1);
+        }
+    };
+    if !output.is_table() {
+        print_report(&plan, output);
+        return;
+    }
+    let checked: error::Result<()> = check_batch_size(plan.file_count())
+        .and_then(|_| check_portable(plan.renames(), ReadOnly::new(&DiskFilesystem)));
+    if let Err(err) = checked {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
+    if !plan.apply() {
+        process::exit(1);
+    }
+}
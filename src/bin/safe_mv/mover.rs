@@ -0,0 +1,391 @@
+use super::{cli::PROGRAM_NAME, duplicates::Duplicates, preserve::Preserved};
+use indicatif::{ProgressBar, ProgressStyle};
+use ray_commands::{
+    backup::set_aside,
+    conflict::ConflictStrategy,
+    hash::hash_file,
+    i18n::{tr, Message},
+    notify::notify_finished,
+    output::Report,
+    plan::{RenamePlan, RenameRecord},
+    retry::with_retries,
+    stats::RunStats,
+    throttle::pace_bytes,
+    verify::{check_copy, check_rename},
+    walk::{walk_entries, WalkEntry},
+};
+use reflink_copy::reflink;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+const BUFFER_SIZE: usize = 1 << 20;
+
+const PROGRESS_TEMPLATE: &str = "{wide_msg} {bytes}/{total_bytes} [{bar:30}] {bytes_per_sec} {eta}";
+
+pub struct MovePlan {
+    /// the moves of single files, resolved against existing files
+    plan: RenamePlan,
+    /// source directories to remove once emptied, deepest first
+    source_dirs: Vec<PathBuf>,
+    /// the sources left out by `--skip-duplicates`, with the files holding
+    /// their content at the destination
+    duplicates: Vec<(PathBuf, PathBuf)>,
+    is_deduplicated: bool,
+    total_size: u64,
+    /// whether the sources stay in place, by `--copy`
+    is_copy: bool,
+    /// whether each file is printed with how it got to its target
+    is_verbose: bool,
+    /// what a file copied keeps of its source
+    preserved: Preserved,
+    /// whether each file is checked once moved or copied, by `--verify`
+    is_verified: bool,
+}
+
+/// how a file got to its target
+#[derive(Clone, Copy)]
+enum Transfer {
+    /// renamed on the same file system
+    Renamed,
+    /// cloned by reflink or clonefile, sharing the data of its source
+    Cloned,
+    /// copied byte by byte and verified
+    Copied,
+}
+
+impl Transfer {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Renamed => "renamed",
+            Self::Cloned => "cloned",
+            Self::Copied => "copied",
+        }
+    }
+}
+
+impl MovePlan {
+    /// plan moving `input_paths` into the directory `destination`, or to `destination`
+    /// itself when it is not a directory; directories are moved file by file,
+    /// and files whose content is already at the destination are left out
+    /// when `is_deduplicated`
+    pub fn new(
+        input_paths: &[PathBuf],
+        destination: &Path,
+        conflict: ConflictStrategy,
+        is_deduplicated: bool,
+    ) -> io::Result<Self> {
+        let mut plan: RenamePlan = RenamePlan::with_conflict(conflict);
+        let mut source_dirs: Vec<PathBuf> = vec![];
+        let mut known: Option<Duplicates> = match is_deduplicated {
+            true => Some(Duplicates::scan(destination)?),
+            false => None,
+        };
+        let mut duplicates: Vec<(PathBuf, PathBuf)> = vec![];
+        let mut add = |source: PathBuf, target: PathBuf| -> io::Result<()> {
+            match known
+                .as_mut()
+                .map(|k| k.find(&source, &target))
+                .transpose()?
+            {
+                Some(Some(original)) => duplicates.push((source, original)),
+                _ => plan.add(source, Ok(target)),
+            }
+            Ok(())
+        };
+        for source in input_paths {
+            let target: PathBuf = match (destination.is_dir(), source.file_name()) {
+                (true, Some(name)) => destination.join(name),
+                _ => destination.to_path_buf(),
+            };
+            if !fs::symlink_metadata(source)?.is_dir() {
+                add(source.clone(), target)?;
+                continue;
+            }
+            let entries: Vec<WalkEntry> = walk_entries(source)?;
+            for entry in entries.iter().rev() {
+                if entry.metadata.is_dir() {
+                    source_dirs.push(entry.path.clone());
+                } else {
+                    add(entry.path.clone(), target.join(&entry.relative))?;
+                }
+            }
+            source_dirs.push(source.clone());
+        }
+
+        let total_size: u64 = plan
+            .renames()
+            .iter()
+            .filter_map(|(source, _)| fs::symlink_metadata(source).ok())
+            .map(|m| m.len())
+            .sum();
+        Ok(Self {
+            plan,
+            source_dirs,
+            duplicates,
+            is_deduplicated,
+            total_size,
+            is_copy: false,
+            is_verbose: false,
+            preserved: Preserved::default(),
+            is_verified: false,
+        })
+    }
+
+    /// a plan copying the files instead, leaving the sources in place
+    pub fn with_copy(self, is_copy: bool) -> Self {
+        Self { is_copy, ..self }
+    }
+
+    /// a plan keeping `preserved` of the source of each file copied
+    pub fn with_preserved(self, preserved: Preserved) -> Self {
+        Self { preserved, ..self }
+    }
+
+    /// a plan checking each file once moved or copied, failing it if it did
+    /// not get there intact
+    pub fn with_verification(self, is_verified: bool) -> Self {
+        Self {
+            is_verified,
+            ..self
+        }
+    }
+
+    /// a plan printing each file with how it got to its target
+    pub fn with_verbose(self, is_verbose: bool) -> Self {
+        Self { is_verbose, ..self }
+    }
+
+    /// the files to move
+    pub fn file_count(&self) -> usize {
+        self.plan.renames().len()
+    }
+
+    /// the moves of single files, as sources and targets
+    pub fn renames(&self) -> &[(PathBuf, PathBuf)] {
+        self.plan.renames()
+    }
+
+    /// move or copy every file, showing the progress and notifying of the
+    /// outcome when asked to; return whether all files were moved or copied
+    pub fn apply(&self) -> bool {
+        self.plan.skipped().iter().for_each(|(p, reason)| {
+            eprintln!("{}", tr(Message::Skipping, &[&p.display(), reason]))
+        });
+        self.duplicates.iter().for_each(|(source, original)| {
+            let reason: String = get_duplicate_reason(original);
+            eprintln!("{}", tr(Message::Skipping, &[&source.display(), &reason]));
+        });
+
+        let progress: ProgressBar = ProgressBar::new(self.total_size);
+        progress.set_style(
+            ProgressStyle::with_template(PROGRESS_TEMPLATE)
+                .unwrap()
+                .progress_chars("=> "),
+        );
+        let mut stats: RunStats = RunStats {
+            skipped: self.plan.skipped().len(),
+            conflicts: self.plan.conflicts(),
+            bytes_moved: Some(0),
+            duplicates: self.is_deduplicated.then_some(self.duplicates.len()),
+            ..RunStats::default()
+        };
+        let start: Instant = Instant::now();
+        for (source, target) in self.plan.renames() {
+            progress.set_message(source.display().to_string());
+            let size: u64 = fs::symlink_metadata(source).map_or(0, |m| m.len());
+            let result: io::Result<Transfer> = match self.is_copy {
+                true => self.copy_file(source, target, &progress),
+                false => self.move_file(source, target, &progress),
+            }
+            .and_then(|transfer| match self.check(source, target) {
+                Some(problem) => Err(io::Error::other(problem)),
+                None => Ok(transfer),
+            });
+            match result {
+                Ok(transfer) => {
+                    if self.is_verbose {
+                        progress.suspend(|| {
+                            let (source, target) = (source.display(), target.display());
+                            println!("{source} -> {target} ({})", transfer.name());
+                        });
+                    }
+                    stats.renamed += 1;
+                    stats.bytes_moved = stats.bytes_moved.map(|b| b + size);
+                }
+                Err(err) => {
+                    let message: Message = match self.is_copy {
+                        true => Message::FailedToCopy,
+                        false => Message::FailedToMove,
+                    };
+                    progress.suspend(|| eprintln!("{}", tr(message, &[&source.display(), &err])));
+                    stats.failed += 1;
+                }
+            }
+        }
+        progress.finish_and_clear();
+        let stats: RunStats = stats.with_elapsed(start.elapsed());
+        stats.print();
+
+        // only emptied directories go, so sources of failed moves stay in place
+        if !self.is_copy {
+            self.source_dirs.iter().for_each(|dir| {
+                let _ = fs::remove_dir(dir);
+            });
+        }
+        let is_done: bool = self.plan.skipped().is_empty() && stats.failed == 0;
+        notify_finished(PROGRAM_NAME, &stats.summary(), !is_done);
+        is_done
+    }
+
+    fn move_file(
+        &self,
+        source: &Path,
+        target: &Path,
+        progress: &ProgressBar,
+    ) -> io::Result<Transfer> {
+        self.prepare_target(target)?;
+        match with_retries(|| fs::rename(source, target)) {
+            Ok(()) => {
+                progress.inc(fs::symlink_metadata(target)?.len());
+                Ok(Transfer::Renamed)
+            }
+            Err(err) if err.kind() == io::ErrorKind::CrossesDevices => {
+                copy_verified(source, target, progress, self.preserved)?;
+                fs::remove_file(source)?;
+                Ok(Transfer::Copied)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// copy `source` to `target` as a clone when their file system can, or
+    /// else byte by byte as across devices
+    fn copy_file(
+        &self,
+        source: &Path,
+        target: &Path,
+        progress: &ProgressBar,
+    ) -> io::Result<Transfer> {
+        self.prepare_target(target)?;
+        // a partial file left by an interrupted copy is continued instead
+        if !get_partial_path(target).exists() && clone_file(source, target, self.preserved).is_ok()
+        {
+            progress.inc(fs::symlink_metadata(target)?.len());
+            return Ok(Transfer::Cloned);
+        }
+        copy_verified(source, target, progress, self.preserved)?;
+        Ok(Transfer::Copied)
+    }
+
+    /// what is wrong with `target` once moved or copied from `source`, by
+    /// `--verify`
+    fn check(&self, source: &Path, target: &Path) -> Option<String> {
+        match (self.is_verified, self.is_copy) {
+            (false, _) => None,
+            (true, true) => check_copy(source, target),
+            (true, false) => check_rename(source, target, false),
+        }
+    }
+
+    /// make way for a file at `target`: set aside the file it replaces and
+    /// create its directory
+    fn prepare_target(&self, target: &Path) -> io::Result<()> {
+        if self.plan.is_overwrite(target) {
+            set_aside(target)?;
+        }
+        if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        Ok(())
+    }
+}
+
+impl Report for MovePlan {
+    type Record = RenameRecord;
+
+    fn records(&self) -> Vec<RenameRecord> {
+        let duplicates = self
+            .duplicates
+            .iter()
+            .map(|(source, original)| RenameRecord::skip(source, &get_duplicate_reason(original)));
+        self.plan.records().into_iter().chain(duplicates).collect()
+    }
+
+    fn print_table(&self) {
+        self.plan.print_table();
+    }
+}
+
+fn get_duplicate_reason(original: &Path) -> String {
+    tr(Message::DuplicateOf, &[&original.display()])
+}
+
+/// copy `source` to `target` through a partial file next to `target`, continuing
+/// a partial file left by an interrupted copy, and only give the copy its name
+/// once its checksum matches the source, with the attributes `preserved` of it
+fn copy_verified(
+    source: &Path,
+    target: &Path,
+    progress: &ProgressBar,
+    preserved: Preserved,
+) -> io::Result<()> {
+    let partial: PathBuf = get_partial_path(target);
+    let metadata: fs::Metadata = fs::metadata(source)?;
+    let mut reader: File = File::open(source)?;
+    let mut writer: File = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&partial)?;
+
+    let mut copied: u64 = writer.metadata()?.len();
+    if copied > metadata.len() {
+        writer.set_len(0)?;
+        copied = 0;
+    }
+    reader.seek(SeekFrom::Start(copied))?;
+    progress.inc(copied);
+
+    let mut buffer: Vec<u8> = vec![0; BUFFER_SIZE];
+    loop {
+        let count: usize = reader.read(&mut buffer)?;
+        if count == 0 {
+            break;
+        }
+        writer.write_all(&buffer[..count])?;
+        progress.inc(count as u64);
+        pace_bytes(count as u64);
+    }
+    writer.sync_all()?;
+
+    if hash_file(source)? != hash_file(&partial)? {
+        fs::remove_file(&partial)?;
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            tr(Message::CopyNotIdentical, &[]),
+        ));
+    }
+    drop(writer);
+    preserved.apply(source, &metadata, &partial)?;
+    fs::rename(&partial, target)
+}
+
+/// clone `source` to `target` by reflink or clonefile, with the attributes
+/// `preserved` of it; fails on file systems without clones and across them
+fn clone_file(source: &Path, target: &Path, preserved: Preserved) -> io::Result<()> {
+    let metadata: fs::Metadata = fs::metadata(source)?;
+    reflink(source, target)?;
+    preserved.apply(source, &metadata, target)
+}
+
+/// `.<name>.part` next to `target`
+fn get_partial_path(target: &Path) -> PathBuf {
+    let name: String = target
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    target.with_file_name(format!(".{name}.part"))
+}
@@ -0,0 +1,95 @@
+use clap::ArgMatches;
+use ray_commands::{
+    platform::{copy_data_streams, copy_finder_tags, list_data_streams},
+    xattrs::copy_xattrs,
+};
+use std::{
+    fs::{self, File, FileTimes},
+    io,
+    path::Path,
+};
+
+pub const ATTRIBUTE_NAMES: [&str; 5] = ["mode", "timestamps", "tags", "xattr", "all"];
+
+/// what a copy keeps of its source
+#[derive(Clone, Copy)]
+pub struct Preserved {
+    is_mode: bool,
+    is_timestamps: bool,
+    is_tags: bool,
+    is_xattrs: bool,
+    /// the alternate data streams, unless by `--strip-ads`
+    is_streams: bool,
+}
+
+/// the permissions, times, Finder tags and alternate data streams, as by default
+impl Default for Preserved {
+    fn default() -> Self {
+        Self {
+            is_mode: true,
+            is_timestamps: true,
+            is_tags: true,
+            is_xattrs: false,
+            is_streams: true,
+        }
+    }
+}
+
+impl Preserved {
+    /// the attributes given by `--preserve`, less those given by `--no-preserve`
+    pub fn from(matches: &ArgMatches) -> Self {
+        let names = |id: &str| -> Vec<String> {
+            matches
+                .get_many::<String>(id)
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect()
+        };
+        let (kept, dropped) = (names("preserve"), names("no_preserve"));
+        let is_kept = |name: &str| {
+            let is_named = |names: &[String]| names.iter().any(|n| n == name || n == "all");
+            is_named(&kept) && !is_named(&dropped)
+        };
+        Self {
+            is_mode: is_kept("mode"),
+            is_timestamps: is_kept("timestamps"),
+            is_tags: is_kept("tags"),
+            is_xattrs: is_kept("xattr"),
+            is_streams: !matches.get_flag("strip_ads"),
+        }
+    }
+
+    /// give the copy `target` the attributes to keep of `source`, with the
+    /// `metadata` read before copying it, which read it
+    pub fn apply(&self, source: &Path, metadata: &fs::Metadata, target: &Path) -> io::Result<()> {
+        // the attributes first and the permissions last, which may leave the
+        // copy read-only
+        if self.is_xattrs {
+            copy_xattrs(source, target)?;
+        } else if self.is_tags {
+            copy_finder_tags(source, target)?;
+        }
+        if self.is_streams {
+            // a copy is still a copy without them, e.g. off NTFS
+            if let Err(err) = copy_data_streams(source, target) {
+                let names: Vec<String> = list_data_streams(source).unwrap_or_default();
+                eprintln!(
+                    "Warning: the data streams {} of {} are lost: {err}",
+                    names.join(", "),
+                    source.display()
+                );
+            }
+        }
+        if self.is_timestamps {
+            let times: FileTimes = FileTimes::new()
+                .set_accessed(metadata.accessed()?)
+                .set_modified(metadata.modified()?);
+            File::options().write(true).open(target)?.set_times(times)?;
+        }
+        if self.is_mode {
+            fs::set_permissions(target, metadata.permissions())?;
+        }
+        Ok(())
+    }
+}
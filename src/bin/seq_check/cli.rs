@@ -0,0 +1,56 @@
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::{
+    clock::get_now_arg,
+    i18n::get_lang_arg,
+    output::get_output_arg,
+    sort::get_sort_arg,
+    walk::{get_content_args, get_no_ignore_arg, get_recursion_args, get_size_args, get_type_arg},
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "seq_check";
+
+const SHORT_DESCRIPTION: &str =
+    "Report missing and duplicated numbers in numbered file names\nsuch as IMG_0001.JPG to IMG_0999.JPG.";
+
+const PATTERN_HELP_MESSAGE: &str = r#"A regular expression matching the file names of a series,
+with the number captured as "num", e.g. "^IMG_(?P<num>\d+)\.JPG$".
+Names matching none of the patterns are ignored.
+By default the last group of digits of the name (before the extension) is used."#;
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_output_arg())
+        .arg(
+            Arg::new("pattern")
+                .short('p')
+                .long("pattern")
+                .help(PATTERN_HELP_MESSAGE)
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("recursive")
+                .short('r')
+                .long("recursive")
+                .help("Include the files in subdirectories")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(get_no_ignore_arg())
+        .args(get_recursion_args())
+        .args(get_size_args())
+        .arg(get_sort_arg())
+        .args(get_content_args())
+        .arg(get_type_arg())
+        .arg(
+            Arg::new("dir")
+                .help("The directory holding the numbered files")
+                .value_parser(value_parser!(PathBuf))
+                .required(true),
+        )
+}
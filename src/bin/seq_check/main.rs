@@ -0,0 +1,64 @@
+use ray_commands::{
+    clock::set_now,
+    i18n::{set_language, tr, Message},
+    output::{get_output_format, print_report, OutputFormat},
+    sort::set_sort_order,
+    walk::{
+        set_content_filter, set_entry_types, set_ignore_rules, set_recursion_limits,
+        set_size_limits,
+    },
+};
+use regex::Regex;
+use sequence::SequenceReport;
+use std::{path::PathBuf, process};
+
+pub mod cli;
+mod sequence;
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    set_ignore_rules(&matches);
+    set_recursion_limits(&matches);
+    set_size_limits(&matches);
+    set_sort_order(&matches);
+    set_content_filter(&matches);
+    set_entry_types(&matches);
+    let dir: PathBuf = matches.get_one::<PathBuf>("dir").unwrap().clone();
+    let recursive: bool = matches.get_flag("recursive");
+    let output: OutputFormat = get_output_format(&matches);
+    let mut patterns: Vec<Regex> = Vec::new();
+    for pattern in matches.get_many::<String>("pattern").unwrap_or_default() {
+        match Regex::new(pattern) {
+            Ok(regex) if regex.capture_names().any(|n| n == Some("num")) => patterns.push(regex),
+            Ok(_) => {
+                eprintln!("{}", tr(Message::PatternWithoutNum, &[pattern]));
+                process::exit(2);
+            }
+            Err(err) => {
+                eprintln!("{}", tr(Message::InvalidPattern, &[pattern, &err]));
+                process::exit(2);
+            }
+        }
+    }
+
+    let report: SequenceReport = match SequenceReport::scan(&dir, recursive, &patterns) {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("{}", tr(Message::FailedToRead, &[&dir.display(), &err]));
+            process::exit(2);
+        }
+    };
+
+    print_report(&report, output);
+
+    if report.has_problems() {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,211 @@
+use ray_commands::{
+    i18n::{tr, Message},
+    output::Report,
+    walk::{list_files, walk_files, WalkEntry},
+};
+use regex::{Captures, Match, Regex};
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Display, io, path::Path};
+
+pub struct SequenceReport {
+    series: Vec<Series>,
+}
+
+struct Series {
+    /// the file name with the number replaced by `#`
+    name: String,
+    first: u64,
+    last: u64,
+    count: usize,
+    missing: Vec<NumberRange>,
+    duplicates: Vec<Duplicate>,
+}
+
+struct NumberRange {
+    first: u64,
+    last: u64,
+}
+
+struct Duplicate {
+    number: u64,
+    files: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct SeriesRecord {
+    series: String,
+    first: u64,
+    last: u64,
+    count: usize,
+    /// the missing numbers and ranges, e.g. "4-6, 9"
+    missing: String,
+    /// the numbers taken by more than one file
+    duplicated: String,
+}
+
+impl SequenceReport {
+    pub fn scan(dir: &Path, recursive: bool, patterns: &[Regex]) -> io::Result<Self> {
+        let files: Vec<WalkEntry> = if recursive {
+            walk_files(dir)?
+        } else {
+            list_files(dir)?
+        };
+        let default_pattern: Regex = Regex::new(r"^(.*?)(?P<num>\d+)(\D*)$").unwrap();
+
+        // series name -> number -> files
+        let mut numbers: BTreeMap<String, BTreeMap<u64, Vec<String>>> = BTreeMap::new();
+        for file in files {
+            let relative: String = file.relative.to_string_lossy().into_owned();
+            let numbered: Option<(String, u64)> = if patterns.is_empty() {
+                Self::match_default(&file, &default_pattern)
+            } else {
+                let name: String = file
+                    .path
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned();
+                patterns
+                    .iter()
+                    .find_map(|p| p.captures(&name))
+                    .and_then(|c| Self::split_number(&name, &c))
+            };
+            if let Some((series, number)) = numbered {
+                // files in different directories never belong to the same series
+                let series: String = match file.relative.parent() {
+                    Some(parent) if !parent.as_os_str().is_empty() => {
+                        parent.join(series).to_string_lossy().into_owned()
+                    }
+                    _ => series,
+                };
+                numbers
+                    .entry(series)
+                    .or_default()
+                    .entry(number)
+                    .or_default()
+                    .push(relative);
+            }
+        }
+
+        Ok(Self {
+            series: numbers
+                .into_iter()
+                .map(|(name, files)| Series::from(name, files))
+                .collect(),
+        })
+    }
+
+    pub fn has_problems(&self) -> bool {
+        self.series
+            .iter()
+            .any(|s| !s.missing.is_empty() || !s.duplicates.is_empty())
+    }
+
+    /// number the file by the last group of digits of its stem
+    fn match_default(file: &WalkEntry, pattern: &Regex) -> Option<(String, u64)> {
+        let stem: String = file.path.file_stem()?.to_string_lossy().into_owned();
+        let captures: Captures = pattern.captures(&stem)?;
+        let (series, number) = Self::split_number(&stem, &captures)?;
+        match file.path.extension() {
+            Some(ext) => Some((format!("{series}.{}", ext.to_string_lossy()), number)),
+            None => Some((series, number)),
+        }
+    }
+
+    /// the name with the `num` capture replaced by `#`, and the captured number
+    fn split_number(name: &str, captures: &Captures) -> Option<(String, u64)> {
+        let num: Match = captures.name("num")?;
+        let number: u64 = num.as_str().parse().ok()?;
+        Some((
+            format!("{}#{}", &name[..num.start()], &name[num.end()..]),
+            number,
+        ))
+    }
+}
+
+impl Report for SequenceReport {
+    type Record = SeriesRecord;
+
+    fn records(&self) -> Vec<SeriesRecord> {
+        self.series
+            .iter()
+            .map(|s| SeriesRecord {
+                series: s.name.clone(),
+                first: s.first,
+                last: s.last,
+                count: s.count,
+                missing: s.format_missing(),
+                duplicated: s
+                    .duplicates
+                    .iter()
+                    .map(|d| d.number.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            })
+            .collect()
+    }
+
+    fn print_table(&self) {
+        for s in &self.series {
+            let args: [&dyn Display; 4] = [&s.name, &s.first, &s.last, &s.count];
+            println!("{}", tr(Message::SeriesRange, &args));
+            if !s.missing.is_empty() {
+                println!("  {}", tr(Message::SeriesMissing, &[&s.format_missing()]));
+            }
+            for d in &s.duplicates {
+                let files: String = d.files.join(", ");
+                println!("  {}", tr(Message::SeriesDuplicated, &[&d.number, &files]));
+            }
+        }
+    }
+}
+
+impl Series {
+    fn from(name: String, files: BTreeMap<u64, Vec<String>>) -> Self {
+        let first: u64 = *files.keys().next().unwrap();
+        let last: u64 = *files.keys().next_back().unwrap();
+
+        let mut missing: Vec<NumberRange> = Vec::new();
+        let mut previous: u64 = first;
+        for &number in files.keys().skip(1) {
+            if number > previous + 1 {
+                missing.push(NumberRange {
+                    first: previous + 1,
+                    last: number - 1,
+                });
+            }
+            previous = number;
+        }
+
+        let count: usize = files.values().map(|f| f.len()).sum();
+        let duplicates: Vec<Duplicate> = files
+            .into_iter()
+            .filter(|(_, f)| f.len() > 1)
+            .map(|(number, files)| Duplicate { number, files })
+            .collect();
+
+        Self {
+            name,
+            first,
+            last,
+            count,
+            missing,
+            duplicates,
+        }
+    }
+
+    /// the missing numbers, single or as ranges, e.g. "4-6, 9"
+    fn format_missing(&self) -> String {
+        self.missing
+            .iter()
+            .map(|r| {
+                if r.first == r.last {
+                    r.first.to_string()
+                } else {
+                    format!("{}-{}", r.first, r.last)
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
@@ -0,0 +1,113 @@
+use super::shuffle::COUNTER_SCOPE_NAMES;
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::{
+    clock::get_now_arg,
+    failure::get_on_error_arg,
+    guard::get_guard_args,
+    hardlinks::get_hardlinks_arg,
+    hooks::get_hook_args,
+    i18n::get_lang_arg,
+    jobs::get_jobs_arg,
+    journal::get_journal_in_dir_arg,
+    notify::get_notify_arg,
+    output::{get_output_arg, get_print0_arg},
+    pipe::{get_files_from_arg, get_pipe_args},
+    plan::{get_confirm_every_arg, get_explain_arg, get_resume_arg},
+    portable::get_portable_arg,
+    prompt::get_yes_arg,
+    report::get_report_args,
+    retry::get_retry_args,
+    serve::get_serve_args,
+    sort::get_sort_arg,
+    stats::get_stats_arg,
+    verify::get_verify_arg,
+    walk::{get_content_args, get_size_args},
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "shuffle_names";
+
+const SHORT_DESCRIPTION: &str =
+    "Rename files to numbers in a random order, so that a slideshow or player\nsorting by name plays them shuffled.";
+
+const KEEP_NAME_HELP_MESSAGE: &str = r#"Keep the original name after the number, e.g. 07_beach.jpg
+instead of 07.jpg; either way the original names are journaled for --undo"#;
+
+const COUNTER_SCOPE_HELP_MESSAGE: &str = r#"Number the files across all directories (global), or restart at 1 in
+each directory (per-dir), e.g. to shuffle the photos of many event
+folders at once"#;
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_yes_arg())
+        .arg(get_output_arg())
+        .args(get_guard_args())
+        .args(get_retry_args())
+        .arg(get_stats_arg())
+        .arg(get_notify_arg())
+        .args(get_pipe_args())
+        .arg(get_print0_arg())
+        .arg(get_files_from_arg())
+        .args(get_serve_args())
+        .args(get_hook_args())
+        .arg(get_on_error_arg())
+        .arg(get_explain_arg())
+        .arg(get_verify_arg())
+        .arg(get_portable_arg())
+        .arg(get_confirm_every_arg())
+        .args(get_report_args())
+        .args(get_size_args())
+        .arg(get_sort_arg())
+        .arg(get_hardlinks_arg())
+        .args(get_content_args())
+        .arg(
+            Arg::new("keep_name")
+                .short('k')
+                .long("keep-name")
+                .help(KEEP_NAME_HELP_MESSAGE)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("counter_scope")
+                .long("counter-scope")
+                .help(COUNTER_SCOPE_HELP_MESSAGE)
+                .value_parser(COUNTER_SCOPE_NAMES)
+                .default_value("global"),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .help("Seed the random order, to get the same order again")
+                .value_parser(value_parser!(u64)),
+        )
+        .arg(
+            Arg::new("undo")
+                .long("undo")
+                .help("Undo the latest renaming done by this command")
+                .action(ArgAction::SetTrue)
+                .exclusive(true),
+        )
+        .arg(get_resume_arg())
+        .arg(get_jobs_arg())
+        .arg(get_journal_in_dir_arg())
+        .arg(
+            Arg::new("input_paths")
+                .help("The path(s) to the file(s) to shuffle")
+                .value_parser(value_parser!(PathBuf))
+                .required_unless_present_any([
+                    "undo",
+                    "resume",
+                    "pipe",
+                    "serve",
+                    "serve_socket",
+                    "files_from",
+                ])
+                .action(ArgAction::Append),
+        )
+}
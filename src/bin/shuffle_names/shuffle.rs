@@ -0,0 +1,75 @@
+use fastrand::Rng;
+use ray_commands::{
+    i18n::{tr, Message},
+    plan::RenamePlan,
+};
+use std::path::{Path, PathBuf};
+
+pub const COUNTER_SCOPE_NAMES: [&str; 2] = ["global", "per-dir"];
+
+/// number each file by its position in a random order, zero-padded so that the
+/// names sort in that order; with `per_dir`, the files of each directory are
+/// numbered apart, from 1
+pub fn plan_renames(
+    input_paths: &[PathBuf],
+    keep_name: bool,
+    per_dir: bool,
+    rng: &mut Rng,
+) -> RenamePlan {
+    let groups: Vec<Vec<usize>> = match per_dir {
+        true => group_by_dir(input_paths),
+        false => vec![(0..input_paths.len()).collect()],
+    };
+    // the position of each file among those of its group, and their count
+    let mut positions: Vec<(usize, usize)> = vec![(0, 0); input_paths.len()];
+    for group in groups {
+        let mut drawn: Vec<usize> = (1..=group.len()).collect();
+        rng.shuffle(&mut drawn);
+        for (index, position) in group.iter().zip(drawn) {
+            positions[*index] = (position, group.len());
+        }
+    }
+
+    let mut plan: RenamePlan = RenamePlan::new();
+    let renames: Vec<(PathBuf, Result<PathBuf, String>)> = input_paths
+        .iter()
+        .zip(positions)
+        .map(|(path, (position, count))| {
+            let width: usize = count.to_string().len().max(2);
+            let prefix: String = format!("{position:0width$}");
+            plan.explain(path, tr(Message::ExplainDrawn, &[&position, &count]));
+            (path.clone(), get_new_path(path, &prefix, keep_name))
+        })
+        .collect();
+    plan.add_batch(renames);
+    plan
+}
+
+/// the indices of `paths` grouped by directory, in order of first appearance
+fn group_by_dir(paths: &[PathBuf]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<(Option<&Path>, Vec<usize>)> = Vec::new();
+    for (index, path) in paths.iter().enumerate() {
+        let dir: Option<&Path> = path.parent();
+        match groups.iter_mut().find(|(d, _)| *d == dir) {
+            Some((_, group)) => group.push(index),
+            None => groups.push((dir, vec![index])),
+        }
+    }
+    groups.into_iter().map(|(_, group)| group).collect()
+}
+
+fn get_new_path(path: &Path, prefix: &str, keep_name: bool) -> Result<PathBuf, String> {
+    if !path.is_file() {
+        return Err(tr(Message::NotAFile, &[]));
+    }
+    let name: String = if keep_name {
+        let name: String = path.file_name().unwrap().to_string_lossy().into_owned();
+        format!("{prefix}_{name}")
+    } else {
+        match path.extension() {
+            Some(ext) => format!("{prefix}.{}", ext.to_string_lossy()),
+            None => prefix.to_string(),
+        }
+    };
+    Ok(path.with_file_name(name))
+}
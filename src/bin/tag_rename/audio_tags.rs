@@ -0,0 +1,376 @@
+//! Just enough of ID3v1/ID3v2, FLAC and Ogg Vorbis comments and MP4 `ilst`
+//! atoms to read the tags used in file name templates.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+#[derive(Default)]
+pub struct AudioTags {
+    pub artist: Option<String>,
+    pub album_artist: Option<String>,
+    pub album: Option<String>,
+    pub title: Option<String>,
+    pub genre: Option<String>,
+    pub track: Option<u32>,
+    pub disc: Option<u32>,
+    pub year: Option<String>,
+}
+
+impl AudioTags {
+    /// the tags of the file, or `None` when its format is not recognized
+    pub fn read(path: &Path) -> io::Result<Option<Self>> {
+        let mut file: BufReader<File> = BufReader::new(File::open(path)?);
+        let mut magic: [u8; 8] = [0; 8];
+        let n: usize = file.read(&mut magic)?;
+        file.seek(SeekFrom::Start(0))?;
+
+        let tags: Option<Self> = match &magic[..n] {
+            [b'I', b'D', b'3', ..] => Some(Self::read_id3v2(&mut file)?),
+            [b'f', b'L', b'a', b'C', ..] => Some(Self::read_flac(&mut file)?),
+            [b'O', b'g', b'g', b'S', ..] => Some(Self::read_ogg(&mut file)?),
+            [_, _, _, _, b'f', b't', b'y', b'p'] => Some(Self::read_mp4(&mut file)?),
+            _ => Self::read_id3v1(&mut file)?,
+        };
+        Ok(tags)
+    }
+
+    fn read_id3v2<R: Read>(file: &mut R) -> io::Result<Self> {
+        let mut header: [u8; 10] = [0; 10];
+        file.read_exact(&mut header)?;
+        let version: u8 = header[3];
+        let flags: u8 = header[5];
+        let mut data: Vec<u8> = vec![0; read_synchsafe(&header[6..10]) as usize];
+        file.read_exact(&mut data)?;
+        if flags & 0x80 != 0 && version < 4 {
+            data = remove_unsynchronisation(&data);
+        }
+
+        let mut position: usize = 0;
+        if flags & 0x40 != 0 && version >= 3 && data.len() >= 4 {
+            let size: usize = if version == 3 {
+                4 + u32::from_be_bytes(data[..4].try_into().unwrap()) as usize
+            } else {
+                read_synchsafe(&data[..4]) as usize
+            };
+            position = size;
+        }
+
+        let mut tags: Self = Self::default();
+        let (id_len, header_len): (usize, usize) = if version == 2 { (3, 6) } else { (4, 10) };
+        while position + header_len <= data.len() && data[position] != 0 {
+            let id: String = String::from_utf8_lossy(&data[position..position + id_len]).into();
+            // ID3v2.2 has 3-byte ids and sizes, later versions 4-byte ones
+            let size_bytes: &[u8] = &data[position + id_len..position + 2 * id_len];
+            let size: usize = match version {
+                2 => u32::from_be_bytes([0, size_bytes[0], size_bytes[1], size_bytes[2]]) as usize,
+                3 => u32::from_be_bytes(size_bytes.try_into().unwrap()) as usize,
+                _ => read_synchsafe(size_bytes) as usize,
+            };
+            let start: usize = position + header_len;
+            let end: usize = (start + size).min(data.len());
+            position = end;
+            if !id.starts_with('T') || start >= end {
+                continue;
+            }
+            let value: String = decode_id3_text(&data[start..end]);
+            match id.as_str() {
+                "TPE1" | "TP1" => tags.artist = non_empty(value),
+                "TPE2" | "TP2" => tags.album_artist = non_empty(value),
+                "TALB" | "TAL" => tags.album = non_empty(value),
+                "TIT2" | "TT2" => tags.title = non_empty(value),
+                "TCON" | "TCO" => tags.genre = non_empty(value),
+                "TRCK" | "TRK" => tags.track = parse_leading_number(&value),
+                "TPOS" | "TPA" => tags.disc = parse_leading_number(&value),
+                "TYER" | "TYE" | "TDRC" => tags.year = non_empty(value.chars().take(4).collect()),
+                _ => {}
+            }
+        }
+        Ok(tags)
+    }
+
+    fn read_id3v1<R: Read + Seek>(file: &mut R) -> io::Result<Option<Self>> {
+        if file.seek(SeekFrom::End(0))? < 128 {
+            return Ok(None);
+        }
+        file.seek(SeekFrom::End(-128))?;
+        let mut tag: [u8; 128] = [0; 128];
+        file.read_exact(&mut tag)?;
+        if &tag[..3] != b"TAG" {
+            return Ok(None);
+        }
+        let text = |bytes: &[u8]| non_empty(decode_latin1(bytes).trim_end().to_string());
+        Ok(Some(Self {
+            title: text(&tag[3..33]),
+            artist: text(&tag[33..63]),
+            album: text(&tag[63..93]),
+            year: text(&tag[93..97]),
+            // ID3v1.1 stores the track in the last byte of the comment
+            track: if tag[125] == 0 && tag[126] != 0 {
+                Some(tag[126] as u32)
+            } else {
+                None
+            },
+            ..Self::default()
+        }))
+    }
+
+    fn read_flac<R: Read + Seek>(file: &mut R) -> io::Result<Self> {
+        file.seek(SeekFrom::Start(4))?;
+        loop {
+            let mut header: [u8; 4] = [0; 4];
+            file.read_exact(&mut header)?;
+            let is_last: bool = header[0] & 0x80 != 0;
+            let block_type: u8 = header[0] & 0x7F;
+            let size: u32 = u32::from_be_bytes([0, header[1], header[2], header[3]]);
+            if block_type == 4 {
+                let mut block: Vec<u8> = vec![0; size as usize];
+                file.read_exact(&mut block)?;
+                return Ok(Self::from_vorbis_comments(&block));
+            }
+            if is_last {
+                return Ok(Self::default());
+            }
+            file.seek(SeekFrom::Current(size as i64))?;
+        }
+    }
+
+    /// the comments are in the second packet of the stream, after the identification header
+    fn read_ogg<R: Read>(file: &mut R) -> io::Result<Self> {
+        let mut packets: Vec<Vec<u8>> = vec![Vec::new()];
+        while packets.len() < 3 {
+            let mut header: [u8; 27] = [0; 27];
+            if file.read_exact(&mut header).is_err() || &header[..4] != b"OggS" {
+                break;
+            }
+            let mut segment_table: Vec<u8> = vec![0; header[26] as usize];
+            file.read_exact(&mut segment_table)?;
+            for segment_len in segment_table {
+                let mut segment: Vec<u8> = vec![0; segment_len as usize];
+                file.read_exact(&mut segment)?;
+                packets.last_mut().unwrap().extend(segment);
+                if segment_len < 255 {
+                    packets.push(Vec::new());
+                }
+            }
+        }
+        let Some(comment_packet) = packets.get(1) else {
+            return Ok(Self::default());
+        };
+        let comments: &[u8] = if comment_packet.starts_with(b"\x03vorbis") {
+            &comment_packet[7..]
+        } else if comment_packet.starts_with(b"OpusTags") {
+            &comment_packet[8..]
+        } else {
+            return Ok(Self::default());
+        };
+        Ok(Self::from_vorbis_comments(comments))
+    }
+
+    fn from_vorbis_comments(block: &[u8]) -> Self {
+        let mut tags: Self = Self::default();
+        let mut position: usize = 0;
+        let read_u32 = |position: &mut usize| -> Option<usize> {
+            let bytes: [u8; 4] = block.get(*position..*position + 4)?.try_into().ok()?;
+            *position += 4;
+            Some(u32::from_le_bytes(bytes) as usize)
+        };
+        let Some(vendor_len) = read_u32(&mut position) else {
+            return tags;
+        };
+        position += vendor_len;
+        let count: usize = read_u32(&mut position).unwrap_or(0);
+        for _ in 0..count {
+            let Some(len) = read_u32(&mut position) else {
+                break;
+            };
+            let Some(comment) = block.get(position..position + len) else {
+                break;
+            };
+            position += len;
+            let comment: String = String::from_utf8_lossy(comment).into_owned();
+            let Some((key, value)) = comment.split_once('=') else {
+                continue;
+            };
+            let value: String = value.to_string();
+            match key.to_ascii_uppercase().as_str() {
+                "ARTIST" if tags.artist.is_none() => tags.artist = non_empty(value),
+                "ALBUMARTIST" | "ALBUM ARTIST" => tags.album_artist = non_empty(value),
+                "ALBUM" => tags.album = non_empty(value),
+                "TITLE" => tags.title = non_empty(value),
+                "GENRE" if tags.genre.is_none() => tags.genre = non_empty(value),
+                "TRACKNUMBER" => tags.track = parse_leading_number(&value),
+                "DISCNUMBER" => tags.disc = parse_leading_number(&value),
+                "DATE" | "YEAR" => tags.year = non_empty(value.chars().take(4).collect()),
+                _ => {}
+            }
+        }
+        tags
+    }
+
+    fn read_mp4<R: Read + Seek>(file: &mut R) -> io::Result<Self> {
+        // find the top-level moov atom
+        let moov: Vec<u8> = loop {
+            let Some((atom_type, size)) = read_atom_header(file)? else {
+                return Ok(Self::default());
+            };
+            if &atom_type == b"moov" {
+                let mut moov: Vec<u8> = vec![0; size as usize];
+                file.read_exact(&mut moov)?;
+                break moov;
+            }
+            file.seek(SeekFrom::Current(size as i64))?;
+        };
+
+        let mut tags: Self = Self::default();
+        let Some(ilst) = find_atom(&moov, &[b"udta", b"meta", b"ilst"]) else {
+            return Ok(tags);
+        };
+        for (item_type, item) in iterate_atoms(ilst) {
+            let Some(data) = find_atom(item, &[b"data"]).filter(|d| d.len() >= 8) else {
+                continue;
+            };
+            // skip the type indicator and locale
+            let value: &[u8] = &data[8..];
+            let text = || non_empty(String::from_utf8_lossy(value).into_owned());
+            let number =
+                || (value.len() >= 4).then(|| u16::from_be_bytes([value[2], value[3]]) as u32);
+            match &item_type {
+                b"\xA9ART" => tags.artist = text(),
+                b"aART" => tags.album_artist = text(),
+                b"\xA9alb" => tags.album = text(),
+                b"\xA9nam" => tags.title = text(),
+                b"\xA9gen" => tags.genre = text(),
+                b"trkn" => tags.track = number().filter(|n| *n > 0),
+                b"disk" => tags.disc = number().filter(|n| *n > 0),
+                b"\xA9day" => tags.year = text().map(|y| y.chars().take(4).collect()),
+                _ => {}
+            }
+        }
+        Ok(tags)
+    }
+}
+
+/// the type and content size of the next atom, `None` at the end of the file
+fn read_atom_header<R: Read>(file: &mut R) -> io::Result<Option<([u8; 4], u64)>> {
+    let mut header: [u8; 8] = [0; 8];
+    if file.read_exact(&mut header).is_err() {
+        return Ok(None);
+    }
+    let atom_type: [u8; 4] = header[4..8].try_into().unwrap();
+    let size: u64 = match u32::from_be_bytes(header[..4].try_into().unwrap()) {
+        0 => return Ok(None), // extends to the end of the file
+        1 => {
+            let mut large_size: [u8; 8] = [0; 8];
+            file.read_exact(&mut large_size)?;
+            u64::from_be_bytes(large_size).saturating_sub(16)
+        }
+        size => (size as u64).saturating_sub(8),
+    };
+    Ok(Some((atom_type, size)))
+}
+
+/// the child atoms of an in-memory atom body, as (type, content)
+fn iterate_atoms(body: &[u8]) -> Vec<([u8; 4], &[u8])> {
+    let mut atoms: Vec<([u8; 4], &[u8])> = Vec::new();
+    let mut position: usize = 0;
+    while position + 8 <= body.len() {
+        let size: usize =
+            u32::from_be_bytes(body[position..position + 4].try_into().unwrap()) as usize;
+        if size < 8 || position + size > body.len() {
+            break;
+        }
+        let atom_type: [u8; 4] = body[position + 4..position + 8].try_into().unwrap();
+        atoms.push((atom_type, &body[position + 8..position + size]));
+        position += size;
+    }
+    atoms
+}
+
+fn find_atom<'a>(body: &'a [u8], path: &[&[u8; 4]]) -> Option<&'a [u8]> {
+    let Some((first, rest)) = path.split_first() else {
+        return Some(body);
+    };
+    let (_, content) = iterate_atoms(body)
+        .into_iter()
+        .find(|(atom_type, _)| atom_type == *first)?;
+    // meta is a full atom, with version and flags before its children
+    let content: &[u8] = if *first == b"meta" {
+        content.get(4..)?
+    } else {
+        content
+    };
+    find_atom(content, rest)
+}
+
+fn read_synchsafe(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .fold(0, |size, byte| (size << 7) | (*byte as u32 & 0x7F))
+}
+
+fn remove_unsynchronisation(data: &[u8]) -> Vec<u8> {
+    let mut result: Vec<u8> = Vec::with_capacity(data.len());
+    for (i, byte) in data.iter().enumerate() {
+        if *byte == 0 && i > 0 && data[i - 1] == 0xFF {
+            continue;
+        }
+        result.push(*byte);
+    }
+    result
+}
+
+/// the first value of a text frame, whose first byte tells the encoding
+fn decode_id3_text(frame: &[u8]) -> String {
+    let (encoding, text) = frame.split_first().unwrap();
+    let decoded: String = match encoding {
+        0 => decode_latin1(text),
+        1 | 2 => {
+            let (big_endian, text) = match text {
+                [0xFE, 0xFF, rest @ ..] => (true, rest),
+                [0xFF, 0xFE, rest @ ..] => (false, rest),
+                _ => (*encoding == 2, text),
+            };
+            let units: Vec<u16> = text
+                .chunks_exact(2)
+                .map(|c| {
+                    if big_endian {
+                        u16::from_be_bytes([c[0], c[1]])
+                    } else {
+                        u16::from_le_bytes([c[0], c[1]])
+                    }
+                })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => String::from_utf8_lossy(text).into_owned(),
+    };
+    decoded.split('\0').next().unwrap_or("").trim().to_string()
+}
+
+fn decode_latin1(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .take_while(|b| **b != 0)
+        .map(|b| *b as char)
+        .collect()
+}
+
+fn non_empty(value: String) -> Option<String> {
+    let value: &str = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// `3` from `"3"` or `"3/12"`
+fn parse_leading_number(value: &str) -> Option<u32> {
+    value
+        .trim()
+        .split('/')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|n| *n > 0)
+}
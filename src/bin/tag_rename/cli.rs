@@ -0,0 +1,111 @@
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::{
+    clock::get_now_arg,
+    failure::get_on_error_arg,
+    guard::get_guard_args,
+    hardlinks::get_hardlinks_arg,
+    hooks::get_hook_args,
+    i18n::get_lang_arg,
+    jobs::get_jobs_arg,
+    journal::get_journal_in_dir_arg,
+    notify::get_notify_arg,
+    output::{get_output_arg, get_print0_arg},
+    pipe::{get_files_from_arg, get_pipe_args},
+    plan::{get_confirm_every_arg, get_explain_arg, get_resume_arg},
+    portable::get_portable_arg,
+    prompt::get_yes_arg,
+    report::get_report_args,
+    retry::get_retry_args,
+    serve::get_serve_args,
+    sort::get_sort_arg,
+    stats::get_stats_arg,
+    verify::get_verify_arg,
+    walk::{get_content_args, get_size_args},
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "tag_rename";
+
+const SHORT_DESCRIPTION: &str = "Rename audio files after their ID3, FLAC, Ogg or MP4 tags.";
+
+const TEMPLATE_HELP_MESSAGE: &str = r#"The new path, relative to the destination directory.
+Placeholders: {artist} {albumartist} {album} {title} {genre}
+              {track} {disc} {year} {name} (the old name) {ext}
+              {parent} (the name of its directory), {parent:N} (its Nth
+              word split at spaces, _ - and ., from the end if negative)
+Numbers can be padded, e.g. {track:02}; "/" creates directories.
+Filters after | transform a value: lower, upper, slug, trunc:N,
+e.g. {title|slug|trunc:40}.
+Files missing a tag used by the template are skipped."#;
+
+const DEFAULT_TEMPLATE: &str = "{artist}/{album}/{track:02} - {title}.{ext}";
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_yes_arg())
+        .arg(get_output_arg())
+        .args(get_guard_args())
+        .args(get_retry_args())
+        .arg(get_stats_arg())
+        .arg(get_notify_arg())
+        .args(get_pipe_args())
+        .arg(get_print0_arg())
+        .arg(get_files_from_arg())
+        .args(get_serve_args())
+        .args(get_hook_args())
+        .arg(get_on_error_arg())
+        .arg(get_explain_arg())
+        .arg(get_verify_arg())
+        .arg(get_portable_arg())
+        .arg(get_confirm_every_arg())
+        .args(get_report_args())
+        .args(get_size_args())
+        .arg(get_sort_arg())
+        .arg(get_hardlinks_arg())
+        .args(get_content_args())
+        .arg(
+            Arg::new("template")
+                .short('t')
+                .long("template")
+                .help(TEMPLATE_HELP_MESSAGE)
+                .default_value(DEFAULT_TEMPLATE),
+        )
+        .arg(
+            Arg::new("dest")
+                .short('d')
+                .long("dest")
+                .help("The directory the new paths are relative to")
+                .value_parser(value_parser!(PathBuf))
+                .default_value("."),
+        )
+        .arg(
+            Arg::new("undo")
+                .long("undo")
+                .help("Undo the latest renaming done by this command")
+                .action(ArgAction::SetTrue)
+                .exclusive(true),
+        )
+        .arg(get_resume_arg())
+        .arg(get_jobs_arg())
+        .arg(get_journal_in_dir_arg())
+        .arg(
+            Arg::new("input_paths")
+                .help("The path(s) to the audio file(s)")
+                .value_parser(value_parser!(PathBuf))
+                .required_unless_present_any([
+                    "undo",
+                    "resume",
+                    "pipe",
+                    "serve",
+                    "serve_socket",
+                    "files_from",
+                ])
+                .action(ArgAction::Append),
+        )
+}
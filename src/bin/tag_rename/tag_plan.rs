@@ -0,0 +1,76 @@
+use super::audio_tags::AudioTags;
+use ray_commands::{
+    i18n::{tr, Message},
+    paths::resolve,
+    plan::RenamePlan,
+    sanitize::sanitize_component,
+    template::{lookup_parent, Template},
+};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+pub fn plan_renames(input_paths: &[PathBuf], template: &Template, dest: &Path) -> RenamePlan {
+    let mut plan: RenamePlan = RenamePlan::new();
+    for path in input_paths {
+        let target: Result<PathBuf, String> =
+            get_new_path(path, template, dest).map(|(target, step)| {
+                plan.explain(path, step);
+                target
+            });
+        plan.add(path.clone(), target);
+    }
+    plan
+}
+
+/// the rendered template under `dest`, and the tags it was rendered from
+fn get_new_path(
+    path: &Path,
+    template: &Template,
+    dest: &Path,
+) -> Result<(PathBuf, String), String> {
+    let tags: AudioTags = AudioTags::read(path)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| tr(Message::NotAudioFile, &[]))?;
+    let values: HashMap<&str, String> = get_tag_values(path, tags);
+
+    let lookup = |key: &str| {
+        values
+            .get(key)
+            .cloned()
+            .or_else(|| lookup_parent(key, path))
+    };
+    let relative: String = template.render(lookup).map_err(|e| e.to_string())?;
+    let step: String = tr(Message::ExplainFromTags, &[&template.describe(lookup)]);
+    Ok((resolve(dest, &relative), step))
+}
+
+/// the template values of a file, each usable as a path component
+fn get_tag_values<'a>(path: &Path, tags: AudioTags) -> HashMap<&'a str, String> {
+    let mut values: HashMap<&str, String> = HashMap::new();
+    let mut insert = |key: &'a str, value: Option<String>| {
+        if let Some(value) = value.map(|v| sanitize_component(&v)) {
+            if !value.is_empty() {
+                values.insert(key, value);
+            }
+        }
+    };
+    insert("albumartist", tags.album_artist.or(tags.artist.clone()));
+    insert("artist", tags.artist);
+    insert("album", tags.album);
+    insert("title", tags.title);
+    insert("genre", tags.genre);
+    insert("track", tags.track.map(|v| v.to_string()));
+    insert("disc", tags.disc.map(|v| v.to_string()));
+    insert("year", tags.year);
+    insert(
+        "name",
+        path.file_stem().map(|s| s.to_string_lossy().into_owned()),
+    );
+    insert(
+        "ext",
+        path.extension().map(|e| e.to_string_lossy().into_owned()),
+    );
+    values
+}
@@ -0,0 +1,36 @@
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::{
+    clock::get_now_arg, guard::get_guard_args, i18n::get_lang_arg, output::get_output_arg,
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "trash";
+
+const SHORT_DESCRIPTION: &str =
+    "Move files and directories to the trash instead of deleting them,\nso that they can be restored from the file manager.";
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_output_arg())
+        .args(get_guard_args())
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .help("Print each path moved to the trash")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("input_paths")
+                .help("The path(s) to the file(s) or directory(ies)")
+                .value_parser(value_parser!(PathBuf))
+                .required(true)
+                .action(ArgAction::Append),
+        )
+}
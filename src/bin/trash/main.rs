@@ -0,0 +1,77 @@
+use ray_commands::{
+    clock::set_now,
+    guard::{check_batch_size, check_paths, set_guard},
+    i18n::{set_language, tr, Message},
+    output::{get_output_format, print_records, OutputFormat},
+    recycle::move_to_trash,
+};
+use serde::Serialize;
+use std::{fs, path::PathBuf, process};
+
+pub mod cli;
+
+#[derive(Serialize)]
+struct TrashRecord {
+    path: String,
+    /// "file", "directory", "symlink" or "missing"
+    kind: &'static str,
+}
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    set_guard(&matches);
+    let input_paths: Vec<PathBuf> = matches
+        .get_many::<PathBuf>("input_paths")
+        .unwrap()
+        .cloned()
+        .collect();
+    if let Err(err) = check_paths(&input_paths) {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
+    let verbose: bool = matches.get_flag("verbose");
+    let output: OutputFormat = get_output_format(&matches);
+
+    if !output.is_table() {
+        let records: Vec<TrashRecord> = input_paths
+            .iter()
+            .map(|p| TrashRecord {
+                path: p.display().to_string(),
+                kind: match fs::symlink_metadata(p) {
+                    Ok(m) if m.is_dir() => "directory",
+                    Ok(m) if m.is_symlink() => "symlink",
+                    Ok(_) => "file",
+                    Err(_) => "missing",
+                },
+            })
+            .collect();
+        print_records(&records, output);
+        return;
+    }
+
+    if let Err(err) = check_batch_size(input_paths.len()) {
+        eprintln!("{err}");
+        process::exit(err.exit_code());
+    }
+    let mut has_failed: bool = false;
+    for path in &input_paths {
+        match move_to_trash(path) {
+            Ok(()) if verbose => println!("{}", tr(Message::MovedToTrash, &[&path.display()])),
+            Ok(()) => {}
+            Err(err) => {
+                eprintln!("{}", tr(Message::FailedToTrash, &[&path.display(), &err]));
+                has_failed = true;
+            }
+        }
+    }
+    if has_failed {
+        process::exit(1);
+    }
+}
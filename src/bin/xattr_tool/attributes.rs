@@ -0,0 +1,138 @@
+use ray_commands::{
+    i18n::{tr, Message},
+    output::{print_records, OutputFormat},
+    prompt::wait_accepting_prompt,
+    walk::{walk_entries, WalkEntry},
+    xattrs::{read_xattrs, Attributes},
+};
+use serde::Serialize;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// values longer than this are shortened when printed
+const MAX_PRINTED_BYTES: usize = 32;
+
+#[derive(Serialize)]
+struct XattrRecord {
+    path: String,
+    name: String,
+    /// the value as text when it is printable, else as 0x and hex; none when stripping
+    value: Option<String>,
+}
+
+/// the input paths and, with `recursive`, everything below the directories among them
+pub fn get_paths(input_paths: &[PathBuf], recursive: bool) -> io::Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = vec![];
+    for path in input_paths {
+        paths.push(path.clone());
+        if recursive && fs::symlink_metadata(path)?.is_dir() {
+            let entries: Vec<WalkEntry> = walk_entries(path)?;
+            paths.extend(entries.into_iter().map(|e| e.path));
+        }
+    }
+    Ok(paths)
+}
+
+/// print each path having attributes, followed by its attributes,
+/// or a record of each attribute in `output`
+pub fn print_xattrs(paths: &[PathBuf], output: OutputFormat) -> io::Result<()> {
+    if !output.is_table() {
+        let mut records: Vec<XattrRecord> = vec![];
+        for path in paths {
+            read_xattrs(path)?.into_iter().for_each(|(name, value)| {
+                records.push(XattrRecord {
+                    path: path.display().to_string(),
+                    name,
+                    value: Some(encode_value(&value)),
+                })
+            });
+        }
+        print_records(&records, output);
+        return Ok(());
+    }
+    for path in paths {
+        let attributes: Attributes = read_xattrs(path)?;
+        if attributes.is_empty() {
+            continue;
+        }
+        println!("{}", path.display());
+        attributes
+            .iter()
+            .for_each(|(name, value)| println!("  {name}: {}", format_value(value)));
+    }
+    Ok(())
+}
+
+/// preview, confirm and remove the attributes of `paths`, only those in `names`
+/// unless it is empty, or only print a record of each in `output`;
+/// return whether anything was removed
+pub fn strip_xattrs(paths: &[PathBuf], names: &[String], output: OutputFormat) -> io::Result<bool> {
+    let mut removals: Vec<(&Path, String)> = vec![];
+    for path in paths {
+        read_xattrs(path)?
+            .into_keys()
+            .filter(|n| names.is_empty() || names.contains(n))
+            .for_each(|n| removals.push((path, n)));
+    }
+    if !output.is_table() {
+        let records: Vec<XattrRecord> = removals
+            .into_iter()
+            .map(|(path, name)| XattrRecord {
+                path: path.display().to_string(),
+                name,
+                value: None,
+            })
+            .collect();
+        print_records(&records, output);
+        return Ok(false);
+    }
+    if removals.is_empty() {
+        println!("{}", tr(Message::NothingToRemove, &[]));
+        return Ok(false);
+    }
+
+    removals
+        .iter()
+        .for_each(|(path, name)| println!("{}: {name}", path.display()));
+    if !wait_accepting_prompt(&tr(Message::AcceptRemovingAttributes, &[])) {
+        println!("{}", tr(Message::NothingDone, &[]));
+        return Ok(false);
+    }
+    for (path, name) in &removals {
+        xattr::remove(path, name)?;
+    }
+    Ok(true)
+}
+
+/// a value as text when it is printable, else as hex
+fn format_value(value: &[u8]) -> String {
+    match std::str::from_utf8(value) {
+        Ok(text) if !text.chars().any(char::is_control) => format!("{text:?}"),
+        _ => {
+            let hex: String = value
+                .iter()
+                .take(MAX_PRINTED_BYTES)
+                .map(|b| format!("{b:02x}"))
+                .collect();
+            let ellipsis: &str = if value.len() > MAX_PRINTED_BYTES {
+                "…"
+            } else {
+                ""
+            };
+            format!("0x{hex}{ellipsis} ({} bytes)", value.len())
+        }
+    }
+}
+
+/// a whole value as text when it is printable, else as hex after 0x
+fn encode_value(value: &[u8]) -> String {
+    match std::str::from_utf8(value) {
+        Ok(text) if !text.chars().any(char::is_control) => text.to_string(),
+        _ => format!(
+            "0x{}",
+            value.iter().map(|b| format!("{b:02x}")).collect::<String>()
+        ),
+    }
+}
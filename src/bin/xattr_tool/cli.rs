@@ -0,0 +1,120 @@
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use ray_commands::{
+    clock::get_now_arg,
+    i18n::get_lang_arg,
+    output::get_output_arg,
+    prompt::get_yes_arg,
+    walk::{get_no_ignore_arg, get_recursion_args},
+};
+use std::path::PathBuf;
+
+pub const PROGRAM_NAME: &str = "xattr_tool";
+
+const SHORT_DESCRIPTION: &str =
+    "List, copy, strip, back up and restore the extended attributes of files,\nsuch as macOS Finder tags, com.apple.quarantine and user.* attributes.";
+
+const BACKUP_HELP_MESSAGE: &str = r#"Save the attributes of a tree to a JSON file, to restore them with
+`restore` after a copy that drops them"#;
+
+pub fn get_cli_parser() -> Command {
+    Command::new(PROGRAM_NAME)
+        .version(crate_version!())
+        .about(SHORT_DESCRIPTION)
+        .next_line_help(true)
+        .arg_required_else_help(true)
+        .arg(get_lang_arg())
+        .arg(get_now_arg())
+        .arg(get_yes_arg())
+        .subcommand(
+            Command::new("list")
+                .about("Print the attributes of files")
+                .arg(get_output_arg())
+                .arg(get_recursive_arg())
+                .arg(get_no_ignore_arg())
+                .args(get_recursion_args())
+                .arg(get_input_paths_arg()),
+        )
+        .subcommand(
+            Command::new("strip")
+                .about("Remove attributes from files")
+                .arg(
+                    Arg::new("name")
+                        .long("name")
+                        .help("Only remove the attribute with this name, e.g. com.apple.quarantine")
+                        .action(ArgAction::Append),
+                )
+                .arg(get_output_arg())
+                .arg(get_recursive_arg())
+                .arg(get_no_ignore_arg())
+                .args(get_recursion_args())
+                .arg(get_input_paths_arg()),
+        )
+        .subcommand(
+            Command::new("copy")
+                .about("Copy the attributes of a file or tree to the same paths in another")
+                .arg(get_recursive_arg())
+                .arg(
+                    Arg::new("source")
+                        .help("The file or tree to copy from")
+                        .value_parser(value_parser!(PathBuf))
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("destination")
+                        .help("The file or tree to copy to")
+                        .value_parser(value_parser!(PathBuf))
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("backup")
+                .about(BACKUP_HELP_MESSAGE)
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .help("The JSON file to write, instead of the standard output"),
+                )
+                .arg(get_recursive_arg())
+                .arg(
+                    Arg::new("path")
+                        .help("The file or tree to back up")
+                        .value_parser(value_parser!(PathBuf))
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("restore")
+                .about("Set the attributes saved by `backup` on the same paths again")
+                .arg(
+                    Arg::new("dir")
+                        .short('d')
+                        .long("dir")
+                        .help("The file or tree to restore to, as it was given to `backup`")
+                        .value_parser(value_parser!(PathBuf))
+                        .default_value("."),
+                )
+                .arg(
+                    Arg::new("backup")
+                        .help("The JSON file written by `backup`")
+                        .value_parser(value_parser!(PathBuf))
+                        .required(true),
+                ),
+        )
+}
+
+fn get_recursive_arg() -> Arg {
+    Arg::new("recursive")
+        .short('r')
+        .long("recursive")
+        .help("Include everything below directories")
+        .action(ArgAction::SetTrue)
+}
+
+fn get_input_paths_arg() -> Arg {
+    Arg::new("input_paths")
+        .help("The path(s) to the file(s) or directory(ies)")
+        .value_parser(value_parser!(PathBuf))
+        .required(true)
+        .action(ArgAction::Append)
+}
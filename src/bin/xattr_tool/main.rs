@@ -0,0 +1,105 @@
+use attributes::{get_paths, print_xattrs, strip_xattrs};
+use ray_commands::output::{get_output_format, OutputFormat};
+use ray_commands::prompt::set_assume_yes;
+use ray_commands::walk::{set_ignore_rules, set_recursion_limits};
+use ray_commands::xattrs::{
+    copy_xattrs, read_snapshot, restore_xattrs, snapshot_xattrs, write_snapshot, XattrEntry,
+};
+use ray_commands::{
+    clock::set_now,
+    i18n::{set_language, tr, Message},
+};
+use std::{fs::File, io, path::PathBuf, process};
+
+mod attributes;
+pub mod cli;
+
+pub fn main() {
+    run(cli::get_cli_parser().get_matches());
+}
+
+/// run the tool on its parsed command-line arguments, also as a `cu` subcommand
+pub fn run(matches: clap::ArgMatches) {
+    // load command-line arguments
+    set_language(matches.get_one::<String>("lang"));
+    set_now(&matches);
+    set_assume_yes(&matches);
+    let result: io::Result<()> = match matches.subcommand() {
+        Some(("list", sub_matches)) => {
+            let input_paths: Vec<PathBuf> = get_input_paths(sub_matches);
+            let recursive: bool = sub_matches.get_flag("recursive");
+            let output: OutputFormat = get_output_format(sub_matches);
+            set_ignore_rules(sub_matches);
+            set_recursion_limits(sub_matches);
+            get_paths(&input_paths, recursive).and_then(|paths| print_xattrs(&paths, output))
+        }
+        Some(("strip", sub_matches)) => {
+            let input_paths: Vec<PathBuf> = get_input_paths(sub_matches);
+            let recursive: bool = sub_matches.get_flag("recursive");
+            let names: Vec<String> = sub_matches
+                .get_many::<String>("name")
+                .unwrap_or_default()
+                .cloned()
+                .collect();
+            let output: OutputFormat = get_output_format(sub_matches);
+            set_ignore_rules(sub_matches);
+            set_recursion_limits(sub_matches);
+            get_paths(&input_paths, recursive)
+                .and_then(|paths| strip_xattrs(&paths, &names, output))
+                .map(|_| ())
+        }
+        Some(("copy", sub_matches)) => {
+            let source: PathBuf = sub_matches.get_one::<PathBuf>("source").unwrap().clone();
+            let destination: PathBuf = sub_matches
+                .get_one::<PathBuf>("destination")
+                .unwrap()
+                .clone();
+            let recursive: bool = sub_matches.get_flag("recursive");
+            if recursive {
+                snapshot_xattrs(&source, true)
+                    .and_then(|snapshot| restore_xattrs(&destination, &snapshot))
+                    .map(|missing| print_missing(&missing))
+            } else {
+                copy_xattrs(&source, &destination)
+            }
+        }
+        Some(("backup", sub_matches)) => {
+            let path: PathBuf = sub_matches.get_one::<PathBuf>("path").unwrap().clone();
+            let output: Option<PathBuf> =
+                sub_matches.get_one::<String>("output").map(PathBuf::from);
+            let recursive: bool = sub_matches.get_flag("recursive");
+            snapshot_xattrs(&path, recursive).and_then(|snapshot| match output {
+                Some(output) => write_snapshot(File::create(output)?, &snapshot),
+                None => write_snapshot(io::stdout().lock(), &snapshot),
+            })
+        }
+        Some(("restore", sub_matches)) => {
+            let backup: PathBuf = sub_matches.get_one::<PathBuf>("backup").unwrap().clone();
+            let dir: PathBuf = sub_matches.get_one::<PathBuf>("dir").unwrap().clone();
+            read_snapshot(&backup)
+                .and_then(|snapshot: Vec<XattrEntry>| restore_xattrs(&dir, &snapshot))
+                .map(|missing| print_missing(&missing))
+        }
+        _ => unreachable!(),
+    };
+
+    if let Err(err) = result {
+        eprintln!("{err}");
+        process::exit(1);
+    }
+}
+
+fn get_input_paths(matches: &clap::ArgMatches) -> Vec<PathBuf> {
+    matches
+        .get_many::<PathBuf>("input_paths")
+        .unwrap()
+        .cloned()
+        .collect()
+}
+
+fn print_missing(missing: &[PathBuf]) {
+    missing.iter().for_each(|p| {
+        let reason: String = tr(Message::FileNotFound, &[]);
+        println!("{}", tr(Message::Skipping, &[&p.display(), &reason]))
+    });
+}
@@ -0,0 +1,71 @@
+//! The current time of a run, fixed by the hidden `--now` option so that what
+//! depends on it, from file ages to the names of journals and the dates of
+//! reports, is the same at every run, as when testing the tools end to end.
+
+use crate::i18n::{tr, Message};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use clap::{Arg, ArgMatches};
+use std::{sync::OnceLock, time::SystemTime};
+
+/// the dates and times taken besides RFC 3339 and a bare date, in local time
+const TIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+/// the time fixed by `--now` for this run, if any
+static NOW: OnceLock<Option<DateTime<Local>>> = OnceLock::new();
+
+/// a date, at midnight, or a date and time, in local time unless it has an
+/// offset as in RFC 3339
+pub fn parse_time(text: &str) -> Option<DateTime<Local>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Some(time.with_timezone(&Local));
+    }
+    let naive: NaiveDateTime = TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()?
+                .and_hms_opt(0, 0, 0)
+        })?;
+    naive.and_local_timezone(Local).earliest()
+}
+
+fn parse_now(text: &str) -> Result<DateTime<Local>, String> {
+    parse_time(text).ok_or_else(|| tr(Message::InvalidDateTime, &[&text]))
+}
+
+/// the hidden `--now` option of every tool
+pub fn get_now_arg() -> Arg {
+    Arg::new("now")
+        .long("now")
+        .help("The current time of the run, e.g. 2024-05-01T10:00:00Z, for tests")
+        .value_name("TIMESTAMP")
+        .value_parser(parse_now)
+        .hide(true)
+        .global(true)
+}
+
+/// fix the current time of this run by `--now`
+pub fn set_now(matches: &ArgMatches) {
+    let now: Option<DateTime<Local>> = matches
+        .try_get_one::<DateTime<Local>>("now")
+        .ok()
+        .flatten()
+        .copied();
+    let _ = NOW.set(now);
+}
+
+/// the current time of this run, as fixed by `--now` or else by the clock
+pub fn now() -> DateTime<Local> {
+    NOW.get().copied().flatten().unwrap_or_else(Local::now)
+}
+
+/// [`now`] as a system time, for comparing with the times of files
+pub fn system_now() -> SystemTime {
+    now().into()
+}
@@ -0,0 +1,50 @@
+//! The configuration file shared by the tools, `config.toml` in the configuration
+//! directory, for settings kept between runs such as naming presets and the file limit of
+//! a batch.
+
+use crate::{journal::APPLICATION_DIR_NAME, preset::Preset};
+use serde::Deserialize;
+use std::{collections::BTreeMap, env, fs, io, path::PathBuf};
+
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// naming presets by name, e.g. `[presets.camera]`, taking over the built-in ones
+    #[serde(default)]
+    pub presets: BTreeMap<String, Preset>,
+    /// the files a batch may change without `--force`, unless `--max-files`
+    pub max_files: Option<usize>,
+    /// the date and time formats of the files of some extensions, by the
+    /// extensions separated by commas, e.g. `"mp4,mov" = "%Y-%m-%d_%H%M"`
+    #[serde(default)]
+    pub extension_formats: BTreeMap<String, String>,
+}
+
+impl Config {
+    /// the shared configuration, empty when there is no configuration file
+    pub fn load() -> Result<Self, String> {
+        let path: PathBuf = get_config_path();
+        let text: String = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(format!("{}: {err}", path.display())),
+        };
+        toml::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))
+    }
+}
+
+/// `$XDG_CONFIG_HOME/ray-commands/config.toml` (`~/.config` by default),
+/// or `%APPDATA%\ray-commands\config.toml` on Windows
+pub fn get_config_path() -> PathBuf {
+    let config_dir: PathBuf = if cfg!(windows) {
+        PathBuf::from(env::var_os("APPDATA").unwrap_or_default())
+    } else {
+        match env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(env::var_os("HOME").unwrap_or_default()).join(".config"),
+        }
+    };
+    config_dir.join(APPLICATION_DIR_NAME).join(CONFIG_FILE_NAME)
+}
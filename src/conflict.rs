@@ -0,0 +1,30 @@
+//! What the renaming tools do when the new name of a file is already taken.
+
+pub const CONFLICT_STRATEGY_NAMES: [&str; 3] = ["skip", "suffix", "overwrite"];
+
+pub const CONFLICT_HELP_MESSAGE: &str = r#"What to do when the new name is taken by an existing file
+or by the new name of another file:
+  skip:      leave the file as it is
+  suffix:    append _1, _2, ... to the new stem until it is free
+  overwrite: move the existing file to the trash, or to its backup by
+             --backup, and take its name (never the name of another
+             renamed file)"#;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictStrategy {
+    #[default]
+    Skip,
+    Suffix,
+    Overwrite,
+}
+
+impl ConflictStrategy {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "skip" => Self::Skip,
+            "suffix" => Self::Suffix,
+            "overwrite" => Self::Overwrite,
+            _ => panic!("Unknown conflict strategy: {name}"),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! What a file holds, told from its first bytes rather than its extension, for
+//! picking the images or documents out of a folder of misnamed downloads.
+
+use infer::MatcherType;
+use std::path::Path;
+
+pub const CATEGORY_NAMES: [&str; 5] = ["image", "video", "audio", "document", "archive"];
+
+/// the MIME types `infer` files under archives that are documents to a reader
+const DOCUMENT_ARCHIVE_TYPES: [&str; 3] = [
+    "application/pdf",
+    "application/rtf",
+    "application/postscript",
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Image,
+    Video,
+    Audio,
+    Document,
+    Archive,
+}
+
+impl Category {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "image" => Self::Image,
+            "video" => Self::Video,
+            "audio" => Self::Audio,
+            "document" => Self::Document,
+            "archive" => Self::Archive,
+            _ => panic!("Unknown category: {name}"),
+        }
+    }
+
+    /// the category of the file at `path`, if its content is of a known type
+    pub fn of(path: &Path) -> Option<Self> {
+        let kind: infer::Type = infer::get_from_path(path).ok()??;
+        match kind.matcher_type() {
+            MatcherType::Image => Some(Self::Image),
+            MatcherType::Video => Some(Self::Video),
+            MatcherType::Audio => Some(Self::Audio),
+            MatcherType::Doc | MatcherType::Book => Some(Self::Document),
+            MatcherType::Archive if DOCUMENT_ARCHIVE_TYPES.contains(&kind.mime_type()) => {
+                Some(Self::Document)
+            }
+            MatcherType::Archive => Some(Self::Archive),
+            _ => None,
+        }
+    }
+}
+
+/// the MIME type of the file at `path` told from its content, e.g. `image/png`
+pub fn get_mime_type(path: &Path) -> Option<&'static str> {
+    infer::get_from_path(path)
+        .ok()?
+        .map(|kind| kind.mime_type())
+}
+
+/// whether `mime_type` matches `pattern`, a MIME type like `image/png`
+/// or a whole top-level type like `image/*`
+pub fn matches_mime(pattern: &str, mime_type: &str) -> bool {
+    match pattern.strip_suffix("/*") {
+        Some(top) => mime_type
+            .split_once('/')
+            .is_some_and(|(t, _)| t.eq_ignore_ascii_case(top)),
+        None => pattern.eq_ignore_ascii_case(mime_type),
+    }
+}
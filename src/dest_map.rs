@@ -0,0 +1,127 @@
+//! Routing renamed files to other directories by `--dest-map`, so that a batch
+//! rename also sorts the files, images to `~/Pictures/...` and PDFs to
+//! `~/Documents/...`, in one plan confirmed at once. Files are matched by name
+//! alone, as the names derived from snapshots touch no file.
+
+use crate::{
+    i18n::{tr, Message},
+    paths::resolve,
+};
+use regex::Regex;
+use serde::Deserialize;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// a destination map: `[[rule]]` tables in TOML, the first matching rule
+/// applying
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DestMapFile {
+    #[serde(rename = "rule")]
+    rules: Vec<RuleEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleEntry {
+    /// extensions without the dot, matched case-insensitively
+    #[serde(default)]
+    extensions: Vec<String>,
+    /// shell pattern matched against the whole file name, e.g. `IMG_*.jpg`
+    glob: Option<String>,
+    /// relative to the directory of the file, or starting with `~/` for the
+    /// home directory
+    destination: String,
+}
+
+#[derive(Clone)]
+struct Rule {
+    extensions: Vec<String>,
+    glob: Option<Regex>,
+    destination: String,
+}
+
+/// the rules of `--dest-map`, routing files to the destination of the first
+/// rule they match and leaving the others in their directories
+#[derive(Clone)]
+pub struct DestMap(Vec<Rule>);
+
+impl DestMap {
+    /// the rules of the TOML file at `path`
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text: String = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let file: DestMapFile = toml::from_str(&text).map_err(|e| e.to_string())?;
+        let mut rules: Vec<Rule> = Vec::new();
+        for (number, entry) in file.rules.into_iter().enumerate() {
+            let glob: Option<Regex> = entry
+                .glob
+                .map(|g| Regex::new(&glob_to_regex(&g)))
+                .transpose()
+                .map_err(|e| tr(Message::RuleFailed, &[&(number + 1), &e]))?;
+            rules.push(Rule {
+                extensions: entry.extensions.iter().map(|e| e.to_lowercase()).collect(),
+                glob,
+                destination: entry.destination,
+            });
+        }
+        Ok(Self(rules))
+    }
+
+    /// the directory of the file at `path` by the first rule its name matches,
+    /// none for a file matching no rule; a rule with no conditions matches any
+    /// file
+    pub fn route(&self, path: &Path) -> Option<PathBuf> {
+        let name: String = path.file_name()?.to_string_lossy().into_owned();
+        let extension: Option<String> =
+            path.extension().map(|e| e.to_string_lossy().to_lowercase());
+        let rule: &Rule = self.0.iter().find(|rule| {
+            (rule.extensions.is_empty()
+                || extension
+                    .as_ref()
+                    .is_some_and(|e| rule.extensions.contains(e)))
+                && rule.glob.as_ref().is_none_or(|g| g.is_match(&name))
+        })?;
+        let dir: &Path = path.parent().unwrap_or(Path::new(""));
+        Some(expand_destination(dir, &rule.destination))
+    }
+}
+
+/// the regular expression of the shell pattern `glob`, where `*` matches any
+/// run of characters, `?` any one, and `[...]` one of a set
+pub fn glob_to_regex(glob: &str) -> String {
+    let mut regex: String = String::from("^");
+    let mut in_set: bool = false;
+    for c in glob.chars() {
+        match c {
+            '[' if !in_set => {
+                in_set = true;
+                regex.push('[');
+            }
+            ']' if in_set => {
+                in_set = false;
+                regex.push(']');
+            }
+            '!' if in_set && regex.ends_with('[') => regex.push('^'),
+            c if in_set => regex.push_str(&regex::escape(&c.to_string())),
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+/// `destination` in the home directory when starting with `~/`, and else
+/// relative to `dir`
+pub fn expand_destination(dir: &Path, destination: &str) -> PathBuf {
+    let home: Option<PathBuf> = env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from);
+    match (destination.strip_prefix("~/"), home) {
+        (Some(relative), Some(home)) => home.join(relative),
+        _ => resolve(dir, destination),
+    }
+}
@@ -0,0 +1,109 @@
+//! The errors of planning and applying file changes, each with the exit code
+//! the tools end with, so that scripts can tell failures apart.
+
+use crate::{
+    i18n::{tr, Message},
+    interrupt::INTERRUPTED_EXIT_CODE,
+    template::TemplateError,
+};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    /// a template, record or argument the tool cannot understand
+    #[error("{}", tr(Message::InvalidFormat, &[.0]))]
+    InvalidFormat(String),
+    #[error("{}", tr(Message::SourceMissing, &[&.0.display()]))]
+    SourceMissing(PathBuf),
+    #[error("{}", tr(Message::AlreadyExists, &[&.0.display()]))]
+    TargetExists(PathBuf),
+    #[error("{}", tr(Message::CrossDevice, &[&.from.display(), &.to.display()]))]
+    CrossDevice { from: PathBuf, to: PathBuf },
+    #[error("{}", tr(Message::PermissionDenied, &[&.0.display()]))]
+    PermissionDenied(PathBuf),
+    /// a `pre_rename` hook failing, with its exit status or why it did not run
+    #[error("{}", tr(Message::HookVetoed, &[.hook, .reason]))]
+    HookVetoed { hook: String, reason: String },
+    /// Ctrl-C pressed while applying, after `done` of the `total` changes
+    #[error("{}", tr(Message::Interrupted, &[.done, .total]))]
+    Interrupted { done: usize, total: usize },
+    /// renames failing and skipped by `--on-error`, `failed` of the `total`
+    #[error("{}", tr(Message::RenamesFailed, &[.failed, .total]))]
+    RenamesFailed { failed: usize, total: usize },
+    /// renames done but found not to hold by `--verify`, `failed` of the `total`
+    #[error("{}", tr(Message::VerifyFailed, &[.failed, .total]))]
+    VerifyFailed { failed: usize, total: usize },
+    /// a root, home or system directory, refused without `--force`
+    #[error("{}", tr(Message::GuardedPath, &[&.0.display()]))]
+    GuardedPath(PathBuf),
+    /// a batch changing more files than `--max-files`, refused without `--force`
+    #[error("{}", tr(Message::TooManyFiles, &[.count, .max]))]
+    TooManyFiles { count: usize, max: usize },
+    /// new names not holding on every platform, refused by `--portable`,
+    /// `failed` of the `total`
+    #[error("{}", tr(Message::NotPortable, &[.failed, .total]))]
+    NotPortable { failed: usize, total: usize },
+    #[error("{}: {source}", .path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
+
+impl Error {
+    /// the error of an operation on `path`, by the kind of `err`
+    pub fn from_io(err: io::Error, path: &Path) -> Self {
+        match err.kind() {
+            io::ErrorKind::NotFound => Self::SourceMissing(path.to_path_buf()),
+            io::ErrorKind::AlreadyExists => Self::TargetExists(path.to_path_buf()),
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied(path.to_path_buf()),
+            _ => Self::Io {
+                path: path.to_path_buf(),
+                source: err,
+            },
+        }
+    }
+
+    /// the error of renaming `source` to `target`, by the kind of `err`
+    pub fn from_rename(err: io::Error, source: &Path, target: &Path) -> Self {
+        match err.kind() {
+            io::ErrorKind::CrossesDevices => Self::CrossDevice {
+                from: source.to_path_buf(),
+                to: target.to_path_buf(),
+            },
+            io::ErrorKind::AlreadyExists | io::ErrorKind::DirectoryNotEmpty => {
+                Self::TargetExists(target.to_path_buf())
+            }
+            _ => Self::from_io(err, source),
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Io { .. } => 1,
+            Self::InvalidFormat(_) => 2, // as for invalid arguments
+            Self::SourceMissing(_) => 3,
+            Self::TargetExists(_) => 4,
+            Self::CrossDevice { .. } => 5,
+            Self::PermissionDenied(_) => 6,
+            Self::HookVetoed { .. } => 7,
+            Self::RenamesFailed { .. } => 8,
+            Self::GuardedPath(_) | Self::TooManyFiles { .. } | Self::NotPortable { .. } => 9,
+            Self::VerifyFailed { .. } => 10,
+            Self::Interrupted { .. } => INTERRUPTED_EXIT_CODE,
+        }
+    }
+}
+
+impl From<TemplateError> for Error {
+    fn from(err: TemplateError) -> Self {
+        Self::InvalidFormat(err.to_string())
+    }
+}
@@ -0,0 +1,243 @@
+//! Reading the EXIF of JPEG and TIFF-based photos, for the placeholders naming
+//! them by how they were taken: `{camera}` and `{lens}` here, `{location}` and
+//! `{country}` in [`crate::geo`].
+
+use crate::sanitize::sanitize_component;
+use std::{
+    fs::{self, File},
+    io::{self, Read},
+    path::Path,
+};
+
+/// the most of a file read for its EXIF, which comes first in a JPEG
+const EXIF_READ_LIMIT: u64 = 256 * 1024;
+
+// reference: https://www.cipa.jp/std/documents/e/DC-008-2012_E.pdf
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+const TAG_MAKE: u16 = 0x010F;
+const TAG_MODEL: u16 = 0x0110;
+/// the tag of the entry pointing to the EXIF IFD
+pub const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_LENS_MAKE: u16 = 0xA433;
+const TAG_LENS_MODEL: u16 = 0xA434;
+const TYPE_ASCII: u16 = 2;
+const TYPE_RATIONAL: u16 = 5;
+
+/// the TIFF structure holding the EXIF of a file
+pub struct Exif {
+    tiff: Vec<u8>,
+    little: bool,
+    /// where the TIFF structure starts in the file
+    start: usize,
+}
+
+/// an entry of an IFD
+pub struct Entry {
+    pub kind: u16,
+    pub count: u32,
+    /// the value, or its offset when longer than 4 bytes
+    pub value: u32,
+    /// where the value field is, for values of up to 4 bytes
+    pub offset: usize,
+}
+
+impl Exif {
+    /// the EXIF of a JPEG or TIFF-based file, none when it has none
+    pub fn read(path: &Path) -> io::Result<Option<Self>> {
+        let mut data: Vec<u8> = Vec::new();
+        File::open(path)?
+            .take(EXIF_READ_LIMIT)
+            .read_to_end(&mut data)?;
+        Ok(Self::parse(data))
+    }
+
+    /// the EXIF of a file as [`Exif::read`] finds it, reading the whole file,
+    /// as the values of a TIFF-based file may be anywhere in it
+    pub fn read_whole(path: &Path) -> io::Result<Option<Self>> {
+        Ok(Self::parse(fs::read(path)?))
+    }
+
+    /// the EXIF in `data`, the first bytes of a file or all of them
+    fn parse(mut data: Vec<u8>) -> Option<Self> {
+        let start: usize = if data.starts_with(&[0xFF, 0xD8]) {
+            find_jpeg_exif(&data)?
+        } else if data.starts_with(b"II*\0") || data.starts_with(b"MM\0*") {
+            0
+        } else {
+            return None;
+        };
+        let tiff: Vec<u8> = data.split_off(start);
+        let little: bool = match tiff.get(0..2)? {
+            b"II" => true,
+            b"MM" => false,
+            _ => return None,
+        };
+        Some(Self {
+            tiff,
+            little,
+            start,
+        })
+    }
+
+    /// where the TIFF structure starts in the file, to which its offsets are
+    /// relative, as for writing a value in place
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// the 16-bit number at `offset` in the TIFF structure, in its byte order
+    pub fn u16_at(&self, offset: usize) -> Option<u16> {
+        let bytes: [u8; 2] = self.tiff.get(offset..offset + 2)?.try_into().ok()?;
+        Some(match self.little {
+            true => u16::from_le_bytes(bytes),
+            false => u16::from_be_bytes(bytes),
+        })
+    }
+
+    /// the 32-bit number at `offset` in the TIFF structure, in its byte order
+    pub fn u32_at(&self, offset: usize) -> Option<u32> {
+        let bytes: [u8; 4] = self.tiff.get(offset..offset + 4)?.try_into().ok()?;
+        Some(match self.little {
+            true => u32::from_le_bytes(bytes),
+            false => u32::from_be_bytes(bytes),
+        })
+    }
+
+    /// the offset of IFD0, the IFD of the image itself
+    pub fn get_ifd0(&self) -> Option<usize> {
+        self.u32_at(4).map(|o| o as usize)
+    }
+
+    /// the entry with `tag` in the IFD at `ifd`
+    pub fn find_entry(&self, ifd: usize, tag: u16) -> Option<Entry> {
+        let count: usize = self.u16_at(ifd)? as usize;
+        (0..count).find_map(|i| {
+            let entry: usize = ifd + 2 + 12 * i;
+            if self.u16_at(entry)? != tag {
+                return None;
+            }
+            Some(Entry {
+                kind: self.u16_at(entry + 2)?,
+                count: self.u32_at(entry + 4)?,
+                value: self.u32_at(entry + 8)?,
+                offset: entry + 8,
+            })
+        })
+    }
+
+    /// the offset of the IFD that the entry with `tag` in the IFD at `ifd`
+    /// points to, as the EXIF and GPS IFDs
+    pub fn find_ifd(&self, ifd: usize, tag: u16) -> Option<usize> {
+        self.find_entry(ifd, tag).map(|e| e.value as usize)
+    }
+
+    /// the text of an ASCII entry, without its trailing NULs and spaces
+    pub fn read_ascii(&self, entry: &Entry) -> Option<String> {
+        if entry.kind != TYPE_ASCII {
+            return None;
+        }
+        let start: usize = match entry.count {
+            0..=4 => entry.offset,
+            _ => entry.value as usize,
+        };
+        let bytes: &[u8] = self.tiff.get(start..start + entry.count as usize)?;
+        let text: String = String::from_utf8_lossy(bytes)
+            .trim_end_matches(['\0', ' '])
+            .to_string();
+        (!text.is_empty()).then_some(text)
+    }
+
+    /// the numbers of a RATIONAL entry, 0 for those dividing by 0
+    pub fn read_rationals(&self, entry: &Entry) -> Option<Vec<f64>> {
+        if entry.kind != TYPE_RATIONAL {
+            return None;
+        }
+        (0..entry.count as usize)
+            .map(|i| {
+                let at: usize = entry.value as usize + 8 * i;
+                let (numerator, denominator) = (self.u32_at(at)?, self.u32_at(at + 4)?);
+                Some(match denominator {
+                    0 => 0.0,
+                    _ => numerator as f64 / denominator as f64,
+                })
+            })
+            .collect()
+    }
+
+    /// the text of the ASCII entry with `tag` in the IFD at `ifd`
+    fn find_ascii(&self, ifd: usize, tag: u16) -> Option<String> {
+        self.read_ascii(&self.find_entry(ifd, tag)?)
+    }
+
+    /// the make and model of the camera, the make left out when the model
+    /// already starts with it, as in `Canon EOS R5`
+    pub fn get_camera(&self) -> Option<String> {
+        let ifd0: usize = self.get_ifd0()?;
+        let model: String = self.find_ascii(ifd0, TAG_MODEL)?;
+        Some(join_make(self.find_ascii(ifd0, TAG_MAKE), model))
+    }
+
+    /// the make and model of the lens, as for [`Exif::get_camera`]
+    pub fn get_lens(&self) -> Option<String> {
+        let exif_ifd: usize = self.find_ifd(self.get_ifd0()?, TAG_EXIF_IFD)?;
+        let model: String = self.find_ascii(exif_ifd, TAG_LENS_MODEL)?;
+        Some(join_make(self.find_ascii(exif_ifd, TAG_LENS_MAKE), model))
+    }
+}
+
+fn join_make(make: Option<String>, model: String) -> String {
+    let make: String = make.unwrap_or_default();
+    let is_prefixed: bool = model
+        .to_lowercase()
+        .starts_with(&make.split_whitespace().next().unwrap_or("").to_lowercase());
+    match is_prefixed {
+        true => model,
+        false => format!("{make} {model}"),
+    }
+}
+
+/// the start of the TIFF structure inside the APP1 segment of a JPEG
+fn find_jpeg_exif(data: &[u8]) -> Option<usize> {
+    let mut position: usize = 2;
+    while position + 4 <= data.len() {
+        if data[position] != 0xFF {
+            return None;
+        }
+        let marker: u8 = data[position + 1];
+        match marker {
+            // padding and markers without a length
+            0xFF => {
+                position += 1;
+                continue;
+            }
+            0x01 | 0xD0..=0xD7 => {
+                position += 2;
+                continue;
+            }
+            // start of scan: image data follows, no more metadata
+            0xDA | 0xD9 => return None,
+            _ => {}
+        }
+        let length: usize = u16::from_be_bytes([data[position + 2], data[position + 3]]) as usize;
+        let payload: &[u8] = data.get(position + 4..position + 2 + length)?;
+        if marker == 0xE1 && payload.starts_with(EXIF_HEADER) {
+            return Some(position + 4 + EXIF_HEADER.len());
+        }
+        position += 2 + length;
+    }
+    None
+}
+
+/// the value of `{camera}` or `{lens}` as `key` for the photo at `path`, its
+/// make and model; none for another key or a photo not telling
+pub fn lookup_camera(key: &str, path: &Path) -> Option<String> {
+    if key != "camera" && key != "lens" {
+        return None;
+    }
+    let exif: Exif = Exif::read(path).ok()??;
+    let value: String = match key {
+        "camera" => exif.get_camera()?,
+        _ => exif.get_lens()?,
+    };
+    Some(sanitize_component(&value)).filter(|v| !v.is_empty())
+}
@@ -0,0 +1,324 @@
+//! The expressions of `--where`, filtering the files of a run by their name,
+//! extension, path, type, size, modification time and Finder tags, e.g.
+//! `ext == 'jpg' && size > 2MB && mtime < 2023-01-01`.
+
+use crate::{
+    clock::parse_time,
+    i18n::{tr, Message},
+    platform::get_finder_tags,
+    size::parse_size,
+};
+use chrono::{DateTime, Local};
+use clap::{Arg, ArgMatches};
+use regex::Regex;
+use std::{cmp::Ordering, fs, path::Path, sync::OnceLock};
+
+const WHERE_HELP_MESSAGE: &str = r#"Only the files for which this expression holds, e.g.
+  "ext == 'jpg' && size > 2MB && mtime < 2023-01-01"
+Fields: name, ext (lower case, without the dot), path, type (file, dir
+or symlink), size, mtime (YYYY-MM-DD, with an optional HH:MM[:SS]) and
+tag (any Finder tag, on macOS, with == != and =~ only); operators: == != < <= > >=, =~ for a regular expression, && || ! and
+parentheses; quote text with spaces"#;
+
+/// the expression of `--where` for this run, if any
+static WHERE_FILTER: OnceLock<Option<Expr>> = OnceLock::new();
+
+#[derive(Clone)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Field, Op, Value),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Name,
+    Ext,
+    Path,
+    Type,
+    Size,
+    Mtime,
+    /// any of the Finder tags
+    Tag,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    /// a match of a regular expression
+    Matches,
+}
+
+#[derive(Clone)]
+pub enum Value {
+    Text(String),
+    Pattern(Regex),
+    Size(u64),
+    Time(DateTime<Local>),
+}
+
+#[derive(Clone, PartialEq)]
+enum Token {
+    /// a field, or a value needing no quotes, e.g. `2MB` or `2023-01-01`
+    Word(String),
+    /// a quoted value
+    Text(String),
+    Op(Op),
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+}
+
+impl Field {
+    fn from(name: &str) -> Option<Self> {
+        match name {
+            "name" => Some(Self::Name),
+            "ext" => Some(Self::Ext),
+            "path" => Some(Self::Path),
+            "type" => Some(Self::Type),
+            "size" => Some(Self::Size),
+            "mtime" => Some(Self::Mtime),
+            "tag" => Some(Self::Tag),
+            _ => None,
+        }
+    }
+
+    /// the value of this field compared with `text`, as typed by the field
+    fn parse_value(&self, op: Op, text: &str) -> Result<Value, String> {
+        match (self, op) {
+            (Self::Size | Self::Mtime, Op::Matches) => Err(tr(Message::ExprMatchesText, &[])),
+            (Self::Size, _) => parse_size(text)
+                .map(Value::Size)
+                .ok_or_else(|| tr(Message::InvalidSize, &[&text])),
+            (Self::Tag, Op::Lt | Op::Le | Op::Gt | Op::Ge) => {
+                Err(tr(Message::ExprTagComparison, &[]))
+            }
+            (Self::Mtime, _) => parse_time(text)
+                .map(Value::Time)
+                .ok_or_else(|| tr(Message::InvalidDateTime, &[&text])),
+            (_, Op::Matches) => Regex::new(text)
+                .map(Value::Pattern)
+                .map_err(|e| e.to_string()),
+            (Self::Ext, _) => Ok(Value::Text(text.trim_start_matches('.').to_lowercase())),
+            _ => Ok(Value::Text(text.to_string())),
+        }
+    }
+}
+
+impl Expr {
+    /// whether the expression holds for the file at `path` with `metadata`
+    pub fn holds(&self, path: &Path, metadata: &fs::Metadata) -> bool {
+        match self {
+            Self::And(a, b) => a.holds(path, metadata) && b.holds(path, metadata),
+            Self::Or(a, b) => a.holds(path, metadata) || b.holds(path, metadata),
+            Self::Not(a) => !a.holds(path, metadata),
+            Self::Compare(field, op, value) => compare(*field, *op, value, path, metadata),
+        }
+    }
+}
+
+fn compare(field: Field, op: Op, value: &Value, path: &Path, metadata: &fs::Metadata) -> bool {
+    if field == Field::Tag {
+        return has_tag(op, value, &get_finder_tags(path));
+    }
+    let text = |field: Field| -> String {
+        match field {
+            Field::Name => path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            Field::Ext => path
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default(),
+            Field::Type if metadata.is_dir() => String::from("dir"),
+            Field::Type if metadata.is_symlink() => String::from("symlink"),
+            Field::Type => String::from("file"),
+            _ => path.to_string_lossy().into_owned(),
+        }
+    };
+    let ordering: Ordering = match value {
+        Value::Pattern(pattern) => return pattern.is_match(&text(field)),
+        Value::Text(expected) => text(field).as_str().cmp(expected.as_str()),
+        Value::Size(size) => metadata.len().cmp(size),
+        Value::Time(time) => match metadata.modified() {
+            Ok(modified) => DateTime::<Local>::from(modified).cmp(time),
+            Err(_) => return false,
+        },
+    };
+    match op {
+        Op::Eq => ordering.is_eq(),
+        Op::Ne => ordering.is_ne(),
+        Op::Lt => ordering.is_lt(),
+        Op::Le => ordering.is_le(),
+        Op::Gt => ordering.is_gt(),
+        Op::Ge => ordering.is_ge(),
+        Op::Matches => false,
+    }
+}
+
+/// whether a file of `tags` has one equal to or matching `value`, or for
+/// `!=`, none equal to it
+fn has_tag(op: Op, value: &Value, tags: &[String]) -> bool {
+    match value {
+        Value::Pattern(pattern) => tags.iter().any(|t| pattern.is_match(t)),
+        Value::Text(tag) if op == Op::Ne => !tags.contains(tag),
+        Value::Text(tag) => tags.contains(tag),
+        _ => false,
+    }
+}
+
+/// parse the expression `text`, as the value parser of `--where`
+pub fn parse_expr(text: &str) -> Result<Expr, String> {
+    let tokens: Vec<Token> = tokenize(text)?;
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+    };
+    let expr: Expr = parser.parse_or()?;
+    match parser.tokens.get(parser.position) {
+        Some(_) => Err(tr(Message::ExprTrailingText, &[])),
+        None => Ok(expr),
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token: Token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '&' if chars.next_if_eq(&'&').is_some() => Token::And,
+            '|' if chars.next_if_eq(&'|').is_some() => Token::Or,
+            '!' if chars.next_if_eq(&'=').is_some() => Token::Op(Op::Ne),
+            '!' => Token::Not,
+            '=' if chars.next_if_eq(&'=').is_some() => Token::Op(Op::Eq),
+            '=' if chars.next_if_eq(&'~').is_some() => Token::Op(Op::Matches),
+            '<' if chars.next_if_eq(&'=').is_some() => Token::Op(Op::Le),
+            '<' => Token::Op(Op::Lt),
+            '>' if chars.next_if_eq(&'=').is_some() => Token::Op(Op::Ge),
+            '>' => Token::Op(Op::Gt),
+            '\'' | '"' => {
+                let mut quoted: String = String::new();
+                loop {
+                    match chars.next() {
+                        Some(q) if q == c => break,
+                        Some(q) => quoted.push(q),
+                        None => return Err(tr(Message::ExprUnclosedQuote, &[&c])),
+                    }
+                }
+                Token::Text(quoted)
+            }
+            '&' | '|' | '=' => return Err(tr(Message::ExprUnexpected, &[&c])),
+            c => {
+                let mut word: String = String::from(c);
+                while let Some(w) =
+                    chars.next_if(|w| !w.is_whitespace() && !"()&|!=<>'\"".contains(*w))
+                {
+                    word.push(w);
+                }
+                Token::Word(word)
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// a recursive descent parser, `||` binding looser than `&&`, and `&&` than `!`
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token: Option<Token> = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn next_if(&mut self, expected: &Token) -> bool {
+        let is_next: bool = self.tokens.get(self.position) == Some(expected);
+        if is_next {
+            self.position += 1;
+        }
+        is_next
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut expr: Expr = self.parse_and()?;
+        while self.next_if(&Token::Or) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut expr: Expr = self.parse_unary()?;
+        while self.next_if(&Token::And) {
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Not) => Ok(Expr::Not(Box::new(self.parse_unary()?))),
+            Some(Token::Open) => {
+                let expr: Expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err(tr(Message::ExprMissingParenthesis, &[])),
+                }
+            }
+            Some(Token::Word(name)) => {
+                let field: Field =
+                    Field::from(&name).ok_or_else(|| tr(Message::ExprUnknownField, &[&name]))?;
+                let Some(Token::Op(op)) = self.next() else {
+                    return Err(tr(Message::ExprMissingComparison, &[&name]));
+                };
+                let value: Value = match self.next() {
+                    Some(Token::Word(text) | Token::Text(text)) => field.parse_value(op, &text)?,
+                    _ => return Err(tr(Message::ExprMissingValue, &[&name])),
+                };
+                Ok(Expr::Compare(field, op, value))
+            }
+            _ => Err(tr(Message::ExprExpectedField, &[])),
+        }
+    }
+}
+
+/// the `--where` option of the tools listing files
+pub fn get_where_arg() -> Arg {
+    Arg::new("where")
+        .long("where")
+        .help(WHERE_HELP_MESSAGE)
+        .value_name("EXPRESSION")
+        .value_parser(parse_expr)
+}
+
+/// filter the files of this run by `--where`
+pub fn set_where_filter(matches: &ArgMatches) {
+    let expr: Option<Expr> = matches.try_get_one::<Expr>("where").ok().flatten().cloned();
+    let _ = WHERE_FILTER.set(expr);
+}
+
+/// whether the file at `path` with `metadata` passes the `--where` filter of
+/// this run, any file without one
+pub fn matches_where(path: &Path, metadata: &fs::Metadata) -> bool {
+    match WHERE_FILTER.get() {
+        Some(Some(expr)) => expr.holds(path, metadata),
+        _ => true,
+    }
+}
@@ -0,0 +1,40 @@
+//! What the renaming tools do when a rename fails partway through a batch, as for
+//! a file that is not writable.
+
+use clap::Arg;
+
+pub const FAILURE_POLICY_NAMES: [&str; 3] = ["abort", "skip", "ask"];
+
+const ON_ERROR_HELP_MESSAGE: &str = r#"What to do when renaming a file fails, e.g. for lack of permission:
+  abort: stop, leaving the renames done before to undo
+  skip:  leave the file as it is and go on with the rest
+  ask:   ask whether to skip it or stop
+The files skipped are listed at the end"#;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum FailurePolicy {
+    #[default]
+    Abort,
+    Skip,
+    Ask,
+}
+
+impl FailurePolicy {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "abort" => Self::Abort,
+            "skip" => Self::Skip,
+            "ask" => Self::Ask,
+            _ => panic!("Unknown failure policy: {name}"),
+        }
+    }
+}
+
+/// the `--on-error` option of the renaming tools
+pub fn get_on_error_arg() -> Arg {
+    Arg::new("on_error")
+        .long("on-error")
+        .help(ON_ERROR_HELP_MESSAGE)
+        .value_parser(FAILURE_POLICY_NAMES)
+        .default_value("abort")
+}
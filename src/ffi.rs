@@ -0,0 +1,178 @@
+//! A C API over [`crate::api`], for file-manager plugins and other programs in
+//! C, C++ or Python by cffi to plan and apply renames as the tools do. Plans go
+//! in as JSON, e.g.
+//!
+//! ```json
+//! {"conflict": "skip", "renames": [{"source": "a.jpg", "target": "b.jpg"}]}
+//! ```
+//!
+//! and results come out as JSON, with an `error` of an `exit_code` and a
+//! `message` on failure; every string returned is to be freed by
+//! [`ray_free_string`]. The functions are declared in `include/ray_commands.h`.
+
+use crate::{
+    api::{self, ConflictStrategy, Error, Operation, PipeRecord, Plan},
+    conflict::CONFLICT_STRATEGY_NAMES,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    ffi::{c_char, CStr, CString},
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+};
+
+/// a plan as given by the caller
+#[derive(Deserialize)]
+struct PlanRequest {
+    /// skip, suffix or overwrite, skip by default
+    #[serde(default)]
+    conflict: Option<String>,
+    renames: Vec<RenameRequest>,
+}
+
+#[derive(Deserialize)]
+struct RenameRequest {
+    source: PathBuf,
+    /// the new name, or none to skip the file for `reason`
+    target: Option<PathBuf>,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    exit_code: i32,
+    message: String,
+}
+
+impl From<&Error> for ErrorResponse {
+    fn from(err: &Error) -> Self {
+        Self {
+            exit_code: err.exit_code(),
+            message: err.to_string(),
+        }
+    }
+}
+
+impl PlanRequest {
+    fn into_plan(self) -> Result<Plan, Error> {
+        let conflict: &str = self.conflict.as_deref().unwrap_or("skip");
+        if !CONFLICT_STRATEGY_NAMES.contains(&conflict) {
+            return Err(Error::InvalidFormat(format!(
+                "unknown conflict strategy: {conflict}"
+            )));
+        }
+        let mut plan: Plan = Plan::with_conflict(ConflictStrategy::from(conflict));
+        for rename in self.renames {
+            let target: Result<PathBuf, String> = rename
+                .target
+                .ok_or_else(|| rename.reason.unwrap_or_default());
+            plan.add(rename.source, target);
+        }
+        Ok(plan)
+    }
+}
+
+/// plan the renames of the JSON `request`, returning
+/// `{"operations": [...]}` without changing any file
+///
+/// # Safety
+///
+/// `request` must be null or a NUL-terminated string valid for the call.
+#[no_mangle]
+pub unsafe extern "C" fn ray_plan(request: *const c_char) -> *mut c_char {
+    respond(|| {
+        let plan: Plan = read_plan(request)?;
+        Ok(json!({ "operations": api::operations(&plan) }))
+    })
+}
+
+/// apply the renames of the JSON `request`, journaled under `program` for
+/// [`ray_undo`], returning `{"records": [...], "renamed": true}` of what became
+/// of each file, with an `error` if the run stopped
+///
+/// # Safety
+///
+/// `program` and `request` must each be null or a NUL-terminated string valid
+/// for the call.
+#[no_mangle]
+pub unsafe extern "C" fn ray_apply(program: *const c_char, request: *const c_char) -> *mut c_char {
+    respond(|| {
+        let program: String = read_str(program, "program")?;
+        let plan: Plan = read_plan(request)?;
+        let (records, result): (Vec<PipeRecord>, api::Result<bool>) = api::apply(plan, &program);
+        Ok(match result {
+            Ok(is_renamed) => json!({ "records": records, "renamed": is_renamed }),
+            Err(err) => json!({ "records": records, "error": ErrorResponse::from(&err) }),
+        })
+    })
+}
+
+/// undo the latest run of `program`, returning `{"operations": [...]}` of the
+/// renames undone
+///
+/// # Safety
+///
+/// `program` must be null or a NUL-terminated string valid for the call.
+#[no_mangle]
+pub unsafe extern "C" fn ray_undo(program: *const c_char) -> *mut c_char {
+    respond(|| {
+        let program: String = read_str(program, "program")?;
+        let operations: Vec<Operation> = api::undo(&program)?;
+        Ok(json!({ "operations": operations }))
+    })
+}
+
+/// free a string returned by this API; null is ignored
+///
+/// # Safety
+///
+/// `text` must be null or a string returned by this API, not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn ray_free_string(text: *mut c_char) {
+    if !text.is_null() {
+        drop(CString::from_raw(text));
+    }
+}
+
+/// the string `text` given for `name`
+///
+/// # Safety
+///
+/// as for the functions of the API taking it
+unsafe fn read_str(text: *const c_char, name: &str) -> Result<String, Error> {
+    if text.is_null() {
+        return Err(Error::InvalidFormat(format!("no {name} given")));
+    }
+    CStr::from_ptr(text)
+        .to_str()
+        .map(str::to_string)
+        .map_err(|_| Error::InvalidFormat(format!("the {name} is not UTF-8")))
+}
+
+/// the plan of the JSON `request`
+///
+/// # Safety
+///
+/// as for the functions of the API taking it
+unsafe fn read_plan(request: *const c_char) -> Result<Plan, Error> {
+    let text: String = read_str(request, "request")?;
+    serde_json::from_str::<PlanRequest>(&text)
+        .map_err(|e| Error::InvalidFormat(e.to_string()))?
+        .into_plan()
+}
+
+/// the JSON response of `handle` as a string for the caller, an error one
+/// when it fails, or panics, which must not unwind into C
+fn respond(handle: impl FnOnce() -> Result<Value, Error>) -> *mut c_char {
+    let response: Value = match panic::catch_unwind(AssertUnwindSafe(handle)) {
+        Ok(Ok(value)) => value,
+        Ok(Err(err)) => json!({ "error": ErrorResponse::from(&err) }),
+        Err(_) => json!({ "error": { "exit_code": 1, "message": "internal error" } }),
+    };
+    // JSON escapes NUL, which a C string cannot hold
+    CString::new(response.to_string())
+        .map(CString::into_raw)
+        .unwrap_or(std::ptr::null_mut())
+}
@@ -0,0 +1,372 @@
+//! The file system as plans see it when planning and renaming: the disk, or a
+//! tree held in memory, on which a plan runs for real without touching a file,
+//! to check what a dry run promises or to test the planner quickly. Planning
+//! gets a [`ReadOnly`] handle to it, and only applying a plan the file system.
+//!
+//! The guarantee holds for the renames going through a
+//! [`RenamePlan`](crate::plan::RenamePlan), and for nothing else: the tools
+//! changing files outside a plan, such as clean_temp, fix_perms or link_farm,
+//! keep their dry runs by stopping at the preview, apart from the step
+//! changing files.
+
+use crate::{backup::set_aside, paths::is_same_file, recycle::move_to_trash, throttle::pace_bytes};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// what a plan needs to know of an entry
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Stat {
+    pub is_dir: bool,
+    /// the size of a file, 0 for a directory
+    pub len: u64,
+}
+
+/// the operations plans do on files, failing as the disk would
+pub trait Filesystem: Send + Sync {
+    /// the entry at `path`, not following a symbolic link
+    fn stat(&self, path: &Path) -> io::Result<Stat>;
+
+    /// the paths of the entries of the directory `path`, joined to it; those
+    /// of the current directory for an empty path
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+
+    /// rename `from` to `to`, replacing a file there
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+
+    /// copy the file `from` to `to`, returning the bytes copied
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<u64>;
+
+    /// remove the file or empty directory at `path`
+    fn remove(&self, path: &Path) -> io::Result<()>;
+
+    /// create the directory `path` and those leading to it
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    /// whether `a` and `b` name the same file; only equal paths do by default
+    fn is_same_file(&self, a: &Path, b: &Path) -> bool {
+        a == b
+    }
+
+    /// move the file at `path` to the trash; removed by default
+    fn trash(&self, path: &Path) -> io::Result<()> {
+        self.remove(path)
+    }
+
+    /// move the file at `path` out of the way of a rename replacing it,
+    /// returning where it went, none for the trash; trashed by default
+    fn set_aside(&self, path: &Path) -> io::Result<Option<PathBuf>> {
+        self.trash(path).map(|()| None)
+    }
+}
+
+/// a handle to a file system that can only read it, the only one the planning,
+/// previews and checks of a [`RenamePlan`](crate::plan::RenamePlan) get, so
+/// that however they grow they cannot change a file, and a dry run stays one
+#[derive(Clone, Copy)]
+pub struct ReadOnly<'a> {
+    filesystem: &'a dyn Filesystem,
+}
+
+impl<'a> ReadOnly<'a> {
+    pub fn new(filesystem: &'a dyn Filesystem) -> Self {
+        Self { filesystem }
+    }
+
+    /// the entry at `path`, not following a symbolic link
+    pub fn stat(&self, path: &Path) -> io::Result<Stat> {
+        self.filesystem.stat(path)
+    }
+
+    /// the paths of the entries of the directory `path`, joined to it
+    pub fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        self.filesystem.read_dir(path)
+    }
+
+    /// whether there is an entry at `path`
+    pub fn exists(&self, path: &Path) -> bool {
+        self.filesystem.stat(path).is_ok()
+    }
+
+    /// whether `a` and `b` name the same file
+    pub fn is_same_file(&self, a: &Path, b: &Path) -> bool {
+        self.filesystem.is_same_file(a, b)
+    }
+}
+
+/// the disk, where a replaced file goes to its backup or the trash
+#[derive(Clone, Copy, Default)]
+pub struct DiskFilesystem;
+
+impl Filesystem for DiskFilesystem {
+    fn stat(&self, path: &Path) -> io::Result<Stat> {
+        let metadata: fs::Metadata = fs::symlink_metadata(path)?;
+        Ok(Stat {
+            is_dir: metadata.is_dir(),
+            len: if metadata.is_dir() { 0 } else { metadata.len() },
+        })
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let listed: &Path = match path.as_os_str().is_empty() {
+            true => Path::new("."),
+            false => path,
+        };
+        fs::read_dir(listed)?
+            .map(|entry| Ok(path.join(entry?.file_name())))
+            .collect()
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        fs::rename(from, to)
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<u64> {
+        let count: u64 = fs::copy(from, to)?;
+        pace_bytes(count);
+        Ok(count)
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        match self.stat(path)?.is_dir {
+            true => fs::remove_dir(path),
+            false => fs::remove_file(path),
+        }
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir_all(path)
+    }
+
+    /// as when they differ only in case or Unicode normalization on a file
+    /// system ignoring the difference
+    fn is_same_file(&self, a: &Path, b: &Path) -> bool {
+        is_same_file(a, b)
+    }
+
+    fn trash(&self, path: &Path) -> io::Result<()> {
+        move_to_trash(path)
+    }
+
+    fn set_aside(&self, path: &Path) -> io::Result<Option<PathBuf>> {
+        set_aside(path)
+    }
+}
+
+#[derive(Clone)]
+enum Entry {
+    Dir,
+    File(Vec<u8>),
+}
+
+/// a tree of files in memory, by their paths as given, failing as a POSIX file
+/// system does: a missing parent, a directory replaced or not empty
+#[derive(Default)]
+pub struct MemoryFilesystem {
+    entries: Mutex<BTreeMap<PathBuf, Entry>>,
+}
+
+impl MemoryFilesystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the tree with the file `path` holding `contents`, along with the
+    /// directories leading to it
+    pub fn with_file(self, path: impl Into<PathBuf>, contents: impl Into<Vec<u8>>) -> Self {
+        let path: PathBuf = path.into();
+        if let Some(parent) = path.parent() {
+            let _ = self.create_dir_all(parent);
+        }
+        self.lock().insert(path, Entry::File(contents.into()));
+        self
+    }
+
+    /// the tree with the directory `path` and those leading to it
+    pub fn with_dir(self, path: impl Into<PathBuf>) -> Self {
+        let _ = self.create_dir_all(&path.into());
+        self
+    }
+
+    /// the paths of the files, sorted
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.lock()
+            .iter()
+            .filter(|(_, entry)| matches!(entry, Entry::File(_)))
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    /// the contents of the file `path`, if there is one
+    pub fn contents(&self, path: &Path) -> Option<Vec<u8>> {
+        match self.lock().get(path) {
+            Some(Entry::File(contents)) => Some(contents.clone()),
+            _ => None,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<PathBuf, Entry>> {
+        // a panic while holding the tree leaves it whole, each change being one step
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// whether `path` may be created in `entries`: the current directory, the root
+/// or a child of a directory there
+fn has_parent(entries: &BTreeMap<PathBuf, Entry>, path: &Path) -> bool {
+    match path.parent() {
+        None => true,
+        Some(parent) if parent.as_os_str().is_empty() => true,
+        Some(parent) => matches!(entries.get(parent), Some(Entry::Dir)),
+    }
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, path.display().to_string())
+}
+
+impl Filesystem for MemoryFilesystem {
+    fn stat(&self, path: &Path) -> io::Result<Stat> {
+        match self.lock().get(path) {
+            Some(Entry::Dir) => Ok(Stat {
+                is_dir: true,
+                len: 0,
+            }),
+            Some(Entry::File(contents)) => Ok(Stat {
+                is_dir: false,
+                len: contents.len() as u64,
+            }),
+            None => Err(not_found(path)),
+        }
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let entries = self.lock();
+        match entries.get(path) {
+            Some(Entry::File(_)) => Err(io::Error::new(
+                io::ErrorKind::NotADirectory,
+                path.display().to_string(),
+            )),
+            None if !path.as_os_str().is_empty() => Err(not_found(path)),
+            _ => Ok(entries
+                .keys()
+                .filter(|p| p.parent() == Some(path))
+                .cloned()
+                .collect()),
+        }
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut entries = self.lock();
+        let entry: Entry = entries.get(from).cloned().ok_or_else(|| not_found(from))?;
+        if !has_parent(&entries, to) {
+            return Err(not_found(to));
+        }
+        if from == to {
+            return Ok(());
+        }
+        match (&entry, entries.get(to)) {
+            (Entry::File(_), Some(Entry::Dir)) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::IsADirectory,
+                    to.display().to_string(),
+                ));
+            }
+            (Entry::Dir, Some(Entry::File(_))) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotADirectory,
+                    to.display().to_string(),
+                ));
+            }
+            (Entry::Dir, Some(Entry::Dir)) if entries.keys().any(|p| p.parent() == Some(to)) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::DirectoryNotEmpty,
+                    to.display().to_string(),
+                ));
+            }
+            _ => (),
+        }
+        if to.starts_with(from) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is inside {}", to.display(), from.display()),
+            ));
+        }
+        // a directory moves with everything under it
+        let moved: Vec<PathBuf> = entries
+            .keys()
+            .filter(|p| p.starts_with(from))
+            .cloned()
+            .collect();
+        for path in moved {
+            let entry: Entry = entries.remove(&path).unwrap();
+            // joining an empty path would add a trailing separator
+            let moved_path: PathBuf = match path.strip_prefix(from).unwrap() {
+                relative if relative.as_os_str().is_empty() => to.to_path_buf(),
+                relative => to.join(relative),
+            };
+            entries.insert(moved_path, entry);
+        }
+        Ok(())
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<u64> {
+        let mut entries = self.lock();
+        let Some(Entry::File(contents)) = entries.get(from).cloned() else {
+            return Err(not_found(from));
+        };
+        if !has_parent(&entries, to) {
+            return Err(not_found(to));
+        }
+        if let Some(Entry::Dir) = entries.get(to) {
+            return Err(io::Error::new(
+                io::ErrorKind::IsADirectory,
+                to.display().to_string(),
+            ));
+        }
+        let len: u64 = contents.len() as u64;
+        entries.insert(to.to_path_buf(), Entry::File(contents));
+        Ok(len)
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        let mut entries = self.lock();
+        match entries.get(path) {
+            None => return Err(not_found(path)),
+            Some(Entry::Dir) if entries.keys().any(|p| p.parent() == Some(path)) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::DirectoryNotEmpty,
+                    path.display().to_string(),
+                ));
+            }
+            _ => (),
+        }
+        entries.remove(path);
+        Ok(())
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        let mut entries = self.lock();
+        for ancestor in path.ancestors().collect::<Vec<&Path>>().into_iter().rev() {
+            if ancestor.as_os_str().is_empty() || ancestor.parent().is_none() {
+                continue;
+            }
+            match entries.get(ancestor) {
+                Some(Entry::File(_)) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::NotADirectory,
+                        ancestor.display().to_string(),
+                    ));
+                }
+                Some(Entry::Dir) => (),
+                None => {
+                    entries.insert(ancestor.to_path_buf(), Entry::Dir);
+                }
+            }
+        }
+        Ok(())
+    }
+}
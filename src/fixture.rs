@@ -0,0 +1,176 @@
+//! Fixtures for testing the tools end to end, here and in programs building on
+//! them: a fresh directory of files with set contents and times, the tools run
+//! in it at a fixed time by `--now` with their output captured, and the tree
+//! they leave listed as text, to compare with the expected snapshot.
+//!
+//! Each fixture has a home of its own for the journals, caches and
+//! configuration of the tools, so that runs neither read nor leave anything of
+//! the user's, and is removed when dropped.
+
+use std::{
+    env,
+    ffi::OsStr,
+    fs::{self, File, FileTimes},
+    io,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::SystemTime,
+};
+
+/// the fixtures made by this process so far, numbering their directories
+static FIXTURE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+pub struct Fixture {
+    root: PathBuf,
+    /// the time of the runs, by `--now`, the clock's when none
+    now: Option<String>,
+}
+
+/// what a run printed and how it ended
+pub struct Run {
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl From<Output> for Run {
+    fn from(output: Output) -> Self {
+        Self {
+            code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        }
+    }
+}
+
+impl Fixture {
+    /// an empty fixture in the temporary directory
+    pub fn new() -> io::Result<Self> {
+        let name: String = format!(
+            "ray-commands-fixture-{}-{}",
+            std::process::id(),
+            FIXTURE_COUNT.fetch_add(1, Ordering::Relaxed)
+        );
+        let root: PathBuf = env::temp_dir().join(name);
+        if root.exists() {
+            fs::remove_dir_all(&root)?;
+        }
+        fs::create_dir_all(root.join("files"))?;
+        fs::create_dir_all(root.join("home"))?;
+        Ok(Self { root, now: None })
+    }
+
+    /// the fixture with the file `name` holding `contents`, along with the
+    /// directories leading to it
+    pub fn with_file(self, name: &str, contents: impl AsRef<[u8]>) -> io::Result<Self> {
+        let path: PathBuf = self.path(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)?;
+        Ok(self)
+    }
+
+    /// the fixture with the empty directory `name`
+    pub fn with_dir(self, name: &str) -> io::Result<Self> {
+        fs::create_dir_all(self.path(name))?;
+        Ok(self)
+    }
+
+    /// the fixture with the file `name` last modified at `time`
+    pub fn with_modified(self, name: &str, time: SystemTime) -> io::Result<Self> {
+        let times: FileTimes = FileTimes::new().set_accessed(time).set_modified(time);
+        File::options()
+            .write(true)
+            .open(self.path(name))?
+            .set_times(times)?;
+        Ok(self)
+    }
+
+    /// the fixture running the tools at `now`, e.g. `2024-05-01T10:00:00Z`
+    pub fn with_now(mut self, now: &str) -> Self {
+        self.now = Some(now.to_string());
+        self
+    }
+
+    /// the directory of the files, where the tools run
+    pub fn dir(&self) -> PathBuf {
+        self.root.join("files")
+    }
+
+    /// the path of the file `name` of the fixture
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.dir().join(name)
+    }
+
+    /// run `program`, e.g. a binary of this crate by `env!("CARGO_BIN_EXE_<name>")`,
+    /// with `args` in the directory of the files, in English and UTC, at the
+    /// time of the fixture and without a terminal
+    pub fn run<I, S>(&self, program: impl AsRef<OsStr>, args: I) -> io::Result<Run>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let home: PathBuf = self.root.join("home");
+        let mut command: Command = Command::new(program);
+        // the options before `args`, which may end with `--` and paths
+        command.args(["--lang", "en"]);
+        if let Some(now) = &self.now {
+            command.arg(format!("--now={now}"));
+        }
+        command
+            .args(args)
+            .current_dir(self.dir())
+            .env("HOME", &home)
+            .env("USERPROFILE", &home)
+            .env("APPDATA", home.join("config"))
+            .env("LOCALAPPDATA", home.join("local"))
+            .env("XDG_CONFIG_HOME", home.join("config"))
+            .env("XDG_CACHE_HOME", home.join("cache"))
+            .env("XDG_STATE_HOME", home.join("state"))
+            .env("TZ", "UTC")
+            .env("LANG", "C")
+            .env("LC_ALL", "C")
+            .env("NO_COLOR", "1");
+        command.output().map(Run::from)
+    }
+
+    /// the files and directories of the fixture, one a line by their path with
+    /// `/` and sorted, a directory ending with `/` and a file followed by its
+    /// size, as a snapshot of the tree
+    pub fn snapshot(&self) -> io::Result<String> {
+        let mut lines: Vec<String> = Vec::new();
+        list_tree(&self.dir(), Path::new(""), &mut lines)?;
+        lines.sort();
+        Ok(lines.join("\n"))
+    }
+
+    /// the contents of the file `name` of the fixture, as text
+    pub fn read(&self, name: &str) -> io::Result<String> {
+        fs::read_to_string(self.path(name))
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+/// add a line for each entry under `dir`, named by its path under `relative`
+fn list_tree(dir: &Path, relative: &Path, lines: &mut Vec<String>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry: fs::DirEntry = entry?;
+        let name: PathBuf = relative.join(entry.file_name());
+        let shown: String = name.to_string_lossy().replace('\\', "/");
+        let metadata: fs::Metadata = fs::symlink_metadata(entry.path())?;
+        if metadata.is_dir() {
+            lines.push(format!("{shown}/"));
+            list_tree(&entry.path(), &name, lines)?;
+        } else {
+            lines.push(format!("{shown} {}", metadata.len()));
+        }
+    }
+    Ok(())
+}
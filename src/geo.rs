@@ -0,0 +1,134 @@
+//! Placing photos by the GPS coordinates in their EXIF, for `{location}` and
+//! `{country}`: the nearest of the cities bundled in `geo/cities.tsv`, so
+//! that no network is needed.
+
+use crate::{exif::Exif, sanitize::sanitize_component};
+use std::{io, path::Path, sync::OnceLock};
+
+/// the bundled cities, one a line: name, country, latitude and longitude
+const CITIES: &str = include_str!("geo/cities.tsv");
+
+/// the farthest a photo is from a city to be placed in it
+const CITY_RADIUS_KM: f64 = 60.0;
+
+/// the farthest a photo is from a city to be placed in its country, nearer
+/// borders than that too coarse to tell
+const COUNTRY_RADIUS_KM: f64 = 400.0;
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+const TAG_GPS_IFD: u16 = 0x8825;
+const TAG_LATITUDE_REF: u16 = 0x0001;
+const TAG_LATITUDE: u16 = 0x0002;
+const TAG_LONGITUDE_REF: u16 = 0x0003;
+const TAG_LONGITUDE: u16 = 0x0004;
+
+struct City {
+    name: &'static str,
+    country: &'static str,
+    latitude: f64,
+    longitude: f64,
+}
+
+/// where a photo was taken
+pub struct Place {
+    /// the nearest city, if near enough
+    pub city: Option<&'static str>,
+    pub country: &'static str,
+}
+
+fn get_cities() -> &'static [City] {
+    static PARSED: OnceLock<Vec<City>> = OnceLock::new();
+    PARSED.get_or_init(|| {
+        CITIES
+            .lines()
+            .filter(|l| !l.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split('\t');
+                Some(City {
+                    name: fields.next()?,
+                    country: fields.next()?,
+                    latitude: fields.next()?.parse().ok()?,
+                    longitude: fields.next()?.parse().ok()?,
+                })
+            })
+            .collect()
+    })
+}
+
+/// the great-circle distance between two points in degrees, in kilometers
+fn get_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (latitude_a, latitude_b) = (a.0.to_radians(), b.0.to_radians());
+    let half_chord: f64 = ((latitude_b - latitude_a) / 2.0).sin().powi(2)
+        + latitude_a.cos() * latitude_b.cos() * ((b.1 - a.1).to_radians() / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * half_chord.sqrt().asin()
+}
+
+/// the place of the coordinates, none far from any bundled city
+pub fn find_place(latitude: f64, longitude: f64) -> Option<Place> {
+    let (city, distance) = get_cities()
+        .iter()
+        .map(|c| {
+            (
+                c,
+                get_distance((latitude, longitude), (c.latitude, c.longitude)),
+            )
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+    (distance <= COUNTRY_RADIUS_KM).then_some(Place {
+        city: (distance <= CITY_RADIUS_KM).then_some(city.name),
+        country: city.country,
+    })
+}
+
+/// the value of `{location}` or `{country}` as `key` for the photo at `path`:
+/// the city it was taken in, or else the country, and the country; none for
+/// another key or a photo without GPS coordinates
+pub fn lookup_location(key: &str, path: &Path) -> Option<String> {
+    if key != "location" && key != "country" {
+        return None;
+    }
+    let (latitude, longitude) = read_coordinates(path).ok()??;
+    let place: Place = find_place(latitude, longitude)?;
+    let value: &str = match key {
+        "location" => place.city.unwrap_or(place.country),
+        _ => place.country,
+    };
+    Some(sanitize_component(value))
+}
+
+/// the latitude and longitude in the EXIF of a JPEG or TIFF-based file, none
+/// when it has none
+pub fn read_coordinates(path: &Path) -> io::Result<Option<(f64, f64)>> {
+    Ok(Exif::read(path)?.and_then(|e| get_coordinates(&e)))
+}
+
+/// the coordinates in the GPS IFD that IFD0 points to
+fn get_coordinates(exif: &Exif) -> Option<(f64, f64)> {
+    let gps_ifd: usize = exif.find_ifd(exif.get_ifd0()?, TAG_GPS_IFD)?;
+    let latitude: f64 = get_degrees(exif, gps_ifd, TAG_LATITUDE, TAG_LATITUDE_REF, "S")?;
+    let longitude: f64 = get_degrees(exif, gps_ifd, TAG_LONGITUDE, TAG_LONGITUDE_REF, "W")?;
+    let is_valid: bool = latitude.abs() <= 90.0 && longitude.abs() <= 180.0;
+    // cameras without a fix write zeros
+    (is_valid && (latitude, longitude) != (0.0, 0.0)).then_some((latitude, longitude))
+}
+
+/// the degrees, minutes and seconds of `tag` in the GPS IFD at `ifd` in
+/// degrees, negative when the letter of `reference_tag` is `negative`
+fn get_degrees(
+    exif: &Exif,
+    ifd: usize,
+    tag: u16,
+    reference_tag: u16,
+    negative: &str,
+) -> Option<f64> {
+    let [degrees, minutes, seconds] = exif.read_rationals(&exif.find_entry(ifd, tag)?)?[..] else {
+        return None;
+    };
+    let value: f64 = degrees + minutes / 60.0 + seconds / 3600.0;
+    let reference: String = exif.read_ascii(&exif.find_entry(ifd, reference_tag)?)?;
+    Some(match reference == negative {
+        true => -value,
+        false => value,
+    })
+}
@@ -0,0 +1,151 @@
+//! A seatbelt against mistyped paths and globs: unless `--force`, the tools
+//! changing files refuse to work on a file system root, the home directory
+//! itself or a system directory, or on more files at once than `--max-files`.
+
+use crate::{
+    config::Config,
+    error,
+    i18n::{tr, Message},
+};
+use clap::{value_parser, Arg, ArgAction, ArgMatches};
+use std::{
+    env, fs,
+    path::{self, Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// the files a batch may change when neither `--max-files` nor the
+/// configuration file sets the limit
+pub const DEFAULT_MAX_FILES: usize = 10_000;
+
+/// whether `--force` was given, and the files a batch of this run may change
+static GUARD: OnceLock<(bool, usize)> = OnceLock::new();
+
+/// the directories holding the system, refused along with everything in them
+#[cfg(unix)]
+const SYSTEM_DIRS: [&str; 13] = [
+    "/bin",
+    "/boot",
+    "/dev",
+    "/etc",
+    "/lib",
+    "/lib32",
+    "/lib64",
+    "/proc",
+    "/sbin",
+    "/sys",
+    "/usr",
+    "/System",
+    "/private/etc",
+];
+
+const MAX_FILES_HELP_MESSAGE: &str = r#"Refuse a batch changing more files than this, unless --force;
+`max_files` in the configuration file sets the default, else 10000"#;
+
+/// the `--force` and `--max-files` options of the tools changing files
+pub fn get_guard_args() -> [Arg; 2] {
+    [
+        Arg::new("force")
+            .long("force")
+            .help("Work even on a file system root, the home directory, a system\ndirectory or more files than --max-files")
+            .action(ArgAction::SetTrue),
+        Arg::new("max_files")
+            .long("max-files")
+            .help(MAX_FILES_HELP_MESSAGE)
+            .value_parser(value_parser!(usize)),
+    ]
+}
+
+/// guard the changes of this run by `--force` and `--max-files`, or else by
+/// `max_files` in the configuration file
+pub fn set_guard(matches: &ArgMatches) {
+    let max_files: usize = match matches.get_one::<usize>("max_files") {
+        Some(max_files) => *max_files,
+        None => match Config::load() {
+            Ok(config) => config.max_files.unwrap_or(DEFAULT_MAX_FILES),
+            Err(err) => {
+                eprintln!("{}", tr(Message::Warning, &[&err]));
+                DEFAULT_MAX_FILES
+            }
+        },
+    };
+    let _ = GUARD.set((matches.get_flag("force"), max_files));
+}
+
+fn is_forced() -> bool {
+    GUARD.get().is_some_and(|(force, _)| *force)
+}
+
+/// refuse, unless `--force`, when any of `paths` is a file system root, the
+/// home directory itself, or a system directory or anything in one
+pub fn check_paths<P: AsRef<Path>>(paths: &[P]) -> error::Result<()> {
+    if is_forced() {
+        return Ok(());
+    }
+    match paths.iter().find(|p| is_guarded(&resolve(p.as_ref()))) {
+        Some(path) => Err(error::Error::GuardedPath(path.as_ref().to_path_buf())),
+        None => Ok(()),
+    }
+}
+
+/// refuse, unless `--force`, a batch changing more than the files allowed
+pub fn check_batch_size(count: usize) -> error::Result<()> {
+    let (force, max_files) = *GUARD.get().unwrap_or(&(false, DEFAULT_MAX_FILES));
+    match !force && count > max_files {
+        true => Err(error::Error::TooManyFiles {
+            count,
+            max: max_files,
+        }),
+        false => Ok(()),
+    }
+}
+
+/// `path` absolute and free of `.`, `..` and linked directories; a link itself
+/// is left as it is, being what the tools change rather than where it points
+fn resolve(path: &Path) -> PathBuf {
+    let absolute: PathBuf = path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    match (absolute.parent(), absolute.file_name()) {
+        (Some(parent), Some(name)) if !matches!(name.to_str(), Some("." | "..")) => {
+            fs::canonicalize(parent)
+                .unwrap_or_else(|_| parent.to_path_buf())
+                .join(name)
+        }
+        _ => fs::canonicalize(&absolute).unwrap_or(absolute),
+    }
+}
+
+/// whether the resolved `path` is one the tools refuse without `--force`
+fn is_guarded(path: &Path) -> bool {
+    path.parent().is_none()
+        || get_home_dir().is_some_and(|home| path == home)
+        || get_system_dirs().iter().any(|dir| path.starts_with(dir))
+}
+
+fn get_home_dir() -> Option<PathBuf> {
+    let home: PathBuf = PathBuf::from(env::var_os(if cfg!(windows) {
+        "USERPROFILE"
+    } else {
+        "HOME"
+    })?);
+    fs::canonicalize(&home).ok().or(Some(home))
+}
+
+#[cfg(unix)]
+fn get_system_dirs() -> Vec<PathBuf> {
+    SYSTEM_DIRS.iter().map(PathBuf::from).collect()
+}
+
+/// the Windows directory and the program directories
+#[cfg(windows)]
+fn get_system_dirs() -> Vec<PathBuf> {
+    [
+        "SystemRoot",
+        "ProgramFiles",
+        "ProgramFiles(x86)",
+        "ProgramData",
+    ]
+    .iter()
+    .filter_map(|v| env::var_os(v))
+    .map(|dir| fs::canonicalize(&dir).unwrap_or_else(|_| PathBuf::from(dir)))
+    .collect()
+}
@@ -0,0 +1,106 @@
+//! Input paths naming the same file by several hard links, which renaming each
+//! to a name of its own, e.g. by their shared time, turns into look-alike
+//! duplicates: warned about, or by `--hardlinks`, kept once or left alone.
+
+use crate::{
+    i18n::{tr, Message},
+    platform::get_hard_link_id,
+};
+use clap::{Arg, ArgMatches};
+use std::{collections::HashMap, fs, path::PathBuf, sync::OnceLock};
+
+pub const HARD_LINK_POLICY_NAMES: [&str; 4] = ["warn", "skip", "first", "all"];
+
+const HARDLINKS_HELP_MESSAGE: &str = r#"What to do with input paths that are hard links to the same file:
+  warn:  rename them all, warning of each set of them
+  skip:  leave them all as they are
+  first: rename only the first of each set
+  all:   rename them all, knowingly"#;
+
+/// what becomes of the hard links among the input paths of this run
+static HARD_LINK_POLICY: OnceLock<HardLinkPolicy> = OnceLock::new();
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum HardLinkPolicy {
+    #[default]
+    Warn,
+    Skip,
+    First,
+    All,
+}
+
+impl HardLinkPolicy {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "warn" => Self::Warn,
+            "skip" => Self::Skip,
+            "first" => Self::First,
+            "all" => Self::All,
+            _ => panic!("Unknown hard link policy: {name}"),
+        }
+    }
+}
+
+/// the `--hardlinks` option of the tools renaming input paths
+pub fn get_hardlinks_arg() -> Arg {
+    Arg::new("hardlinks")
+        .long("hardlinks")
+        .help(HARDLINKS_HELP_MESSAGE)
+        .value_parser(HARD_LINK_POLICY_NAMES)
+        .default_value("warn")
+}
+
+/// handle the hard links among the input paths of this run by `--hardlinks`
+pub fn set_hard_link_policy(matches: &ArgMatches) {
+    let policy: HardLinkPolicy = matches
+        .try_get_one::<String>("hardlinks")
+        .ok()
+        .flatten()
+        .map_or_else(HardLinkPolicy::default, |name| HardLinkPolicy::from(name));
+    let _ = HARD_LINK_POLICY.set(policy);
+}
+
+/// `paths` with the hard links to the same file among them handled by the
+/// policy of this run, each set told of on standard error unless by `all`
+pub fn handle_hard_links(paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    let policy: HardLinkPolicy = HARD_LINK_POLICY.get().copied().unwrap_or_default();
+    if policy == HardLinkPolicy::All {
+        return paths;
+    }
+    // the indices of the paths of each file linked more than once
+    let mut links: HashMap<(u64, u64), Vec<usize>> = HashMap::new();
+    for (i, path) in paths.iter().enumerate() {
+        if let Some(id) = fs::metadata(path).ok().as_ref().and_then(get_hard_link_id) {
+            links.entry(id).or_default().push(i);
+        }
+    }
+    let mut sets: Vec<Vec<usize>> = links.into_values().filter(|set| set.len() > 1).collect();
+    sets.sort();
+
+    let mut is_dropped: Vec<bool> = vec![false; paths.len()];
+    for set in &sets {
+        let names: Vec<String> = set
+            .iter()
+            .map(|i| paths[*i].display().to_string())
+            .collect();
+        let names: String = names.join(", ");
+        match policy {
+            HardLinkPolicy::Skip => {
+                let reason: String = tr(Message::HardLinked, &[]);
+                eprintln!("{}", tr(Message::Skipping, &[&names, &reason]));
+                set.iter().for_each(|i| is_dropped[*i] = true);
+            }
+            HardLinkPolicy::First => {
+                let first: String = paths[set[0]].display().to_string();
+                eprintln!("{}", tr(Message::RenamingFirstLink, &[&first, &names]));
+                set[1..].iter().for_each(|i| is_dropped[*i] = true);
+            }
+            _ => eprintln!("{}", tr(Message::HardLinksApart, &[&names])),
+        }
+    }
+    paths
+        .into_iter()
+        .zip(is_dropped)
+        .filter_map(|(path, is_dropped)| (!is_dropped).then_some(path))
+        .collect()
+}
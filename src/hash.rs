@@ -0,0 +1,196 @@
+//! Content hashes of files, and the cache of them kept across runs by the tools
+//! hashing whole trees, so that a run over a mostly unchanged archive only
+//! hashes the files changed since the last one.
+
+use crate::{jobs::is_single_threaded, journal::APPLICATION_DIR_NAME};
+use clap::{Arg, ArgAction, ArgMatches};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
+
+const HASH_CACHE_FILE_NAME: &str = "hashes.jsonl";
+
+/// the lines of the cache file past which it is compacted when mostly stale
+const COMPACTION_LINES: usize = 10_000;
+
+/// the cached hashes of this run, loaded at the first one wanted; none when
+/// the cache is off by `--no-hash-cache` or for tools not hashing trees
+static HASH_CACHE: OnceLock<Option<Mutex<HashCache>>> = OnceLock::new();
+
+/// whether the hashes of this run are cached, unless `--no-hash-cache`
+static IS_HASH_CACHED: OnceLock<bool> = OnceLock::new();
+
+/// what identifies the content of a file as long as it is not changed: the
+/// device and inode it is on, its size and its modification time
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+struct CacheKey {
+    dev: u64,
+    inode: u64,
+    size: u64,
+    mtime_secs: i64,
+    mtime_nanos: u32,
+}
+
+/// a line of the cache file
+#[derive(Serialize, Deserialize)]
+struct CacheLine {
+    #[serde(flatten)]
+    key: CacheKey,
+    hash: String,
+}
+
+/// the hashes read from the cache file, to which those of new files are added
+struct HashCache {
+    path: PathBuf,
+    hashes: HashMap<CacheKey, String>,
+}
+
+impl HashCache {
+    /// the cache in `path`, empty when there is none yet; a line that cannot
+    /// be read, as from a run stopped while writing it, is left out, and the
+    /// file is rewritten without its stale lines once they outnumber the others
+    fn load(path: PathBuf) -> Self {
+        let mut hashes: HashMap<CacheKey, String> = HashMap::new();
+        let mut line_count: usize = 0;
+        if let Ok(file) = File::open(&path) {
+            for line in BufReader::new(file).lines().map_while(Result::ok) {
+                line_count += 1;
+                if let Ok(line) = serde_json::from_str::<CacheLine>(&line) {
+                    hashes.insert(line.key, line.hash);
+                }
+            }
+        }
+        let cache: Self = Self { path, hashes };
+        if line_count > COMPACTION_LINES && line_count > 2 * cache.hashes.len() {
+            let _ = cache.compact();
+        }
+        cache
+    }
+
+    /// rewrite the cache file with a line for each hash, through a temporary
+    /// file so that runs reading it meanwhile see the old one
+    fn compact(&self) -> io::Result<()> {
+        let temporary: PathBuf = self.path.with_extension("jsonl.tmp");
+        let mut file: File = File::create(&temporary)?;
+        for (key, hash) in &self.hashes {
+            let line: CacheLine = CacheLine {
+                key: *key,
+                hash: hash.clone(),
+            };
+            writeln!(file, "{}", serde_json::to_string(&line).unwrap())?;
+        }
+        file.sync_all()?;
+        fs::rename(&temporary, &self.path)
+    }
+
+    /// add the hash of a file to the cache and append it to the cache file,
+    /// where later lines win over earlier ones; a cache that cannot be
+    /// written only costs hashing again
+    fn insert(&mut self, key: CacheKey, hash: &str) {
+        self.hashes.insert(key, hash.to_string());
+        let line: CacheLine = CacheLine {
+            key,
+            hash: hash.to_string(),
+        };
+        if let Some(parent) = self.path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(mut file) = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+        {
+            let _ = writeln!(file, "{}", serde_json::to_string(&line).unwrap());
+        }
+    }
+}
+
+/// BLAKE3 digest of the file content as a lowercase hex string, memory-mapped
+/// and on the threads of `--jobs` for large files
+pub fn hash_file(path: &Path) -> io::Result<String> {
+    let mut hasher: blake3::Hasher = blake3::Hasher::new();
+    // small files and those that cannot be mapped, as pipes, are read instead
+    match is_single_threaded() {
+        true => hasher.update_mmap(path)?,
+        false => hasher.update_mmap_rayon(path)?,
+    };
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+/// the BLAKE3 digest of the file content as `hash_file` gives it, taken from
+/// the cache when the file is unchanged since it was hashed, by its device,
+/// inode, size and modification time
+pub fn hash_file_cached(path: &Path) -> io::Result<String> {
+    let cache: Option<&Mutex<HashCache>> = HASH_CACHE
+        .get_or_init(|| {
+            IS_HASH_CACHED
+                .get()
+                .copied()
+                .unwrap_or(false)
+                .then(|| Mutex::new(HashCache::load(get_hash_cache_path())))
+        })
+        .as_ref();
+    let (Some(cache), Some(key)) = (cache, get_cache_key(path)) else {
+        return hash_file(path);
+    };
+    if let Some(hash) = cache.lock().unwrap().hashes.get(&key) {
+        return Ok(hash.clone());
+    }
+    let hash: String = hash_file(path)?;
+    cache.lock().unwrap().insert(key, &hash);
+    Ok(hash)
+}
+
+/// the key of the file at `path` in the cache, none without inodes
+#[cfg(unix)]
+fn get_cache_key(path: &Path) -> Option<CacheKey> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata: fs::Metadata = fs::metadata(path).ok()?;
+    Some(CacheKey {
+        dev: metadata.dev(),
+        inode: metadata.ino(),
+        size: metadata.size(),
+        mtime_secs: metadata.mtime(),
+        mtime_nanos: u32::try_from(metadata.mtime_nsec()).ok()?,
+    })
+}
+
+#[cfg(not(unix))]
+fn get_cache_key(_path: &Path) -> Option<CacheKey> {
+    None
+}
+
+/// `$XDG_CACHE_HOME/ray-commands/hashes.jsonl` (`~/.cache` by default), or
+/// `%LOCALAPPDATA%\ray-commands\hashes.jsonl` on Windows
+fn get_hash_cache_path() -> PathBuf {
+    let cache_dir: PathBuf = if cfg!(windows) {
+        PathBuf::from(env::var_os("LOCALAPPDATA").unwrap_or_default())
+    } else {
+        match env::var_os("XDG_CACHE_HOME").filter(|d| !d.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(env::var_os("HOME").unwrap_or_default()).join(".cache"),
+        }
+    };
+    cache_dir
+        .join(APPLICATION_DIR_NAME)
+        .join(HASH_CACHE_FILE_NAME)
+}
+
+/// the `--no-hash-cache` flag of the tools hashing trees
+pub fn get_no_hash_cache_arg() -> Arg {
+    Arg::new("no_hash_cache")
+        .long("no-hash-cache")
+        .help("Hash every file again instead of reusing the hashes of unchanged files")
+        .action(ArgAction::SetTrue)
+}
+
+/// cache the hashes of this run unless `--no-hash-cache`
+pub fn set_hash_cache(matches: &ArgMatches) {
+    let _ = IS_HASH_CACHED.set(!matches.get_flag("no_hash_cache"));
+}
@@ -0,0 +1,122 @@
+//! Commands run before and after a plan is applied, which read the plan as JSON
+//! on their standard input, e.g. to update a photo database with the new names.
+
+use crate::{
+    error::{self, Error},
+    i18n::{tr, Message},
+};
+use clap::{Arg, ArgMatches};
+use serde::Serialize;
+use std::{
+    io::{self, Write}, // Write for write_all
+    process::{Command, ExitStatus, Stdio},
+};
+
+pub const PRE_RENAME_HELP_MESSAGE: &str = r#"A shell command run before renaming, with the plan as JSON on its
+standard input; renaming stops unless it exits successfully"#;
+
+pub const POST_RENAME_HELP_MESSAGE: &str = r#"A shell command run after renaming, with the renames done as JSON
+on its standard input"#;
+
+#[derive(Clone, Default)]
+pub struct Hooks {
+    pre_rename: Option<String>,
+    post_rename: Option<String>,
+}
+
+/// what a hook reads on its standard input
+#[derive(Serialize)]
+struct HookInput<'a, T> {
+    program: &'a str,
+    /// "pre_rename" or "post_rename"
+    event: &'a str,
+    renames: &'a [T],
+}
+
+impl Hooks {
+    /// the hooks given by `--hook-pre-rename` and `--hook-post-rename`
+    pub fn from(matches: &ArgMatches) -> Self {
+        Self {
+            pre_rename: matches.get_one::<String>("hook_pre_rename").cloned(),
+            post_rename: matches.get_one::<String>("hook_post_rename").cloned(),
+        }
+    }
+
+    /// run the `pre_rename` hook, which vetoes the renames by failing
+    pub fn run_pre_rename<T: Serialize>(&self, program: &str, renames: &[T]) -> error::Result<()> {
+        let Some(command) = &self.pre_rename else {
+            return Ok(());
+        };
+        let input: HookInput<T> = HookInput {
+            program,
+            event: "pre_rename",
+            renames,
+        };
+        match run_hook(command, &input) {
+            Ok(status) if status.success() => Ok(()),
+            Ok(status) => Err(Error::HookVetoed {
+                hook: command.clone(),
+                reason: status.to_string(),
+            }),
+            Err(err) => Err(Error::HookVetoed {
+                hook: command.clone(),
+                reason: err.to_string(),
+            }),
+        }
+    }
+
+    /// run the `post_rename` hook, only warning when it fails
+    /// as the renames are already done
+    pub fn run_post_rename<T: Serialize>(&self, program: &str, renames: &[T]) {
+        let Some(command) = &self.post_rename else {
+            return;
+        };
+        let input: HookInput<T> = HookInput {
+            program,
+            event: "post_rename",
+            renames,
+        };
+        let reason: String = match run_hook(command, &input) {
+            Ok(status) if status.success() => return,
+            Ok(status) => status.to_string(),
+            Err(err) => err.to_string(),
+        };
+        eprintln!("{}", tr(Message::HookFailed, &[command, &reason]));
+    }
+}
+
+/// the `--hook-*` options of the renaming tools
+pub fn get_hook_args() -> [Arg; 2] {
+    [
+        Arg::new("hook_pre_rename")
+            .long("hook-pre-rename")
+            .help(PRE_RENAME_HELP_MESSAGE),
+        Arg::new("hook_post_rename")
+            .long("hook-post-rename")
+            .help(POST_RENAME_HELP_MESSAGE),
+    ]
+}
+
+/// run `command` in the shell with `input` as JSON on its standard input
+fn run_hook<T: Serialize>(command: &str, input: &T) -> io::Result<ExitStatus> {
+    let mut child = get_shell_command(command).stdin(Stdio::piped()).spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // a hook not reading its input closes the pipe early
+        let _ = stdin.write_all(serde_json::to_string(input)?.as_bytes());
+    }
+    child.wait()
+}
+
+#[cfg(unix)]
+pub(crate) fn get_shell_command(command: &str) -> Command {
+    let mut shell: Command = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+pub(crate) fn get_shell_command(command: &str) -> Command {
+    let mut shell: Command = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
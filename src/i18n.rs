@@ -0,0 +1,1028 @@
+//! The messages shared by the tools, in English and Traditional Chinese, picked by
+//! `--lang` or else the locale of the environment.
+
+use clap::Arg;
+use std::{env, fmt::Display, sync::OnceLock};
+
+pub const LANGUAGE_NAMES: [&str; 2] = ["en", "zh-TW"];
+
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    TraditionalChinese,
+}
+
+impl Language {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "en" => Self::English,
+            "zh-TW" => Self::TraditionalChinese,
+            _ => panic!("Unknown language: {name}"),
+        }
+    }
+
+    /// the language of the first locale variable set, e.g. `LANG=zh_TW.UTF-8`;
+    /// Chinese in Taiwan, Hong Kong and Macau or written in Hant is Traditional
+    pub fn detect() -> Self {
+        let locale: String = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|v| env::var(v).ok())
+            .find(|l| !l.is_empty())
+            .unwrap_or_default();
+        let locale: String = locale.to_lowercase().replace('-', "_");
+        let is_traditional: bool = ["zh_tw", "zh_hk", "zh_mo", "zh_hant"]
+            .iter()
+            .any(|l| locale.starts_with(l));
+        match is_traditional {
+            true => Self::TraditionalChinese,
+            false => Self::English,
+        }
+    }
+}
+
+/// the `--lang` option every tool takes
+pub fn get_lang_arg() -> Arg {
+    Arg::new("lang")
+        .long("lang")
+        .help("The language of messages, by default that of the locale")
+        .value_parser(LANGUAGE_NAMES)
+        .global(true)
+}
+
+/// use the language named by `--lang`, or else the locale's, for all messages
+pub fn set_language(name: Option<&String>) {
+    let language: Language = match name {
+        Some(name) => Language::from(name),
+        None => Language::detect(),
+    };
+    let _ = LANGUAGE.set(language);
+}
+
+pub fn get_language() -> Language {
+    *LANGUAGE.get_or_init(Language::detect)
+}
+
+#[derive(Clone, Copy)]
+pub enum Message {
+    AcceptRenaming,
+    /// {0}: page, {1}: pages
+    AcceptPage,
+    NothingDone,
+    NothingToRename,
+    NothingToRestore,
+    NothingToUndo,
+    NothingToResume,
+    /// {0}: path, {1}: reason
+    Skipping,
+    HardLinked,
+    /// {0}: path renamed, {1}: paths linked
+    RenamingFirstLink,
+    /// {0}: paths linked
+    HardLinksApart,
+    /// {0}: program
+    UndoWith,
+    /// {0}: program
+    ResumeWith,
+    /// {0}: path
+    Overwriting,
+    /// {0}: path
+    AlreadyExists,
+    /// {0}: path
+    NewNameOfAnother,
+    /// {0}: target, {1}: target of another file
+    CaseCollision,
+    FileNotFound,
+    /// {0}: actual size, {1}: recorded size
+    SizeDiffers,
+    HashDiffers,
+    /// {0}: error
+    CannotHash,
+    /// {0}: error
+    FailedToUndo,
+    /// {0}: error
+    FailedToResume,
+    OldHeader,
+    NewHeader,
+    /// {0}: question
+    CannotAsk,
+    AnswerYes,
+    AnswerNo,
+    AnswerAll,
+    AnswerQuit,
+    /// {0}: detail
+    InvalidFormat,
+    /// {0}: path
+    SourceMissing,
+    /// {0}: source, {1}: target
+    CrossDevice,
+    /// {0}: path
+    PermissionDenied,
+    /// {0}: hook command, {1}: exit status or error
+    HookVetoed,
+    /// {0}: hook command, {1}: exit status or error
+    HookFailed,
+    /// {0}: renames done, {1}: renames planned
+    Interrupted,
+    /// {0}: error
+    SkipFailed,
+    /// {0}: renames failed, {1}: renames planned
+    RenamesFailed,
+    /// {0}: renamed, {1}: skipped, {2}: conflicts, {3}: failed, {4}: elapsed time
+    RunStats,
+    /// {0}: size
+    BytesMoved,
+    /// {0}: files
+    DuplicatesLeft,
+    /// {0}: lines shown, {1}: lines in all
+    PagerStatus,
+    HowHeader,
+    /// {0}: path taken
+    ExplainSuffixed,
+    /// {0}: path taken
+    ExplainOverwriting,
+    /// {0}: path, {1}: error
+    ReportFailed,
+    /// {0}: path
+    GuardedPath,
+    /// {0}: files in the batch, {1}: files allowed
+    TooManyFiles,
+    /// {0}: new names not portable, {1}: renames planned
+    NotPortable,
+    /// {0}: bytes allowed
+    NameTooLong,
+    /// {0}: character
+    ForbiddenChar,
+    ControlChar,
+    TrailingDotOrSpace,
+    /// {0}: device name
+    DeviceName,
+    /// {0}: characters allowed
+    PathTooLong,
+    /// {0}: path of the other file
+    CaseAmbiguous,
+    /// {0}: program
+    RunFinished,
+    /// {0}: program
+    RunFailed,
+    /// {0}: error
+    NotifyFailed,
+    /// {0}: target
+    TargetGone,
+    /// {0}: source
+    SourceLeft,
+    /// {0}: copy
+    CopyDiffers,
+    /// {0}: renames not holding, {1}: renames checked
+    VerifyFailed,
+    /// {0}: path, {1}: error
+    FailedToRead,
+    /// {0}: error
+    FailedToReadRecords,
+    /// {0}: error
+    FailedToReadPaths,
+    /// {0}: error
+    FailedToWriteRecords,
+    /// {0}: error
+    FailedToReadSources,
+    /// {0}: path, {1}: error
+    FailedToWrite,
+    /// {0}: error
+    FailedToWriteExport,
+    /// {0}: path, {1}: error
+    FailedToRemove,
+    /// {0}: path, {1}: error
+    FailedToChange,
+    /// {0}: path, {1}: error
+    FailedToCopy,
+    /// {0}: path, {1}: error
+    FailedToMove,
+    /// {0}: path, {1}: error
+    FailedToTrash,
+    /// {0}: error
+    FailedToCompareTrees,
+    /// {0}: error
+    FailedToCreateLinks,
+    /// {0}: path
+    MovedToTrash,
+    /// {0}: warning
+    Warning,
+    /// {0}: error
+    InvalidTemplate,
+    /// {0}: size
+    InvalidSize,
+    /// {0}: pattern, {1}: error
+    InvalidPattern,
+    /// {0}: pattern
+    PatternWithoutNum,
+    /// {0}: path, {1}: error
+    InvalidRulesFile,
+    /// {0}: template, {1}: error
+    InvalidRuleTemplate,
+    SkipRuleMoving,
+    RuleWithoutAction,
+    /// {0}: value, {1}: error
+    InvalidAttributeValue,
+    /// {0}: format, {1}: error
+    InvalidTimeFormat,
+    /// {0}: format, {1}: reason
+    UnsortableFormat,
+    /// {0}: conversion
+    NotPadded,
+    /// {0}: placeholder, {1}: padded placeholder
+    NotZeroPadded,
+    /// {0}: unit
+    BeforeTheDay,
+    /// {0}: unit, {1}: finer unit
+    ComesAfter,
+    TheYear,
+    TheMonth,
+    TheWeek,
+    TheDay,
+    TheHour,
+    TheMinute,
+    TheSecond,
+    TheFraction,
+    TheWeekday,
+    /// {0}: conversion
+    MonthByName,
+    /// {0}: conversion
+    TwelveHourClock,
+    /// {0}: conversion
+    MonthBeforeYear,
+    /// {0}: count
+    LinksInPlace,
+    /// {0}: series, {1}: first, {2}: last, {3}: count
+    SeriesRange,
+    /// {0}: numbers
+    SeriesMissing,
+    /// {0}: number, {1}: files
+    SeriesDuplicated,
+    NothingToShift,
+    AcceptShift,
+    NoExifDate,
+    ShiftedOutOfRange,
+    /// {0}: path
+    NotADirectory,
+    /// {0}: path
+    DuplicateOf,
+    PermissionsFollowPolicy,
+    AcceptPermissionChanges,
+    NoSimilarImages,
+    /// {0}: group number
+    SimilarGroup,
+    KeptImage,
+    DuplicateImage,
+    /// {0}: path
+    SimilarTo,
+    NothingToRemove,
+    AcceptRemovingAttributes,
+    AcceptRemoval,
+    NoLeftovers,
+    LargestFiles,
+    HeaviestDirectories,
+    /// {0}: length, {1}: unit, {2}: limit
+    PathExceeds,
+    /// {0}: length, {1}: unit, {2}: limit
+    NameExceeds,
+    /// {0}: depth, {1}: limit
+    DepthExceeds,
+    /// {0}: path
+    SameNameIgnoringCase,
+    /// {0}: problems, {1}: paths
+    ProblemsFound,
+    /// {0}: characters
+    ForbiddenChars,
+    /// {0}: characters
+    NotIso9660Chars,
+    MoreThanOneDot,
+    PdfWithoutPages,
+    PdfWithoutScan,
+    /// {0}: error
+    OcrNotRun,
+    /// {0}: error
+    OcrFailed,
+    NameCommandGaveNone,
+    /// {0}: error
+    NameCommandInvalidJson,
+    /// {0}: error
+    NameCommandFailed,
+    /// {0}: encoding
+    NotValidEncoding,
+    NoCreationTime,
+    /// {0}: path
+    NoFileName,
+    NoPathsNorRecords,
+    NoTitleFound,
+    NameNotUtf8,
+    NotAFile,
+    NotAudioFile,
+    NotScreenshotName,
+    /// {0}: line
+    MappingEmptyName,
+    /// {0}: line, {1}: name
+    MappingListedTwice,
+    /// {0}: line, {1}: name
+    MappingSharedTarget,
+    /// {0}: line, {1}: name, {2}: other name
+    MappingCaseOnly,
+    /// {0}: line, {1}: name
+    MappingMissing,
+    MappingInvalid,
+    StatusRenamed,
+    StatusSkipped,
+    StatusDeclined,
+    StatusFailed,
+    StatusNotDone,
+    /// {0}: program
+    ReportTitle,
+    /// {0}: time
+    ReportWritten,
+    ReportSummary,
+    ReportTotal,
+    ReportFiles,
+    ReportPreview,
+    ReportBefore,
+    ReportAfter,
+    ReportSource,
+    ReportStatus,
+    ReportError,
+    /// {0}: path
+    OverwritingBackedUp,
+    NotInTrash,
+    TrashNotListable,
+    FromTrash,
+    /// the new name of a file moved to the trash instead
+    ToTrash,
+    /// {0}: files
+    FilesTrashed,
+    StatusTrashed,
+    ExplainBeyondNewestGroup,
+    /// {0}: groups
+    ExplainBeyondNewestGroups,
+    ExplainTimeFromName,
+    /// {0}: UTC offset
+    ExplainNoTimeInName,
+    ExplainNumberedSameTime,
+    /// {0}: position, {1}: files
+    ExplainDrawn,
+    /// {0}: rule number
+    ExplainRule,
+    /// {0}: time
+    ExplainOffsetAdded,
+    /// {0}: number
+    ExplainNumberedAlike,
+    /// {0}: UTC offset
+    ExplainModificationTime,
+    /// {0}: UTC offset
+    ExplainCreationTime,
+    ExplainTitleMetadata,
+    ExplainFirstHeading,
+    /// {0}: characters
+    ExplainTruncated,
+    /// {0}: placeholders and their values
+    ExplainFromTags,
+    /// {0}: placeholders and their values
+    ExplainFromProperties,
+    /// {0}: placeholders and their values
+    ExplainFromRecognizedText,
+    /// {0}: encoding
+    ExplainDecodedAs,
+    /// {0}: encoding
+    ExplainDetectedAs,
+    ExplainComposed,
+    ExplainDecomposed,
+    ExpectedAge,
+    /// {0}: text
+    InvalidDateTime,
+    /// {0}: text, {1}: error
+    NeitherTimeNorFile,
+    /// {0}: rule
+    InvalidFormatFor,
+    /// {0}: preset, {1}: presets
+    UnknownPreset,
+    ExprMatchesText,
+    ExprTagComparison,
+    ExprTrailingText,
+    /// {0}: quote
+    ExprUnclosedQuote,
+    /// {0}: character
+    ExprUnexpected,
+    ExprMissingParenthesis,
+    /// {0}: field
+    ExprUnknownField,
+    /// {0}: field
+    ExprMissingComparison,
+    /// {0}: field
+    ExprMissingValue,
+    ExprExpectedField,
+    /// {0}: text
+    InvalidDelay,
+    /// {0}: text
+    InvalidDelayUnit,
+    /// {0}: path, {1}: error
+    ManifestUnreadable,
+    /// {0}: text
+    InvalidRate,
+    /// {0}: text
+    InvalidRateUnit,
+    /// {0}: text
+    InvalidChunkSize,
+    /// {0}: text
+    InvalidRotation,
+    ExpectedOctalMode,
+    /// {0}: text
+    InvalidOffset,
+    /// {0}: rule number, {1}: error
+    RuleFailed,
+    /// {0}: position
+    TemplateUnbalancedBrace,
+    /// {0}: width
+    TemplateInvalidWidth,
+    /// {0}: filter
+    TemplateInvalidFilter,
+    /// {0}: placeholder
+    TemplateMissingValue,
+    NotOfficeDocument,
+    NotOfficeDocumentNorScan,
+    CopyNotIdentical,
+    AcceptMovingDuplicates,
+}
+
+impl Message {
+    fn template(&self, language: Language) -> &'static str {
+        use Language::*;
+        use Message::*;
+        match (self, language) {
+            (AcceptRenaming, English) => "Accept the above renaming?",
+            (AcceptRenaming, TraditionalChinese) => "接受以上的重新命名嗎？",
+            (AcceptPage, English) => "Accept the above renaming, page {0} of {1}?",
+            (AcceptPage, TraditionalChinese) => "接受以上的重新命名嗎？（第 {0}/{1} 頁）",
+            (NothingDone, English) => "Nothing done.",
+            (NothingDone, TraditionalChinese) => "未做任何變更。",
+            (NothingToRename, English) => "Nothing to rename.",
+            (NothingToRename, TraditionalChinese) => "沒有需要重新命名的檔案。",
+            (NothingToRestore, English) => "Nothing to restore.",
+            (NothingToRestore, TraditionalChinese) => "沒有需要還原的檔案。",
+            (NothingToUndo, English) => "Nothing to undo.",
+            (NothingToUndo, TraditionalChinese) => "沒有可以復原的操作。",
+            (NothingToResume, English) => "Nothing to resume.",
+            (NothingToResume, TraditionalChinese) => "沒有可以繼續的操作。",
+            (Skipping, English) => "Skipping {0}: {1}",
+            (Skipping, TraditionalChinese) => "略過 {0}：{1}",
+            (HardLinked, English) => "hard links to the same file",
+            (HardLinked, TraditionalChinese) => "皆為同一檔案的硬連結",
+            (RenamingFirstLink, English) => {
+                "Renaming only {0} of {1}, hard links to the same file"
+            }
+            (RenamingFirstLink, TraditionalChinese) => {
+                "{1} 皆為同一檔案的硬連結，只重新命名 {0}"
+            }
+            (HardLinksApart, English) => {
+                "Warning: {0} are hard links to the same file, each renamed apart"
+            }
+            (HardLinksApart, TraditionalChinese) => {
+                "警告：{0} 皆為同一檔案的硬連結，將各自重新命名"
+            }
+            (UndoWith, English) => "Undo with: {0} --undo",
+            (UndoWith, TraditionalChinese) => "復原請執行：{0} --undo",
+            (ResumeWith, English) => "Resume with: {0} --resume",
+            (ResumeWith, TraditionalChinese) => "繼續請執行：{0} --resume",
+            (Overwriting, English) => "Overwriting {0} (moved to the trash)",
+            (Overwriting, TraditionalChinese) => "覆寫 {0}（原檔移至垃圾桶）",
+            (AlreadyExists, English) => "{0} already exists",
+            (AlreadyExists, TraditionalChinese) => "{0} 已存在",
+            (NewNameOfAnother, English) => "{0} is the new name of another file",
+            (NewNameOfAnother, TraditionalChinese) => "{0} 是另一個檔案的新名稱",
+            (CaseCollision, English) => {
+                "{0} differs only in case from {1}, the new name of another file, and would replace it on exFAT, NTFS or APFS"
+            }
+            (CaseCollision, TraditionalChinese) => {
+                "{0} 與另一個檔案的新名稱 {1} 僅大小寫不同，在 exFAT、NTFS 或 APFS 上會取代該檔案"
+            }
+            (FileNotFound, English) => "file not found",
+            (FileNotFound, TraditionalChinese) => "找不到檔案",
+            (SizeDiffers, English) => "size is {0}, recorded {1}",
+            (SizeDiffers, TraditionalChinese) => "大小為 {0}，紀錄為 {1}",
+            (HashDiffers, English) => "content hash differs from the record",
+            (HashDiffers, TraditionalChinese) => "內容雜湊值與紀錄不符",
+            (CannotHash, English) => "cannot hash: {0}",
+            (CannotHash, TraditionalChinese) => "無法計算雜湊值：{0}",
+            (FailedToUndo, English) => "Failed to undo: {0}",
+            (FailedToUndo, TraditionalChinese) => "復原失敗：{0}",
+            (FailedToResume, English) => "Failed to resume: {0}",
+            (FailedToResume, TraditionalChinese) => "繼續失敗：{0}",
+            (OldHeader, English) => "old",
+            (OldHeader, TraditionalChinese) => "原名稱",
+            (NewHeader, English) => "new",
+            (NewHeader, TraditionalChinese) => "新名稱",
+            (CannotAsk, English) => {
+                "Cannot ask \"{0}\": there is no terminal; add --yes to answer yes."
+            }
+            (CannotAsk, TraditionalChinese) => {
+                "無法詢問「{0}」：沒有終端機；加上 --yes 即一律回答是。"
+            }
+            (AnswerYes, English) => "yes",
+            (AnswerYes, TraditionalChinese) => "是",
+            (AnswerNo, English) => "no",
+            (AnswerNo, TraditionalChinese) => "否",
+            (AnswerAll, English) => "yes to this and all remaining",
+            (AnswerAll, TraditionalChinese) => "是，並套用到其餘所有項目",
+            (AnswerQuit, English) => "no to this and all remaining",
+            (AnswerQuit, TraditionalChinese) => "否，並套用到其餘所有項目",
+            (InvalidFormat, English) => "invalid format: {0}",
+            (InvalidFormat, TraditionalChinese) => "格式無效：{0}",
+            (SourceMissing, English) => "{0} does not exist",
+            (SourceMissing, TraditionalChinese) => "{0} 不存在",
+            (CrossDevice, English) => {
+                "cannot rename {0} to {1} across file systems; move it with safe_mv"
+            }
+            (CrossDevice, TraditionalChinese) => {
+                "無法跨檔案系統將 {0} 重新命名為 {1}；請改用 safe_mv 移動"
+            }
+            (PermissionDenied, English) => "permission denied: {0}",
+            (PermissionDenied, TraditionalChinese) => "權限不足：{0}",
+            (HookVetoed, English) => "the hook `{0}` vetoed the renames: {1}",
+            (HookVetoed, TraditionalChinese) => "掛鉤 `{0}` 否決了重新命名：{1}",
+            (HookFailed, English) => "the hook `{0}` failed: {1}",
+            (HookFailed, TraditionalChinese) => "掛鉤 `{0}` 執行失敗：{1}",
+            (Interrupted, English) => "interrupted after {0} of {1} renames",
+            (Interrupted, TraditionalChinese) => "已中斷，完成 {1} 項重新命名中的 {0} 項",
+            (SkipFailed, English) => "{0}; skip this file and go on?",
+            (SkipFailed, TraditionalChinese) => "{0}；略過此檔案並繼續嗎？",
+            (RenamesFailed, English) => "{0} of {1} renames failed",
+            (RenamesFailed, TraditionalChinese) => "{1} 項重新命名中有 {0} 項失敗",
+            (RunStats, English) => "Renamed {0}, skipped {1}, conflicts {2}, failed {3}, in {4}",
+            (RunStats, TraditionalChinese) => {
+                "已重新命名 {0} 項，略過 {1} 項，名稱衝突 {2} 項，失敗 {3} 項，耗時 {4}"
+            }
+            (BytesMoved, English) => "Moved {0}",
+            (BytesMoved, TraditionalChinese) => "已移動 {0}",
+            (DuplicatesLeft, English) => "Left {0} duplicates of files at the destination",
+            (DuplicatesLeft, TraditionalChinese) => "略過 {0} 個與目的地檔案重複的檔案",
+            (PagerStatus, English) => {
+                "-- {0}/{1} lines; space: next page, enter: next line, q: all --"
+            }
+            (PagerStatus, TraditionalChinese) => {
+                "-- 第 {0}/{1} 行；空白鍵：下一頁，Enter：下一行，q：顯示其餘 --"
+            }
+            (HowHeader, English) => "how",
+            (HowHeader, TraditionalChinese) => "由來",
+            (ExplainSuffixed, English) => "{0} taken, numbered",
+            (ExplainSuffixed, TraditionalChinese) => "{0} 已被占用，加上編號",
+            (ExplainOverwriting, English) => "{0} taken, overwriting it",
+            (ExplainOverwriting, TraditionalChinese) => "{0} 已被占用，覆寫該檔案",
+            (ReportFailed, English) => "Failed to write the report {0}: {1}",
+            (ReportFailed, TraditionalChinese) => "無法寫入報告 {0}：{1}",
+            (GuardedPath, English) => {
+                "refusing to work on {0}, a root, home or system directory; add --force if meant"
+            }
+            (GuardedPath, TraditionalChinese) => {
+                "拒絕處理 {0}：這是根目錄、家目錄或系統目錄；若確有此意，請加上 --force"
+            }
+            (TooManyFiles, English) => {
+                "refusing to change {0} files, more than {1}; add --force or raise --max-files"
+            }
+            (TooManyFiles, TraditionalChinese) => {
+                "拒絕變更 {0} 個檔案，超過上限 {1} 個；請加上 --force 或調高 --max-files"
+            }
+            (NotPortable, English) => {
+                "refusing the plan: {0} of {1} new names do not hold on every platform"
+            }
+            (NotPortable, TraditionalChinese) => {
+                "拒絕此計畫：{1} 個新名稱中有 {0} 個無法通用於所有平台"
+            }
+            (NameTooLong, English) => "the name is longer than {0} bytes",
+            (NameTooLong, TraditionalChinese) => "名稱超過 {0} 位元組",
+            (ForbiddenChar, English) => "the name has {0}, forbidden on Windows",
+            (ForbiddenChar, TraditionalChinese) => "名稱含有 Windows 禁用的字元 {0}",
+            (ControlChar, English) => "the name has a control character",
+            (ControlChar, TraditionalChinese) => "名稱含有控制字元",
+            (TrailingDotOrSpace, English) => {
+                "the name ends with a dot or a space, dropped on Windows"
+            }
+            (TrailingDotOrSpace, TraditionalChinese) => {
+                "名稱以句點或空格結尾，在 Windows 上會被去除"
+            }
+            (DeviceName, English) => "{0} is a device name on Windows",
+            (DeviceName, TraditionalChinese) => "{0} 在 Windows 上是裝置名稱",
+            (PathTooLong, English) => {
+                "the path is longer than {0} characters, too long for Windows"
+            }
+            (PathTooLong, TraditionalChinese) => "路徑超過 {0} 個字元，對 Windows 而言過長",
+            (CaseAmbiguous, English) => {
+                "the name differs from that of {0} only in case or normalization"
+            }
+            (CaseAmbiguous, TraditionalChinese) => {
+                "名稱與 {0} 的名稱僅有大小寫或正規化形式的差異"
+            }
+            (RunFinished, English) => "{0} finished",
+            (RunFinished, TraditionalChinese) => "{0} 已完成",
+            (RunFailed, English) => "{0} failed",
+            (RunFailed, TraditionalChinese) => "{0} 失敗",
+            (NotifyFailed, English) => "Failed to show a notification: {0}",
+            (NotifyFailed, TraditionalChinese) => "無法顯示通知：{0}",
+            (TargetGone, English) => "{0} is missing after renaming",
+            (TargetGone, TraditionalChinese) => "重新命名後找不到 {0}",
+            (SourceLeft, English) => "{0} is still there after renaming",
+            (SourceLeft, TraditionalChinese) => "重新命名後 {0} 仍然存在",
+            (CopyDiffers, English) => "{0} differs from its source after copying",
+            (CopyDiffers, TraditionalChinese) => "複製後 {0} 與來源不同",
+            (VerifyFailed, English) => {
+                "{0} of {1} renames did not hold on disk; the file system may be unreliable"
+            }
+            (VerifyFailed, TraditionalChinese) => {
+                "{1} 項重新命名中有 {0} 項未確實寫入磁碟；檔案系統可能不可靠"
+            }
+            (FailedToRead, English) => "Failed to read {0}: {1}",
+            (FailedToRead, TraditionalChinese) => "無法讀取 {0}：{1}",
+            (FailedToReadRecords, English) => "Failed to read the piped records: {0}",
+            (FailedToReadRecords, TraditionalChinese) => "無法讀取管線傳入的紀錄：{0}",
+            (FailedToReadPaths, English) => "Failed to read the paths: {0}",
+            (FailedToReadPaths, TraditionalChinese) => "無法讀取路徑：{0}",
+            (FailedToWriteRecords, English) => "Failed to write the piped records: {0}",
+            (FailedToWriteRecords, TraditionalChinese) => "無法寫出管線紀錄：{0}",
+            (FailedToReadSources, English) => "Failed to read the sources: {0}",
+            (FailedToReadSources, TraditionalChinese) => "無法讀取來源：{0}",
+            (FailedToWrite, English) => "Failed to write {0}: {1}",
+            (FailedToWrite, TraditionalChinese) => "無法寫入 {0}：{1}",
+            (FailedToWriteExport, English) => "Failed to write the export: {0}",
+            (FailedToWriteExport, TraditionalChinese) => "無法寫出匯出檔：{0}",
+            (FailedToRemove, English) => "Failed to remove {0}: {1}",
+            (FailedToRemove, TraditionalChinese) => "無法移除 {0}：{1}",
+            (FailedToChange, English) => "Failed to change {0}: {1}",
+            (FailedToChange, TraditionalChinese) => "無法變更 {0}：{1}",
+            (FailedToCopy, English) => "Failed to copy {0}: {1}",
+            (FailedToCopy, TraditionalChinese) => "無法複製 {0}：{1}",
+            (FailedToMove, English) => "Failed to move {0}: {1}",
+            (FailedToMove, TraditionalChinese) => "無法移動 {0}：{1}",
+            (FailedToTrash, English) => "Failed to move {0} to the trash: {1}",
+            (FailedToTrash, TraditionalChinese) => "無法將 {0} 移至垃圾桶：{1}",
+            (FailedToCompareTrees, English) => "Failed to compare the trees: {0}",
+            (FailedToCompareTrees, TraditionalChinese) => "無法比較目錄樹：{0}",
+            (FailedToCreateLinks, English) => "Failed to create the links: {0}",
+            (FailedToCreateLinks, TraditionalChinese) => "無法建立連結：{0}",
+            (MovedToTrash, English) => "Moved {0} to the trash",
+            (MovedToTrash, TraditionalChinese) => "已將 {0} 移至垃圾桶",
+            (Warning, English) => "Warning: {0}",
+            (Warning, TraditionalChinese) => "警告：{0}",
+            (InvalidTemplate, English) => "Invalid template: {0}",
+            (InvalidTemplate, TraditionalChinese) => "樣板無效：{0}",
+            (InvalidSize, English) => "Invalid size: {0}",
+            (InvalidSize, TraditionalChinese) => "大小無效：{0}",
+            (InvalidPattern, English) => "Invalid pattern {0}: {1}",
+            (InvalidPattern, TraditionalChinese) => "樣式 {0} 無效：{1}",
+            (PatternWithoutNum, English) => "The pattern {0} has no capture group named \"num\".",
+            (PatternWithoutNum, TraditionalChinese) => "樣式 {0} 沒有名為 \"num\" 的擷取群組。",
+            (InvalidRulesFile, English) => "Invalid rules file {0}: {1}",
+            (InvalidRulesFile, TraditionalChinese) => "規則檔 {0} 無效：{1}",
+            (InvalidRuleTemplate, English) => "invalid template {0}: {1}",
+            (InvalidRuleTemplate, TraditionalChinese) => "樣板 {0} 無效：{1}",
+            (SkipRuleMoving, English) => "a rule skipping files moves none",
+            (SkipRuleMoving, TraditionalChinese) => "略過檔案的規則不應移動檔案",
+            (RuleWithoutAction, English) => "a rule needs a destination, a template or skip",
+            (RuleWithoutAction, TraditionalChinese) => "規則需要目的地、樣板或略過",
+            (InvalidAttributeValue, English) => "invalid attribute value {0}: {1}",
+            (InvalidAttributeValue, TraditionalChinese) => "屬性值 {0} 無效：{1}",
+            (InvalidTimeFormat, English) => "invalid format \"{0}\": {1}",
+            (InvalidTimeFormat, TraditionalChinese) => "格式 \"{0}\" 無效：{1}",
+            (UnsortableFormat, English) => "names in the format \"{0}\" will not sort by time: {1}",
+            (UnsortableFormat, TraditionalChinese) => "格式 \"{0}\" 的名稱不會依時間排序：{1}",
+            (NotPadded, English) => "{0} is not padded",
+            (NotPadded, TraditionalChinese) => "{0} 未補零",
+            (NotZeroPadded, English) => "{0} is not zero-padded, e.g. {1}",
+            (NotZeroPadded, TraditionalChinese) => "{0} 未補零，例如 {1}",
+            (BeforeTheDay, English) => "{0} comes before the day",
+            (BeforeTheDay, TraditionalChinese) => "{0} 排在日之前",
+            (ComesAfter, English) => "{0} comes after {1}",
+            (ComesAfter, TraditionalChinese) => "{0} 排在{1}之後",
+            (TheYear, English) => "the year",
+            (TheYear, TraditionalChinese) => "年",
+            (TheMonth, English) => "the month",
+            (TheMonth, TraditionalChinese) => "月",
+            (TheWeek, English) => "the week",
+            (TheWeek, TraditionalChinese) => "週",
+            (TheDay, English) => "the day",
+            (TheDay, TraditionalChinese) => "日",
+            (TheHour, English) => "the hour",
+            (TheHour, TraditionalChinese) => "時",
+            (TheMinute, English) => "the minute",
+            (TheMinute, TraditionalChinese) => "分",
+            (TheSecond, English) => "the second",
+            (TheSecond, TraditionalChinese) => "秒",
+            (TheFraction, English) => "the fraction of a second",
+            (TheFraction, TraditionalChinese) => "秒的小數",
+            (TheWeekday, English) => "the weekday",
+            (TheWeekday, TraditionalChinese) => "星期",
+            (MonthByName, English) => "{0} names the month, which sorts alphabetically",
+            (MonthByName, TraditionalChinese) => "{0} 以名稱表示月份，會依字母排序",
+            (TwelveHourClock, English) => {
+                "{0} is on the 12-hour clock, where 1 PM sorts before 2 AM"
+            }
+            (TwelveHourClock, TraditionalChinese) => "{0} 採 12 小時制，下午 1 點會排在上午 2 點之前",
+            (MonthBeforeYear, English) => "{0} puts the month or weekday before the year",
+            (MonthBeforeYear, TraditionalChinese) => "{0} 將月份或星期放在年份之前",
+            (LinksInPlace, English) => "{0} links in place.",
+            (LinksInPlace, TraditionalChinese) => "已建立 {0} 個連結。",
+            (SeriesRange, English) => "{0}: {1}-{2}, {3} files",
+            (SeriesRange, TraditionalChinese) => "{0}：{1}-{2}，共 {3} 個檔案",
+            (SeriesMissing, English) => "missing: {0}",
+            (SeriesMissing, TraditionalChinese) => "缺少：{0}",
+            (SeriesDuplicated, English) => "duplicated: {0} ({1})",
+            (SeriesDuplicated, TraditionalChinese) => "重複：{0}（{1}）",
+            (NothingToShift, English) => "Nothing to shift.",
+            (NothingToShift, TraditionalChinese) => "沒有需要調整的時間。",
+            (AcceptShift, English) => "Accept the above time shift?",
+            (AcceptShift, TraditionalChinese) => "接受以上的時間調整嗎？",
+            (NoExifDate, English) => "no EXIF date",
+            (NoExifDate, TraditionalChinese) => "沒有 EXIF 日期",
+            (ShiftedOutOfRange, English) => "shifted date out of range",
+            (ShiftedOutOfRange, TraditionalChinese) => "調整後的日期超出範圍",
+            (NotADirectory, English) => "{0} is not a directory.",
+            (NotADirectory, TraditionalChinese) => "{0} 不是目錄。",
+            (DuplicateOf, English) => "a duplicate of {0}",
+            (DuplicateOf, TraditionalChinese) => "與 {0} 重複",
+            (PermissionsFollowPolicy, English) => "All permissions already follow the policy.",
+            (PermissionsFollowPolicy, TraditionalChinese) => "所有權限皆已符合規則。",
+            (AcceptPermissionChanges, English) => "Accept the above permission changes?",
+            (AcceptPermissionChanges, TraditionalChinese) => "接受以上的權限變更嗎？",
+            (NoSimilarImages, English) => "No similar images found.",
+            (NoSimilarImages, TraditionalChinese) => "未找到相似的圖片。",
+            (SimilarGroup, English) => "group {0}:",
+            (SimilarGroup, TraditionalChinese) => "第 {0} 組：",
+            (KeptImage, English) => "keep",
+            (KeptImage, TraditionalChinese) => "保留",
+            (DuplicateImage, English) => "dup ",
+            (DuplicateImage, TraditionalChinese) => "重複",
+            (SimilarTo, English) => "similar to {0}",
+            (SimilarTo, TraditionalChinese) => "與 {0} 相似",
+            (NothingToRemove, English) => "Nothing to remove.",
+            (NothingToRemove, TraditionalChinese) => "沒有需要移除的項目。",
+            (AcceptRemovingAttributes, English) => "Remove the above attributes?",
+            (AcceptRemovingAttributes, TraditionalChinese) => "移除以上的屬性嗎？",
+            (AcceptRemoval, English) => "Accept the above removal?",
+            (AcceptRemoval, TraditionalChinese) => "接受以上的移除嗎？",
+            (NoLeftovers, English) => "No leftovers found.",
+            (NoLeftovers, TraditionalChinese) => "未找到殘留檔案。",
+            (LargestFiles, English) => "Largest files:",
+            (LargestFiles, TraditionalChinese) => "最大的檔案：",
+            (HeaviestDirectories, English) => "Heaviest directories:",
+            (HeaviestDirectories, TraditionalChinese) => "最大的目錄：",
+            (PathExceeds, English) => "path of {0} {1} exceeds {2}",
+            (PathExceeds, TraditionalChinese) => "路徑長 {0} {1}，超過 {2}",
+            (NameExceeds, English) => "name of {0} {1} exceeds {2}",
+            (NameExceeds, TraditionalChinese) => "名稱長 {0} {1}，超過 {2}",
+            (DepthExceeds, English) => "depth of {0} exceeds {1}",
+            (DepthExceeds, TraditionalChinese) => "深度 {0} 超過 {1}",
+            (SameNameIgnoringCase, English) => "same name as {0} ignoring case",
+            (SameNameIgnoringCase, TraditionalChinese) => "忽略大小寫時與 {0} 同名",
+            (ProblemsFound, English) => "{0} problem(s) in {1} path(s).",
+            (ProblemsFound, TraditionalChinese) => "{1} 個路徑中有 {0} 個問題。",
+            (ForbiddenChars, English) => "forbidden characters {0}",
+            (ForbiddenChars, TraditionalChinese) => "含禁用字元 {0}",
+            (NotIso9660Chars, English) => "characters outside A-Z, 0-9 and _: {0}",
+            (NotIso9660Chars, TraditionalChinese) => "含 A-Z、0-9 與 _ 以外的字元：{0}",
+            (MoreThanOneDot, English) => "more than one dot",
+            (MoreThanOneDot, TraditionalChinese) => "含多個點",
+            (PdfWithoutPages, English) => "the PDF has no pages",
+            (PdfWithoutPages, TraditionalChinese) => "PDF 沒有頁面",
+            (PdfWithoutScan, English) => "no text nor JPEG scan on the first page of the PDF",
+            (PdfWithoutScan, TraditionalChinese) => "PDF 第一頁沒有文字也沒有 JPEG 掃描",
+            (OcrNotRun, English) => "failed to run the OCR command: {0}",
+            (OcrNotRun, TraditionalChinese) => "無法執行 OCR 指令：{0}",
+            (OcrFailed, English) => "the OCR command failed: {0}",
+            (OcrFailed, TraditionalChinese) => "OCR 指令失敗：{0}",
+            (NameCommandGaveNone, English) => "the name command gave no name",
+            (NameCommandGaveNone, TraditionalChinese) => "命名指令未給出名稱",
+            (NameCommandInvalidJson, English) => "the name command printed invalid JSON: {0}",
+            (NameCommandInvalidJson, TraditionalChinese) => "命名指令輸出的 JSON 無效：{0}",
+            (NameCommandFailed, English) => "the name command failed: {0}",
+            (NameCommandFailed, TraditionalChinese) => "命名指令失敗：{0}",
+            (NotValidEncoding, English) => "not valid {0}",
+            (NotValidEncoding, TraditionalChinese) => "不是有效的 {0}",
+            (NoCreationTime, English) => "the file system keeps no creation time",
+            (NoCreationTime, TraditionalChinese) => "檔案系統未保存建立時間",
+            (NoFileName, English) => "{0} has no file name",
+            (NoFileName, TraditionalChinese) => "{0} 沒有檔名",
+            (NoPathsNorRecords, English) => {
+                "no paths given, and no records piped in on standard input"
+            }
+            (NoPathsNorRecords, TraditionalChinese) => "未指定路徑，標準輸入也沒有傳入紀錄",
+            (NoTitleFound, English) => "no title found",
+            (NoTitleFound, TraditionalChinese) => "找不到標題",
+            (NameNotUtf8, English) => "name is not valid UTF-8",
+            (NameNotUtf8, TraditionalChinese) => "名稱不是有效的 UTF-8",
+            (NotAFile, English) => "not a file",
+            (NotAFile, TraditionalChinese) => "不是檔案",
+            (NotAudioFile, English) => "not a recognized audio file",
+            (NotAudioFile, TraditionalChinese) => "不是可辨識的音訊檔",
+            (NotScreenshotName, English) => "not a screenshot name",
+            (NotScreenshotName, TraditionalChinese) => "不是螢幕截圖的名稱",
+            (MappingEmptyName, English) => "line {0}: empty name",
+            (MappingEmptyName, TraditionalChinese) => "第 {0} 行：名稱空白",
+            (MappingListedTwice, English) => "line {0}: {1} is listed twice",
+            (MappingListedTwice, TraditionalChinese) => "第 {0} 行：{1} 重複列出",
+            (MappingSharedTarget, English) => "line {0}: {1} is the target of several files",
+            (MappingSharedTarget, TraditionalChinese) => "第 {0} 行：{1} 是多個檔案的新名稱",
+            (MappingCaseOnly, English) => {
+                "line {0}: {1} differs only in case from {2}, the target of another file"
+            }
+            (MappingCaseOnly, TraditionalChinese) => "第 {0} 行：{1} 與另一個檔案的新名稱 {2} 僅大小寫不同",
+            (MappingMissing, English) => "line {0}: {1} does not exist",
+            (MappingMissing, TraditionalChinese) => "第 {0} 行：{1} 不存在",
+            (MappingInvalid, English) => "The mapping is invalid; nothing done.",
+            (MappingInvalid, TraditionalChinese) => "對照表無效，未做任何變更。",
+            (StatusRenamed, English) => "Renamed",
+            (StatusRenamed, TraditionalChinese) => "已重新命名",
+            (StatusSkipped, English) => "Skipped",
+            (StatusSkipped, TraditionalChinese) => "已略過",
+            (StatusDeclined, English) => "Declined",
+            (StatusDeclined, TraditionalChinese) => "已拒絕",
+            (StatusFailed, English) => "Failed",
+            (StatusFailed, TraditionalChinese) => "失敗",
+            (StatusNotDone, English) => "Not done",
+            (StatusNotDone, TraditionalChinese) => "未執行",
+            (ReportTitle, English) => "Renaming report of {0}",
+            (ReportTitle, TraditionalChinese) => "{0} 的重新命名報告",
+            (ReportWritten, English) => "Written {0}",
+            (ReportWritten, TraditionalChinese) => "寫於 {0}",
+            (ReportSummary, English) => "Summary",
+            (ReportSummary, TraditionalChinese) => "摘要",
+            (ReportTotal, English) => "Total",
+            (ReportTotal, TraditionalChinese) => "總計",
+            (ReportFiles, English) => "Files",
+            (ReportFiles, TraditionalChinese) => "檔案",
+            (ReportPreview, English) => "Preview",
+            (ReportPreview, TraditionalChinese) => "預覽",
+            (ReportBefore, English) => "Before",
+            (ReportBefore, TraditionalChinese) => "原名",
+            (ReportAfter, English) => "After",
+            (ReportAfter, TraditionalChinese) => "新名",
+            (ReportSource, English) => "Source",
+            (ReportSource, TraditionalChinese) => "來源",
+            (ReportStatus, English) => "Status",
+            (ReportStatus, TraditionalChinese) => "狀態",
+            (ReportError, English) => "Error",
+            (ReportError, TraditionalChinese) => "錯誤",
+            (OverwritingBackedUp, English) => "Overwriting {0} (backed up beside it)",
+            (OverwritingBackedUp, TraditionalChinese) => "覆寫 {0}（原檔備份於旁）",
+            (NotInTrash, English) => "not in the trash",
+            (NotInTrash, TraditionalChinese) => "不在垃圾桶中",
+            (TrashNotListable, English) => "the trash cannot be listed here",
+            (TrashNotListable, TraditionalChinese) => "此處無法列出垃圾桶",
+            (FromTrash, English) => "(from the trash)",
+            (FromTrash, TraditionalChinese) => "（從垃圾桶移回）",
+            (ToTrash, English) => "(to the trash)",
+            (ToTrash, TraditionalChinese) => "（移至垃圾桶）",
+            (FilesTrashed, English) => "Moved {0} to the trash",
+            (FilesTrashed, TraditionalChinese) => "已將 {0} 個檔案移至垃圾桶",
+            (StatusTrashed, English) => "Trashed",
+            (StatusTrashed, TraditionalChinese) => "已移至垃圾桶",
+            (ExplainBeyondNewestGroup, English) => "older than the newest group, kept by --rotate",
+            (ExplainBeyondNewestGroup, TraditionalChinese) => "早於 --rotate 保留的最新一組",
+            (ExplainBeyondNewestGroups, English) => {
+                "older than the {0} newest groups, kept by --rotate"
+            }
+            (ExplainBeyondNewestGroups, TraditionalChinese) => "早於 --rotate 保留的最新 {0} 組",
+            (ExplainTimeFromName, English) => "time from the name",
+            (ExplainTimeFromName, TraditionalChinese) => "時間取自名稱",
+            (ExplainNoTimeInName, English) => "no time in the name, modification time (UTC{0})",
+            (ExplainNoTimeInName, TraditionalChinese) => "名稱中沒有時間，改用修改時間（UTC{0}）",
+            (ExplainNumberedSameTime, English) => {
+                "numbered after another screenshot of the same time"
+            }
+            (ExplainNumberedSameTime, TraditionalChinese) => "與另一張同時間的螢幕截圖重複，加上編號",
+            (ExplainDrawn, English) => "position {0} of {1} drawn",
+            (ExplainDrawn, TraditionalChinese) => "抽中第 {0} 位，共 {1} 位",
+            (ExplainRule, English) => "rule {0}",
+            (ExplainRule, TraditionalChinese) => "規則 {0}",
+            (ExplainOffsetAdded, English) => {
+                "UTC offset added, {0} being repeated by daylight saving time"
+            }
+            (ExplainOffsetAdded, TraditionalChinese) => "加上 UTC 偏移，因日光節約時間使 {0} 重複出現",
+            (ExplainNumberedAlike, English) => "numbered _{0}, named alike with another file",
+            (ExplainNumberedAlike, TraditionalChinese) => "與另一個檔案名稱相同，編號為 _{0}",
+            (ExplainModificationTime, English) => "modification time (UTC{0})",
+            (ExplainModificationTime, TraditionalChinese) => "修改時間（UTC{0}）",
+            (ExplainCreationTime, English) => "creation time (UTC{0})",
+            (ExplainCreationTime, TraditionalChinese) => "建立時間（UTC{0}）",
+            (ExplainTitleMetadata, English) => "the Title metadata",
+            (ExplainTitleMetadata, TraditionalChinese) => "Title 中繼資料",
+            (ExplainFirstHeading, English) => "the first heading of the first page",
+            (ExplainFirstHeading, TraditionalChinese) => "第一頁的第一個標題",
+            (ExplainTruncated, English) => "cut to {0} characters by --max-length",
+            (ExplainTruncated, TraditionalChinese) => "依 --max-length 截為 {0} 個字元",
+            (ExplainFromTags, English) => "from the tags {0}",
+            (ExplainFromTags, TraditionalChinese) => "取自標籤 {0}",
+            (ExplainFromProperties, English) => "from the properties {0}",
+            (ExplainFromProperties, TraditionalChinese) => "取自文件屬性 {0}",
+            (ExplainFromRecognizedText, English) => "from the recognized text {0}",
+            (ExplainFromRecognizedText, TraditionalChinese) => "取自辨識出的文字 {0}",
+            (ExplainDecodedAs, English) => "decoded as {0}, by --from",
+            (ExplainDecodedAs, TraditionalChinese) => "依 --from 以 {0} 解碼",
+            (ExplainDetectedAs, English) => "decoded as {0}, the encoding detected",
+            (ExplainDetectedAs, TraditionalChinese) => "以偵測到的編碼 {0} 解碼",
+            (ExplainComposed, English) => "characters composed, to NFC",
+            (ExplainComposed, TraditionalChinese) => "組合字元，轉為 NFC",
+            (ExplainDecomposed, English) => "characters decomposed, to NFD",
+            (ExplainDecomposed, TraditionalChinese) => "分解字元，轉為 NFD",
+            (ExpectedAge, English) => "expected an age such as 12h or 7d",
+            (ExpectedAge, TraditionalChinese) => "應為 12h 或 7d 之類的時間長度",
+            (InvalidDateTime, English) => "invalid date and time: {0}",
+            (InvalidDateTime, TraditionalChinese) => "日期時間無效：{0}",
+            (NeitherTimeNorFile, English) => "neither a date and time nor a file: {0}: {1}",
+            (NeitherTimeNorFile, TraditionalChinese) => "既不是日期時間也不是檔案：{0}：{1}",
+            (InvalidFormatFor, English) => "invalid --format-for {0}, expected EXTENSIONS=FORMAT",
+            (InvalidFormatFor, TraditionalChinese) => "--format-for {0} 無效，應為 EXTENSIONS=FORMAT",
+            (UnknownPreset, English) => "unknown preset {0}, expected one of {1}",
+            (UnknownPreset, TraditionalChinese) => "未知的預設 {0}，應為 {1} 其中之一",
+            (ExprMatchesText, English) => "=~ compares text, not sizes or times",
+            (ExprMatchesText, TraditionalChinese) => "=~ 只比較文字，不比較大小或時間",
+            (ExprTagComparison, English) => "a tag is compared by == != or =~ only",
+            (ExprTagComparison, TraditionalChinese) => "標籤只能以 == != 或 =~ 比較",
+            (ExprTrailingText, English) => "unexpected text after the expression",
+            (ExprTrailingText, TraditionalChinese) => "運算式之後有多餘的文字",
+            (ExprUnclosedQuote, English) => "unclosed quote {0}",
+            (ExprUnclosedQuote, TraditionalChinese) => "引號 {0} 未閉合",
+            (ExprUnexpected, English) => "unexpected {0}",
+            (ExprUnexpected, TraditionalChinese) => "不應出現的 {0}",
+            (ExprMissingParenthesis, English) => "missing )",
+            (ExprMissingParenthesis, TraditionalChinese) => "缺少 )",
+            (ExprUnknownField, English) => "unknown field: {0}",
+            (ExprUnknownField, TraditionalChinese) => "未知的欄位：{0}",
+            (ExprMissingComparison, English) => "missing comparison after {0}",
+            (ExprMissingComparison, TraditionalChinese) => "{0} 之後缺少比較",
+            (ExprMissingValue, English) => "missing value to compare {0} with",
+            (ExprMissingValue, TraditionalChinese) => "缺少與 {0} 比較的值",
+            (ExprExpectedField, English) => "expected a field, ! or (",
+            (ExprExpectedField, TraditionalChinese) => "應為欄位、! 或 (",
+            (InvalidDelay, English) => "invalid delay: {0}",
+            (InvalidDelay, TraditionalChinese) => "延遲無效：{0}",
+            (InvalidDelayUnit, English) => "invalid delay: {0}, expected e.g. 500ms or 2s",
+            (InvalidDelayUnit, TraditionalChinese) => "延遲無效：{0}，應為 500ms 或 2s 之類",
+            (ManifestUnreadable, English) => "cannot read the manifest {0}: {1}",
+            (ManifestUnreadable, TraditionalChinese) => "無法讀取清單 {0}：{1}",
+            (InvalidRate, English) => "invalid rate: {0}",
+            (InvalidRate, TraditionalChinese) => "速率無效：{0}",
+            (InvalidRateUnit, English) => "invalid rate: {0}, expected e.g. 50/s or 20MB/s",
+            (InvalidRateUnit, TraditionalChinese) => "速率無效：{0}，應為 50/s 或 20MB/s 之類",
+            (InvalidChunkSize, English) => "invalid chunk size: {0}, expected a positive number",
+            (InvalidChunkSize, TraditionalChinese) => "批次大小無效：{0}，應為正數",
+            (InvalidRotation, English) => {
+                "invalid rotation: {0}, expected keep=N with N a positive number"
+            }
+            (InvalidRotation, TraditionalChinese) => "輪替無效：{0}，應為 keep=N，N 為正數",
+            (ExpectedOctalMode, English) => "expected an octal mode such as 644",
+            (ExpectedOctalMode, TraditionalChinese) => "應為 644 之類的八進位權限",
+            (InvalidOffset, English) => "Invalid offset: {0}",
+            (InvalidOffset, TraditionalChinese) => "偏移無效：{0}",
+            (RuleFailed, English) => "rule {0}: {1}",
+            (RuleFailed, TraditionalChinese) => "規則 {0}：{1}",
+            (TemplateUnbalancedBrace, English) => "unbalanced brace at {0}",
+            (TemplateUnbalancedBrace, TraditionalChinese) => "位置 {0} 的大括號不成對",
+            (TemplateInvalidWidth, English) => "invalid width \"{0}\"",
+            (TemplateInvalidWidth, TraditionalChinese) => "寬度 \"{0}\" 無效",
+            (TemplateInvalidFilter, English) => "invalid filter \"{0}\"",
+            (TemplateInvalidFilter, TraditionalChinese) => "篩選器 \"{0}\" 無效",
+            (TemplateMissingValue, English) => "no value for {0}",
+            (TemplateMissingValue, TraditionalChinese) => "{0} 沒有值",
+            (NotOfficeDocument, English) => "not a docx, xlsx, pptx, odt, ods or odp document",
+            (NotOfficeDocument, TraditionalChinese) => "不是 docx、xlsx、pptx、odt、ods 或 odp 文件",
+            (NotOfficeDocumentNorScan, English) => {
+                "not a docx, xlsx, pptx, odt, ods or odp document, nor a scan"
+            }
+            (NotOfficeDocumentNorScan, TraditionalChinese) => {
+                "不是 docx、xlsx、pptx、odt、ods 或 odp 文件，也不是掃描檔"
+            }
+            (CopyNotIdentical, English) => "the copy differs from the source; run again to retry",
+            (CopyNotIdentical, TraditionalChinese) => "副本與來源不同；請再執行一次以重試",
+            (AcceptMovingDuplicates, English) => "Move the above duplicates for review?",
+            (AcceptMovingDuplicates, TraditionalChinese) => "要移動以上重複的檔案以供檢查嗎？",
+        }
+    }
+}
+
+/// `message` in the current language, with `{0}`, `{1}`, … replaced by `args`
+/// in one pass, so that braces within the arguments are left as they are
+pub fn tr(message: Message, args: &[&dyn Display]) -> String {
+    let mut text: String = String::new();
+    let mut rest: &str = message.template(get_language());
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+        let placeholder: Option<(&&dyn Display, usize)> = rest.find('}').and_then(|end| {
+            let arg: &&dyn Display = args.get(rest[..end].parse::<usize>().ok()?)?;
+            Some((arg, end))
+        });
+        match placeholder {
+            Some((arg, end)) => {
+                text.push_str(&arg.to_string());
+                rest = &rest[end + 1..];
+            }
+            None => text.push('{'),
+        }
+    }
+    text.push_str(rest);
+    text
+}
@@ -0,0 +1,31 @@
+//! Ctrl-C while files are being changed, noted rather than obeyed at once, so
+//! that the operation in flight finishes and its journal entry is written.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Once,
+};
+
+/// the exit code of a run stopped by Ctrl-C, 128 + SIGINT as shells report it
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+static HANDLER: Once = Once::new();
+
+/// from now on, note Ctrl-C for `is_interrupted` instead of ending the process;
+/// a second Ctrl-C ends it anyway, for an operation that hangs
+pub fn watch_interrupts() {
+    HANDLER.call_once(|| {
+        let _ = ctrlc::set_handler(|| {
+            if INTERRUPTED.swap(true, Ordering::SeqCst) {
+                std::process::exit(INTERRUPTED_EXIT_CODE);
+            }
+        });
+    });
+}
+
+/// whether Ctrl-C was pressed since `watch_interrupts`
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
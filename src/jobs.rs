@@ -0,0 +1,46 @@
+//! The threads the tools work on at once, by `--jobs`, as for hashing large
+//! files on all cores of a machine with fast storage.
+
+use clap::{value_parser, Arg, ArgMatches};
+use std::sync::OnceLock;
+
+/// the threads of this run, those of every core when none are given
+static JOBS: OnceLock<Option<usize>> = OnceLock::new();
+
+const JOBS_HELP_MESSAGE: &str = r#"Work on this many threads at once, as when hashing large files; one
+per core by default; renames not depending on each other run at once
+only when given, as on a network share"#;
+
+/// the `--jobs` option of the tools working on several threads
+pub fn get_jobs_arg() -> Arg {
+    Arg::new("jobs")
+        .short('j')
+        .long("jobs")
+        .help(JOBS_HELP_MESSAGE)
+        .value_parser(value_parser!(u64).range(1..))
+}
+
+/// work on the threads given by `--jobs` in this run
+pub fn set_jobs(matches: &ArgMatches) {
+    let jobs: Option<usize> = matches
+        .get_one::<u64>("jobs")
+        .map(|n| usize::try_from(*n).unwrap_or(usize::MAX));
+    if JOBS.set(jobs).is_ok() {
+        if let Some(jobs) = jobs {
+            // the pool is built once, at the first run of a process
+            let _ = rayon_core::ThreadPoolBuilder::new()
+                .num_threads(jobs)
+                .build_global();
+        }
+    }
+}
+
+/// whether this run works on a single thread, by `--jobs 1`
+pub fn is_single_threaded() -> bool {
+    JOBS.get().copied().flatten() == Some(1)
+}
+
+/// the threads given by `--jobs` in this run, if any
+pub fn get_jobs() -> Option<usize> {
+    JOBS.get().copied().flatten()
+}
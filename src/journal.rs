@@ -0,0 +1,213 @@
+//! The undo journal: one JSON object per line, each recording a single rename,
+//! or a move to the trash, in the order it was applied; by `--journal-in-dir`,
+//! also in a hidden sidecar inside the directory of each file renamed, by paths
+//! relative to it, so that the folder can be restored by `restore_names`
+//! wherever it goes.
+
+use crate::paths::{decode_raw_path, encode_raw_path};
+use chrono::Local;
+use clap::{Arg, ArgAction, ArgMatches};
+use serde::{Deserialize, Serialize};
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+pub(crate) const APPLICATION_DIR_NAME: &str = "ray-commands";
+
+/// the name of the journal kept inside a directory by `--journal-in-dir`
+pub const SIDECAR_JOURNAL_NAME: &str = ".cu-journal.json";
+
+const JOURNAL_IN_DIR_HELP_MESSAGE: &str = r#"Also journal each rename in a hidden .cu-journal.json inside the
+directory of the file, by paths relative to it, so that the undo goes
+along when the folder is copied elsewhere: restore_names .cu-journal.json"#;
+
+/// whether the renames of this run are also journaled in sidecars
+static IS_SIDECAR_JOURNALED: OnceLock<bool> = OnceLock::new();
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct JournalEntry {
+    /// path before the rename, lossy when it is not valid UTF-8
+    pub from: String,
+    /// path after the rename, lossy when it is not valid UTF-8
+    pub to: String,
+    /// the exact bytes of `from` in hexadecimal, when it is not valid UTF-8
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_raw: Option<String>,
+    /// the exact bytes of `to` in hexadecimal, when it is not valid UTF-8
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_raw: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// whether `from` went to the trash instead, `to` being empty
+    #[serde(
+        default,
+        rename = "trashed",
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub is_trashed: bool,
+}
+
+impl JournalEntry {
+    pub fn new(from: &Path, to: &Path) -> Self {
+        Self {
+            from: from.to_string_lossy().into_owned(),
+            to: to.to_string_lossy().into_owned(),
+            from_raw: encode_raw_path(from),
+            to_raw: encode_raw_path(to),
+            size: None,
+            hash: None,
+            is_trashed: false,
+        }
+    }
+
+    /// the entry of the move of `from` to the trash
+    pub fn trashed(from: &Path) -> Self {
+        Self {
+            is_trashed: true,
+            ..Self::new(from, Path::new(""))
+        }
+    }
+
+    pub fn from_path(&self) -> PathBuf {
+        self.from_raw
+            .as_deref()
+            .and_then(decode_raw_path)
+            .unwrap_or_else(|| PathBuf::from(&self.from))
+    }
+
+    pub fn to_path(&self) -> PathBuf {
+        self.to_raw
+            .as_deref()
+            .and_then(decode_raw_path)
+            .unwrap_or_else(|| PathBuf::from(&self.to))
+    }
+}
+
+pub fn read_journal(path: &Path) -> io::Result<Vec<JournalEntry>> {
+    BufReader::new(File::open(path)?)
+        .lines()
+        .filter(|line| !line.as_ref().is_ok_and(|l| l.trim().is_empty()))
+        .map(|line| {
+            serde_json::from_str(&line?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+        .collect()
+}
+
+pub fn append_journal(path: &Path, entries: &[JournalEntry]) -> io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file: File = OpenOptions::new().create(true).append(true).open(path)?;
+    for entry in entries {
+        writeln!(file, "{}", serde_json::to_string(entry).unwrap())?;
+    }
+    file.flush()
+}
+
+/// `$XDG_STATE_HOME/ray-commands/<program>` (`~/.local/state` by default),
+/// or `%LOCALAPPDATA%\ray-commands\<program>` on Windows
+pub fn get_journal_dir(program: &str) -> PathBuf {
+    let state_dir: PathBuf = if cfg!(windows) {
+        PathBuf::from(env::var_os("LOCALAPPDATA").unwrap_or_default())
+    } else {
+        match env::var_os("XDG_STATE_HOME").filter(|d| !d.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(env::var_os("HOME").unwrap_or_default()).join(".local/state"),
+        }
+    };
+    state_dir.join(APPLICATION_DIR_NAME).join(program)
+}
+
+/// a fresh journal for one run of `program`, named after the current time
+pub fn new_journal_path(program: &str) -> PathBuf {
+    let name: String = Local::now().format("%Y%m%d-%H%M%S%.3f.jsonl").to_string();
+    get_journal_dir(program).join(name)
+}
+
+/// the `--journal-in-dir` flag of the renaming tools
+pub fn get_journal_in_dir_arg() -> Arg {
+    Arg::new("journal_in_dir")
+        .long("journal-in-dir")
+        .help(JOURNAL_IN_DIR_HELP_MESSAGE)
+        .action(ArgAction::SetTrue)
+}
+
+/// journal the renames of this run in sidecars too, by `--journal-in-dir`
+pub fn set_sidecar_journal(matches: &ArgMatches) {
+    let is_journaled: bool = matches
+        .try_get_one::<bool>("journal_in_dir")
+        .ok()
+        .flatten()
+        .is_some_and(|is_journaled| *is_journaled);
+    let _ = IS_SIDECAR_JOURNALED.set(is_journaled);
+}
+
+/// whether the renames of this run are journaled in sidecars too
+pub fn is_sidecar_journaled() -> bool {
+    IS_SIDECAR_JOURNALED.get().copied().unwrap_or(false)
+}
+
+/// whether `path` names the journal kept inside a directory
+pub fn is_sidecar_journal(path: &Path) -> bool {
+    path.file_name().is_some_and(|n| n == SIDECAR_JOURNAL_NAME)
+}
+
+/// journal the rename of `entry`, by absolute paths, in the sidecar of the
+/// directory it was renamed from, by paths relative to it; a new name outside
+/// the directory stays absolute
+pub fn append_sidecar_journal(entry: &JournalEntry) -> io::Result<()> {
+    let (from, to) = (entry.from_path(), entry.to_path());
+    let Some(dir) = from.parent() else {
+        return Ok(());
+    };
+    let relative = |path: &Path| path.strip_prefix(dir).unwrap_or(path).to_path_buf();
+    let sidecar_entry: JournalEntry = JournalEntry {
+        size: entry.size,
+        hash: entry.hash.clone(),
+        is_trashed: entry.is_trashed,
+        ..JournalEntry::new(&relative(&from), &relative(&to))
+    };
+    append_journal(&dir.join(SIDECAR_JOURNAL_NAME), &[sidecar_entry])
+}
+
+/// the steps planned by the run journaled at `journal`, kept beside it in the
+/// format of the journal until the run gets through them all
+pub fn get_pending_path(journal: &Path) -> PathBuf {
+    journal.with_extension("pending")
+}
+
+/// record the steps a run journaling at `journal` is about to take, replacing
+/// those of the run it resumes
+pub fn write_pending(journal: &Path, entries: &[JournalEntry]) -> io::Result<()> {
+    let pending: PathBuf = get_pending_path(journal);
+    match fs::remove_file(&pending) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => append_journal(&pending, entries),
+    }
+}
+
+/// the journal of the latest run of `program` stopped before getting through
+/// its steps, by a crash, Ctrl-C or a failure
+pub fn find_pending_journal(program: &str) -> Option<PathBuf> {
+    fs::read_dir(get_journal_dir(program))
+        .ok()?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == "pending"))
+        .max()
+        .map(|p| p.with_extension("jsonl"))
+}
+
+/// the journal of the latest run of `program` that has not been undone
+pub fn find_latest_journal(program: &str) -> Option<PathBuf> {
+    fs::read_dir(get_journal_dir(program))
+        .ok()?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == "jsonl"))
+        .max()
+}
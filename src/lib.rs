@@ -0,0 +1,61 @@
+//! Shared building blocks for the command-line utilities under `src/bin`.
+//!
+//! Programs depending on the library should keep to [`api`], whose items are
+//! stable across releases by semantic versioning; the other modules may change
+//! with the tools in any release.
+
+pub mod age;
+pub mod api;
+pub mod backup;
+pub mod clock;
+pub mod config;
+pub mod conflict;
+pub mod content;
+pub mod dest_map;
+pub mod error;
+pub mod exif;
+pub mod expr;
+pub mod failure;
+pub mod ffi;
+pub mod filesystem;
+pub mod fixture;
+pub mod geo;
+pub mod guard;
+pub mod hardlinks;
+pub mod hash;
+pub mod hooks;
+pub mod i18n;
+pub mod interrupt;
+pub mod jobs;
+pub mod journal;
+pub mod manifest;
+pub mod mapping;
+pub mod name_command;
+pub mod naming;
+pub mod notify;
+pub mod ordering;
+pub mod output;
+pub mod paths;
+pub mod pipe;
+pub mod plan;
+pub mod platform;
+pub mod portable;
+pub mod preset;
+pub mod preview;
+pub mod prompt;
+pub mod recycle;
+pub mod report;
+pub mod restore;
+pub mod retry;
+pub mod sanitize;
+pub mod serve;
+pub mod size;
+pub mod sort;
+pub mod stats;
+pub mod table;
+pub mod template;
+pub mod throttle;
+pub mod time_format;
+pub mod verify;
+pub mod walk;
+pub mod xattrs;
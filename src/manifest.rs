@@ -0,0 +1,139 @@
+//! The JSON manifest: a snapshot of the files of a directory,
+//! identifying each file by size and content hash; with `--since`, a run
+//! takes only the files added or modified since the snapshot.
+
+use crate::{
+    hash::hash_file_cached,
+    i18n::{tr, Message},
+};
+use chrono::DateTime;
+use clap::{Arg, ArgMatches};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+const SINCE_HELP_MESSAGE: &str = r#"Only take the files added or modified since this manifest, as written
+by export_names --format json, e.g. from a card not wiped between
+imports; a file is unchanged when the manifest has its path, or the end
+of it, with its size, and its modification time and hash if recorded"#;
+
+/// the manifest of `--since` for this run, if any
+static SINCE_MANIFEST: OnceLock<Option<Since>> = OnceLock::new();
+
+/// the entries of a manifest by file name, to look the files of a run up in
+#[derive(Clone)]
+pub struct Since {
+    entries: HashMap<OsString, Vec<ManifestEntry>>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ManifestEntry {
+    /// path relative to the directory the manifest describes
+    pub name: String,
+    pub size: u64,
+    /// modification time in RFC 3339
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+pub fn read_manifest(path: &Path) -> io::Result<Vec<ManifestEntry>> {
+    serde_json::from_str(&fs::read_to_string(path)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn write_manifest<W: io::Write>(mut writer: W, entries: &[ManifestEntry]) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut writer, entries)?;
+    writeln!(writer)
+}
+
+impl Since {
+    pub fn from(entries: Vec<ManifestEntry>) -> Self {
+        let mut by_name: HashMap<OsString, Vec<ManifestEntry>> = HashMap::new();
+        for entry in entries {
+            if let Some(name) = Path::new(&entry.name).file_name() {
+                by_name.entry(name.to_os_string()).or_default().push(entry);
+            }
+        }
+        Self { entries: by_name }
+    }
+
+    /// whether the file at `path` with `metadata` is new or differs from its
+    /// entry; only regular files are compared, never directories or links
+    pub fn is_changed(&self, path: &Path, metadata: &fs::Metadata) -> bool {
+        if !metadata.is_file() {
+            return true;
+        }
+        let Some(entries) = path.file_name().and_then(|n| self.entries.get(n)) else {
+            return true;
+        };
+        !entries
+            .iter()
+            .filter(|e| path.ends_with(PathBuf::from(&e.name)))
+            .any(|e| is_unchanged(e, path, metadata))
+    }
+}
+
+/// whether the file at `path` with `metadata` is the one `entry` recorded,
+/// hashing it only when all else matches
+fn is_unchanged(entry: &ManifestEntry, path: &Path, metadata: &fs::Metadata) -> bool {
+    if entry.size != metadata.len() {
+        return false;
+    }
+    if let Some(mtime) = &entry.mtime {
+        let recorded: Option<i64> = DateTime::parse_from_rfc3339(mtime)
+            .ok()
+            .map(|t| t.timestamp());
+        let modified: Option<i64> = metadata
+            .modified()
+            .ok()
+            .map(|t| DateTime::<chrono::Utc>::from(t).timestamp());
+        if recorded.is_none() || recorded != modified {
+            return false;
+        }
+    }
+    match &entry.hash {
+        Some(hash) => hash_file_cached(path).is_ok_and(|h| &h == hash),
+        None => true,
+    }
+}
+
+fn parse_since(text: &str) -> Result<Since, String> {
+    read_manifest(Path::new(text))
+        .map(Since::from)
+        .map_err(|e| tr(Message::ManifestUnreadable, &[&text, &e]))
+}
+
+/// the `--since` option of the tools taking batches of files
+pub fn get_since_arg() -> Arg {
+    Arg::new("since")
+        .long("since")
+        .help(SINCE_HELP_MESSAGE)
+        .value_name("MANIFEST")
+        .value_parser(parse_since)
+}
+
+/// take only the files changed since the manifest of `--since` in this run
+pub fn set_since_manifest(matches: &ArgMatches) {
+    let since: Option<Since> = matches
+        .try_get_one::<Since>("since")
+        .ok()
+        .flatten()
+        .cloned();
+    let _ = SINCE_MANIFEST.set(since);
+}
+
+/// whether the file at `path` with `metadata` is changed since the manifest
+/// of `--since`, any file without one
+pub fn is_changed_since(path: &Path, metadata: &fs::Metadata) -> bool {
+    match SINCE_MANIFEST.get() {
+        Some(Some(since)) => since.is_changed(path, metadata),
+        _ => true,
+    }
+}
@@ -0,0 +1,50 @@
+//! The CSV/TSV rename mapping: an `old,new` table with optional
+//! `size`, `mtime` and `hash` columns, as edited in a spreadsheet.
+
+use serde::{Deserialize, Serialize};
+use std::{io, path::Path};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MappingRow {
+    pub old: String,
+    pub new: String,
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub mtime: Option<String>,
+    #[serde(default)]
+    pub hash: Option<String>,
+}
+
+/// tab for `.tsv` files, comma otherwise
+pub fn get_delimiter(path: &Path) -> u8 {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("tsv") => b'\t',
+        _ => b',',
+    }
+}
+
+pub fn read_mapping(path: &Path) -> io::Result<Vec<MappingRow>> {
+    csv::ReaderBuilder::new()
+        .delimiter(get_delimiter(path))
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .map_err(io::Error::from)?
+        .deserialize()
+        .map(|row| row.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)))
+        .collect()
+}
+
+pub fn write_mapping<W: io::Write>(
+    writer: W,
+    delimiter: u8,
+    rows: &[MappingRow],
+) -> io::Result<()> {
+    let mut writer: csv::Writer<W> = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(writer);
+    for row in rows {
+        writer.serialize(row).map_err(io::Error::from)?;
+    }
+    writer.flush()
+}
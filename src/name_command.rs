@@ -0,0 +1,159 @@
+//! New names given by an external command, for naming logic the tools do not
+//! have, such as OCR, image captioning or a lookup service.
+//!
+//! The command is run once per file with the path as its last argument and
+//! prints the new stem, or with `--name-batch` once for all files, reading a
+//! JSON array of paths and printing a JSON object from each path to its new
+//! stem, or to null to leave the file as it is. The extension is kept.
+
+use crate::{
+    conflict::ConflictStrategy,
+    hooks::get_shell_command,
+    i18n::{tr, Message},
+    plan::RenamePlan,
+    sanitize::sanitize_component,
+};
+use clap::{Arg, ArgAction, ArgMatches};
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    io::Write, // Write for write_all
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+};
+
+pub const NAME_FROM_COMMAND_HELP_MESSAGE: &str = r#"Take the new stem of each file from a shell command run with the path
+as its last argument and printing the stem, e.g. "ocr-title";
+a file is skipped when the command fails or prints nothing"#;
+
+pub const NAME_BATCH_HELP_MESSAGE: &str = r#"Run the name command once, with a JSON array of all paths on its
+standard input, and read a JSON object from each path to its new stem,
+or to null, from its standard output"#;
+
+pub struct NameCommand {
+    command: String,
+    is_batch: bool,
+}
+
+impl NameCommand {
+    /// the command given by `--name-from-command`, if any
+    pub fn from(matches: &ArgMatches) -> Option<Self> {
+        matches
+            .get_one::<String>("name_from_command")
+            .map(|command| Self {
+                command: command.clone(),
+                is_batch: matches.get_flag("name_batch"),
+            })
+    }
+
+    /// the new stem of each of `paths`, in order, or why it has none
+    pub fn get_stems(&self, paths: &[PathBuf]) -> Vec<Result<String, String>> {
+        let stems: Vec<Result<String, String>> = match self.is_batch {
+            true => match self.run_batch(paths) {
+                Ok(mut stems) => paths
+                    .iter()
+                    .map(|p| match stems.remove(&p.display().to_string()) {
+                        Some(Some(stem)) => Ok(stem),
+                        _ => Err(tr(Message::NameCommandGaveNone, &[])),
+                    })
+                    .collect(),
+                Err(err) => vec![Err(err); paths.len()],
+            },
+            false => paths.iter().map(|p| self.run_single(p)).collect(),
+        };
+        stems
+            .into_iter()
+            .map(|stem| {
+                let stem: String = sanitize_component(&stem?);
+                match stem.is_empty() {
+                    true => Err(tr(Message::NameCommandGaveNone, &[])),
+                    false => Ok(stem),
+                }
+            })
+            .collect()
+    }
+
+    /// rename each of `paths` to its new stem, keeping its extension
+    pub fn plan_renames(&self, paths: &[PathBuf], conflict: ConflictStrategy) -> RenamePlan {
+        let mut plan: RenamePlan = RenamePlan::with_conflict(conflict);
+        for (path, stem) in paths.iter().zip(self.get_stems(paths)) {
+            let target: Result<PathBuf, String> = stem.map(|stem| {
+                let mut name: OsString = OsString::from(stem);
+                if let Some(ext) = path.extension() {
+                    name.push(".");
+                    name.push(ext);
+                }
+                path.with_file_name(name)
+            });
+            plan.add(path.clone(), target);
+        }
+        plan
+    }
+
+    /// the first line printed by the command run on `path`
+    fn run_single(&self, path: &Path) -> Result<String, String> {
+        let output: Output = get_file_command(&self.command, path)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| e.to_string())?;
+        let stdout: String = get_checked_stdout(output)?;
+        Ok(stdout.lines().next().unwrap_or("").trim().to_string())
+    }
+
+    /// the stems printed by the command for all `paths` at once, by path
+    fn run_batch(&self, paths: &[PathBuf]) -> Result<HashMap<String, Option<String>>, String> {
+        let input: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+        let mut child = get_shell_command(&self.command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| e.to_string())?;
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(serde_json::to_string(&input).unwrap().as_bytes());
+        }
+        let output: Output = child.wait_with_output().map_err(|e| e.to_string())?;
+        let stdout: String = get_checked_stdout(output)?;
+        serde_json::from_str(&stdout).map_err(|e| tr(Message::NameCommandInvalidJson, &[&e]))
+    }
+}
+
+/// the `--name-from-command` and `--name-batch` options
+pub fn get_name_command_args() -> [Arg; 2] {
+    [
+        Arg::new("name_from_command")
+            .long("name-from-command")
+            .help(NAME_FROM_COMMAND_HELP_MESSAGE),
+        Arg::new("name_batch")
+            .long("name-batch")
+            .help(NAME_BATCH_HELP_MESSAGE)
+            .action(ArgAction::SetTrue)
+            .requires("name_from_command"),
+    ]
+}
+
+/// the standard output of a command, or its first line of errors when it failed
+fn get_checked_stdout(output: Output) -> Result<String, String> {
+    if !output.status.success() {
+        let stderr: String = String::from_utf8_lossy(&output.stderr).into_owned();
+        return Err(match stderr.lines().next() {
+            Some(line) => tr(Message::NameCommandFailed, &[&line]),
+            None => tr(Message::NameCommandFailed, &[&output.status]),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(unix)]
+fn get_file_command(command: &str, path: &Path) -> Command {
+    let mut shell: Command = get_shell_command(&format!("{command} \"$1\""));
+    shell.arg("sh").arg(path);
+    shell
+}
+
+#[cfg(windows)]
+fn get_file_command(command: &str, path: &Path) -> Command {
+    let mut shell: Command = get_shell_command(command);
+    shell.arg(path);
+    shell
+}
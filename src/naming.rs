@@ -0,0 +1,275 @@
+//! New names derived from what is known of each file alone: its metadata is
+//! read once into a [`FileSnapshot`], and naming and planning from snapshots
+//! touch no file, so that the same snapshots and options always give the same
+//! plan, whether previewed, piped for `--apply` or tested.
+
+use crate::{
+    conflict::ConflictStrategy,
+    dest_map::DestMap,
+    error::{self, Error},
+    filesystem::ReadOnly,
+    i18n::{tr, Message},
+    plan::RenamePlan,
+    platform::get_created_time,
+    preset::TimeFormats,
+    time_format::{TimeFormat, TimeSource},
+};
+use chrono::{DateTime, Local};
+use std::{
+    collections::{BTreeMap, HashSet},
+    ffi::{OsStr, OsString},
+    fs, io,
+    iter::zip,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+/// what naming a file needs to know of it
+#[derive(Clone)]
+pub struct FileSnapshot {
+    pub path: PathBuf,
+    pub modified: SystemTime,
+    /// the creation time, where the file system keeps it
+    pub created: Option<SystemTime>,
+}
+
+impl FileSnapshot {
+    /// the snapshot of the file at `path`, as it is on disk now
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let metadata: fs::Metadata = fs::metadata(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            modified: metadata.modified()?,
+            created: get_created_time(&metadata).ok(),
+        })
+    }
+
+    /// the time of the file by `source`
+    pub fn time(&self, source: TimeSource) -> io::Result<SystemTime> {
+        match source {
+            TimeSource::Modified => Ok(self.modified),
+            TimeSource::Created => self.created.ok_or_else(|| {
+                io::Error::new(io::ErrorKind::Unsupported, tr(Message::NoCreationTime, &[]))
+            }),
+        }
+    }
+}
+
+/// how the files of a run are named
+#[derive(Clone)]
+pub struct NamingOptions {
+    pub time_formats: TimeFormats,
+    pub time_source: TimeSource,
+    /// the gap between files by time starting a new session, by `--sessionize`
+    pub session_gap: Option<Duration>,
+    /// the directories files are moved to as they are renamed, by `--dest-map`
+    pub dest_map: Option<DestMap>,
+}
+
+/// where a file is among the sessions of the files named together
+#[derive(Clone, Copy)]
+struct Session {
+    /// the session, from 1
+    number: usize,
+    /// the file in its session, from 1
+    position: usize,
+}
+
+/// the directory, stem and extension of `path`; a name starting with a dot is
+/// all stem, as for `.bashrc`
+pub fn split_name(path: &Path) -> (PathBuf, OsString, Option<OsString>) {
+    let dir: PathBuf = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let full_name: &OsStr = path.file_name().unwrap_or(path.as_os_str());
+    if full_name.as_encoded_bytes().starts_with(b".") {
+        return (dir, full_name.to_os_string(), None);
+    }
+    let name_path: &Path = Path::new(full_name);
+    let stem: OsString = name_path.file_stem().unwrap_or(full_name).to_os_string();
+    (dir, stem, name_path.extension().map(OsStr::to_os_string))
+}
+
+/// the new path of `file`, named by its time in the format of its extension
+/// and keeping its extension, and its directory unless routed elsewhere by the
+/// destination map; a file alone has no session
+pub fn derive_name(file: &FileSnapshot, options: &NamingOptions) -> error::Result<PathBuf> {
+    derive_name_in(file, options, None)
+}
+
+/// the new path of `file` by [`derive_name`], in `session` if any
+fn derive_name_in(
+    file: &FileSnapshot,
+    options: &NamingOptions,
+    session: Option<Session>,
+) -> error::Result<PathBuf> {
+    let time: DateTime<Local> = file
+        .time(options.time_source)
+        .map_err(|e| Error::from_io(e, &file.path))?
+        .into();
+    let (mut dir, stem, extension) = split_name(&file.path);
+    if let Some(destination) = options.dest_map.as_ref().and_then(|m| m.route(&file.path)) {
+        dir = destination;
+    }
+    let time_format: &TimeFormat = options.time_formats.get(&file.path);
+    let mut values: Vec<(&str, String)> = vec![("name", stem.to_string_lossy().into_owned())];
+    if let Some(session) = session {
+        values.push(("session", session.number.to_string()));
+        values.push(("session_seq", session.position.to_string()));
+    }
+    let mut name: OsString = OsString::from(time_format.format_with(&time, &file.path, &values)?);
+    if let Some(extension) = extension.filter(|e| !e.is_empty()) {
+        name.push(".");
+        name.push(extension);
+    }
+    Ok(dir.join(name))
+}
+
+/// the new paths of `files` by [`derive_name`], in their sessions by
+/// `session_gap` if any, with those named alike told apart: by their UTC
+/// offsets where their times differ in them, as in the hour repeated when
+/// daylight saving time ends, and else by `_1`, `_2`, ... after all but the
+/// first; along with a note for each file told apart, by its index
+pub fn derive_names(
+    files: &[FileSnapshot],
+    options: &NamingOptions,
+) -> (Vec<error::Result<PathBuf>>, Vec<(usize, String)>) {
+    let times: Vec<Option<DateTime<Local>>> = files
+        .iter()
+        .map(|f| f.time(options.time_source).ok().map(DateTime::from))
+        .collect();
+    let sessions: Vec<Option<Session>> = match options.session_gap {
+        Some(gap) => find_sessions(&times, gap),
+        None => vec![None; files.len()],
+    };
+    let mut names: Vec<error::Result<PathBuf>> = zip(files, sessions)
+        .map(|(f, session)| derive_name_in(f, options, session))
+        .collect();
+    let mut notes: Vec<(usize, String)> = Vec::new();
+    for indices in find_alike(&names) {
+        let offsets: HashSet<String> = indices
+            .iter()
+            .filter_map(|&i| times[i])
+            .map(|t| t.format("%z").to_string())
+            .collect();
+        if offsets.len() < 2 {
+            continue;
+        }
+        for &i in &indices {
+            if let (Ok(name), Some(time)) = (&mut names[i], times[i]) {
+                *name = append_to_stem(name, &time.format("%z").to_string());
+                let repeated: String = time.format("%Y-%m-%d %H:%M:%S").to_string();
+                let note: String = tr(Message::ExplainOffsetAdded, &[&repeated]);
+                notes.push((i, note));
+            }
+        }
+    }
+    for indices in find_alike(&names) {
+        for (n, &i) in indices.iter().enumerate().skip(1) {
+            if let Ok(name) = &mut names[i] {
+                *name = append_to_stem(name, &format!("_{n}"));
+                notes.push((i, tr(Message::ExplainNumberedAlike, &[&n])));
+            }
+        }
+    }
+    (names, notes)
+}
+
+/// the session of each file of `times`, in time order a new one starting
+/// after a gap longer than `gap`; none for a file without a time
+fn find_sessions(times: &[Option<DateTime<Local>>], gap: Duration) -> Vec<Option<Session>> {
+    let mut order: Vec<(DateTime<Local>, usize)> = times
+        .iter()
+        .enumerate()
+        .filter_map(|(i, t)| t.map(|t| (t, i)))
+        .collect();
+    order.sort();
+    let mut sessions: Vec<Option<Session>> = vec![None; times.len()];
+    let mut previous: Option<DateTime<Local>> = None;
+    let mut session: Session = Session {
+        number: 1,
+        position: 0,
+    };
+    for (time, i) in order {
+        if previous.is_some_and(|p| (time - p).to_std().unwrap_or_default() > gap) {
+            session.number += 1;
+            session.position = 0;
+        }
+        session.position += 1;
+        sessions[i] = Some(session);
+        previous = Some(time);
+    }
+    sessions
+}
+
+/// the indices of the names shared by several files, each group in order;
+/// names differing only in case are shared, naming the same file where case
+/// is ignored, as on exFAT, NTFS and APFS
+fn find_alike(names: &[error::Result<PathBuf>]) -> Vec<Vec<usize>> {
+    let mut groups: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, name) in names.iter().enumerate() {
+        if let Ok(name) = name {
+            let folded: String = name.to_string_lossy().to_lowercase();
+            groups.entry(folded).or_default().push(i);
+        }
+    }
+    let mut alike: Vec<Vec<usize>> = groups.into_values().filter(|g| g.len() > 1).collect();
+    alike.sort();
+    alike
+}
+
+/// `path` with `suffix` after its stem, before its extension
+fn append_to_stem(path: &Path, suffix: &str) -> PathBuf {
+    let (dir, mut stem, extension) = split_name(path);
+    stem.push(suffix);
+    if let Some(extension) = extension.filter(|e| !e.is_empty()) {
+        stem.push(".");
+        stem.push(extension);
+    }
+    dir.join(stem)
+}
+
+/// the names taken in the directories `files` are renamed in, theirs and those
+/// `options` route them to, as the snapshot of existing names for
+/// [`plan_names`]; a directory not there yet has none
+pub fn read_existing(
+    files: &[FileSnapshot],
+    options: &NamingOptions,
+    reader: ReadOnly,
+) -> HashSet<PathBuf> {
+    let dirs: HashSet<PathBuf> = files
+        .iter()
+        .flat_map(|f| {
+            let dir: PathBuf = f.path.parent().map(Path::to_path_buf).unwrap_or_default();
+            let routed: Option<PathBuf> = options.dest_map.as_ref().and_then(|m| m.route(&f.path));
+            [Some(dir), routed]
+        })
+        .flatten()
+        .collect();
+    dirs.iter()
+        .filter_map(|d| reader.read_dir(d).ok())
+        .flatten()
+        .collect()
+}
+
+/// the plan renaming `files` by [`derive_names`], resolving new names taken by
+/// `existing` files with `conflict`; a file whose name cannot be derived is
+/// skipped
+pub fn plan_names(
+    files: &[FileSnapshot],
+    existing: HashSet<PathBuf>,
+    options: &NamingOptions,
+    conflict: ConflictStrategy,
+) -> RenamePlan {
+    let mut plan: RenamePlan = RenamePlan::with_conflict(conflict).with_existing(existing);
+    let (names, notes) = derive_names(files, options);
+    for (i, note) in notes {
+        plan.explain(&files[i].path, note);
+    }
+    plan.add_batch(
+        files
+            .iter()
+            .zip(names)
+            .map(|(f, name)| (f.path.clone(), name.map_err(|e| e.to_string())))
+            .collect(),
+    );
+    plan
+}
@@ -0,0 +1,66 @@
+//! A desktop notification with the outcome of a run by `--notify`, for those
+//! starting a long batch on a big archive and turning to something else.
+
+use crate::{
+    error,
+    i18n::{tr, Message},
+    stats::RunStats,
+};
+use clap::{Arg, ArgAction, ArgMatches};
+use notify_rust::Notification;
+use std::sync::OnceLock;
+
+static NOTIFY: OnceLock<bool> = OnceLock::new();
+
+/// the `--notify` flag of the tools running long batches
+pub fn get_notify_arg() -> Arg {
+    Arg::new("notify")
+        .long("notify")
+        .help("Show a desktop notification with the outcome when the run ends,\nor each round in watch mode")
+        .action(ArgAction::SetTrue)
+}
+
+/// notify of the end of the runs of this run by `--notify`
+pub fn set_notify(matches: &ArgMatches) {
+    let _ = NOTIFY.set(matches.get_flag("notify"));
+}
+
+/// show a notification that a run of `program` ended, failed when `is_failure`,
+/// with `summary` as its text, when `--notify` asks; a desktop without
+/// notifications only gets a warning
+pub fn notify_finished(program: &str, summary: &str, is_failure: bool) {
+    if !NOTIFY.get().copied().unwrap_or(false) {
+        return;
+    }
+    let title: String = match is_failure {
+        true => tr(Message::RunFailed, &[&program]),
+        false => tr(Message::RunFinished, &[&program]),
+    };
+    if let Err(err) = Notification::new()
+        .appname(program)
+        .summary(&title)
+        .body(summary)
+        .show()
+    {
+        eprintln!("{}", tr(Message::NotifyFailed, &[&err]));
+    }
+}
+
+/// notify of a run of `program` ending in `result`, with its counts when it
+/// got to changing files
+pub fn notify_outcome<T>(program: &str, stats: Option<&RunStats>, result: &error::Result<T>) {
+    let counts: Option<String> = stats.map(RunStats::summary);
+    match result {
+        Ok(_) => {
+            let summary: String = counts.unwrap_or_else(|| tr(Message::NothingDone, &[]));
+            notify_finished(program, &summary, false);
+        }
+        Err(err) => {
+            let summary: String = match counts {
+                Some(counts) => format!("{err}\n{counts}"),
+                None => err.to_string(),
+            };
+            notify_finished(program, &summary, true);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use crate::filesystem::ReadOnly;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+/// Order a batch of renames so that no step overwrites a file another step has
+/// yet to move away.
+///
+/// Chains (A→B, B→C) run from their end, and cycles (A→B, B→A) are broken by
+/// moving one file to a temporary name first, free in `reader`. Sources must be
+/// distinct, and so must targets; renames onto themselves are dropped.
+pub fn order_renames(renames: &[(PathBuf, PathBuf)], reader: ReadOnly) -> Vec<(PathBuf, PathBuf)> {
+    let mut pending: Vec<(PathBuf, PathBuf)> =
+        renames.iter().filter(|(s, d)| s != d).cloned().collect();
+    let mut used: HashSet<PathBuf> = renames
+        .iter()
+        .flat_map(|(s, d)| [s.clone(), d.clone()])
+        .collect();
+    let mut steps: Vec<(PathBuf, PathBuf)> = Vec::new();
+
+    while !pending.is_empty() {
+        let sources: HashSet<PathBuf> = pending.iter().map(|(s, _)| s.clone()).collect();
+        let (ready, blocked): (Vec<_>, Vec<_>) =
+            pending.into_iter().partition(|(_, d)| !sources.contains(d));
+        pending = blocked;
+        if !ready.is_empty() {
+            steps.extend(ready);
+            continue;
+        }
+
+        // everything left is part of a cycle: park one file under a temporary name
+        let (source, target) = pending.remove(0);
+        let temp: PathBuf = get_temp_name(&source, &mut used, reader);
+        steps.push((source, temp.clone()));
+        pending.push((temp, target));
+    }
+    steps
+}
+
+/// Split the steps of [`order_renames`] into groups that may run at once, each
+/// the positions of its steps in order.
+///
+/// Steps sharing a path, or one path being inside a directory another moves,
+/// are in the same group, as are paths differing only in case, for file
+/// systems ignoring it. The empty target of a step moving a file to the trash
+/// is shared with none.
+pub fn group_renames(steps: &[(PathBuf, PathBuf)]) -> Vec<Vec<usize>> {
+    let key = |path: &Path| path.to_string_lossy().to_lowercase();
+    let mut groups: Vec<usize> = (0..steps.len()).collect();
+    let find = |groups: &mut Vec<usize>, mut i: usize| {
+        while groups[i] != i {
+            groups[i] = groups[groups[i]];
+            i = groups[i];
+        }
+        i
+    };
+    let union = |groups: &mut Vec<usize>, i: usize, j: usize| {
+        let (a, b) = (find(groups, i), find(groups, j));
+        groups[a.max(b)] = a.min(b);
+    };
+    // the first step using each path
+    let mut by_path: HashMap<String, usize> = HashMap::new();
+    for (i, step) in steps.iter().enumerate() {
+        for path in get_step_paths(step) {
+            let first: usize = *by_path.entry(key(path)).or_insert(i);
+            union(&mut groups, i, first);
+        }
+    }
+    for (i, step) in steps.iter().enumerate() {
+        for path in get_step_paths(step) {
+            for ancestor in path.ancestors().skip(1) {
+                if let Some(&j) = by_path.get(&key(ancestor)) {
+                    union(&mut groups, i, j);
+                }
+            }
+        }
+    }
+    let mut grouped: Vec<Vec<usize>> = Vec::new();
+    let mut positions: HashMap<usize, usize> = HashMap::new();
+    for i in 0..steps.len() {
+        let root: usize = find(&mut groups, i);
+        let position: usize = *positions.entry(root).or_insert_with(|| {
+            grouped.push(Vec::new());
+            grouped.len() - 1
+        });
+        grouped[position].push(i);
+    }
+    grouped
+}
+
+/// the paths of `step`, less the empty target of a move to the trash
+fn get_step_paths((source, target): &(PathBuf, PathBuf)) -> impl Iterator<Item = &PathBuf> {
+    [source, target]
+        .into_iter()
+        .filter(|p| !p.as_os_str().is_empty())
+}
+
+/// a hidden sibling of `path` that no other step uses and nothing occupies
+fn get_temp_name(path: &Path, used: &mut HashSet<PathBuf>, reader: ReadOnly) -> PathBuf {
+    let name: String = path.file_name().unwrap().to_string_lossy().into_owned();
+    let temp: PathBuf = (0..)
+        .map(|n| path.with_file_name(format!(".{name}.tmp-rename-{n}")))
+        .find(|p| !used.contains(p) && !reader.exists(p))
+        .unwrap();
+    used.insert(temp.clone());
+    temp
+}
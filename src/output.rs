@@ -0,0 +1,190 @@
+//! The formats every tool prints its results in, a table for reading or flat
+//! records for scripts, picked by `--output`, and the new paths of the files
+//! renamed for `xargs -0` by `--print0`.
+
+use clap::{builder::PossibleValue, Arg, ArgAction, ArgMatches};
+use serde::Serialize;
+use std::{
+    io::{self, Write},
+    path::Path,
+    sync::OnceLock,
+};
+
+/// whether standard output is kept for the new paths of `--print0`, the
+/// messages and questions of this run going to standard error
+static PRINT0: OnceLock<bool> = OnceLock::new();
+
+pub const OUTPUT_FORMAT_NAMES: [&str; 5] = ["table", "json", "jsonl", "tsv", "yaml"];
+
+const PRINT0_HELP_MESSAGE: &str = r#"Print the new path of each file renamed on standard output, ended by
+a NUL byte as xargs -0 reads them, and everything else on standard error"#;
+
+pub const OUTPUT_HELP_MESSAGE: &str = r#"The format of the results:
+  table: aligned columns for reading
+  json:  an array of records
+  jsonl: one JSON record per line
+  tsv:   tab-separated records under a header line
+  yaml:  a list of records
+A tool changing files prints what it would change in the formats
+other than table, and changes nothing"#;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Jsonl,
+    Tsv,
+    Yaml,
+}
+
+impl OutputFormat {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "table" | "text" => Self::Table,
+            "json" => Self::Json,
+            "jsonl" => Self::Jsonl,
+            "tsv" => Self::Tsv,
+            "yaml" => Self::Yaml,
+            _ => panic!("Unknown output format: {name}"),
+        }
+    }
+
+    pub fn is_table(&self) -> bool {
+        *self == Self::Table
+    }
+}
+
+/// the results of a tool, as a table or as one flat record per row
+pub trait Report {
+    type Record: Serialize;
+
+    fn records(&self) -> Vec<Self::Record>;
+
+    fn print_table(&self);
+}
+
+/// the `--output` option every tool takes
+pub fn get_output_arg() -> Arg {
+    Arg::new("output")
+        .long("output")
+        .help(OUTPUT_HELP_MESSAGE)
+        .value_parser(
+            OUTPUT_FORMAT_NAMES
+                .map(PossibleValue::new)
+                .into_iter()
+                // the name of the table before every tool had the formats
+                .chain([PossibleValue::new("text").hide(true)])
+                .collect::<Vec<PossibleValue>>(),
+        )
+        .default_value("table")
+}
+
+/// the format named by `--output`
+pub fn get_output_format(matches: &clap::ArgMatches) -> OutputFormat {
+    OutputFormat::from(matches.get_one::<String>("output").unwrap())
+}
+
+/// the `--print0` flag of the renaming tools
+pub fn get_print0_arg() -> Arg {
+    Arg::new("print0")
+        .long("print0")
+        .help(PRINT0_HELP_MESSAGE)
+        .action(ArgAction::SetTrue)
+        .conflicts_with("pipe")
+}
+
+/// keep standard output for the new paths of this run by `--print0`
+pub fn set_print0(matches: &ArgMatches) {
+    let _ = PRINT0.set(matches.get_flag("print0"));
+}
+
+/// whether standard output is kept for the new paths, by `--print0`
+pub fn is_print0() -> bool {
+    PRINT0.get().copied().unwrap_or(false)
+}
+
+/// print `path` ended by a NUL byte for `--print0`, at once for `xargs -0`
+/// to start on it
+pub fn print_path0(path: &Path) {
+    let mut stdout = io::stdout().lock();
+    let _ = stdout
+        .write_all(path.as_os_str().as_encoded_bytes())
+        .and_then(|()| stdout.write_all(b"\0"))
+        .and_then(|()| stdout.flush());
+}
+
+pub fn print_report(report: &impl Report, format: OutputFormat) {
+    match format {
+        OutputFormat::Table => report.print_table(),
+        _ => print_records(&report.records(), format),
+    }
+}
+
+/// print `records` in one of the formats for scripts
+pub fn print_records<T: Serialize>(records: &[T], format: OutputFormat) {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(records).unwrap()),
+        OutputFormat::Jsonl => records
+            .iter()
+            .for_each(|r| println!("{}", serde_json::to_string(r).unwrap())),
+        OutputFormat::Tsv => {
+            let mut writer = csv::WriterBuilder::new()
+                .delimiter(b'\t')
+                .from_writer(io::stdout().lock());
+            records.iter().for_each(|r| writer.serialize(r).unwrap());
+            writer.flush().unwrap();
+        }
+        OutputFormat::Yaml => print!("{}", serde_yaml_ng::to_string(records).unwrap()),
+        OutputFormat::Table => unreachable!("a table is printed by its report"),
+    }
+}
+
+/// records printed a chunk at a time in one of the formats for scripts, for
+/// reports too large to hold at once; the output is the same as printing all
+/// the records together
+pub struct RecordStream {
+    format: OutputFormat,
+    printed: usize,
+}
+
+impl RecordStream {
+    pub fn new(format: OutputFormat) -> Self {
+        Self { format, printed: 0 }
+    }
+
+    /// print the next `records`, after those printed before
+    pub fn print<T: Serialize>(&mut self, records: &[T]) {
+        if records.is_empty() {
+            return;
+        }
+        match self.format {
+            OutputFormat::Json => records.iter().enumerate().for_each(|(i, r)| {
+                let separator: &str = if self.printed + i == 0 { "[" } else { "," };
+                let text: String = serde_json::to_string_pretty(r).unwrap();
+                print!("{separator}\n  {}", text.replace('\n', "\n  "));
+            }),
+            // the header line only above the first records
+            OutputFormat::Tsv => {
+                let mut writer = csv::WriterBuilder::new()
+                    .delimiter(b'\t')
+                    .has_headers(self.printed == 0)
+                    .from_writer(io::stdout().lock());
+                records.iter().for_each(|r| writer.serialize(r).unwrap());
+                writer.flush().unwrap();
+            }
+            // one record per line or list item, so that chunks join up
+            _ => print_records(records, self.format),
+        }
+        self.printed += records.len();
+    }
+
+    /// end the records, as with the closing bracket of a JSON array
+    pub fn finish(self) {
+        match (self.format, self.printed) {
+            (OutputFormat::Json | OutputFormat::Yaml, 0) => print_records::<()>(&[], self.format),
+            (OutputFormat::Json, _) => println!("\n]"),
+            _ => (),
+        }
+    }
+}
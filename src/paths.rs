@@ -0,0 +1,110 @@
+use std::{
+    collections::HashSet,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// `name` relative to `dir`, without a leading `./` for the current directory
+pub fn resolve(dir: &Path, name: impl AsRef<Path>) -> PathBuf {
+    if dir == Path::new(".") {
+        name.as_ref().to_path_buf()
+    } else {
+        dir.join(name)
+    }
+}
+
+/// `path` itself when it is free, else the first free `<stem>_<n>.<ext>` sibling,
+/// where free means neither on disk nor in `taken`; the result is added to `taken`
+pub fn get_free_path(path: &Path, taken: &mut HashSet<PathBuf>) -> PathBuf {
+    find_free_path(path, taken, |p| fs::symlink_metadata(p).is_ok())
+}
+
+/// `path` itself when it is free, else the first free `<stem>_<n>.<ext>` sibling,
+/// where free means neither `exists` nor in `taken`; the result is added to `taken`
+pub fn find_free_path(
+    path: &Path,
+    taken: &mut HashSet<PathBuf>,
+    exists: impl Fn(&Path) -> bool,
+) -> PathBuf {
+    let is_free = |p: &Path, taken: &HashSet<PathBuf>| !taken.contains(p) && !exists(p);
+    if is_free(path, taken) {
+        taken.insert(path.to_path_buf());
+        return path.to_path_buf();
+    }
+    let free: PathBuf = (1..)
+        .map(|n| {
+            let mut name: OsString = path.file_stem().unwrap().to_os_string();
+            name.push(format!("_{n}"));
+            if let Some(ext) = path.extension() {
+                name.push(".");
+                name.push(ext);
+            }
+            path.with_file_name(name)
+        })
+        .find(|p| is_free(p, taken))
+        .unwrap();
+    taken.insert(free.clone());
+    free
+}
+
+/// whether `a` and `b` name the same file, as when they differ only in case or
+/// Unicode normalization on a file system that ignores the difference
+pub fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (fs::symlink_metadata(a), fs::symlink_metadata(b)) {
+        #[cfg(unix)]
+        (Ok(ma), Ok(mb)) => {
+            use std::os::unix::fs::MetadataExt;
+            ma.dev() == mb.dev() && ma.ino() == mb.ino()
+        }
+        #[cfg(not(unix))]
+        (Ok(_), Ok(_)) => match (fs::canonicalize(a), fs::canonicalize(b)) {
+            (Ok(ca), Ok(cb)) => ca == cb,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// the bytes of `path` in hexadecimal when it is not valid UTF-8, so that
+/// records in text formats can name it exactly
+pub fn encode_raw_path(path: &Path) -> Option<String> {
+    if path.to_str().is_some() {
+        return None;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        Some(encode_hex(path.as_os_str().as_bytes()))
+    }
+    #[cfg(not(unix))]
+    None
+}
+
+/// the path encoded by `encode_raw_path`
+pub fn decode_raw_path(text: &str) -> Option<PathBuf> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        decode_hex(text).map(|bytes| PathBuf::from(OsString::from_vec(bytes)))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = text;
+        None
+    }
+}
+
+pub(crate) fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+pub(crate) fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
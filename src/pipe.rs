@@ -0,0 +1,244 @@
+//! The records the tools pass each other with `--pipe`, one JSON object per line
+//! for each file with where it is, its size and modification time, the new name
+//! planned for it and what became of it, so that the tools chain in a shell
+//! pipeline, e.g. `image_dups DIR --pipe | rename_mod_time --pipe --apply`.
+//!
+//! A tool reading records works on the files where they are now, planning its
+//! own new names; the records of files skipped or failed upstream are passed on
+//! as they are.
+
+use crate::i18n::{tr, Message};
+use chrono::{DateTime, Local};
+use clap::{value_parser, Arg, ArgAction, ArgMatches};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, IsTerminal, Write},
+    path::{Path, PathBuf},
+};
+
+const PIPE_HELP_MESSAGE: &str = r#"Print a JSON record per file for the next tool of a pipeline, and with
+no paths given, read the files from the records of the previous one on
+standard input"#;
+
+const FILES_FROM_HELP_MESSAGE: &str = r#"Also take the paths listed in this file, one per line, or those on
+standard input for -; the confirmation is then asked on the terminal"#;
+
+const APPLY_HELP_MESSAGE: &str = r#"With --pipe, rename the files without asking, instead of only planning
+the new names"#;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PipeStatus {
+    /// found by a tool listing files, e.g. a duplicate
+    Listed,
+    /// given a new name, not taken yet
+    Planned,
+    Renamed,
+    /// left as it is, e.g. for a conflict
+    Skipped,
+    Failed,
+    /// moved to the trash instead of renamed, or to be by a plan not applied
+    Trashed,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PipeRecord {
+    /// where the file is now
+    pub path: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// the modification time, in RFC 3339
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<String>,
+    /// the new name planned for the file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proposed: Option<PathBuf>,
+    pub status: PipeStatus,
+    /// why the file was listed, skipped or failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl PipeRecord {
+    /// the record of the file at `path`, with its metadata as it is now
+    pub fn new(path: &Path, status: PipeStatus) -> Self {
+        let metadata: Option<fs::Metadata> = fs::metadata(path).ok();
+        Self {
+            path: path.to_path_buf(),
+            size: metadata.as_ref().map(fs::Metadata::len),
+            modified: metadata
+                .and_then(|m| m.modified().ok())
+                .map(|time| DateTime::<Local>::from(time).to_rfc3339()),
+            proposed: None,
+            status,
+            reason: None,
+        }
+    }
+
+    pub fn with_proposed(self, proposed: &Path) -> Self {
+        Self {
+            proposed: Some(proposed.to_path_buf()),
+            ..self
+        }
+    }
+
+    pub fn with_reason(self, reason: impl Into<String>) -> Self {
+        Self {
+            reason: Some(reason.into()),
+            ..self
+        }
+    }
+
+    /// whether a tool downstream works on the file, unlike one skipped,
+    /// failed or moved to the trash upstream
+    fn is_open(&self) -> bool {
+        !matches!(
+            self.status,
+            PipeStatus::Skipped | PipeStatus::Failed | PipeStatus::Trashed
+        )
+    }
+}
+
+/// the records a tool given `--pipe` reads and writes
+pub struct Pipe {
+    /// the records read of the files to work on
+    incoming: Vec<PipeRecord>,
+    /// the records read of the files skipped or failed upstream, written first
+    passed_on: Vec<PipeRecord>,
+    is_reading: bool,
+    is_applied: bool,
+}
+
+impl Pipe {
+    /// the pipe of a tool given `--pipe`, reading the records on standard input
+    /// when the tool takes input paths and none are given, by arguments or
+    /// by `--files-from`
+    pub fn from(matches: &ArgMatches) -> io::Result<Option<Self>> {
+        if !matches.get_flag("pipe") {
+            return Ok(None);
+        }
+        let is_reading: bool = matches
+            .try_get_many::<PathBuf>("input_paths")
+            .is_ok_and(|paths| paths.is_none())
+            && !matches.contains_id("files_from");
+        if is_reading && io::stdin().is_terminal() {
+            return Err(io::Error::other(tr(Message::NoPathsNorRecords, &[])));
+        }
+        let records: Vec<PipeRecord> = match is_reading {
+            true => read_records(io::stdin().lock())?,
+            false => Vec::new(),
+        };
+        let (incoming, passed_on) = records.into_iter().partition(PipeRecord::is_open);
+        Ok(Some(Self {
+            incoming,
+            passed_on,
+            is_reading,
+            is_applied: matches.try_get_one::<bool>("apply").ok().flatten() == Some(&true),
+        }))
+    }
+
+    /// whether the files to work on come from standard input
+    pub fn is_reading(&self) -> bool {
+        self.is_reading
+    }
+
+    /// whether to change the files, by `--apply`, rather than plan only
+    pub fn is_applied(&self) -> bool {
+        self.is_applied
+    }
+
+    /// the paths of the files to work on read from standard input
+    fn paths(&self) -> Vec<PathBuf> {
+        self.incoming.iter().map(|r| r.path.clone()).collect()
+    }
+
+    /// write `records` to standard output, after the records passed on
+    /// the first time
+    pub fn write(&mut self, records: &[PipeRecord]) -> io::Result<()> {
+        let passed_on: Vec<PipeRecord> = std::mem::take(&mut self.passed_on);
+        let mut stdout = io::stdout().lock();
+        for record in passed_on.iter().chain(records) {
+            serde_json::to_writer(&mut stdout, record)?;
+            writeln!(stdout)?;
+        }
+        stdout.flush()
+    }
+}
+
+/// the records of `reader`, one per line, ignoring blank lines
+fn read_records(reader: impl BufRead) -> io::Result<Vec<PipeRecord>> {
+    let mut records: Vec<PipeRecord> = Vec::new();
+    for line in reader.lines() {
+        let line: String = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        records.push(serde_json::from_str(&line)?);
+    }
+    Ok(records)
+}
+
+/// the paths listed in the file at `path`, one per line, or on standard input
+/// for `-`, ignoring blank lines
+fn read_files_from(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let reader: Box<dyn BufRead> = match path.to_str() {
+        Some("-") => Box::new(io::stdin().lock()),
+        _ => Box::new(BufReader::new(fs::File::open(path)?)),
+    };
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for line in reader.lines() {
+        let line: String = line?;
+        if !line.trim().is_empty() {
+            paths.push(PathBuf::from(line));
+        }
+    }
+    Ok(paths)
+}
+
+/// the input paths of a tool, those of the records read by `pipe` when it
+/// reads them, or those given on the command line and by `--files-from`
+pub fn get_input_paths(matches: &ArgMatches, pipe: Option<&Pipe>) -> io::Result<Vec<PathBuf>> {
+    if let Some(pipe) = pipe.filter(|pipe| pipe.is_reading()) {
+        return Ok(pipe.paths());
+    }
+    let mut paths: Vec<PathBuf> = matches
+        .get_many::<PathBuf>("input_paths")
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+    if let Some(files_from) = matches.get_one::<PathBuf>("files_from") {
+        paths.extend(read_files_from(files_from)?);
+    }
+    Ok(paths)
+}
+
+/// the `--files-from` option of the tools taking input paths
+pub fn get_files_from_arg() -> Arg {
+    Arg::new("files_from")
+        .long("files-from")
+        .help(FILES_FROM_HELP_MESSAGE)
+        .value_name("FILE")
+        .value_parser(value_parser!(PathBuf))
+}
+
+/// the `--pipe` flag of the tools listing files
+pub fn get_pipe_arg() -> Arg {
+    Arg::new("pipe")
+        .long("pipe")
+        .help(PIPE_HELP_MESSAGE)
+        .action(ArgAction::SetTrue)
+}
+
+/// the `--pipe` and `--apply` flags of the tools changing files
+pub fn get_pipe_args() -> [Arg; 2] {
+    [
+        get_pipe_arg(),
+        Arg::new("apply")
+            .long("apply")
+            .help(APPLY_HELP_MESSAGE)
+            .action(ArgAction::SetTrue)
+            .requires("pipe"),
+    ]
+}
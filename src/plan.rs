@@ -0,0 +1,1368 @@
+//! The preview-confirm-apply cycle shared by the renaming tools, with every
+//! applied rename journaled for `--undo` and `restore_names`.
+
+use crate::{
+    backup::is_backed_up,
+    conflict::ConflictStrategy,
+    error::{self, Error},
+    failure::FailurePolicy,
+    filesystem::{DiskFilesystem, Filesystem, ReadOnly},
+    guard::check_batch_size,
+    hooks::Hooks,
+    i18n::{tr, Message},
+    interrupt::{is_interrupted, watch_interrupts},
+    jobs::get_jobs,
+    journal::{
+        append_journal, append_sidecar_journal, find_pending_journal, get_pending_path,
+        is_sidecar_journaled, new_journal_path, read_journal, write_pending, JournalEntry,
+    },
+    notify::notify_outcome,
+    ordering::{group_renames, order_renames},
+    output::{is_print0, print_path0, Report},
+    paths::find_free_path,
+    pipe::{Pipe, PipeRecord, PipeStatus},
+    portable::check_portable,
+    preview::render_renaming_table,
+    prompt::{ask, print_paged, wait_accepting_prompt, Answer},
+    report::{ReportRow, Reports, Status},
+    retry::with_retries,
+    stats::RunStats,
+    table::Table,
+    verify::check_rename_in,
+};
+use clap::{value_parser, Arg, ArgAction, ArgMatches};
+use console::Color;
+use serde::Serialize;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fs, io,
+    path::{self, Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{self, Sender},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+const CONFIRM_EVERY_HELP_MESSAGE: &str = r#"Show and confirm the renames in pages of this many, each renamed or
+skipped on its own; a file keeps its name if its new name stays taken"#;
+
+#[derive(Serialize)]
+pub struct RenameRecord {
+    /// "rename", "overwrite", "trash" or "skip"
+    action: &'static str,
+    source: String,
+    target: Option<String>,
+    /// why a skipped file cannot be renamed
+    reason: Option<String>,
+    /// how the new name was derived, by `--explain`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    explanation: Option<String>,
+}
+
+impl RenameRecord {
+    pub fn rename(source: &Path, target: &Path) -> Self {
+        Self {
+            action: "rename",
+            source: source.display().to_string(),
+            target: Some(target.display().to_string()),
+            reason: None,
+            explanation: None,
+        }
+    }
+
+    /// the record of the rename of `source` to `target`, replacing a file there
+    pub fn overwrite(source: &Path, target: &Path) -> Self {
+        Self {
+            action: "overwrite",
+            ..Self::rename(source, target)
+        }
+    }
+
+    /// the record of the move of `source` to the trash
+    pub fn trash(source: &Path) -> Self {
+        Self {
+            action: "trash",
+            source: source.display().to_string(),
+            target: None,
+            reason: None,
+            explanation: None,
+        }
+    }
+
+    pub fn skip(source: &Path, reason: &str) -> Self {
+        Self {
+            action: "skip",
+            source: source.display().to_string(),
+            target: None,
+            reason: Some(reason.to_string()),
+            explanation: None,
+        }
+    }
+
+    /// the record noting how its new name was derived
+    pub fn explained(self, explanation: String) -> Self {
+        Self {
+            explanation: Some(explanation),
+            ..self
+        }
+    }
+
+    /// "rename", "overwrite", "trash" or "skip"
+    pub fn action(&self) -> &str {
+        self.action
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// the new path, none for a file skipped or moved to the trash
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    /// why a skipped file cannot be renamed
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+}
+
+#[derive(Default)]
+pub struct RenamePlan {
+    renames: Vec<(PathBuf, PathBuf)>,
+    /// files moved to the trash instead of renamed, as those pruned by `--rotate`
+    removals: Vec<PathBuf>,
+    /// files that cannot be renamed, with the reason
+    skipped: Vec<(PathBuf, String)>,
+    targets: HashSet<PathBuf>,
+    /// the planned targets by their lower case paths, which name the same file
+    /// on the file systems ignoring case, as exFAT, NTFS and APFS
+    folded_targets: HashMap<String, PathBuf>,
+    /// existing files replaced by a rename
+    overwrites: BTreeSet<PathBuf>,
+    /// sources of a batch, whose names are free for the other files of the batch
+    vacated: HashSet<PathBuf>,
+    /// the names known to exist, checked instead of the file system when given
+    existing: Option<HashSet<PathBuf>>,
+    /// the file system planned against and renamed on, the disk when none
+    filesystem: Option<Arc<dyn Filesystem>>,
+    /// the new names found taken when planning
+    conflicts: usize,
+    /// how the new name of each source was derived, step by step
+    explanations: HashMap<PathBuf, Vec<String>>,
+    is_explained: bool,
+    /// the renames shown and confirmed at a time, all at once when none
+    confirm_every: Option<usize>,
+    /// the reports of the run to write, by `--report` and `--report-html`
+    reports: Reports,
+    /// whether the renames are checked once done, by `--verify`
+    is_verified: bool,
+    conflict: ConflictStrategy,
+    failure: FailurePolicy,
+    hooks: Hooks,
+    /// whether the plan runs for `--pipe`, without asking and printing records
+    /// rather than messages on standard output
+    is_piped: bool,
+    /// the journal of the run the plan resumes or is a chunk of, continued
+    /// instead of a new one
+    journal: Option<PathBuf>,
+}
+
+impl RenamePlan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// a plan resolving taken targets with `conflict` instead of skipping the files
+    pub fn with_conflict(conflict: ConflictStrategy) -> Self {
+        Self {
+            conflict,
+            ..Self::default()
+        }
+    }
+
+    /// a plan running `hooks` around its renames
+    pub fn with_hooks(mut self, hooks: Hooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// a plan skipping or asking about the files failing to rename by `failure`
+    pub fn with_failure_policy(mut self, failure: FailurePolicy) -> Self {
+        self.failure = failure;
+        self
+    }
+
+    /// a plan showing how each new name was derived in its preview and records
+    pub fn with_explanations(mut self, is_explained: bool) -> Self {
+        self.is_explained = is_explained;
+        self
+    }
+
+    /// a plan shown and confirmed in pages of `page_size` renames, each accepted
+    /// or skipped on its own, instead of all at once
+    pub fn with_confirm_every(mut self, page_size: Option<usize>) -> Self {
+        self.confirm_every = page_size;
+        self
+    }
+
+    /// a plan writing `reports` of its run
+    pub fn with_reports(mut self, reports: Reports) -> Self {
+        self.reports = reports;
+        self
+    }
+
+    /// a plan checking that its renames hold on disk once done, failing if not
+    pub fn with_verification(mut self, is_verified: bool) -> Self {
+        self.is_verified = is_verified;
+        self
+    }
+
+    /// a plan checking new names against `existing`, a snapshot of the names
+    /// taken, instead of the disk, so that planning reads no file
+    pub fn with_existing(mut self, existing: HashSet<PathBuf>) -> Self {
+        self.existing = Some(existing);
+        self
+    }
+
+    /// a plan planned against and renamed on `filesystem` instead of the disk,
+    /// e.g. a tree in memory, journaling nothing to undo
+    pub fn with_filesystem(mut self, filesystem: Arc<dyn Filesystem>) -> Self {
+        self.filesystem = Some(filesystem);
+        self
+    }
+
+    /// the file system of the plan, for applying it alone
+    fn filesystem(&self) -> &dyn Filesystem {
+        self.filesystem.as_deref().unwrap_or(&DiskFilesystem)
+    }
+
+    /// the file system of the plan to read, for planning, previews and checks
+    fn reader(&self) -> ReadOnly<'_> {
+        ReadOnly::new(self.filesystem())
+    }
+
+    /// note a step of deriving the new name of `source`, e.g. where its date came from
+    pub fn explain(&mut self, source: &Path, step: impl Into<String>) {
+        self.explanations
+            .entry(source.to_path_buf())
+            .or_default()
+            .push(step.into());
+    }
+
+    /// the steps noted for `source`, joined
+    fn explanation(&self, source: &Path) -> String {
+        self.explanations
+            .get(source)
+            .map(|steps| steps.join("; "))
+            .unwrap_or_default()
+    }
+
+    /// add the rename of `source` to `target`, or the reason it has no target;
+    /// a target that exists or is already planned is resolved by the conflict strategy
+    pub fn add(&mut self, source: PathBuf, target: Result<PathBuf, String>) {
+        let target: PathBuf = match target {
+            Err(reason) => return self.skipped.push((source, reason)),
+            Ok(target) if target == source => return,
+            Ok(target) => target,
+        };
+        // a target differing from another only in case would replace it where
+        // case is ignored, whatever the file system here
+        let alike: Option<PathBuf> = match self.targets.contains(&target) {
+            true => None,
+            false => self.folded_targets.get(&fold_case(&target)).cloned(),
+        };
+        let is_planned: bool = self.targets.contains(&target) || alike.is_some();
+        let exists: bool = self.exists(&source, &target) && !self.vacated.contains(&target);
+        if !is_planned && !exists {
+            self.plan_target(&target);
+            return self.renames.push((source, target));
+        }
+
+        self.conflicts += 1;
+        match self.conflict {
+            ConflictStrategy::Suffix => {
+                let folded: &HashMap<String, PathBuf> = &self.folded_targets;
+                let is_alike = |p: &Path| folded.contains_key(&fold_case(p));
+                let free: PathBuf = match &self.existing {
+                    Some(existing) => find_free_path(&target, &mut self.targets, |p| {
+                        existing.contains(p) || is_alike(p)
+                    }),
+                    None => {
+                        // the field alone, borrowed along with the targets
+                        let reader: ReadOnly =
+                            ReadOnly::new(self.filesystem.as_deref().unwrap_or(&DiskFilesystem));
+                        find_free_path(&target, &mut self.targets, |p| {
+                            reader.exists(p) || is_alike(p)
+                        })
+                    }
+                };
+                self.plan_target(&free);
+                if let Some(other) = &alike {
+                    let warning: String = tr(
+                        Message::CaseCollision,
+                        &[&target.display(), &other.display()],
+                    );
+                    eprintln!("{}", tr(Message::Warning, &[&warning]));
+                }
+                self.explain(&source, tr(Message::ExplainSuffixed, &[&target.display()]));
+                self.renames.push((source, free))
+            }
+            ConflictStrategy::Overwrite if !is_planned => {
+                self.explain(
+                    &source,
+                    tr(Message::ExplainOverwriting, &[&target.display()]),
+                );
+                self.plan_target(&target);
+                self.overwrites.insert(target.clone());
+                self.renames.push((source, target))
+            }
+            _ if is_planned => {
+                let reason: String = match &alike {
+                    Some(other) => tr(
+                        Message::CaseCollision,
+                        &[&target.display(), &other.display()],
+                    ),
+                    None => tr(Message::NewNameOfAnother, &[&target.display()]),
+                };
+                self.skipped.push((source, reason))
+            }
+            _ => {
+                let reason: String = tr(Message::AlreadyExists, &[&target.display()]);
+                self.skipped.push((source, reason))
+            }
+        }
+    }
+
+    /// add the move of `source` to the trash, taken before the renames
+    pub fn trash(&mut self, source: PathBuf) {
+        self.removals.push(source);
+    }
+
+    /// note `target` as planned, also by its lower case path
+    fn plan_target(&mut self, target: &Path) {
+        self.targets.insert(target.to_path_buf());
+        self.folded_targets
+            .insert(fold_case(target), target.to_path_buf());
+    }
+
+    /// whether `target` is taken by a file other than `source`, in the snapshot
+    /// of the plan if it has one
+    fn exists(&self, source: &Path, target: &Path) -> bool {
+        match &self.existing {
+            Some(existing) => existing.contains(target),
+            // a target naming the source itself differs only in case or normalization
+            None => {
+                let reader: ReadOnly = self.reader();
+                reader.exists(target) && !reader.is_same_file(source, target)
+            }
+        }
+    }
+
+    /// add renames whose targets may be the current names of other files of the
+    /// batch, as when shuffling or renumbering; chains and cycles among them are
+    /// ordered when applying
+    pub fn add_batch(&mut self, renames: Vec<(PathBuf, Result<PathBuf, String>)>) {
+        self.vacated.extend(
+            renames
+                .iter()
+                .filter(|(s, t)| t.as_ref().is_ok_and(|t| t != s))
+                .map(|(s, _)| s.clone()),
+        );
+        for (source, target) in renames {
+            self.add(source, target);
+        }
+
+        // a skipped file keeps its name, so the rename counting on it being free
+        // is resolved again, as a conflict with an existing file
+        loop {
+            let moved: HashSet<&PathBuf> = self.renames.iter().map(|(s, _)| s).collect();
+            let blocked: Option<usize> = self
+                .renames
+                .iter()
+                .position(|(_, t)| self.vacated.contains(t) && !moved.contains(t));
+            let Some(index) = blocked else {
+                break;
+            };
+            let (source, target) = self.renames.remove(index);
+            self.targets.remove(&target);
+            self.folded_targets.remove(&fold_case(&target));
+            self.vacated.remove(&target);
+            self.add(source, Ok(target));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.renames.is_empty() && self.removals.is_empty()
+    }
+
+    /// the planned renames, in order
+    pub fn renames(&self) -> &[(PathBuf, PathBuf)] {
+        &self.renames
+    }
+
+    /// the files to move to the trash, in order
+    pub fn removals(&self) -> &[PathBuf] {
+        &self.removals
+    }
+
+    /// the files that cannot be renamed, with the reason
+    pub fn skipped(&self) -> &[(PathBuf, String)] {
+        &self.skipped
+    }
+
+    /// the number of new names found taken by existing files or other new names
+    pub fn conflicts(&self) -> usize {
+        self.conflicts
+    }
+
+    /// whether the rename to `target` replaces an existing file
+    pub fn is_overwrite(&self, target: &Path) -> bool {
+        self.overwrites.contains(target)
+    }
+
+    /// print the skipped files, the table of the renames and the files to move
+    /// to the trash, and the files the renames replace, all through a pager
+    /// when they do not fit in the terminal; return whether there is anything
+    /// to rename or move
+    pub fn print_preview(&self) -> bool {
+        let mut lines: Vec<String> = self
+            .skipped
+            .iter()
+            .map(|(p, reason)| tr(Message::Skipping, &[&p.display(), reason]))
+            .collect();
+        if self.is_empty() {
+            lines.push(tr(Message::NothingToRename, &[]));
+            print_paged(&lines);
+            return false;
+        }
+
+        lines.extend(self.render_table(&self.renames, &self.removals));
+        lines.extend(
+            self.overwrites
+                .iter()
+                .map(|p| tr(get_overwriting_message(), &[&p.display()])),
+        );
+        print_paged(&lines);
+        true
+    }
+
+    /// the lines of the renaming table of `renames`, followed by the rows of
+    /// the `removals`
+    fn render_table(&self, renames: &[(PathBuf, PathBuf)], removals: &[PathBuf]) -> Vec<String> {
+        if self.is_explained {
+            return self.render_explained_table(renames, removals);
+        }
+        let trash: String = tr(Message::ToTrash, &[]);
+        let operations: Vec<(String, String)> = renames
+            .iter()
+            .map(|(o, n)| (o.display().to_string(), n.display().to_string()))
+            .chain(
+                removals
+                    .iter()
+                    .map(|o| (o.display().to_string(), trash.clone())),
+            )
+            .collect();
+        render_renaming_table(&operations)
+    }
+
+    /// the lines of the renaming table with a column of how each name was derived
+    fn render_explained_table(
+        &self,
+        renames: &[(PathBuf, PathBuf)],
+        removals: &[PathBuf],
+    ) -> Vec<String> {
+        let headers: [String; 3] = [
+            tr(Message::OldHeader, &[]),
+            tr(Message::NewHeader, &[]),
+            tr(Message::HowHeader, &[]),
+        ];
+        let mut table: Table = Table::new(&headers.each_ref().map(String::as_str))
+            .color(1, Color::Green)
+            .diff(1, 0);
+        for (source, target) in renames {
+            table.add_row(vec![
+                source.display().to_string(),
+                target.display().to_string(),
+                self.explanation(source),
+            ]);
+        }
+        for source in removals {
+            table.add_row(vec![
+                source.display().to_string(),
+                tr(Message::ToTrash, &[]),
+                self.explanation(source),
+            ]);
+        }
+        table.render()
+    }
+
+    /// print the skipped files, then show the renames in pages of `page_size`,
+    /// asking whether to do those of each; return the renames accepted, less
+    /// those whose new names stay taken by the files of the pages skipped
+    fn confirm_pages(&self, page_size: usize) -> Vec<(PathBuf, PathBuf)> {
+        let skipped: Vec<String> = self
+            .skipped
+            .iter()
+            .map(|(p, reason)| tr(Message::Skipping, &[&p.display(), reason]))
+            .collect();
+        print_paged(&skipped);
+
+        let pages: usize = self.renames.len().div_ceil(page_size);
+        let mut accepted: Vec<(PathBuf, PathBuf)> = Vec::new();
+        let mut kept: HashSet<PathBuf> = HashSet::new();
+        // the answer for the pages left, once all or quit is answered
+        let mut final_answer: Option<Answer> = None;
+        for (index, page) in self.renames.chunks(page_size).enumerate() {
+            let answer: Answer = final_answer.unwrap_or_else(|| {
+                let mut lines: Vec<String> = self.render_table(page, &[]);
+                lines.extend(
+                    page.iter()
+                        .filter(|(_, t)| self.overwrites.contains(t))
+                        .map(|(_, t)| tr(get_overwriting_message(), &[&t.display()])),
+                );
+                print_paged(&lines);
+                let question: String = tr(Message::AcceptPage, &[&(index + 1), &pages]);
+                let answers: [Answer; 4] = [Answer::Yes, Answer::No, Answer::All, Answer::Quit];
+                ask(&question, &answers, Answer::Yes)
+            });
+            if matches!(answer, Answer::All | Answer::Quit) {
+                final_answer = Some(answer);
+            }
+            match answer.is_accepted() {
+                true => accepted.extend_from_slice(page),
+                false => kept.extend(page.iter().map(|(s, _)| s.clone())),
+            }
+        }
+
+        // a file skipped keeps its name, and so does a file to be renamed to it
+        while let Some(index) = accepted.iter().position(|(_, t)| kept.contains(t)) {
+            let (source, target) = accepted.remove(index);
+            let reason: String = tr(Message::AlreadyExists, &[&target.display()]);
+            self.print_message(&tr(Message::Skipping, &[&source.display(), &reason]));
+            kept.insert(source);
+        }
+        accepted
+    }
+
+    /// show the files to move to the trash and ask whether to move them all;
+    /// return those accepted
+    fn confirm_removals(&self) -> Vec<PathBuf> {
+        if self.removals.is_empty() {
+            return Vec::new();
+        }
+        print_paged(&self.render_table(&[], &self.removals));
+        match wait_accepting_prompt(&tr(Message::AcceptRemoval, &[])) {
+            true => self.removals.clone(),
+            false => Vec::new(),
+        }
+    }
+
+    /// refuse a batch too large, then preview, confirm and rename, recording each rename in a new journal of `program`;
+    /// the moves to the trash are confirmed apart from the renames, and journaled along;
+    /// return whether anything was renamed, or stop at Ctrl-C or at a rename failing
+    /// unless the failure policy skips it, leaving the journal of the renames done
+    /// to undo them
+    /// and writing the report of the run and notifying of its outcome when asked to,
+    /// however it ends; by `--print0`, print the new paths of the files renamed
+    pub fn apply(&self, program: &str, to_print_prompt: bool) -> error::Result<bool> {
+        let mut log: RunLog = RunLog::default();
+        let result: error::Result<bool> = self.run(program, to_print_prompt, &mut log);
+        if is_print0() {
+            self.print_reached(&log);
+        }
+        self.finish(program, &log, &result);
+        result
+    }
+
+    /// plan or, by `--apply`, rename without asking as `apply` does, writing
+    /// a record of each file of the plan to `pipe` instead of the preview
+    pub fn apply_piped(self, program: &str, pipe: &mut Pipe) -> error::Result<bool> {
+        let (records, result) = self.run_piped(program, pipe.is_applied());
+        // standard output, named as on command lines
+        pipe.write(&records)
+            .map_err(|e| Error::from_io(e, Path::new("-")))?;
+        result
+    }
+
+    /// plan or, when `is_applied`, rename without asking as `apply` does,
+    /// printing nothing on standard output; return the records of the files of
+    /// the plan, as planned or as they became
+    pub fn run_piped(
+        mut self,
+        program: &str,
+        is_applied: bool,
+    ) -> (Vec<PipeRecord>, error::Result<bool>) {
+        self.is_piped = true;
+        let mut log: RunLog = RunLog::default();
+        let result: error::Result<bool> = match is_applied {
+            true => self.run(program, false, &mut log),
+            false => Ok(false),
+        };
+        if is_applied {
+            self.finish(program, &log, &result);
+        }
+        (self.pipe_records(&log, is_applied), result)
+    }
+
+    /// print the new paths of the files renamed by the run logged in `log`, by
+    /// `--print0`
+    fn print_reached(&self, log: &RunLog) {
+        self.renames
+            .iter()
+            .filter(|(_, target)| log.reached.contains(target))
+            .for_each(|(_, target)| print_path0(target));
+    }
+
+    /// write the reports of a run and notify of its outcome, when asked to
+    fn finish(&self, program: &str, log: &RunLog, result: &error::Result<bool>) {
+        if !self.reports.is_empty() {
+            self.reports.write(program, &self.report_rows(log));
+        }
+        notify_outcome(program, log.stats.as_ref(), result);
+    }
+
+    /// apply the plan as `apply` does, noting in `log` what became of each rename
+    fn run(&self, program: &str, to_print_prompt: bool, log: &mut RunLog) -> error::Result<bool> {
+        check_batch_size(self.renames.len() + self.removals.len())?;
+        check_portable(&self.renames, self.reader())?;
+        let (renames, removals): (Vec<(PathBuf, PathBuf)>, Vec<PathBuf>) = match self.confirm_every
+        {
+            // `--apply` is the consent of a piped run
+            _ if self.is_piped => (self.renames.clone(), self.removals.clone()),
+            Some(page_size) if to_print_prompt && !self.is_empty() => {
+                (self.confirm_pages(page_size), self.confirm_removals())
+            }
+            _ => {
+                if !self.print_preview() {
+                    return Ok(false);
+                }
+                // the renames and the moves to the trash are accepted apart
+                let is_accepted = |is_planned: bool, question: Message| {
+                    is_planned && (!to_print_prompt || wait_accepting_prompt(&tr(question, &[])))
+                };
+                let renames: Vec<(PathBuf, PathBuf)> =
+                    match is_accepted(!self.renames.is_empty(), Message::AcceptRenaming) {
+                        true => self.renames.clone(),
+                        false => Vec::new(),
+                    };
+                let removals: Vec<PathBuf> =
+                    match is_accepted(!self.removals.is_empty(), Message::AcceptRemoval) {
+                        true => self.removals.clone(),
+                        false => Vec::new(),
+                    };
+                (renames, removals)
+            }
+        };
+        log.accepted = renames
+            .iter()
+            .map(|(s, _)| s.clone())
+            .chain(removals.iter().cloned())
+            .collect();
+        if renames.is_empty() && removals.is_empty() {
+            self.print_message(&tr(Message::NothingDone, &[]));
+            return Ok(false);
+        }
+        // the files of the pages skipped are left as they are
+        let declined: usize =
+            self.renames.len() + self.removals.len() - renames.len() - removals.len();
+
+        let records: Vec<RenameRecord> = self.records_of(&renames, &removals);
+        self.hooks.run_pre_rename(program, &records)?;
+
+        // rename files, journaling absolute paths so that undo works from anywhere;
+        // Ctrl-C stops between renames, so that each one done is journaled
+        watch_interrupts();
+        let journal: PathBuf = match &self.journal {
+            Some(journal) => journal.clone(),
+            None => new_journal_path(program),
+        };
+        // the moves to the trash go first, each a step to an empty path
+        let ordered: Vec<(PathBuf, PathBuf)> = removals
+            .iter()
+            .map(|source| (source.clone(), PathBuf::new()))
+            .chain(order_renames(&renames, self.reader()))
+            .collect();
+        // the steps to take stay recorded until taken, for `--resume`
+        if self.filesystem.is_none() {
+            let steps: Vec<JournalEntry> = ordered
+                .iter()
+                .map(|(s, t)| get_journal_entry(s, t))
+                .collect::<error::Result<_>>()?;
+            write_pending(&journal, &steps).map_err(|e| Error::from_io(e, &journal))?;
+        }
+        let mut done: Vec<&(PathBuf, PathBuf)> = Vec::new();
+        let mut failed: Vec<(PathBuf, String)> = Vec::new();
+        let mut failure: FailurePolicy = self.failure;
+        let start: Instant = Instant::now();
+        // journal each step done and note each failing, on this thread
+        let mut handle = |i: usize, result: error::Result<Vec<JournalEntry>>| {
+            let step @ (source, target) = &ordered[i];
+            match result {
+                Ok(entries) => {
+                    // renames off the disk are not for undoing
+                    if self.filesystem.is_none() {
+                        let journaled: io::Result<()> = match is_sidecar_journaled() {
+                            true => entries.iter().try_for_each(append_sidecar_journal),
+                            false => Ok(()),
+                        }
+                        .and_then(|()| append_journal(&journal, &entries));
+                        journaled.map_err(|e| Error::from_io(e, &journal))?;
+                    }
+                    match target.as_os_str().is_empty() {
+                        true => log.trashed.insert(source.clone()),
+                        false => log.reached.insert(target.clone()),
+                    };
+                    done.push(step);
+                    Ok(Outcome::Done)
+                }
+                Err(err) if is_skipped(&mut failure, &err) => {
+                    log.failed.insert(source.clone(), err.to_string());
+                    failed.push((source.clone(), err.to_string()));
+                    Ok(Outcome::Skipped)
+                }
+                Err(err) => {
+                    log.failed.insert(source.clone(), err.to_string());
+                    Ok(Outcome::Stopped(err))
+                }
+            }
+        };
+        let applied: error::Result<(bool, Option<Error>)> = match get_jobs() {
+            Some(jobs) if jobs > 1 => {
+                self.rename_groups(&ordered, &group_renames(&ordered), jobs, &mut handle)
+            }
+            _ => self.rename_steps(&ordered, &mut handle),
+        };
+        // an unfinished run reports the steps done, which may include parked names;
+        // the hook hears of them however the run ends
+        let renamed: Vec<RenameRecord> = match done.len() == ordered.len() {
+            true => records.into_iter().filter(|r| r.action != "skip").collect(),
+            false => done.iter().map(|(s, t)| self.record(s, t)).collect(),
+        };
+        self.hooks.run_post_rename(program, &renamed);
+        let (is_stopped, fatal): (bool, Option<Error>) = applied?;
+        if let Some(err) = fatal {
+            print_failed(&failed);
+            log.stats = Some(self.print_stats(&done, declined, failed.len() + 1, start.elapsed()));
+            return Err(err);
+        }
+        if self.filesystem.is_none() && !is_stopped {
+            let _ = fs::remove_file(get_pending_path(&journal));
+        }
+        print_failed(&failed);
+        log.stats = Some(self.print_stats(&done, declined, failed.len(), start.elapsed()));
+        if self.filesystem.is_none() {
+            self.print_message(&tr(Message::UndoWith, &[&program]));
+            if is_stopped {
+                self.print_message(&tr(Message::ResumeWith, &[&program]));
+            }
+        }
+        // renames lost by the file system fail the run, whatever else happened
+        if self.is_verified {
+            let (problems, checked) = self.verify(&done, &log.reached);
+            problems.iter().for_each(|p| eprintln!("{p}"));
+            if !problems.is_empty() {
+                return Err(Error::VerifyFailed {
+                    failed: problems.len(),
+                    total: checked,
+                });
+            }
+        }
+        if is_stopped {
+            return Err(Error::Interrupted {
+                done: done.len(),
+                total: ordered.len(),
+            });
+        }
+        if !failed.is_empty() {
+            return Err(Error::RenamesFailed {
+                failed: failed.len(),
+                total: ordered.len(),
+            });
+        }
+        Ok(true)
+    }
+
+    /// take `steps` in order, handing what became of each to `handle`, which
+    /// fails as journaling does; return whether stopped by Ctrl-C and the
+    /// error of the step stopping the run, if any
+    fn rename_steps(
+        &self,
+        steps: &[(PathBuf, PathBuf)],
+        handle: &mut impl FnMut(usize, error::Result<Vec<JournalEntry>>) -> error::Result<Outcome>,
+    ) -> error::Result<(bool, Option<Error>)> {
+        // names still taken by files failing to move, which no later rename may replace
+        let mut held: HashSet<&PathBuf> = HashSet::new();
+        for (i, (source, target)) in steps.iter().enumerate() {
+            if is_interrupted() {
+                return Ok((true, None));
+            }
+            let result: error::Result<Vec<JournalEntry>> = match held.contains(target) {
+                true => Err(Error::TargetExists(target.clone())),
+                false => self.rename(source, target),
+            };
+            match handle(i, result)? {
+                Outcome::Done => (),
+                Outcome::Skipped => {
+                    held.insert(source);
+                }
+                Outcome::Stopped(err) => return Ok((false, Some(err))),
+            }
+        }
+        Ok((false, None))
+    }
+
+    /// take the `groups` of `steps` on `jobs` threads at once, by `--jobs`,
+    /// each group in order on one thread, as for a share with a long round
+    /// trip; what became of each step is handed to `handle` on this thread,
+    /// which a thread whose step failed waits on to go on or stop; return as
+    /// for [`RenamePlan::rename_steps`]
+    fn rename_groups(
+        &self,
+        steps: &[(PathBuf, PathBuf)],
+        groups: &[Vec<usize>],
+        jobs: usize,
+        handle: &mut impl FnMut(usize, error::Result<Vec<JournalEntry>>) -> error::Result<Outcome>,
+    ) -> error::Result<(bool, Option<Error>)> {
+        let next: AtomicUsize = AtomicUsize::new(0);
+        let is_halted: AtomicBool = AtomicBool::new(false);
+        let (sender, receiver) =
+            mpsc::channel::<(usize, error::Result<Vec<JournalEntry>>, Sender<bool>)>();
+        thread::scope(|scope| {
+            for _ in 0..jobs.min(groups.len()) {
+                let sender = sender.clone();
+                let (next, is_halted) = (&next, &is_halted);
+                scope.spawn(move || {
+                    let (reply, replies) = mpsc::channel::<bool>();
+                    while let Some(group) = groups.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        // as for `rename_steps`, within the group
+                        let mut held: HashSet<&PathBuf> = HashSet::new();
+                        for &i in group {
+                            if is_halted.load(Ordering::Relaxed) || is_interrupted() {
+                                return;
+                            }
+                            let (source, target) = &steps[i];
+                            let result: error::Result<Vec<JournalEntry>> =
+                                match held.contains(target) {
+                                    true => Err(Error::TargetExists(target.clone())),
+                                    false => self.rename(source, target),
+                                };
+                            let is_failed: bool = result.is_err();
+                            if sender.send((i, result, reply.clone())).is_err() {
+                                return;
+                            }
+                            // a failed step is either skipped or stops the run
+                            if is_failed {
+                                match replies.recv() {
+                                    Ok(true) => {
+                                        held.insert(source);
+                                    }
+                                    _ => return,
+                                }
+                            }
+                        }
+                    }
+                });
+            }
+            drop(sender);
+            let mut fatal: Option<Error> = None;
+            let mut broken: Option<Error> = None;
+            let mut taken: usize = 0;
+            for (i, result, reply) in receiver {
+                taken += 1;
+                let is_failed: bool = result.is_err();
+                // the steps finishing after the run stopped are still noted
+                let is_going_on: bool = match handle(i, result) {
+                    Ok(Outcome::Done | Outcome::Skipped) => true,
+                    Ok(Outcome::Stopped(err)) => {
+                        fatal.get_or_insert(err);
+                        false
+                    }
+                    Err(err) => {
+                        broken.get_or_insert(err);
+                        false
+                    }
+                };
+                if !is_going_on {
+                    is_halted.store(true, Ordering::Relaxed);
+                }
+                if is_failed {
+                    let _ = reply.send(is_going_on);
+                }
+            }
+            if let Some(err) = broken {
+                return Err(err);
+            }
+            let is_stopped: bool = fatal.is_none() && taken < steps.len() && is_interrupted();
+            Ok((is_stopped, fatal))
+        })
+    }
+
+    /// the problems found with the steps `done` to the planned targets, whose
+    /// sources may stay only as new names `reached` by other files, and the
+    /// number of steps checked
+    fn verify(
+        &self,
+        done: &[&(PathBuf, PathBuf)],
+        reached: &HashSet<PathBuf>,
+    ) -> (Vec<String>, usize) {
+        let checked: Vec<&&(PathBuf, PathBuf)> = done
+            .iter()
+            .filter(|(_, t)| self.targets.contains(t))
+            .collect();
+        let problems: Vec<String> = checked
+            .iter()
+            .filter_map(|(s, t)| check_rename_in(self.reader(), s, t, reached.contains(s)))
+            .collect();
+        (problems, checked.len())
+    }
+
+    /// the rows of the report of a run, one for each file of the plan
+    fn report_rows(&self, log: &RunLog) -> Vec<ReportRow> {
+        let skipped = self.skipped.iter().map(|(source, reason)| {
+            ReportRow::new(source, None, self.explanation(source), Status::Skipped)
+                .with_error(reason.clone())
+        });
+        let renames = self.renames.iter().map(|(source, target)| {
+            let status: Status = if log.failed.contains_key(source) {
+                Status::Failed
+            } else if log.reached.contains(target) {
+                Status::Renamed
+            } else if !log.accepted.contains(source) {
+                Status::Declined
+            } else {
+                Status::NotDone
+            };
+            ReportRow::new(source, Some(target), self.explanation(source), status)
+                .with_error(log.failed.get(source).cloned().unwrap_or_default())
+        });
+        let removals = self.removals.iter().map(|source| {
+            let status: Status = if log.failed.contains_key(source) {
+                Status::Failed
+            } else if log.trashed.contains(source) {
+                Status::Trashed
+            } else if !log.accepted.contains(source) {
+                Status::Declined
+            } else {
+                Status::NotDone
+            };
+            ReportRow::new(source, None, self.explanation(source), status)
+                .with_error(log.failed.get(source).cloned().unwrap_or_default())
+        });
+        skipped.chain(removals).chain(renames).collect()
+    }
+
+    /// the records of the files of the plan for `--pipe`, as planned, or after
+    /// a run applying it as logged in `log`
+    fn pipe_records(&self, log: &RunLog, is_applied: bool) -> Vec<PipeRecord> {
+        let skipped = self.skipped.iter().map(|(source, reason)| {
+            PipeRecord::new(source, PipeStatus::Skipped).with_reason(reason.clone())
+        });
+        let renames = self.renames.iter().map(|(source, target)| {
+            if let Some(err) = log.failed.get(source) {
+                PipeRecord::new(source, PipeStatus::Failed)
+                    .with_proposed(target)
+                    .with_reason(err.clone())
+            } else if is_applied && log.reached.contains(target) {
+                PipeRecord::new(target, PipeStatus::Renamed)
+            } else {
+                PipeRecord::new(source, PipeStatus::Planned).with_proposed(target)
+            }
+        });
+        let removals = self
+            .removals
+            .iter()
+            .map(|source| match log.failed.get(source) {
+                Some(err) => PipeRecord::new(source, PipeStatus::Failed).with_reason(err.clone()),
+                None => PipeRecord::new(source, PipeStatus::Trashed),
+            });
+        skipped.chain(removals).chain(renames).collect()
+    }
+
+    /// print the counts of a run having done the steps `done`, of which only
+    /// those to the planned targets count, not those parking files of a cycle,
+    /// and the moves to the trash apart; the files of the pages declined count
+    /// as skipped; return the counts
+    fn print_stats(
+        &self,
+        done: &[&(PathBuf, PathBuf)],
+        declined: usize,
+        failed: usize,
+        elapsed: Duration,
+    ) -> RunStats {
+        let stats: RunStats = RunStats {
+            renamed: done
+                .iter()
+                .filter(|(_, t)| self.targets.contains(t))
+                .count(),
+            skipped: self.skipped.len() + declined,
+            conflicts: self.conflicts,
+            failed,
+            trashed: (!self.removals.is_empty()).then(|| {
+                done.iter()
+                    .filter(|(_, t)| t.as_os_str().is_empty())
+                    .count()
+            }),
+            ..RunStats::default()
+        }
+        .with_elapsed(elapsed);
+        match self.is_piped || is_print0() {
+            true => stats.eprint(),
+            false => stats.print(),
+        }
+        stats
+    }
+
+    /// print `message` for reading, to standard error when the plan runs for
+    /// `--pipe` or standard output is kept for `--print0`
+    fn print_message(&self, message: &str) {
+        match self.is_piped || is_print0() {
+            true => eprintln!("{message}"),
+            false => println!("{message}"),
+        }
+    }
+
+    /// take the step of `source` to `target`: move it to the trash for an empty
+    /// target, or else rename it, creating its directory and moving a file it
+    /// replaces to its backup or the trash; return the journal entries of the
+    /// step, that of the file set aside first
+    fn rename(&self, source: &Path, target: &Path) -> error::Result<Vec<JournalEntry>> {
+        let filesystem: &dyn Filesystem = self.filesystem();
+        if target.as_os_str().is_empty() {
+            let entry: JournalEntry = get_journal_entry(source, target)?;
+            with_retries(|| filesystem.trash(source)).map_err(|e| Error::from_io(e, source))?;
+            return Ok(vec![entry]);
+        }
+        if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+            filesystem
+                .create_dir_all(parent)
+                .map_err(|e| Error::from_io(e, parent))?;
+        }
+        let entry: JournalEntry = JournalEntry {
+            size: with_retries(|| filesystem.stat(source)).ok().map(|s| s.len),
+            ..get_journal_entry(source, target)?
+        };
+        // the replaced file stays recoverable, from its backup or the trash,
+        // and goes back there on undo
+        let mut entries: Vec<JournalEntry> = Vec::new();
+        if self.overwrites.contains(target) {
+            let backup: Option<PathBuf> = filesystem
+                .set_aside(target)
+                .map_err(|e| Error::from_io(e, target))?;
+            let aside: &Path = backup.as_deref().unwrap_or(Path::new(""));
+            entries.push(get_journal_entry(target, aside)?);
+        }
+        with_retries(|| filesystem.rename(source, target))
+            .map_err(|e| Error::from_rename(e, source, target))?;
+        entries.push(entry);
+        Ok(entries)
+    }
+
+    /// the records of the files skipped, of `removals` and of `renames`
+    fn records_of(
+        &self,
+        renames: &[(PathBuf, PathBuf)],
+        removals: &[PathBuf],
+    ) -> Vec<RenameRecord> {
+        let skipped = self
+            .skipped
+            .iter()
+            .map(|(s, reason)| self.explain_record(RenameRecord::skip(s, reason), s));
+        let removals = removals.iter().map(|s| self.record(s, Path::new("")));
+        let renames = renames.iter().map(|(s, t)| self.record(s, t));
+        skipped.chain(removals).chain(renames).collect()
+    }
+
+    /// the record of the planned step of `source` to `target`, the trash for an
+    /// empty target
+    fn record(&self, source: &Path, target: &Path) -> RenameRecord {
+        let record: RenameRecord = if target.as_os_str().is_empty() {
+            RenameRecord::trash(source)
+        } else if self.overwrites.contains(target) {
+            RenameRecord::overwrite(source, target)
+        } else {
+            RenameRecord::rename(source, target)
+        };
+        self.explain_record(record, source)
+    }
+
+    /// `record` with how the name of `source` was derived, when the plan explains
+    fn explain_record(&self, record: RenameRecord, source: &Path) -> RenameRecord {
+        match self.is_explained {
+            true => record.explained(self.explanation(source)),
+            false => record,
+        }
+    }
+}
+
+/// what became of the renames of a run, for its report
+#[derive(Default)]
+struct RunLog {
+    /// the sources of the renames accepted when confirming
+    accepted: HashSet<PathBuf>,
+    /// the new names the files reached
+    reached: HashSet<PathBuf>,
+    /// the files moved to the trash
+    trashed: HashSet<PathBuf>,
+    /// the sources failing to rename, with the error
+    failed: HashMap<PathBuf, String>,
+    /// the counts of the run, once it got to renaming
+    stats: Option<RunStats>,
+}
+
+/// the journal entry of the rename of `source` to `target`, or of its move to
+/// the trash for an empty target, by absolute paths so that undo works from
+/// anywhere
+fn get_journal_entry(source: &Path, target: &Path) -> error::Result<JournalEntry> {
+    let source: PathBuf = path::absolute(source).map_err(|e| Error::from_io(e, source))?;
+    if target.as_os_str().is_empty() {
+        return Ok(JournalEntry::trashed(&source));
+    }
+    Ok(JournalEntry::new(
+        &source,
+        &path::absolute(target).map_err(|e| Error::from_io(e, target))?,
+    ))
+}
+
+/// the note of a file replaced, which goes to its backup by `--backup` or
+/// else to the trash
+fn get_overwriting_message() -> Message {
+    match is_backed_up() {
+        true => Message::OverwritingBackedUp,
+        false => Message::Overwriting,
+    }
+}
+
+/// whether to go on past `err` by `policy`, asking when it says so;
+/// answering all skips the later failures without asking
+fn is_skipped(policy: &mut FailurePolicy, err: &Error) -> bool {
+    match policy {
+        FailurePolicy::Abort => false,
+        FailurePolicy::Skip => true,
+        FailurePolicy::Ask => {
+            let question: String = tr(Message::SkipFailed, &[err]);
+            match ask(
+                &question,
+                &[Answer::Yes, Answer::No, Answer::All],
+                Answer::No,
+            ) {
+                Answer::All => {
+                    *policy = FailurePolicy::Skip;
+                    true
+                }
+                answer => answer.is_accepted(),
+            }
+        }
+    }
+}
+
+/// `path` in lower case, as the file systems ignoring case compare names
+fn fold_case(path: &Path) -> String {
+    path.to_string_lossy().to_lowercase()
+}
+
+/// what became of a step of a run, for the run to go on or not
+enum Outcome {
+    Done,
+    /// failed, and left as it is by `--on-error`
+    Skipped,
+    Stopped(Error),
+}
+
+/// list the files left as they are after failing to rename
+fn print_failed(failed: &[(PathBuf, String)]) {
+    failed
+        .iter()
+        .for_each(|(p, reason)| eprintln!("{}", tr(Message::Skipping, &[&p.display(), reason])));
+}
+
+impl Report for RenamePlan {
+    type Record = RenameRecord;
+
+    fn records(&self) -> Vec<RenameRecord> {
+        self.records_of(&self.renames, &self.removals)
+    }
+
+    fn print_table(&self) {
+        self.print_preview();
+    }
+}
+
+/// the `--confirm-every` option of the renaming tools
+pub fn get_confirm_every_arg() -> Arg {
+    Arg::new("confirm_every")
+        .long("confirm-every")
+        .help(CONFIRM_EVERY_HELP_MESSAGE)
+        .value_parser(value_parser!(u64).range(1..))
+}
+
+/// the page size given by `--confirm-every`
+pub fn get_confirm_every(matches: &ArgMatches) -> Option<usize> {
+    matches
+        .get_one::<u64>("confirm_every")
+        .map(|n| usize::try_from(*n).unwrap_or(usize::MAX))
+}
+
+/// the `--resume` flag of the renaming tools
+pub fn get_resume_arg() -> Arg {
+    Arg::new("resume")
+        .long("resume")
+        .help("Go on with the latest renaming by this command stopped before the end,\nby a crash, Ctrl-C or a failure, from the first rename not done")
+        .action(ArgAction::SetTrue)
+        .conflicts_with("undo")
+}
+
+/// resume the latest run of `program` stopped before getting through its
+/// renames: those journaled are done, and so is one whose source is gone to
+/// its new name, as when stopped before journaling it; the others are checked
+/// again against the disk, previewed, confirmed and journaled with the rest
+pub fn resume_latest(program: &str) -> error::Result<()> {
+    let Some(journal) = find_pending_journal(program) else {
+        println!("{}", tr(Message::NothingToResume, &[]));
+        return Ok(());
+    };
+    let pending: PathBuf = get_pending_path(&journal);
+    let steps: Vec<JournalEntry> =
+        read_journal(&pending).map_err(|e| Error::from_io(e, &pending))?;
+    let done: HashSet<(PathBuf, PathBuf)> = match read_journal(&journal) {
+        Ok(entries) => entries
+            .iter()
+            .map(|e| (e.from_path(), e.to_path()))
+            .collect(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => HashSet::new(),
+        Err(err) => return Err(Error::from_io(err, &journal)),
+    };
+    let reader: ReadOnly = ReadOnly::new(&DiskFilesystem);
+    let mut remaining: Vec<(PathBuf, Result<PathBuf, String>)> = Vec::new();
+    let mut unjournaled: Vec<JournalEntry> = Vec::new();
+    // the names the remaining steps bring files to, as parked files of a cycle
+    let mut produced: HashSet<PathBuf> = HashSet::new();
+    let mut removals: Vec<PathBuf> = Vec::new();
+    for step in steps {
+        let (source, target) = (step.from_path(), step.to_path());
+        if done.contains(&(source.clone(), target.clone())) {
+            continue;
+        }
+        if step.is_trashed {
+            match reader.exists(&source) {
+                true => removals.push(source),
+                false => unjournaled.push(step),
+            }
+        } else if reader.exists(&source) || produced.contains(&source) {
+            produced.insert(target.clone());
+            remaining.push((source, Ok(target)));
+        } else if reader.exists(&target) {
+            unjournaled.push(step);
+        } else {
+            remaining.push((source, Err(tr(Message::FileNotFound, &[]))));
+        }
+    }
+    append_journal(&journal, &unjournaled).map_err(|e| Error::from_io(e, &journal))?;
+    let mut plan: RenamePlan = RenamePlan {
+        journal: Some(journal.clone()),
+        ..RenamePlan::new()
+    };
+    plan.add_batch(remaining);
+    removals.into_iter().for_each(|source| plan.trash(source));
+    if plan.is_empty() {
+        plan.skipped
+            .iter()
+            .for_each(|(p, reason)| println!("{}", tr(Message::Skipping, &[&p.display(), reason])));
+        println!("{}", tr(Message::NothingToResume, &[]));
+        let _ = fs::remove_file(&pending);
+        return Ok(());
+    }
+    plan.apply(program, true).map(|_| ())
+}
+
+/// apply the `plans` of the chunks of a run in turn as [`RenamePlan::apply`]
+/// does, each made only when reached so that one chunk is held at once, all
+/// recorded in one journal and one report so that the run is undone and
+/// reported as a whole; stop at a chunk not accepted, at Ctrl-C or at a rename
+/// failing unless the failure policy skips it. With `pipe`, plan or, by
+/// `--apply`, rename without asking as [`RenamePlan::apply_piped`] does,
+/// writing the records chunk by chunk
+pub fn apply_chunks(
+    program: &str,
+    plans: impl IntoIterator<Item = RenamePlan>,
+    to_print_prompt: bool,
+    mut pipe: Option<&mut Pipe>,
+) -> error::Result<bool> {
+    let journal: PathBuf = new_journal_path(program);
+    let is_applied: bool = pipe.as_ref().is_none_or(|p| p.is_applied());
+    let mut reports: Reports = Reports::default();
+    let mut rows: Vec<ReportRow> = Vec::new();
+    let mut stats: Option<RunStats> = None;
+    let mut result: error::Result<bool> = Ok(false);
+    // the renames failing and skipped by the failure policy, and those planned
+    let (mut failed, mut total): (usize, usize) = (0, 0);
+    for plan in plans {
+        let plan: RenamePlan = RenamePlan {
+            journal: Some(journal.clone()),
+            is_piped: pipe.is_some(),
+            ..plan
+        };
+        let mut log: RunLog = RunLog::default();
+        let chunk_result: error::Result<bool> = match is_applied {
+            true => plan.run(program, to_print_prompt, &mut log),
+            false => Ok(false),
+        };
+        match &mut pipe {
+            // standard output, named as on command lines
+            Some(pipe) => pipe
+                .write(&plan.pipe_records(&log, is_applied))
+                .map_err(|e| Error::from_io(e, Path::new("-")))?,
+            None if is_print0() => plan.print_reached(&log),
+            None => (),
+        }
+        if !plan.reports.is_empty() {
+            reports = plan.reports.clone();
+            rows.extend(plan.report_rows(&log));
+        }
+        if let Some(chunk_stats) = log.stats {
+            stats = Some(match stats {
+                Some(stats) => stats + chunk_stats,
+                None => chunk_stats,
+            });
+        }
+        total += plan.renames.len();
+        match chunk_result {
+            // a chunk with renames and none done was not accepted
+            Ok(false) if is_applied && !plan.is_empty() => break,
+            Ok(is_renamed) => result = result.map(|r| r || is_renamed),
+            // the files left as they are do not keep the later chunks from going on
+            Err(Error::RenamesFailed {
+                failed: chunk_failed,
+                ..
+            }) => {
+                failed += chunk_failed;
+                result = Ok(true);
+            }
+            Err(err) => {
+                result = Err(err);
+                break;
+            }
+        }
+    }
+    if failed > 0 && result.is_ok() {
+        result = Err(Error::RenamesFailed { failed, total });
+    }
+    if is_applied {
+        if !reports.is_empty() {
+            reports.write(program, &rows);
+        }
+        notify_outcome(program, stats.as_ref(), &result);
+    }
+    result
+}
+
+/// the `--explain` flag of the renaming tools
+pub fn get_explain_arg() -> Arg {
+    Arg::new("explain")
+        .long("explain")
+        .help("Show how each new name was derived, e.g. where its date came from")
+        .action(ArgAction::SetTrue)
+}
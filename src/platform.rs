@@ -0,0 +1,245 @@
+//! What the platforms tell of files beyond what `std::fs::Metadata` portably
+//! exposes: whether a file is hidden, by its leading dot on Unix and also by
+//! its hidden and system attributes on Windows, its creation time where the
+//! file system keeps one, its Finder tags on macOS, and its alternate data
+//! streams on Windows, such as the `Zone.Identifier` of downloads.
+
+use crate::i18n::{tr, Message};
+use std::{fs::Metadata, io, path::Path, time::SystemTime};
+
+/// the extended attribute holding the Finder tags of a file on macOS, a binary
+/// property list of their names
+pub const FINDER_TAGS_XATTR: &str = "com.apple.metadata:_kMDItemUserTags";
+
+/// the attributes hiding a file from Explorer and `dir`
+#[cfg(windows)]
+const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+#[cfg(windows)]
+const FILE_ATTRIBUTE_SYSTEM: u32 = 0x4;
+
+/// when the file of `metadata` was created: its creation time on Windows, and
+/// its birth time on macOS and on Linux file systems keeping one
+pub fn get_created_time(metadata: &Metadata) -> io::Result<SystemTime> {
+    metadata.created().map_err(|err| match err.kind() {
+        io::ErrorKind::Unsupported => io::Error::new(err.kind(), tr(Message::NoCreationTime, &[])),
+        _ => err,
+    })
+}
+
+/// the device and inode of the file of `metadata` when it has several hard
+/// links, the same for each of them; none elsewhere than on Unix
+#[cfg(unix)]
+pub fn get_hard_link_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    (metadata.is_file() && metadata.nlink() > 1).then(|| (metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+pub fn get_hard_link_id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// whether the file at `path` with `metadata` is hidden: named with a leading
+/// dot, or on Windows, also marked hidden or system
+pub fn is_hidden(path: &Path, metadata: &Metadata) -> bool {
+    let has_dot: bool = path
+        .file_name()
+        .is_some_and(|n| n.as_encoded_bytes().starts_with(b"."));
+    has_dot || has_hidden_attribute(metadata)
+}
+
+#[cfg(windows)]
+fn has_hidden_attribute(metadata: &Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    metadata.file_attributes() & (FILE_ATTRIBUTE_HIDDEN | FILE_ATTRIBUTE_SYSTEM) != 0
+}
+
+#[cfg(not(windows))]
+fn has_hidden_attribute(_metadata: &Metadata) -> bool {
+    false
+}
+
+/// the Finder tags of the file at `path`, none where the platform keeps none
+#[cfg(target_os = "macos")]
+pub fn get_finder_tags(path: &Path) -> Vec<String> {
+    match xattr::get(path, FINDER_TAGS_XATTR) {
+        Ok(Some(plist)) => parse_finder_tags(&plist).unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn get_finder_tags(_path: &Path) -> Vec<String> {
+    Vec::new()
+}
+
+/// give the file at `target` the Finder tags of the one at `source`, where the
+/// platform keeps them
+#[cfg(target_os = "macos")]
+pub fn copy_finder_tags(source: &Path, target: &Path) -> io::Result<()> {
+    match xattr::get(source, FINDER_TAGS_XATTR)? {
+        Some(plist) => xattr::set(target, FINDER_TAGS_XATTR, &plist),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn copy_finder_tags(_source: &Path, _target: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// the tag names of the binary property list `plist`, an array of strings
+/// each naming a tag, followed by a newline and its color number if any
+pub fn parse_finder_tags(plist: &[u8]) -> Option<Vec<String>> {
+    if !plist.starts_with(b"bplist00") || plist.len() < 40 {
+        return None;
+    }
+    // the trailer: the sizes of offsets and references, the number of the top
+    // object and where the table of the offsets of the objects starts
+    let trailer: &[u8] = &plist[plist.len() - 32..];
+    let (offset_size, reference_size) = (trailer[6] as usize, trailer[7] as usize);
+    let top: usize = read_number(&trailer[16..24])? as usize;
+    let table: usize = read_number(&trailer[24..32])? as usize;
+    let offset_of = |object: usize| -> Option<usize> {
+        let start: usize = table + object * offset_size;
+        Some(read_number(plist.get(start..start + offset_size)?)? as usize)
+    };
+
+    let (kind, count, start) = read_object(plist, offset_of(top)?)?;
+    if kind != 0xA {
+        return None;
+    }
+    (0..count)
+        .map(|i| {
+            let at: usize = start + i * reference_size;
+            let object: usize = read_number(plist.get(at..at + reference_size)?)? as usize;
+            let (kind, length, start) = read_object(plist, offset_of(object)?)?;
+            let name: String = match kind {
+                // ASCII
+                0x5 => String::from_utf8_lossy(plist.get(start..start + length)?).into_owned(),
+                // UTF-16 big-endian
+                0x6 => {
+                    let units: Vec<u16> = plist
+                        .get(start..start + 2 * length)?
+                        .chunks_exact(2)
+                        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                        .collect();
+                    String::from_utf16_lossy(&units)
+                }
+                _ => return None,
+            };
+            Some(match name.split_once('\n') {
+                Some((tag, _color)) => tag.to_string(),
+                None => name,
+            })
+        })
+        .collect()
+}
+
+/// the type, the length and where the content starts of the object of a binary
+/// property list at `offset`
+fn read_object(plist: &[u8], offset: usize) -> Option<(u8, usize, usize)> {
+    let marker: u8 = *plist.get(offset)?;
+    let (kind, length) = (marker >> 4, (marker & 0xF) as usize);
+    if length != 0xF {
+        return Some((kind, length, offset + 1));
+    }
+    // a longer length follows as an integer object
+    let int_marker: u8 = *plist.get(offset + 1)?;
+    if int_marker >> 4 != 0x1 {
+        return None;
+    }
+    let size: usize = 1 << (int_marker & 0xF);
+    let length: u64 = read_number(plist.get(offset + 2..offset + 2 + size)?)?;
+    Some((kind, length as usize, offset + 2 + size))
+}
+
+/// the big-endian number of at most 8 `bytes`
+fn read_number(bytes: &[u8]) -> Option<u64> {
+    (bytes.len() <= 8).then(|| bytes.iter().fold(0, |n, b| n << 8 | u64::from(*b)))
+}
+
+/// the names of the alternate data streams of the file at `path`, e.g.
+/// `Zone.Identifier`, none where the platform keeps none
+#[cfg(windows)]
+pub fn list_data_streams(path: &Path) -> io::Result<Vec<String>> {
+    use std::{mem, os::windows::ffi::OsStrExt};
+    use windows_sys::Win32::{
+        Foundation::{ERROR_HANDLE_EOF, INVALID_HANDLE_VALUE},
+        Storage::FileSystem::{
+            FindClose, FindFirstStreamW, FindNextStreamW, FindStreamInfoStandard,
+            WIN32_FIND_STREAM_DATA,
+        },
+    };
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain([0]).collect();
+    // SAFETY: the data is plain integers, and the path is NUL-terminated
+    let mut data: WIN32_FIND_STREAM_DATA = unsafe { mem::zeroed() };
+    let handle = unsafe {
+        FindFirstStreamW(
+            wide.as_ptr(),
+            FindStreamInfoStandard,
+            (&mut data as *mut WIN32_FIND_STREAM_DATA).cast(),
+            0,
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        let err: io::Error = io::Error::last_os_error();
+        return match err.raw_os_error() == Some(ERROR_HANDLE_EOF as i32) {
+            true => Ok(Vec::new()),
+            false => Err(err),
+        };
+    }
+    let mut names: Vec<String> = Vec::new();
+    loop {
+        // names read `:name:$DATA`, and the main stream `::$DATA`
+        let length: usize = data
+            .cStreamName
+            .iter()
+            .position(|c| *c == 0)
+            .unwrap_or(data.cStreamName.len());
+        let name: String = String::from_utf16_lossy(&data.cStreamName[..length]);
+        if let Some(name) = name
+            .strip_prefix(':')
+            .and_then(|n| n.strip_suffix(":$DATA"))
+        {
+            if !name.is_empty() {
+                names.push(name.to_string());
+            }
+        }
+        if unsafe { FindNextStreamW(handle, (&mut data as *mut WIN32_FIND_STREAM_DATA).cast()) }
+            == 0
+        {
+            break;
+        }
+    }
+    unsafe { FindClose(handle) };
+    Ok(names)
+}
+
+#[cfg(not(windows))]
+pub fn list_data_streams(_path: &Path) -> io::Result<Vec<String>> {
+    Ok(Vec::new())
+}
+
+/// give the file at `target` the alternate data streams of the one at `source`
+#[cfg(windows)]
+pub fn copy_data_streams(source: &Path, target: &Path) -> io::Result<()> {
+    use std::{ffi::OsString, fs::File};
+    for name in list_data_streams(source)? {
+        let stream_path = |path: &Path| {
+            let mut stream: OsString = path.as_os_str().to_os_string();
+            stream.push(format!(":{name}"));
+            stream
+        };
+        let mut reader: File = File::open(stream_path(source))?;
+        let mut writer: File = File::create(stream_path(target))?;
+        io::copy(&mut reader, &mut writer)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn copy_data_streams(_source: &Path, _target: &Path) -> io::Result<()> {
+    Ok(())
+}
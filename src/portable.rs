@@ -0,0 +1,178 @@
+//! Checking by `--portable` that the new names of a plan hold on Windows, macOS
+//! and Linux alike, as for files synced or copied between them: the strictest
+//! rules of the three apply, and a plan breaking any is refused before
+//! anything is renamed.
+
+use crate::{
+    error::{self, Error},
+    filesystem::ReadOnly,
+    i18n::{tr, Message},
+    sanitize::{FORBIDDEN_CHARS, RESERVED_NAMES},
+};
+use clap::{Arg, ArgAction, ArgMatches};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{self, Component, Path, PathBuf},
+    sync::OnceLock,
+};
+use unicode_normalization::UnicodeNormalization;
+
+const PORTABLE_HELP_MESSAGE: &str = r#"Refuse the plan unless every new name is valid and unambiguous on
+Windows, macOS and Linux alike: no characters Windows forbids, no
+device names such as CON, no trailing dots or spaces, names of at most
+255 bytes and paths of at most 260 characters, and no two names in a
+directory differing only in case or Unicode normalization"#;
+
+/// the most bytes of a name, on Linux, and of UTF-16 units on Windows and macOS
+const MAX_NAME_BYTES: usize = 255;
+
+/// the most characters of a path on Windows, unless long paths are enabled
+const MAX_PATH_CHARS: usize = 260;
+
+/// whether the new names of this run are checked, by `--portable`
+static PORTABLE: OnceLock<bool> = OnceLock::new();
+
+/// the `--portable` option of the renaming tools
+pub fn get_portable_arg() -> Arg {
+    Arg::new("portable")
+        .long("portable")
+        .help(PORTABLE_HELP_MESSAGE)
+        .action(ArgAction::SetTrue)
+}
+
+/// check the new names of this run by `--portable`
+pub fn set_portable(matches: &ArgMatches) {
+    let _ = PORTABLE.set(matches.get_flag("portable"));
+}
+
+/// refuse, by `--portable`, renames whose targets do not hold on every
+/// platform, printing why for each; the directories of the targets are listed
+/// through `reader`
+pub fn check_portable(renames: &[(PathBuf, PathBuf)], reader: ReadOnly) -> error::Result<()> {
+    if !PORTABLE.get().copied().unwrap_or(false) {
+        return Ok(());
+    }
+    let problems: Vec<(PathBuf, String)> = find_unportable(renames, reader);
+    for (target, reason) in &problems {
+        eprintln!("{}: {reason}", target.display());
+    }
+    let failed: usize = problems
+        .iter()
+        .map(|(t, _)| t)
+        .collect::<HashSet<&PathBuf>>()
+        .len();
+    match failed {
+        0 => Ok(()),
+        _ => Err(Error::NotPortable {
+            failed,
+            total: renames.len(),
+        }),
+    }
+}
+
+/// the targets of `renames` that do not hold on every platform, and why
+pub fn find_unportable(renames: &[(PathBuf, PathBuf)], reader: ReadOnly) -> Vec<(PathBuf, String)> {
+    let mut problems: Vec<(PathBuf, String)> = Vec::new();
+    for (source, target) in renames {
+        // the directories kept from the source were there before
+        let kept: usize = zip_count(source.parent(), target.parent());
+        for component in target.components().skip(kept) {
+            if let Component::Normal(name) = component {
+                let name: String = name.to_string_lossy().into_owned();
+                problems.extend(check_name(&name).map(|r| (target.clone(), r)));
+            }
+        }
+        let length: usize = path::absolute(target)
+            .unwrap_or_else(|_| target.clone())
+            .to_string_lossy()
+            .encode_utf16()
+            .count();
+        if length >= MAX_PATH_CHARS {
+            let reason: String = tr(Message::PathTooLong, &[&MAX_PATH_CHARS]);
+            problems.push((target.clone(), reason));
+        }
+    }
+    problems.extend(find_ambiguous(renames, reader));
+    problems
+}
+
+/// the leading components that `a` and `b` share
+fn zip_count(a: Option<&Path>, b: Option<&Path>) -> usize {
+    match (a, b) {
+        (Some(a), Some(b)) => a
+            .components()
+            .zip(b.components())
+            .take_while(|(a, b)| a == b)
+            .count(),
+        _ => 0,
+    }
+}
+
+/// why the file name `name` does not hold on every platform, if it does not
+fn check_name(name: &str) -> Option<String> {
+    if name.len() > MAX_NAME_BYTES {
+        return Some(tr(Message::NameTooLong, &[&MAX_NAME_BYTES]));
+    }
+    if let Some(c) = name.chars().find(|c| FORBIDDEN_CHARS.contains(c)) {
+        return Some(tr(Message::ForbiddenChar, &[&c]));
+    }
+    if name.chars().any(char::is_control) {
+        return Some(tr(Message::ControlChar, &[]));
+    }
+    if name.ends_with(['.', ' ']) {
+        return Some(tr(Message::TrailingDotOrSpace, &[]));
+    }
+    let stem: &str = name.split('.').next().unwrap_or("").trim_end();
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        return Some(tr(Message::DeviceName, &[&stem]));
+    }
+    None
+}
+
+/// `name` as the file systems ignoring case and normalization see it
+fn fold(name: &Path) -> String {
+    name.to_string_lossy()
+        .nfc()
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// the targets of `renames` differing only in case or Unicode normalization
+/// from another target or from a file staying in their directories, listed
+/// through `reader`
+fn find_ambiguous(renames: &[(PathBuf, PathBuf)], reader: ReadOnly) -> Vec<(PathBuf, String)> {
+    let moved: HashSet<&Path> = renames.iter().map(|(s, _)| s.as_path()).collect();
+    let mut problems: Vec<(PathBuf, String)> = Vec::new();
+    let mut seen: HashMap<String, &Path> = HashMap::new();
+    // the files staying in each directory of the targets, by their folded paths
+    let mut staying: HashMap<PathBuf, HashMap<String, PathBuf>> = HashMap::new();
+    for (_, target) in renames {
+        let folded: String = fold(target);
+        match seen.get(&folded) {
+            Some(other) if *other != target.as_path() => {
+                let reason: String = tr(Message::CaseAmbiguous, &[&other.display()]);
+                problems.push((target.clone(), reason));
+                continue;
+            }
+            Some(_) => continue,
+            None => {
+                seen.insert(folded.clone(), target);
+            }
+        }
+        let dir: PathBuf = target.parent().unwrap_or(Path::new("")).to_path_buf();
+        let files: &HashMap<String, PathBuf> = staying.entry(dir.clone()).or_insert_with(|| {
+            reader
+                .read_dir(&dir)
+                .into_iter()
+                .flatten()
+                .filter(|p| !moved.contains(p.as_path()))
+                .map(|p| (fold(&p), p))
+                .collect()
+        });
+        if let Some(other) = files.get(&folded).filter(|o| *o != target) {
+            let reason: String = tr(Message::CaseAmbiguous, &[&other.display()]);
+            problems.push((target.clone(), reason));
+        }
+    }
+    problems
+}
@@ -0,0 +1,226 @@
+//! Named date and time formats for the tools naming files by time, so that
+//! `--preset compact` stands for `%Y%m%d_%H%M%S` without remembering strftime.
+
+use crate::{
+    clock::parse_time,
+    config::Config,
+    i18n::{tr, Message},
+    time_format::{get_week_start, TimeFormat},
+};
+use chrono::{DateTime, Utc};
+use clap::{parser::ValueSource, Arg, ArgAction, ArgMatches};
+use serde::Deserialize;
+use std::{collections::BTreeSet, fs, path::Path};
+
+/// the presets every user has, which the configuration file may take over
+pub const BUILTIN_PRESETS: [(&str, &str); 7] = [
+    // ISO 8601 basic, free of the colons some file systems reject
+    ("iso", "%Y%m%dT%H%M%S"),
+    ("compact", "%Y%m%d_%H%M%S"),
+    ("photo", "IMG_%Y%m%d_%H%M%S"),
+    ("sortable", "%Y-%m-%d_%H-%M-%S"),
+    // backups rotated by day, ISO week and month, one name for each
+    ("daily", "daily-%Y-%m-%d"),
+    ("weekly", "weekly-%G-W%V"),
+    ("monthly", "monthly-%Y-%m"),
+];
+
+const PRESET_HELP_MESSAGE: &str = r#"A named format, instead of -f:
+  iso:      20240501T100000
+  compact:  20240501_100000
+  photo:    IMG_20240501_100000
+  sortable: 2024-05-01_10-00-00
+  daily:    daily-2024-05-01, for rotating backups, as by --rotate
+  weekly:   weekly-2024-W18, by ISO week
+  monthly:  monthly-2024-05
+or one defined under [presets.<name>] with a `format` in the shared
+configuration file, e.g. ~/.config/ray-commands/config.toml"#;
+
+const FORMAT_FOR_HELP_MESSAGE: &str = r#"A format for the files of some extensions, instead of -f or --preset,
+e.g. mp4,mov=%Y-%m-%d_%H%M or pdf=%Y-%m-%d_{name}; repeatable, and
+taking over the [extension_formats] of the configuration file"#;
+
+/// the format of names by the time from `--relative-to`, unless given
+const RELATIVE_TIME_FORMAT: &str = "T{elapsed}";
+
+const RELATIVE_TO_HELP_MESSAGE: &str = r#"Name the files by the time elapsed since ANCHOR, a date and time such
+as 2024-05-01T09:00 or a file, by its modification time, e.g. T+00-03-21
+for a recording starting 3 minutes and 21 seconds in, as by the format
+T{elapsed} unless -f is given"#;
+
+const OFFSET_HELP_MESSAGE: &str = r#"End each format with the UTC offset, %z, e.g. +0100, so that names
+stay apart across the hour repeated when daylight saving time ends"#;
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Preset {
+    /// the date and time format, following chrono's strftime
+    pub format: String,
+}
+
+/// the formats of a run, by the extension of each file
+#[derive(Clone)]
+pub struct TimeFormats {
+    default: TimeFormat,
+    /// the lower case extensions and their formats, the first matching applying
+    by_extension: Vec<(String, TimeFormat)>,
+}
+
+impl TimeFormats {
+    /// `default` for the files of every extension
+    pub fn new(default: TimeFormat) -> Self {
+        Self {
+            default,
+            by_extension: Vec::new(),
+        }
+    }
+
+    /// these formats, with `time_format` for the files of `extension`
+    pub fn with_extension(mut self, extension: &str, time_format: TimeFormat) -> Self {
+        self.by_extension
+            .push((extension.to_lowercase(), time_format));
+        self
+    }
+
+    /// the format of the file at `path`
+    pub fn get(&self, path: &Path) -> &TimeFormat {
+        let extension: Option<String> =
+            path.extension().map(|e| e.to_string_lossy().to_lowercase());
+        self.by_extension
+            .iter()
+            .find(|(e, _)| extension.as_ref() == Some(e))
+            .map_or(&self.default, |(_, format)| format)
+    }
+}
+
+/// the `--preset` option of the tools naming files by time
+pub fn get_preset_arg() -> Arg {
+    Arg::new("preset")
+        .long("preset")
+        .help(PRESET_HELP_MESSAGE)
+        .conflicts_with("format")
+}
+
+/// the format named by `--preset`, looked up in the configuration file and then
+/// among the built-in presets, or else the one given by `-f`, with weeks
+/// starting on the day given by `--week-start`; a format whose names would not
+/// sort by time is warned about, or refused by `--strict`
+pub fn get_time_format(matches: &ArgMatches) -> Result<TimeFormat, String> {
+    parse_time_format(&get_format_text(matches)?, matches)
+}
+
+/// the `--format-for` option of the tools naming files by time
+pub fn get_format_for_arg() -> Arg {
+    Arg::new("format_for")
+        .long("format-for")
+        .help(FORMAT_FOR_HELP_MESSAGE)
+        .value_name("EXTENSIONS=FORMAT")
+        .action(ArgAction::Append)
+}
+
+/// the `--relative-to` option of the tools naming files by time
+pub fn get_relative_to_arg() -> Arg {
+    Arg::new("relative_to")
+        .long("relative-to")
+        .help(RELATIVE_TO_HELP_MESSAGE)
+        .value_name("ANCHOR")
+        .value_parser(parse_anchor)
+}
+
+/// the instant of `text`, a date and time or else the path of a file
+fn parse_anchor(text: &str) -> Result<DateTime<Utc>, String> {
+    if let Some(time) = parse_time(text) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    fs::metadata(text)
+        .and_then(|m| m.modified())
+        .map(DateTime::from)
+        .map_err(|e| tr(Message::NeitherTimeNorFile, &[&text, &e]))
+}
+
+/// the `--timestamp-with-offset` flag of the tools naming files by time
+pub fn get_offset_arg() -> Arg {
+    Arg::new("timestamp_with_offset")
+        .long("timestamp-with-offset")
+        .help(OFFSET_HELP_MESSAGE)
+        .action(ArgAction::SetTrue)
+}
+
+/// the format of [`get_time_format`], taken over for some extensions by those
+/// of `--format-for`, or else by those of the configuration file
+pub fn get_time_formats(matches: &ArgMatches) -> Result<TimeFormats, String> {
+    let default: TimeFormat = get_time_format(matches)?;
+    let mut rules: Vec<(String, String)> = Vec::new();
+    for rule in matches
+        .get_many::<String>("format_for")
+        .into_iter()
+        .flatten()
+    {
+        let (extensions, format) = rule
+            .split_once('=')
+            .ok_or_else(|| tr(Message::InvalidFormatFor, &[rule]))?;
+        rules.push((extensions.to_string(), format.to_string()));
+    }
+    rules.extend(Config::load()?.extension_formats);
+    let mut by_extension: Vec<(String, TimeFormat)> = Vec::new();
+    for (extensions, format) in rules {
+        let time_format: TimeFormat = parse_time_format(&format, matches)?;
+        for extension in extensions.split(',') {
+            let extension: String = extension.trim().trim_start_matches('.').to_lowercase();
+            by_extension.push((extension, time_format.clone()));
+        }
+    }
+    Ok(TimeFormats {
+        default,
+        by_extension,
+    })
+}
+
+fn parse_time_format(format: &str, matches: &ArgMatches) -> Result<TimeFormat, String> {
+    let format: String = match matches.try_get_one::<bool>("timestamp_with_offset") {
+        Ok(Some(true)) => format!("{format}%z"),
+        _ => format.to_string(),
+    };
+    let format: &str = &format;
+    let mut time_format: TimeFormat = TimeFormat::parse(format, get_week_start(matches))
+        .map_err(|e| tr(Message::InvalidTimeFormat, &[&format, &e]))?;
+    if let Ok(Some(anchor)) = matches.try_get_one::<DateTime<Utc>>("relative_to") {
+        time_format = time_format.with_anchor(*anchor);
+    }
+    if let Some(reason) = time_format.find_unsortable() {
+        let problem: String = tr(Message::UnsortableFormat, &[&format, &reason]);
+        if matches.get_flag("strict") {
+            return Err(problem);
+        }
+        eprintln!("{}", tr(Message::Warning, &[&problem]));
+    }
+    Ok(time_format)
+}
+
+fn get_format_text(matches: &ArgMatches) -> Result<String, String> {
+    let Some(name) = matches.get_one::<String>("preset") else {
+        let is_relative: bool = matches
+            .try_get_one::<DateTime<Utc>>("relative_to")
+            .is_ok_and(|a| a.is_some());
+        if is_relative && matches.value_source("format") == Some(ValueSource::DefaultValue) {
+            return Ok(RELATIVE_TIME_FORMAT.to_string());
+        }
+        return Ok(matches.get_one::<String>("format").unwrap().clone());
+    };
+    let config: Config = Config::load()?;
+    if let Some(preset) = config.presets.get(name) {
+        return Ok(preset.format.clone());
+    }
+    match BUILTIN_PRESETS.iter().find(|(n, _)| n == name) {
+        Some((_, format)) => Ok(format.to_string()),
+        None => {
+            let names: BTreeSet<&str> = BUILTIN_PRESETS
+                .iter()
+                .map(|(n, _)| *n)
+                .chain(config.presets.keys().map(String::as_str))
+                .collect();
+            let names: Vec<&str> = names.into_iter().collect();
+            Err(tr(Message::UnknownPreset, &[&name, &names.join(", ")]))
+        }
+    }
+}
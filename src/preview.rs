@@ -0,0 +1,69 @@
+use crate::{
+    i18n::{tr, Message},
+    table::Table,
+};
+use console::Color;
+
+// following Unicode standard 15.1.0
+// reference: https://en.wikipedia.org/w/index.php?title=CJK_Unified_Ideographs&direction=next&oldid=1203587452
+const CHINESE_UNICODE_RANGE: [[u32; 2]; 6] = [
+    [0x04_E00, 0x09_FFF], // unified basic chars
+    [0x03_400, 0x04_DBF], // extension A
+    [0x20_000, 0x2A_6DF], // extension B
+    [0x2A_700, 0x2E_E5F], // extensions C, D, E, F, I
+    [0x30_000, 0x32_3AF], // extensions G, H
+    [0x0F_900, 0x0F_AFF], // round-trip compatibility
+                          // [0x03_300, 0x03_3FF], // non-unified chars for legacy systems
+                          // [0x0F_E30, 0x0E_F4F], // non-unified chars for legacy systems
+                          // [0x0F_900, 0x0F_AFF], // non-unified chars for legacy systems
+                          // [0x2F_800, 0x2F_A1F], // non-unified chars for legacy systems
+];
+
+/// combining diacritical marks, drawn over the preceding character
+const COMBINING_MARK_RANGE: [u32; 2] = [0x03_00, 0x03_6F];
+
+/// number of terminal columns taken by `text`, counting Chinese characters as two
+/// and combining marks as none
+pub fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| {
+            if c.is_ascii() {
+                1
+            } else if CHINESE_UNICODE_RANGE
+                .iter()
+                .any(|r| c as u32 >= r[0] && c as u32 <= r[1])
+            {
+                2
+            } else if c as u32 >= COMBINING_MARK_RANGE[0] && c as u32 <= COMBINING_MARK_RANGE[1] {
+                0
+            } else {
+                1 // unknown
+            }
+        })
+        .sum()
+}
+
+/// `text` followed by spaces up to `width` display columns
+pub fn pad_to_width(text: &str, width: usize) -> String {
+    let padding: usize = width.saturating_sub(display_width(text));
+    format!("{text}{}", " ".repeat(padding))
+}
+
+/// print the `old new` table shown before renaming
+pub fn print_renaming_table(operations: &[(String, String)]) {
+    get_renaming_table(operations).print();
+}
+
+/// the lines of the `old new` table, for previews adding lines around it
+pub fn render_renaming_table(operations: &[(String, String)]) -> Vec<String> {
+    get_renaming_table(operations).render()
+}
+
+fn get_renaming_table(operations: &[(String, String)]) -> Table {
+    let (old, new) = (tr(Message::OldHeader, &[]), tr(Message::NewHeader, &[]));
+    let mut table: Table = Table::new(&[&old, &new]).color(1, Color::Green).diff(1, 0);
+    operations
+        .iter()
+        .for_each(|(o, n)| table.add_row(vec![o.clone(), n.clone()]));
+    table
+}
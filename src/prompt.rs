@@ -0,0 +1,225 @@
+//! Questions asked before changing files, and the paging of the long plans
+//! printed before them.
+
+use crate::{
+    i18n::{tr, Message},
+    output::is_print0,
+};
+use clap::{Arg, ArgAction, ArgMatches};
+use console::{Key, Term};
+use std::{
+    env,
+    fs::File,
+    io::{self, BufRead, BufReader, IsTerminal, Write},
+    process::{Command, Stdio},
+    sync::OnceLock,
+};
+
+/// the pager showing plans taller than the terminal, unless `$PAGER` is set;
+/// it quits at once on plans fitting in one screen and leaves them on it
+const DEFAULT_PAGER: &str = "less -FRX";
+
+/// the controlling terminal, answering when standard input carries data
+#[cfg(unix)]
+const TERMINAL_PATH: &str = "/dev/tty";
+#[cfg(windows)]
+const TERMINAL_PATH: &str = "CONIN$";
+
+/// whether every question is answered yes without asking, by `--yes`
+static ASSUME_YES: OnceLock<bool> = OnceLock::new();
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Yes,
+    No,
+    /// yes to this and every following question
+    All,
+    /// no to this and every following question
+    Quit,
+}
+
+impl Answer {
+    fn key(&self) -> char {
+        match self {
+            Self::Yes => 'y',
+            Self::No => 'n',
+            Self::All => 'a',
+            Self::Quit => 'q',
+        }
+    }
+
+    fn description(&self) -> String {
+        match self {
+            Self::Yes => tr(Message::AnswerYes, &[]),
+            Self::No => tr(Message::AnswerNo, &[]),
+            Self::All => tr(Message::AnswerAll, &[]),
+            Self::Quit => tr(Message::AnswerQuit, &[]),
+        }
+    }
+
+    /// the answer `reply` stands for, also accepting 是 and 否
+    fn parse(reply: &str) -> Option<Self> {
+        match reply {
+            "y" | "yes" | "是" => Some(Self::Yes),
+            "n" | "no" | "否" => Some(Self::No),
+            "a" => Some(Self::All),
+            "q" => Some(Self::Quit),
+            _ => None,
+        }
+    }
+
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, Self::Yes | Self::All)
+    }
+}
+
+/// ask `question` until the user picks one of `answers`, `default` on an empty
+/// answer and `?` to explain them, on the controlling terminal when standard
+/// input is not one, as when the paths come on it; by `--yes`, answer yes
+/// without asking; without a terminal to ask on, or when the input ends, say
+/// so and answer `Quit` instead of waiting forever
+pub fn ask(question: &str, answers: &[Answer], default: Answer) -> Answer {
+    if ASSUME_YES.get().copied().unwrap_or(false) {
+        return match answers.contains(&Answer::All) {
+            true => Answer::All,
+            false => Answer::Yes,
+        };
+    }
+    let Some(mut input) = open_terminal() else {
+        eprintln!("{}", tr(Message::CannotAsk, &[&question]));
+        return Answer::Quit;
+    };
+    let keys: Vec<String> = answers
+        .iter()
+        .map(|a| match *a == default {
+            true => a.key().to_ascii_uppercase().to_string(),
+            false => a.key().to_string(),
+        })
+        .collect();
+    // standard output is kept for the new paths by `--print0`
+    let mut output: Box<dyn Write> = match is_print0() {
+        true => Box::new(io::stderr()),
+        false => Box::new(io::stdout()),
+    };
+    loop {
+        let _ = write!(output, "{question} [{}/?] ", keys.join("/"));
+        let _ = output.flush();
+        let mut buffer: String = String::new();
+        if input.read_line(&mut buffer).unwrap_or(0) == 0 {
+            let _ = writeln!(output);
+            return Answer::Quit;
+        }
+        let reply: String = buffer.trim().to_lowercase();
+        if reply.is_empty() {
+            return default;
+        }
+        if let Some(answer) = Answer::parse(&reply).filter(|a| answers.contains(a)) {
+            return answer;
+        }
+        if reply == "?" {
+            for answer in answers {
+                let _ = writeln!(output, "{} - {}", answer.key(), answer.description());
+            }
+        }
+    }
+}
+
+/// where to read answers: standard input when it is a terminal, or else the
+/// controlling terminal, none without one, as in cron jobs and CI
+fn open_terminal() -> Option<Box<dyn BufRead>> {
+    if io::stdin().is_terminal() {
+        return Some(Box::new(io::stdin().lock()));
+    }
+    let terminal: File = File::open(TERMINAL_PATH).ok()?;
+    Some(Box::new(BufReader::new(terminal)))
+}
+
+/// the `--yes` flag of the tools asking before changing files
+pub fn get_yes_arg() -> Arg {
+    Arg::new("yes")
+        .short('y')
+        .long("yes")
+        .help("Answer yes to every question instead of asking, e.g. with no terminal")
+        .action(ArgAction::SetTrue)
+}
+
+/// answer yes to every question of this run by `--yes`
+pub fn set_assume_yes(matches: &ArgMatches) {
+    let _ = ASSUME_YES.set(matches.get_flag("yes"));
+}
+
+/// ask `question` until the user answers; return whether it is accepted
+pub fn wait_accepting_prompt(question: &str) -> bool {
+    ask(question, &[Answer::Yes, Answer::No], Answer::Yes).is_accepted()
+}
+
+/// print `lines`, through a pager when they do not fit in the terminal,
+/// the built-in one when `$PAGER` cannot be started; by `--print0`, on
+/// standard error without paging
+pub fn print_paged(lines: &[String]) {
+    if is_print0() {
+        lines.iter().for_each(|l| eprintln!("{l}"));
+        return;
+    }
+    let terminal: Term = Term::stdout();
+    let rows: usize = match terminal.size_checked() {
+        Some((rows, _)) => rows as usize,
+        None => usize::MAX,
+    };
+    // room is kept for the question following the lines
+    if lines.len() < rows || !io::stdout().is_terminal() {
+        lines.iter().for_each(|l| println!("{l}"));
+    } else if !write_to_pager(lines) {
+        page(&terminal, lines, rows);
+    }
+}
+
+/// print `lines` a screen of `rows` at a time, waiting for a key between them:
+/// space for the next screen, enter for the next line and `q` to stop paging
+fn page(terminal: &Term, lines: &[String], rows: usize) {
+    let screen: usize = rows.saturating_sub(1).max(1);
+    let mut shown: usize = 0;
+    let mut step: usize = screen;
+    while shown < lines.len() {
+        let end: usize = lines.len().min(shown + step);
+        lines[shown..end].iter().for_each(|l| println!("{l}"));
+        shown = end;
+        if shown == lines.len() {
+            break;
+        }
+        let status: String = tr(Message::PagerStatus, &[&shown, &lines.len()]);
+        let _ = terminal.write_str(&status);
+        let key: io::Result<Key> = terminal.read_key();
+        let _ = terminal.clear_line();
+        step = match key {
+            Ok(Key::Enter) => 1,
+            Ok(Key::Char('q')) | Ok(Key::Escape) | Err(_) => lines.len(),
+            Ok(_) => screen,
+        };
+    }
+}
+
+/// return whether the pager could be started to show `lines`
+fn write_to_pager(lines: &[String]) -> bool {
+    let pager: String = env::var("PAGER").unwrap_or_else(|_| String::from(DEFAULT_PAGER));
+    let mut words = pager.split_whitespace();
+    let Some(program) = words.next() else {
+        return false;
+    };
+    let Ok(mut child) = Command::new(program)
+        .args(words)
+        .stdin(Stdio::piped())
+        .spawn()
+    else {
+        return false;
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        for line in lines {
+            // the pager quit before the end
+            if writeln!(stdin, "{line}").is_err() {
+                break;
+            }
+        }
+    }
+    child.wait().is_ok()
+}
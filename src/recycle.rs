@@ -0,0 +1,96 @@
+//! Moving files to the trash of the desktop (freedesktop.org trash, macOS Trash,
+//! Windows Recycle Bin) instead of deleting them for good, and back.
+
+use crate::i18n::{tr, Message};
+use std::{io, path::Path};
+
+/// move `path`, a file or a whole directory, to the trash
+pub fn move_to_trash(path: &Path) -> io::Result<()> {
+    trash::delete(path).map_err(into_io_error)
+}
+
+/// whether a file moved to the trash from `path` is still there, false where
+/// the trash cannot be listed
+pub fn is_in_trash(path: &Path) -> bool {
+    find_in_trash(path).is_ok()
+}
+
+/// move the file last moved to the trash from `path` back there, where the
+/// trash can be listed: on Windows and the freedesktop.org trash
+#[cfg(any(
+    windows,
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+pub fn restore_from_trash(path: &Path) -> io::Result<()> {
+    trash::os_limited::restore_all([find_in_trash(path)?]).map_err(into_io_error)
+}
+
+/// move the file last moved to the trash from `path` back there, where the
+/// trash can be listed: on Windows and the freedesktop.org trash
+#[cfg(not(any(
+    windows,
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+pub fn restore_from_trash(path: &Path) -> io::Result<()> {
+    find_in_trash(path).map(|_| ())
+}
+
+/// the item last moved to the trash from `path`
+#[cfg(any(
+    windows,
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+fn find_in_trash(path: &Path) -> io::Result<trash::TrashItem> {
+    // the trash records the path with its directory resolved
+    let path: std::path::PathBuf = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => std::fs::canonicalize(parent)
+            .unwrap_or_else(|_| parent.to_path_buf())
+            .join(name),
+        _ => path.to_path_buf(),
+    };
+    trash::os_limited::list()
+        .map_err(into_io_error)?
+        .into_iter()
+        .filter(|item| item.original_path() == path)
+        .max_by_key(|item| item.time_deleted)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, tr(Message::NotInTrash, &[])))
+}
+
+/// the item last moved to the trash from `path`
+#[cfg(not(any(
+    windows,
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+fn find_in_trash(_path: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        tr(Message::TrashNotListable, &[]),
+    ))
+}
+
+fn into_io_error(err: trash::Error) -> io::Error {
+    match err {
+        trash::Error::FileSystem { source, .. } => source,
+        other => io::Error::other(other.to_string()),
+    }
+}
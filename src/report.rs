@@ -0,0 +1,365 @@
+//! The CSV report written by `--report` after a run, a row per file with its old
+//! and new name, where the new name came from and what became of it, for those
+//! keeping an audit trail of their renames, and the same as a self-contained
+//! HTML page by `--report-html`, for those signing off on them.
+
+use crate::{
+    clock::now,
+    i18n::{tr, Message},
+    sort::{compare_names, get_sort_order},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::{value_parser, Arg, ArgAction, ArgMatches};
+use image::{ImageFormat, ImageReader};
+use serde::Serialize;
+use std::{
+    fmt::Write, // Write for write!
+    fs::{self, File},
+    io::{self, Cursor},
+    path::{Path, PathBuf},
+};
+
+/// the longer side of a thumbnail in the HTML report, in pixels
+const THUMBNAIL_SIZE: u32 = 96;
+
+const REPORT_HTML_CSS: &str = r#"body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; vertical-align: middle; }
+th { background: #f0f0f0; }
+td.name { font-family: monospace; word-break: break-all; }
+td.thumbnail { text-align: center; }
+.summary td:last-child { text-align: right; }
+.renamed { color: #1a7f37; }
+.skipped, .declined { color: #9a6700; }
+.failed { color: #cf222e; }
+.not_done { color: #6e7781; }
+.trashed { color: #8250df; }"#;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Renamed,
+    /// left as it is when planning, e.g. for a conflict
+    Skipped,
+    /// declined when confirming, or a page of it
+    Declined,
+    Failed,
+    /// planned but not reached, as after Ctrl-C or a rename aborting the run
+    NotDone,
+    /// moved to the trash instead of renamed, as by `--rotate`
+    Trashed,
+}
+
+impl Status {
+    const ALL: [Self; 6] = [
+        Self::Renamed,
+        Self::Skipped,
+        Self::Declined,
+        Self::Failed,
+        Self::NotDone,
+        Self::Trashed,
+    ];
+
+    /// the class of the status in the HTML report, as named in the CSV one
+    fn name(self) -> &'static str {
+        match self {
+            Self::Renamed => "renamed",
+            Self::Skipped => "skipped",
+            Self::Declined => "declined",
+            Self::Failed => "failed",
+            Self::NotDone => "not_done",
+            Self::Trashed => "trashed",
+        }
+    }
+
+    fn label(self) -> String {
+        let message: Message = match self {
+            Self::Renamed => Message::StatusRenamed,
+            Self::Skipped => Message::StatusSkipped,
+            Self::Declined => Message::StatusDeclined,
+            Self::Failed => Message::StatusFailed,
+            Self::NotDone => Message::StatusNotDone,
+            Self::Trashed => Message::StatusTrashed,
+        };
+        tr(message, &[])
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct ReportRow {
+    pub old: String,
+    pub new: String,
+    /// where the new name came from, e.g. the time source
+    pub source: String,
+    pub status: Status,
+    pub error: String,
+}
+
+impl ReportRow {
+    pub fn new(old: &Path, new: Option<&Path>, source: String, status: Status) -> Self {
+        Self {
+            old: old.display().to_string(),
+            new: new.map(|p| p.display().to_string()).unwrap_or_default(),
+            source,
+            status,
+            error: String::new(),
+        }
+    }
+
+    /// the row with the error or reason of its status
+    pub fn with_error(self, error: impl Into<String>) -> Self {
+        Self {
+            error: error.into(),
+            ..self
+        }
+    }
+}
+
+/// the rows of a report, written as they come
+pub struct ReportWriter {
+    path: PathBuf,
+    writer: csv::Writer<File>,
+}
+
+impl ReportWriter {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer: csv::Writer::from_path(path)?,
+        })
+    }
+
+    pub fn write(&mut self, row: &ReportRow) -> io::Result<()> {
+        Ok(self.writer.serialize(row)?)
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// write `rows` as the report at `path`
+pub fn write_report(path: &Path, rows: &[ReportRow]) -> io::Result<()> {
+    let mut writer: ReportWriter = ReportWriter::create(path)?;
+    for row in rows {
+        writer.write(row)?;
+    }
+    writer.finish()
+}
+
+/// write `rows` of a run of `program` as the HTML report at `path`, with a
+/// thumbnail of each image when `thumbnails`
+pub fn write_html_report(
+    path: &Path,
+    program: &str,
+    rows: &[ReportRow],
+    thumbnails: bool,
+) -> io::Result<()> {
+    fs::write(path, render_html(program, rows, thumbnails))
+}
+
+/// the HTML report of `rows`, a page needing no other file
+fn render_html(program: &str, rows: &[ReportRow], thumbnails: bool) -> String {
+    let title: String = tr(Message::ReportTitle, &[&escape_html(program)]);
+    let written: String = tr(
+        Message::ReportWritten,
+        &[&now().format("%Y-%m-%d %H:%M:%S")],
+    );
+    let mut html: String = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>\n{REPORT_HTML_CSS}\n</style>\n</head>\n<body>\n\
+         <h1>{title}</h1>\n<p>{written}</p>\n",
+    );
+
+    let _ = writeln!(
+        html,
+        "<h2>{}</h2>\n<table class=\"summary\" style=\"width: auto\">",
+        tr(Message::ReportSummary, &[])
+    );
+    for status in Status::ALL {
+        let count: usize = rows.iter().filter(|row| row.status == status).count();
+        let _ = writeln!(
+            html,
+            "<tr><th class=\"{}\">{}</th><td>{count}</td></tr>",
+            status.name(),
+            status.label(),
+        );
+    }
+    let _ = writeln!(
+        html,
+        "<tr><th>{}</th><td>{}</td></tr>\n</table>",
+        tr(Message::ReportTotal, &[]),
+        rows.len()
+    );
+
+    let _ = write!(
+        html,
+        "<h2>{}</h2>\n<table>\n<tr><th>#</th>",
+        tr(Message::ReportFiles, &[])
+    );
+    if thumbnails {
+        let _ = write!(html, "<th>{}</th>", tr(Message::ReportPreview, &[]));
+    }
+    for header in [
+        Message::ReportBefore,
+        Message::ReportAfter,
+        Message::ReportSource,
+        Message::ReportStatus,
+        Message::ReportError,
+    ] {
+        let _ = write!(html, "<th>{}</th>", tr(header, &[]));
+    }
+    html.push_str("</tr>\n");
+    for (index, row) in rows.iter().enumerate() {
+        let _ = write!(html, "<tr><td>{}</td>", index + 1);
+        if thumbnails {
+            // the file is at its new name once renamed
+            let current: &str = match row.status {
+                Status::Renamed => &row.new,
+                _ => &row.old,
+            };
+            html.push_str("<td class=\"thumbnail\">");
+            if let Some(uri) = thumbnail(Path::new(current)) {
+                let _ = write!(html, "<img src=\"{uri}\" alt=\"\">");
+            }
+            html.push_str("</td>");
+        }
+        let _ = writeln!(
+            html,
+            "<td class=\"name\">{}</td><td class=\"name\">{}</td><td>{}</td>\
+             <td class=\"{}\">{}</td><td>{}</td></tr>",
+            escape_html(&row.old),
+            escape_html(&row.new),
+            escape_html(&row.source),
+            row.status.name(),
+            row.status.label(),
+            escape_html(&row.error),
+        );
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+/// a PNG thumbnail of the image at `path` as a data URI, or none for a file
+/// that is not an image of a known format
+fn thumbnail(path: &Path) -> Option<String> {
+    let reader = ImageReader::open(path).ok()?.with_guessed_format().ok()?;
+    reader.format()?;
+    let image = reader
+        .decode()
+        .ok()?
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+    let mut png: Vec<u8> = vec![];
+    image
+        .to_rgba8()
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .ok()?;
+    Some(format!("data:image/png;base64,{}", STANDARD.encode(png)))
+}
+
+/// `text` with the characters special to HTML escaped
+fn escape_html(text: &str) -> String {
+    let mut escaped: String = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// the reports of a run asked for by `--report`, `--report-html` and
+/// `--report-thumbnails`
+#[derive(Clone, Default)]
+pub struct Reports {
+    csv: Option<PathBuf>,
+    html: Option<PathBuf>,
+    thumbnails: bool,
+}
+
+impl Reports {
+    pub fn from(matches: &ArgMatches) -> Self {
+        Self {
+            csv: matches.get_one::<PathBuf>("report").cloned(),
+            html: matches.get_one::<PathBuf>("report_html").cloned(),
+            thumbnails: matches.get_flag("report_thumbnails"),
+        }
+    }
+
+    /// where to write the CSV report, if anywhere
+    pub fn csv(&self) -> Option<&Path> {
+        self.csv.as_deref()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.csv.is_none() && self.html.is_none()
+    }
+
+    pub fn has_html(&self) -> bool {
+        self.html.is_some()
+    }
+
+    /// write `rows` of a run of `program` as each report asked for, warning of
+    /// any that cannot be written
+    pub fn write(&self, program: &str, rows: &[ReportRow]) {
+        let rows: &[ReportRow] = &sort_rows(rows);
+        if let Some(path) = &self.csv {
+            if let Err(err) = write_report(path, rows) {
+                eprintln!("{}", tr(Message::ReportFailed, &[&path.display(), &err]));
+            }
+        }
+        self.write_html(program, rows);
+    }
+
+    /// write `rows` as the HTML report only, for a run writing its CSV report
+    /// file by file
+    pub fn write_html(&self, program: &str, rows: &[ReportRow]) {
+        if let Some(path) = &self.html {
+            let rows: &[ReportRow] = &sort_rows(rows);
+            if let Err(err) = write_html_report(path, program, rows, self.thumbnails) {
+                eprintln!("{}", tr(Message::ReportFailed, &[&path.display(), &err]));
+            }
+        }
+    }
+}
+
+/// `rows` by their old names in the order of `--sort` if given, or else in
+/// the order of the run
+fn sort_rows(rows: &[ReportRow]) -> Vec<ReportRow> {
+    let mut rows: Vec<ReportRow> = rows.to_vec();
+    if get_sort_order().is_some() {
+        rows.sort_by(|a, b| compare_names(&a.old, &b.old));
+    }
+    rows
+}
+
+/// the `--report`, `--report-html` and `--report-thumbnails` options of the
+/// renaming tools
+pub fn get_report_args() -> [Arg; 3] {
+    [
+        Arg::new("report")
+            .long("report")
+            .help("After the run, write a CSV file of each file's old and new name, where\nthe new name came from, and whether it was renamed, with any error")
+            .value_parser(value_parser!(PathBuf)),
+        Arg::new("report_html")
+            .long("report-html")
+            .help("After the run, write the same as a self-contained HTML page, with\nthe counts of each outcome, e.g. for a client to sign off on")
+            .value_parser(value_parser!(PathBuf)),
+        Arg::new("report_thumbnails")
+            .long("report-thumbnails")
+            .help("Show a thumbnail of each image in the HTML report")
+            .action(ArgAction::SetTrue)
+            .requires("report_html"),
+    ]
+}
@@ -0,0 +1,370 @@
+//! Renaming files back to recorded names, shared by `restore_names` and the
+//! `--undo` of the renaming tools.
+
+use crate::{
+    backup::{is_backed_up, set_aside},
+    error::{self, Error},
+    hash::hash_file_cached,
+    i18n::{tr, Message},
+    interrupt::{is_interrupted, watch_interrupts},
+    journal::{
+        find_latest_journal, get_pending_path, is_sidecar_journal, read_journal, JournalEntry,
+    },
+    manifest::{read_manifest, ManifestEntry},
+    mapping::{read_mapping, MappingRow},
+    output::Report,
+    paths::resolve,
+    plan::RenameRecord,
+    preview::print_renaming_table,
+    prompt::wait_accepting_prompt,
+    recycle::{is_in_trash, restore_from_trash},
+    retry::with_retries,
+    walk::{walk_files, WalkEntry},
+};
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Copy)]
+pub enum RecordFormat {
+    Journal,
+    Csv,
+    Json,
+}
+
+impl RecordFormat {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "journal" => Self::Journal,
+            "csv" => Self::Csv,
+            "json" => Self::Json,
+            _ => panic!("Unknown record format: {name}"),
+        }
+    }
+
+    pub fn guess(path: &Path) -> Self {
+        match path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .as_deref()
+        {
+            Some("csv") | Some("tsv") => Self::Csv,
+            _ if is_sidecar_journal(path) => Self::Journal,
+            Some("json") => Self::Json,
+            _ => Self::Journal,
+        }
+    }
+}
+
+/// a file to be renamed back to its recorded name, or moved back from the trash
+struct Restoration {
+    current: PathBuf,
+    original: PathBuf,
+    size: Option<u64>,
+    hash: Option<String>,
+    /// whether the file is in the trash rather than at `current`
+    is_trashed: bool,
+    /// whether another file at `original` goes to its backup first, by `--backup`
+    is_replacing: bool,
+}
+
+pub struct RestorePlan {
+    restorations: Vec<Restoration>,
+    /// files that cannot be restored safely, with the reason
+    skipped: Vec<(PathBuf, String)>,
+}
+
+impl RestorePlan {
+    /// the restorations of `record`, its names relative to `dir`, or for the
+    /// journal kept inside a directory, to that directory
+    pub fn load(record: &Path, format: RecordFormat, dir: &Path) -> error::Result<Self> {
+        let dir: &Path = match record.parent() {
+            Some(parent) if is_sidecar_journal(record) => parent,
+            _ => dir,
+        };
+        let invalid_record = |e: io::Error| match e.kind() {
+            io::ErrorKind::InvalidData => {
+                Error::InvalidFormat(format!("{}: {e}", record.display()))
+            }
+            _ => Error::from_io(e, record),
+        };
+        let candidates: Vec<Restoration> = match format {
+            RecordFormat::Journal => {
+                Self::from_journal(read_journal(record).map_err(invalid_record)?, dir)
+            }
+            RecordFormat::Csv => {
+                Self::from_mapping(read_mapping(record).map_err(invalid_record)?, dir)
+            }
+            RecordFormat::Json => {
+                let entries: Vec<ManifestEntry> = read_manifest(record).map_err(invalid_record)?;
+                Self::from_manifest(entries, dir).map_err(|e| Error::from_io(e, dir))?
+            }
+        };
+        Ok(Self::validate(candidates))
+    }
+
+    /// return whether anything was renamed, or stop at the first rename failing or at Ctrl-C
+    pub fn restore_names(&self, to_print_prompt: bool) -> error::Result<bool> {
+        self.print_skipped();
+        if self.restorations.is_empty() {
+            println!("{}", tr(Message::NothingToRestore, &[]));
+            return Ok(false);
+        }
+
+        self.print_restoring_operations();
+        if to_print_prompt {
+            let to_rename: bool = wait_accepting_prompt(&tr(Message::AcceptRenaming, &[]));
+            if !to_rename {
+                println!("{}", tr(Message::NothingDone, &[]));
+                return Ok(false);
+            }
+        }
+
+        self.restore()?;
+        Ok(true)
+    }
+
+    /// rename the files back without asking or printing anything, in order, as
+    /// chains in a journal depend on it; Ctrl-C stops between renames
+    fn restore(&self) -> error::Result<()> {
+        watch_interrupts();
+        for (done, r) in self.restorations.iter().enumerate() {
+            if is_interrupted() {
+                return Err(Error::Interrupted {
+                    done,
+                    total: self.restorations.len(),
+                });
+            }
+            if let Some(parent) = r.original.parent() {
+                fs::create_dir_all(parent).map_err(|e| Error::from_io(e, parent))?;
+            }
+            if r.is_replacing {
+                set_aside(&r.original).map_err(|e| Error::from_io(e, &r.original))?;
+            }
+            if r.is_trashed {
+                restore_from_trash(&r.original).map_err(|e| Error::from_io(e, &r.original))?;
+                continue;
+            }
+            with_retries(|| fs::rename(&r.current, &r.original))
+                .map_err(|e| Error::from_rename(e, &r.current, &r.original))?;
+        }
+        Ok(())
+    }
+
+    /// undo the journaled renames, latest first
+    fn from_journal(entries: Vec<JournalEntry>, dir: &Path) -> Vec<Restoration> {
+        entries
+            .into_iter()
+            .rev()
+            .map(|e| Restoration {
+                current: resolve(dir, e.to_path()),
+                original: resolve(dir, e.from_path()),
+                size: e.size,
+                hash: e.hash,
+                is_trashed: e.is_trashed,
+                is_replacing: false,
+            })
+            .collect()
+    }
+
+    fn from_mapping(rows: Vec<MappingRow>, dir: &Path) -> Vec<Restoration> {
+        rows.into_iter()
+            .filter(|r| r.old != r.new)
+            .map(|r| Restoration {
+                current: resolve(dir, &r.new),
+                original: resolve(dir, &r.old),
+                size: r.size,
+                hash: r.hash,
+                is_trashed: false,
+                is_replacing: false,
+            })
+            .collect()
+    }
+
+    /// locate each recorded file by its content hash,
+    /// as a manifest does not record what the files were renamed to
+    fn from_manifest(entries: Vec<ManifestEntry>, dir: &Path) -> io::Result<Vec<Restoration>> {
+        let files: Vec<WalkEntry> = walk_files(dir)?;
+        let mut claimed: HashSet<PathBuf> = HashSet::new();
+        let mut restorations: Vec<Restoration> = Vec::new();
+        for entry in entries {
+            let original: PathBuf = resolve(dir, &entry.name);
+            let Some(hash) = entry.hash else {
+                continue;
+            };
+            if hash_file_cached(&original).is_ok_and(|h| h == hash) {
+                continue; // still in place
+            }
+            for file in files.iter().filter(|f| f.metadata.len() == entry.size) {
+                if claimed.contains(&file.path) || hash_file_cached(&file.path)? != hash {
+                    continue;
+                }
+                claimed.insert(file.path.clone());
+                restorations.push(Restoration {
+                    current: file.path.clone(),
+                    original,
+                    size: Some(entry.size),
+                    hash: Some(hash),
+                    is_trashed: false,
+                    is_replacing: false,
+                });
+                break;
+            }
+        }
+        Ok(restorations)
+    }
+
+    /// keep only the restorations whose source is the recorded file and whose
+    /// target is free, or by `--backup` taken by a file other than one restored
+    /// before, simulating the renames in order
+    fn validate(candidates: Vec<Restoration>) -> Self {
+        let mut vacated: HashSet<PathBuf> = HashSet::new();
+        let mut created: HashSet<PathBuf> = HashSet::new();
+        let mut restorations: Vec<Restoration> = Vec::new();
+        let mut skipped: Vec<(PathBuf, String)> = Vec::new();
+        for mut r in candidates {
+            let on_disk = |p: &Path| fs::symlink_metadata(p).is_ok();
+            let exists: bool = match r.is_trashed {
+                true => is_in_trash(&r.original),
+                false => {
+                    created.contains(&r.current)
+                        || (on_disk(&r.current) && !vacated.contains(&r.current))
+                }
+            };
+            let is_occupied: bool = on_disk(&r.original) && !vacated.contains(&r.original);
+            r.is_replacing = is_occupied && !created.contains(&r.original) && is_backed_up();
+            let target_taken: bool =
+                !r.is_replacing && (created.contains(&r.original) || is_occupied);
+
+            let reason: Option<String> = if !exists && r.is_trashed {
+                Some(tr(Message::NotInTrash, &[]))
+            } else if !exists {
+                Some(tr(Message::FileNotFound, &[]))
+            } else if target_taken {
+                Some(tr(Message::AlreadyExists, &[&r.original.display()]))
+            } else if r.is_trashed || created.contains(&r.current) {
+                None // content was checked at the first rename of the chain
+            } else {
+                Self::check_content(&r)
+            };
+            match reason {
+                Some(reason) if r.is_trashed => skipped.push((r.original, reason)),
+                Some(reason) => skipped.push((r.current, reason)),
+                None => {
+                    vacated.insert(r.current.clone());
+                    created.remove(&r.current);
+                    vacated.remove(&r.original);
+                    created.insert(r.original.clone());
+                    restorations.push(r);
+                }
+            }
+        }
+        Self {
+            restorations,
+            skipped,
+        }
+    }
+
+    fn check_content(r: &Restoration) -> Option<String> {
+        if let Some(size) = r.size {
+            let actual: u64 = fs::metadata(&r.current).map(|m| m.len()).unwrap_or(0);
+            if actual != size {
+                return Some(tr(Message::SizeDiffers, &[&actual, &size]));
+            }
+        }
+        if let Some(hash) = &r.hash {
+            match hash_file_cached(&r.current) {
+                Ok(actual) if actual == *hash => {}
+                Ok(_) => return Some(tr(Message::HashDiffers, &[])),
+                Err(err) => return Some(tr(Message::CannotHash, &[&err])),
+            }
+        }
+        None
+    }
+
+    fn print_skipped(&self) {
+        self.skipped
+            .iter()
+            .for_each(|(p, reason)| println!("{}", tr(Message::Skipping, &[&p.display(), reason])));
+    }
+
+    fn print_restoring_operations(&self) {
+        let operations: Vec<(String, String)> = self
+            .restorations
+            .iter()
+            .map(|r| {
+                let current: String = match r.is_trashed {
+                    true => tr(Message::FromTrash, &[]),
+                    false => r.current.display().to_string(),
+                };
+                (current, r.original.display().to_string())
+            })
+            .collect();
+        print_renaming_table(&operations);
+        self.restorations
+            .iter()
+            .filter(|r| r.is_replacing)
+            .for_each(|r| {
+                println!(
+                    "{}",
+                    tr(Message::OverwritingBackedUp, &[&r.original.display()])
+                )
+            });
+    }
+}
+
+impl Report for RestorePlan {
+    type Record = RenameRecord;
+
+    fn records(&self) -> Vec<RenameRecord> {
+        let skipped = self
+            .skipped
+            .iter()
+            .map(|(p, reason)| RenameRecord::skip(p, reason));
+        let restorations = self.restorations.iter().map(|r| match r.is_replacing {
+            true => RenameRecord::overwrite(&r.current, &r.original),
+            false => RenameRecord::rename(&r.current, &r.original),
+        });
+        skipped.chain(restorations).collect()
+    }
+
+    fn print_table(&self) {
+        self.print_skipped();
+        self.print_restoring_operations();
+    }
+}
+
+/// undo the latest journaled run of `program` as `undo_latest` does, without
+/// asking or printing anything; return the renames undone, none when there
+/// was no run to undo
+pub fn undo_latest_quietly(program: &str) -> error::Result<Vec<RenameRecord>> {
+    let Some(journal) = find_latest_journal(program) else {
+        return Ok(Vec::new());
+    };
+    let plan: RestorePlan = RestorePlan::load(&journal, RecordFormat::Journal, Path::new("."))?;
+    plan.restore()?;
+    forget_journal(&journal)?;
+    Ok(plan.records())
+}
+
+/// undo the latest journaled run of `program`, forgetting its journal once undone
+pub fn undo_latest(program: &str) -> error::Result<()> {
+    let Some(journal) = find_latest_journal(program) else {
+        println!("{}", tr(Message::NothingToUndo, &[]));
+        return Ok(());
+    };
+    let plan: RestorePlan = RestorePlan::load(&journal, RecordFormat::Journal, Path::new("."))?;
+    if plan.restore_names(true)? {
+        forget_journal(&journal)?;
+    }
+    Ok(())
+}
+
+/// remove `journal` once undone, and the steps left of its run, not to be resumed
+fn forget_journal(journal: &Path) -> error::Result<()> {
+    fs::remove_file(journal).map_err(|e| Error::from_io(e, journal))?;
+    let _ = fs::remove_file(get_pending_path(journal));
+    Ok(())
+}
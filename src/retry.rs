@@ -0,0 +1,106 @@
+//! Retrying file operations failing for a moment, as renames and metadata reads
+//! on SMB and NFS shares now and then do, by `--retries` and `--retry-delay`,
+//! each paced by `--throttle`.
+
+use crate::{
+    i18n::{tr, Message},
+    throttle::{get_throttle_arg, paced, set_throttle},
+};
+use clap::{value_parser, Arg, ArgMatches};
+use std::{io, sync::OnceLock, thread, time::Duration};
+
+/// the retries after a transient failure, and the delay before the first one,
+/// doubled before each next one
+static RETRY_POLICY: OnceLock<(u32, Duration)> = OnceLock::new();
+
+const RETRIES_HELP_MESSAGE: &str = r#"Retry a rename or metadata read failing with a transient error, as on
+network shares, this many times"#;
+
+const RETRY_DELAY_HELP_MESSAGE: &str = r#"The wait before the first retry, e.g. 500ms, 2s or 1m, doubled before
+each next one"#;
+
+/// `EIO`, the error network file systems report for a dropped connection
+#[cfg(unix)]
+const EIO: i32 = 5;
+
+/// the `--retries`, `--retry-delay` and `--throttle` options of the tools
+/// changing files
+pub fn get_retry_args() -> [Arg; 3] {
+    [
+        Arg::new("retries")
+            .long("retries")
+            .help(RETRIES_HELP_MESSAGE)
+            .value_parser(value_parser!(u32))
+            .default_value("0"),
+        Arg::new("retry_delay")
+            .long("retry-delay")
+            .help(RETRY_DELAY_HELP_MESSAGE)
+            .value_parser(parse_delay)
+            .default_value("1s"),
+        get_throttle_arg(),
+    ]
+}
+
+/// parse a delay like `250ms`, `2s`, `1m` or `1h`, refusing one too long to hold
+fn parse_delay(text: &str) -> Result<Duration, String> {
+    let invalid = || tr(Message::InvalidDelay, &[&text]);
+    let split: usize = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let amount: u64 = text[..split].parse().map_err(|_| invalid())?;
+    let in_seconds = |seconds: u64| amount.checked_mul(seconds).map(Duration::from_secs);
+    match &text[split..] {
+        "ms" => Ok(Duration::from_millis(amount)),
+        "s" => Ok(Duration::from_secs(amount)),
+        "m" => in_seconds(60).ok_or_else(invalid),
+        "h" => in_seconds(3_600).ok_or_else(invalid),
+        _ => Err(tr(Message::InvalidDelayUnit, &[&text])),
+    }
+}
+
+/// retry the file operations of this run by `--retries` and `--retry-delay`,
+/// and pace them by `--throttle`
+pub fn set_retry_policy(matches: &ArgMatches) {
+    let _ = RETRY_POLICY.set((
+        *matches.get_one::<u32>("retries").unwrap(),
+        *matches.get_one::<Duration>("retry_delay").unwrap(),
+    ));
+    set_throttle(matches);
+}
+
+/// whether `err` may well not happen again in a moment
+fn is_transient(err: &io::Error) -> bool {
+    use io::ErrorKind::*;
+    #[cfg(unix)]
+    if err.raw_os_error() == Some(EIO) {
+        return true;
+    }
+    matches!(
+        err.kind(),
+        Interrupted
+            | WouldBlock
+            | TimedOut
+            | ResourceBusy
+            | StaleNetworkFileHandle
+            | ConnectionReset
+            | ConnectionAborted
+            | NetworkDown
+            | NetworkUnreachable
+            | HostUnreachable
+    )
+}
+
+/// run `operation` in its turn by `--throttle`, again after a growing delay
+/// while it fails with a transient error and retries of this run are left
+pub fn with_retries<T>(mut operation: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    let (retries, delay) = *RETRY_POLICY.get().unwrap_or(&(0, Duration::ZERO));
+    let mut delay: Duration = delay;
+    for _ in 0..retries {
+        match paced(&mut operation) {
+            Err(err) if is_transient(&err) => thread::sleep(delay),
+            result => return result,
+        }
+        delay = delay.saturating_mul(2);
+    }
+    paced(operation)
+}
@@ -0,0 +1,38 @@
+//! Turning arbitrary text (tags, titles, metadata) into a safe file name component.
+
+/// characters not allowed in file names on Windows, which also covers Linux and macOS
+pub const FORBIDDEN_CHARS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+const REPLACEMENT: char = '_';
+
+/// names Windows reserves for devices, with or without an extension
+pub const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Replace path separators, characters forbidden on common file systems and
+/// control characters, collapse whitespace, and trim leading/trailing spaces
+/// and trailing dots, so that `text` is usable as a single path component.
+///
+/// Returns an empty string when nothing usable is left.
+pub fn sanitize_component(text: &str) -> String {
+    let replaced: String = text
+        .chars()
+        .map(|c| {
+            if FORBIDDEN_CHARS.contains(&c) || c.is_control() {
+                REPLACEMENT
+            } else {
+                c
+            }
+        })
+        .collect();
+    let collapsed: String = replaced.split_whitespace().collect::<Vec<&str>>().join(" ");
+    let trimmed: &str = collapsed.trim_end_matches(['.', ' ']);
+
+    let stem: &str = trimmed.split('.').next().unwrap_or("");
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        return format!("{REPLACEMENT}{trimmed}");
+    }
+    trimmed.to_string()
+}
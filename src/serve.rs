@@ -0,0 +1,269 @@
+//! A long-running JSON-RPC 2.0 server by `--serve`, taking a request per line on
+//! standard input, or on each connection to a Unix socket by `--serve-socket`,
+//! so that editors and file managers plan and apply renames with the options of
+//! a tool without starting it for every file.
+//!
+//! The methods:
+//!   plan  {"paths": [...]}: the records of the files as planned, as by `--pipe`
+//!   apply {"paths": [...]}: rename as planned without asking, returning the
+//!                           records of what became of each file
+//!   undo  {}:               undo the latest run of the tool, returning the
+//!                           renames undone
+
+use crate::{
+    error::{self, Error},
+    guard::check_paths,
+    plan::RenamePlan,
+    restore::undo_latest_quietly,
+    walk::filter_paths,
+};
+use clap::{value_parser, Arg, ArgAction, ArgMatches};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+};
+
+const SERVE_HELP_MESSAGE: &str = r#"Serve JSON-RPC 2.0 requests, one per line on standard input, planning
+and renaming with the other options given: plan and apply take
+{"paths": [...]}, undo takes no parameters"#;
+
+/// the error codes of JSON-RPC 2.0; those of failed renames are the exit codes
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+
+#[derive(Deserialize)]
+struct Request {
+    /// none for a notification, which gets no response
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct PathsParams {
+    paths: Vec<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct Response {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ResponseError>,
+}
+
+#[derive(Serialize)]
+struct ResponseError {
+    code: i32,
+    message: String,
+    /// the records of the files of an `apply` failing partway
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<Value>,
+}
+
+impl ResponseError {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+/// the server of a tool planning renames of paths with `planner`
+struct Server<'a, F> {
+    program: &'a str,
+    planner: F,
+}
+
+impl<F: FnMut(&[PathBuf]) -> RenamePlan> Server<'_, F> {
+    /// answer the requests on `reader` on `writer`, until the end of `reader`
+    fn serve(&mut self, reader: impl BufRead, mut writer: impl Write) -> io::Result<()> {
+        for line in reader.lines() {
+            let line: String = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle(&line) {
+                serde_json::to_writer(&mut writer, &response)?;
+                writeln!(writer)?;
+                writer.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// the response to the request on `line`, none for a notification
+    fn handle(&mut self, line: &str) -> Option<Response> {
+        let value: Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(err) => return Some(error_response(Value::Null, PARSE_ERROR, err.to_string())),
+        };
+        let request: Request = match serde_json::from_value(value) {
+            Ok(request) => request,
+            Err(err) => {
+                return Some(error_response(
+                    Value::Null,
+                    INVALID_REQUEST,
+                    err.to_string(),
+                ));
+            }
+        };
+        let outcome: Result<Value, ResponseError> = self.call(&request);
+        let id: Value = request.id?;
+        Some(match outcome {
+            Ok(result) => Response {
+                jsonrpc: "2.0",
+                id,
+                result: Some(result),
+                error: None,
+            },
+            Err(error) => Response {
+                jsonrpc: "2.0",
+                id,
+                result: None,
+                error: Some(error),
+            },
+        })
+    }
+
+    fn call(&mut self, request: &Request) -> Result<Value, ResponseError> {
+        match request.method.as_str() {
+            "plan" => {
+                let plan: RenamePlan = self.plan(request)?;
+                let (records, _) = plan.run_piped(self.program, false);
+                Ok(to_value(&records))
+            }
+            "apply" => {
+                let plan: RenamePlan = self.plan(request)?;
+                match plan.run_piped(self.program, true) {
+                    (records, Ok(_)) => Ok(to_value(&records)),
+                    (records, Err(err)) => Err(ResponseError {
+                        data: Some(to_value(&records)),
+                        ..error_of(&err)
+                    }),
+                }
+            }
+            "undo" => match undo_latest_quietly(self.program) {
+                Ok(records) => Ok(to_value(&records)),
+                Err(err) => Err(error_of(&err)),
+            },
+            method => Err(ResponseError::new(
+                METHOD_NOT_FOUND,
+                format!("unknown method: {method}"),
+            )),
+        }
+    }
+
+    /// the plan for the paths of `request`, refusing guarded paths
+    fn plan(&mut self, request: &Request) -> Result<RenamePlan, ResponseError> {
+        let params: PathsParams = request
+            .params
+            .clone()
+            .ok_or_else(|| ResponseError::new(INVALID_PARAMS, "missing paths"))
+            .and_then(|p| {
+                serde_json::from_value(p)
+                    .map_err(|err| ResponseError::new(INVALID_PARAMS, err.to_string()))
+            })?;
+        let paths: Vec<PathBuf> = filter_paths(params.paths);
+        check_paths(&paths).map_err(|err| error_of(&err))?;
+        Ok((self.planner)(&paths))
+    }
+}
+
+fn error_response(id: Value, code: i32, message: String) -> Response {
+    Response {
+        jsonrpc: "2.0",
+        id,
+        result: None,
+        error: Some(ResponseError::new(code, message)),
+    }
+}
+
+/// the response error of `err`, coded by the exit code of the tools
+fn error_of(err: &Error) -> ResponseError {
+    ResponseError::new(err.exit_code(), err.to_string())
+}
+
+fn to_value<T: Serialize>(records: &T) -> Value {
+    serde_json::to_value(records).unwrap()
+}
+
+/// whether a tool was asked to serve, by `--serve` or `--serve-socket`
+pub fn is_serving(matches: &ArgMatches) -> bool {
+    matches.get_flag("serve") || matches.contains_id("serve_socket")
+}
+
+/// serve the requests of `--serve` on standard input, or those of each
+/// connection in turn to the socket of `--serve-socket`, planning the renames
+/// of `program` with `planner`
+pub fn serve(
+    program: &str,
+    matches: &ArgMatches,
+    planner: impl FnMut(&[PathBuf]) -> RenamePlan,
+) -> error::Result<()> {
+    let mut server = Server { program, planner };
+    match matches.get_one::<PathBuf>("serve_socket") {
+        Some(socket) => serve_socket(&mut server, socket),
+        None => server
+            .serve(io::stdin().lock(), io::stdout().lock())
+            .map_err(|e| Error::from_io(e, Path::new("-"))),
+    }
+}
+
+#[cfg(unix)]
+fn serve_socket<F: FnMut(&[PathBuf]) -> RenamePlan>(
+    server: &mut Server<F>,
+    socket: &Path,
+) -> error::Result<()> {
+    use std::{
+        io::BufReader,
+        os::unix::net::{UnixListener, UnixStream},
+    };
+    let listener: UnixListener =
+        UnixListener::bind(socket).map_err(|e| Error::from_io(e, socket))?;
+    for stream in listener.incoming() {
+        let stream: UnixStream = stream.map_err(|e| Error::from_io(e, socket))?;
+        let reader: BufReader<&UnixStream> = BufReader::new(&stream);
+        // a client going away ends its connection, not the server
+        if let Err(err) = server.serve(reader, &stream) {
+            eprintln!("{}: {err}", socket.display());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn serve_socket<F: FnMut(&[PathBuf]) -> RenamePlan>(
+    _server: &mut Server<F>,
+    _socket: &Path,
+) -> error::Result<()> {
+    Err(Error::InvalidFormat(
+        "--serve-socket needs Unix sockets".to_string(),
+    ))
+}
+
+/// the `--serve` and `--serve-socket` options of the tools renaming paths
+pub fn get_serve_args() -> [Arg; 2] {
+    [
+        Arg::new("serve")
+            .long("serve")
+            .help(SERVE_HELP_MESSAGE)
+            .action(ArgAction::SetTrue)
+            .conflicts_with("pipe"),
+        Arg::new("serve_socket")
+            .long("serve-socket")
+            .help("Serve the requests of --serve on the connections to this Unix socket")
+            .value_parser(value_parser!(PathBuf))
+            .conflicts_with("pipe"),
+    ]
+}
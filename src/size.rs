@@ -0,0 +1,40 @@
+const UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+
+/// `bytes` in the largest binary unit keeping the value at least 1, e.g. `5.1 MiB`
+pub fn format_size(bytes: u64) -> String {
+    let mut value: f64 = bytes as f64;
+    let mut unit: usize = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// parse a size like `4096`, `10K`, `1.5 GiB` or `20MB`, where bare and `iB` units
+/// are binary (K = KiB = 1024) and `B` units decimal (KB = 1000)
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text: &str = text.trim();
+    let split: usize = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let value: f64 = text[..split].parse().ok()?;
+    let unit: String = text[split..].trim().to_uppercase();
+    let (prefix, suffix): (&str, &str) = match unit.find(['I', 'B']) {
+        Some(i) => unit.split_at(i),
+        None => (&unit, "IB"),
+    };
+    let exponent: i32 = ["", "K", "M", "G", "T", "P"]
+        .iter()
+        .position(|p| *p == prefix)? as i32;
+    let base: f64 = match suffix {
+        "IB" | "" => 1024.0,
+        "B" => 1000.0,
+        _ => return None,
+    };
+    Some((value * base.powi(exponent)).round() as u64)
+}
@@ -0,0 +1,190 @@
+//! The order of the files a tool lists and renames: by name, byte by byte, or
+//! by `--sort natural` as with `ls -v`, numbers within names compared by value,
+//! so that `img2` comes before `img10`. `--sort pinyin` and `--sort stroke`
+//! collate Chinese names as a Chinese reader would, by the ICU collation of
+//! that name, whatever the locale of the system.
+
+use clap::{Arg, ArgMatches};
+use icu_collator::{Collator, CollatorOptions, Numeric};
+use icu_locid::{locale, Locale};
+use std::{cmp::Ordering, path::Path, sync::OnceLock};
+
+pub const SORT_ORDER_NAMES: [&str; 4] = ["name", "natural", "pinyin", "stroke"];
+
+const SORT_HELP_MESSAGE: &str = r#"The order of the files: name, byte by byte, or natural, with numbers
+compared by value, e.g. img2 before img10; pinyin or stroke, natural
+with Chinese characters by their pinyin or their strokes, e.g. 北京 before
+上海 by pinyin and after by stroke; the given order of the paths named on
+the command line, and name for directories, by default"#;
+
+/// the order set by `--sort`, none for the defaults
+static SORT_ORDER: OnceLock<Option<SortOrder>> = OnceLock::new();
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Name,
+    Natural,
+    /// by the pinyin of Chinese characters, as in mainland dictionaries
+    Pinyin,
+    /// by the strokes of Chinese characters, as in Taiwan and Hong Kong
+    Stroke,
+}
+
+impl SortOrder {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "name" => Self::Name,
+            "natural" => Self::Natural,
+            "pinyin" => Self::Pinyin,
+            "stroke" => Self::Stroke,
+            _ => panic!("Unknown sort order: {name}"),
+        }
+    }
+}
+
+/// the `--sort` option of the tools listing files
+pub fn get_sort_arg() -> Arg {
+    Arg::new("sort")
+        .long("sort")
+        .help(SORT_HELP_MESSAGE)
+        .value_name("ORDER")
+        .value_parser(SORT_ORDER_NAMES)
+}
+
+/// order the files of this run by `--sort`
+pub fn set_sort_order(matches: &ArgMatches) {
+    let order: Option<SortOrder> = matches
+        .try_get_one::<String>("sort")
+        .ok()
+        .flatten()
+        .map(|name| SortOrder::from(name));
+    let _ = SORT_ORDER.set(order);
+}
+
+/// the order set by `--sort` for this run, if any
+pub fn get_sort_order() -> Option<SortOrder> {
+    SORT_ORDER.get().copied().flatten()
+}
+
+/// compare `a` and `b` in the order of this run, by name unless another
+pub fn compare_paths(a: &Path, b: &Path) -> Ordering {
+    match get_sort_order() {
+        Some(SortOrder::Natural) => compare_natural_paths(a, b),
+        Some(order @ (SortOrder::Pinyin | SortOrder::Stroke)) => {
+            compare_components(a, b, |x, y| collate(order, x, y))
+        }
+        _ => a.cmp(b),
+    }
+}
+
+/// compare the names `a` and `b` in the order of this run, by name unless
+/// another
+pub fn compare_names(a: &str, b: &str) -> Ordering {
+    match get_sort_order() {
+        Some(SortOrder::Natural) => compare_natural(a, b),
+        Some(order @ (SortOrder::Pinyin | SortOrder::Stroke)) => {
+            collate(order, a, b).then_with(|| a.cmp(b))
+        }
+        _ => a.cmp(b),
+    }
+}
+
+thread_local! {
+    /// the collators of `--sort pinyin` and `stroke`, one a thread as they
+    /// cannot be shared
+    static PINYIN: Collator = new_collator(locale!("zh"));
+    static STROKE: Collator = new_collator(locale!("zh-u-co-stroke"));
+}
+
+/// the collator of `locale`, numbers within names compared by value as by
+/// `--sort natural`
+fn new_collator(locale: Locale) -> Collator {
+    let mut options: CollatorOptions = CollatorOptions::new();
+    options.numeric = Some(Numeric::On);
+    // the collation data is built in, so that it cannot be missing
+    Collator::try_new(&(&locale).into(), options).expect("built-in collation data")
+}
+
+/// compare `a` and `b` by the collation of `order`, pinyin or stroke
+fn collate(order: SortOrder, a: &str, b: &str) -> Ordering {
+    let collator = match order {
+        SortOrder::Stroke => &STROKE,
+        _ => &PINYIN,
+    };
+    collator.with(|c| c.compare(a, b))
+}
+
+/// compare `a` and `b` naturally component by component, so that a directory
+/// comes before the entries of its later siblings
+pub fn compare_natural_paths(a: &Path, b: &Path) -> Ordering {
+    compare_components(a, b, compare_natural)
+}
+
+/// compare `a` and `b` component by component with `compare`, then byte by
+/// byte when alike
+fn compare_components(a: &Path, b: &Path, compare: impl Fn(&str, &str) -> Ordering) -> Ordering {
+    let mut a_components = a.components();
+    let mut b_components = b.components();
+    loop {
+        match (a_components.next(), b_components.next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let ordering: Ordering = compare(
+                    &x.as_os_str().to_string_lossy(),
+                    &y.as_os_str().to_string_lossy(),
+                );
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+        }
+    }
+}
+
+/// compare `a` and `b` with their runs of digits by value, e.g. `img2` before
+/// `img10`, and the rest character by character; of equal values, the one
+/// with fewer leading zeros comes first
+pub fn compare_natural(a: &str, b: &str) -> Ordering {
+    let (mut a_chars, mut b_chars) = (a.chars().peekable(), b.chars().peekable());
+    let mut zeros: Ordering = Ordering::Equal;
+    loop {
+        let (x, y) = match (a_chars.peek(), b_chars.peek()) {
+            (None, None) => return zeros,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => (*x, *y),
+        };
+        if x.is_ascii_digit() && y.is_ascii_digit() {
+            let (x_digits, y_digits) = (take_digits(&mut a_chars), take_digits(&mut b_chars));
+            let (x_value, y_value) = (
+                x_digits.trim_start_matches('0'),
+                y_digits.trim_start_matches('0'),
+            );
+            let ordering: Ordering = x_value
+                .len()
+                .cmp(&y_value.len())
+                .then_with(|| x_value.cmp(y_value));
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+            zeros = zeros.then(x_digits.len().cmp(&y_digits.len()));
+            continue;
+        }
+        if x != y {
+            return x.cmp(&y);
+        }
+        a_chars.next();
+        b_chars.next();
+    }
+}
+
+/// the run of ASCII digits at the start of `chars`
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut digits: String = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        digits.push(c);
+    }
+    digits
+}
@@ -0,0 +1,161 @@
+//! The counts printed at the end of a run changing files, for reading or as JSON
+//! by `--stats json` for the scripts tracking what each run did.
+
+use crate::{
+    i18n::{tr, Message},
+    size::format_size,
+};
+use clap::{Arg, ArgMatches};
+use serde::Serialize;
+use std::{ops::Add, sync::OnceLock, time::Duration};
+
+pub const STATS_FORMAT_NAMES: [&str; 3] = ["text", "json", "none"];
+
+const STATS_HELP_MESSAGE: &str = r#"How to print the counts of what was done at the end of the run:
+  text: a line for reading
+  json: a JSON object, for scripts
+  none: nothing"#;
+
+static STATS_FORMAT: OnceLock<StatsFormat> = OnceLock::new();
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatsFormat {
+    #[default]
+    Text,
+    Json,
+    None,
+}
+
+impl StatsFormat {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "text" => Self::Text,
+            "json" => Self::Json,
+            "none" => Self::None,
+            _ => panic!("Unknown stats format: {name}"),
+        }
+    }
+}
+
+#[derive(Serialize, Default)]
+pub struct RunStats {
+    pub renamed: usize,
+    /// files left as they are when planning, conflicts skipped among them
+    pub skipped: usize,
+    /// new names taken by existing files or by the new names of other files
+    pub conflicts: usize,
+    pub failed: usize,
+    pub elapsed_seconds: f64,
+    /// the bytes of the files moved, by the tools moving files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes_moved: Option<u64>,
+    /// the files left out as duplicates of files at the destination, by the
+    /// tools importing files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicates: Option<usize>,
+    /// the files moved to the trash instead of renamed, by
+    /// `rename_mod_time --rotate`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trashed: Option<usize>,
+}
+
+impl RunStats {
+    pub fn with_elapsed(self, elapsed: Duration) -> Self {
+        Self {
+            elapsed_seconds: elapsed.as_secs_f64(),
+            ..self
+        }
+    }
+
+    /// the counts as lines for reading
+    pub fn summary(&self) -> String {
+        let elapsed: String = format!("{:.2} s", self.elapsed_seconds);
+        let args: [&dyn std::fmt::Display; 5] = [
+            &self.renamed,
+            &self.skipped,
+            &self.conflicts,
+            &self.failed,
+            &elapsed,
+        ];
+        let mut summary: String = tr(Message::RunStats, &args);
+        if let Some(bytes) = self.bytes_moved {
+            summary.push('\n');
+            summary.push_str(&tr(Message::BytesMoved, &[&format_size(bytes)]));
+        }
+        if let Some(duplicates) = self.duplicates.filter(|d| *d > 0) {
+            summary.push('\n');
+            summary.push_str(&tr(Message::DuplicatesLeft, &[&duplicates]));
+        }
+        if let Some(trashed) = self.trashed {
+            summary.push('\n');
+            summary.push_str(&tr(Message::FilesTrashed, &[&trashed]));
+        }
+        summary
+    }
+
+    /// the counts in the format given by `--stats`, none for `none`
+    fn render(&self) -> Option<String> {
+        match STATS_FORMAT.get().copied().unwrap_or_default() {
+            StatsFormat::Text => Some(self.summary()),
+            StatsFormat::Json => Some(serde_json::to_string(self).unwrap()),
+            StatsFormat::None => None,
+        }
+    }
+
+    /// print the counts in the format given by `--stats`
+    pub fn print(&self) {
+        if let Some(text) = self.render() {
+            println!("{text}");
+        }
+    }
+
+    /// print the counts as `print` does but to standard error, for a run
+    /// printing records on standard output
+    pub fn eprint(&self) {
+        if let Some(text) = self.render() {
+            eprintln!("{text}");
+        }
+    }
+}
+
+/// the counts of a run made of two parts, as of its chunks
+impl Add for RunStats {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            renamed: self.renamed + other.renamed,
+            skipped: self.skipped + other.skipped,
+            conflicts: self.conflicts + other.conflicts,
+            failed: self.failed + other.failed,
+            elapsed_seconds: self.elapsed_seconds + other.elapsed_seconds,
+            bytes_moved: add_counts(self.bytes_moved, other.bytes_moved),
+            duplicates: add_counts(self.duplicates, other.duplicates),
+            trashed: add_counts(self.trashed, other.trashed),
+        }
+    }
+}
+
+/// the sum of two counts kept by some runs only, none when neither kept it
+fn add_counts<T: Add<Output = T>>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, b) => a.or(b),
+    }
+}
+
+/// the `--stats` option of the tools changing files
+pub fn get_stats_arg() -> Arg {
+    Arg::new("stats")
+        .long("stats")
+        .help(STATS_HELP_MESSAGE)
+        .value_parser(STATS_FORMAT_NAMES)
+        .default_value("text")
+}
+
+/// print the counts of this run in the format given by `--stats`
+pub fn set_stats_format(matches: &ArgMatches) {
+    let _ = STATS_FORMAT.set(StatsFormat::from(
+        matches.get_one::<String>("stats").unwrap(),
+    ));
+}
@@ -0,0 +1,289 @@
+//! The tables tools print as previews and reports, aligned by display width
+//! so that CJK names line up.
+
+use crate::{
+    preview::{display_width, pad_to_width},
+    prompt::print_paged,
+};
+use console::{colors_enabled, Color, Style};
+use std::iter::zip;
+
+/// the gap between columns of a table without borders
+const COLUMN_GAP: &str = "  ";
+
+/// the mark replacing the start of a truncated cell
+const ELLIPSIS: char = '…';
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    Left,
+    Right,
+    Center,
+}
+
+struct Column {
+    header: String,
+    alignment: Alignment,
+    max_width: Option<usize>,
+    color: Option<Color>,
+    /// the column whose cells those of this one are compared with
+    diff_against: Option<usize>,
+}
+
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+    has_borders: bool,
+    has_colors: bool,
+}
+
+impl Table {
+    /// a table of left-aligned columns, colored when the terminal supports it
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            columns: headers
+                .iter()
+                .map(|h| Column {
+                    header: h.to_string(),
+                    alignment: Alignment::Left,
+                    max_width: None,
+                    color: None,
+                    diff_against: None,
+                })
+                .collect(),
+            rows: vec![],
+            has_borders: false,
+            has_colors: colors_enabled(),
+        }
+    }
+
+    pub fn align(mut self, column: usize, alignment: Alignment) -> Self {
+        self.columns[column].alignment = alignment;
+        self
+    }
+
+    /// cut the cells of `column` wider than `width`, keeping their end,
+    /// where file names are
+    pub fn truncate(mut self, column: usize, width: usize) -> Self {
+        self.columns[column].max_width = Some(width);
+        self
+    }
+
+    pub fn color(mut self, column: usize, color: Color) -> Self {
+        self.columns[column].color = Some(color);
+        self
+    }
+
+    /// highlight in each cell of `column` what differs from the cell of `against`,
+    /// dimming the start and end they share, as a new name against the old one
+    pub fn diff(mut self, column: usize, against: usize) -> Self {
+        self.columns[column].diff_against = Some(against);
+        self
+    }
+
+    pub fn with_borders(mut self, has_borders: bool) -> Self {
+        self.has_borders = has_borders;
+        self
+    }
+
+    pub fn with_colors(mut self, has_colors: bool) -> Self {
+        self.has_colors = has_colors;
+        self
+    }
+
+    pub fn add_row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// the lines of the table, header first
+    pub fn render(&self) -> Vec<String> {
+        let rows: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(&self.columns)
+                    .map(|(cell, column)| match column.max_width {
+                        Some(width) => truncate_start(cell, width),
+                        None => cell.clone(),
+                    })
+                    .collect()
+            })
+            .collect();
+        let widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                rows.iter()
+                    .map(|row| display_width(&row[i]))
+                    .chain([display_width(&column.header)])
+                    .max()
+                    .unwrap()
+            })
+            .collect();
+
+        let header: Vec<String> = self.columns.iter().map(|c| c.header.clone()).collect();
+        let bold: Style = Style::new().bold().force_styling(self.has_colors);
+        let mut lines: Vec<String> = vec![];
+        let separator: String = widths
+            .iter()
+            .map(|w| "-".repeat(w + 2))
+            .collect::<Vec<String>>()
+            .join("+");
+        if self.has_borders {
+            lines.push(format!("+{separator}+"));
+        }
+        lines.push(self.render_row(&header, &widths, Some(&bold)));
+        if self.has_borders {
+            lines.push(format!("+{separator}+"));
+        }
+        for row in &rows {
+            lines.push(self.render_row(row, &widths, None));
+        }
+        if self.has_borders {
+            lines.push(format!("+{separator}+"));
+        }
+        lines
+    }
+
+    /// print the table, through a pager when it does not fit in the terminal
+    pub fn print(&self) {
+        print_paged(&self.render());
+    }
+
+    fn render_row(&self, cells: &[String], widths: &[usize], style: Option<&Style>) -> String {
+        let last: usize = cells.len() - 1;
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&self.columns)
+            .zip(widths)
+            .enumerate()
+            .map(|(i, ((cell, column), width))| {
+                if let (None, Some(against), true) = (style, column.diff_against, self.has_colors) {
+                    let (left, right) = get_padding(cell, *width, column.alignment);
+                    // nothing follows the last column to be aligned with
+                    let right: usize = if i == last && !self.has_borders {
+                        0
+                    } else {
+                        right
+                    };
+                    let diff: String = render_diff(cell, &cells[against], column.color);
+                    return format!("{}{diff}{}", " ".repeat(left), " ".repeat(right));
+                }
+                let mut text: String = align(cell, *width, column.alignment);
+                // nothing follows the last column to be aligned with
+                if i == last && !self.has_borders {
+                    text.truncate(text.trim_end().len());
+                }
+                let style: Style = match (style, column.color) {
+                    (Some(style), _) => style.clone(),
+                    (None, Some(color)) => Style::new().fg(color),
+                    (None, None) => Style::new(),
+                };
+                style
+                    .force_styling(self.has_colors)
+                    .apply_to(text)
+                    .to_string()
+            })
+            .collect();
+        if self.has_borders {
+            format!("| {} |", padded.join(" | "))
+        } else {
+            padded.join(COLUMN_GAP)
+        }
+    }
+}
+
+/// `text` padded with spaces to `width` display columns
+fn align(text: &str, width: usize, alignment: Alignment) -> String {
+    match alignment {
+        Alignment::Left => pad_to_width(text, width),
+        _ => {
+            let (left, right) = get_padding(text, width, alignment);
+            format!("{}{text}{}", " ".repeat(left), " ".repeat(right))
+        }
+    }
+}
+
+/// the spaces before and after `text` aligning it in `width` display columns
+fn get_padding(text: &str, width: usize, alignment: Alignment) -> (usize, usize) {
+    let padding: usize = width.saturating_sub(display_width(text));
+    match alignment {
+        Alignment::Left => (0, padding),
+        Alignment::Right => (padding, 0),
+        Alignment::Center => (padding / 2, padding - padding / 2),
+    }
+}
+
+/// `text` in `color`, with the words at its start and end it shares with `other`
+/// dimmed and the rest in bold, so that what changed stands out
+fn render_diff(text: &str, other: &str, color: Option<Color>) -> String {
+    let words: Vec<&str> = split_words(text);
+    let other_words: Vec<&str> = split_words(other);
+    let prefix: usize = zip(&words, &other_words)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix: usize = zip(
+        words[prefix..].iter().rev(),
+        other_words[prefix..].iter().rev(),
+    )
+    .take_while(|(a, b)| a == b)
+    .count();
+    let style: Style = match color {
+        Some(color) => Style::new().fg(color).force_styling(true),
+        None => Style::new().force_styling(true),
+    };
+    [
+        (&words[..prefix], style.clone().dim()),
+        (&words[prefix..words.len() - suffix], style.clone().bold()),
+        (&words[words.len() - suffix..], style.dim()),
+    ]
+    .into_iter()
+    .filter(|(part, _)| !part.is_empty())
+    .map(|(part, style)| style.apply_to(part.concat()).to_string())
+    .collect()
+}
+
+/// `text` as runs of letters and digits, and each other character on its own
+fn split_words(text: &str) -> Vec<&str> {
+    let mut words: Vec<&str> = vec![];
+    let mut start: usize = 0;
+    for (i, c) in text.char_indices() {
+        let next: usize = i + c.len_utf8();
+        let is_word_going_on: bool = c.is_alphanumeric()
+            && text[next..]
+                .chars()
+                .next()
+                .is_some_and(char::is_alphanumeric);
+        if !is_word_going_on {
+            words.push(&text[start..next]);
+            start = next;
+        }
+    }
+    words
+}
+
+/// the end of `text` fitting in `width` display columns, after an ellipsis
+fn truncate_start(text: &str, width: usize) -> String {
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+    let mut kept: Vec<char> = vec![];
+    let mut kept_width: usize = 1; // the ellipsis
+    for c in text.chars().rev() {
+        let char_width: usize = display_width(c.encode_utf8(&mut [0; 4]));
+        if kept_width + char_width > width {
+            break;
+        }
+        kept_width += char_width;
+        kept.push(c);
+    }
+    std::iter::once(ELLIPSIS)
+        .chain(kept.into_iter().rev())
+        .collect()
+}
@@ -0,0 +1,341 @@
+//! File name templates such as `{artist}/{album}/{track:02} - {title}.{ext}`.
+//!
+//! A placeholder is a key in braces, optionally followed by `:` and a minimum
+//! width; a width starting with `0` pads with zeros instead of spaces. Filters
+//! after `|` transform the value before padding, in order, e.g.
+//! `{title|slug|trunc:20}`. `{{` and `}}` stand for literal braces.
+//!
+//! `{parent}` stands for the name of the directory of the file, and
+//! `{parent:N}` for its Nth word, counting from the end when negative, its
+//! `:N` an argument of the key rather than a width. `{line1}` stands for the
+//! first line of a text file, and `{head:N}` for its first N characters.
+//! `{location}` and `{country}` stand for where a photo was taken, by its GPS
+//! coordinates, and `{camera}` and `{lens}` for what it was taken with.
+
+use crate::{
+    exif::lookup_camera,
+    geo::lookup_location,
+    i18n::{tr, Message},
+    sanitize::sanitize_component,
+};
+use std::{
+    fmt,
+    fs::File,
+    io::{self, Read},
+    path::{self, Path},
+};
+
+/// the keys whose text after `:` is an argument, passed on with the key
+const KEYS_WITH_ARGUMENT: [&str; 2] = ["parent", "head"];
+
+/// the most of a file read for `{line1}` and `{head:N}`
+const CONTENT_READ_LIMIT: u64 = 64 * 1024;
+
+/// a transformation of the value of a placeholder, after `|`
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    Lower,
+    Upper,
+    /// lower case words of letters and digits joined by `-`
+    Slug,
+    /// the first characters, at most this many
+    Truncate(usize),
+}
+
+impl Filter {
+    fn parse(text: &str) -> Result<Self, TemplateError> {
+        let (name, arg) = match text.trim().split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (text.trim(), None),
+        };
+        match (name, arg) {
+            ("lower", None) => Ok(Self::Lower),
+            ("upper", None) => Ok(Self::Upper),
+            ("slug", None) => Ok(Self::Slug),
+            ("trunc", Some(length)) => length
+                .parse()
+                .map(Self::Truncate)
+                .map_err(|_| TemplateError::InvalidFilter(text.to_string())),
+            _ => Err(TemplateError::InvalidFilter(text.to_string())),
+        }
+    }
+
+    fn apply(&self, value: &str) -> String {
+        match self {
+            Self::Lower => value.to_lowercase(),
+            Self::Upper => value.to_uppercase(),
+            Self::Slug => value
+                .to_lowercase()
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .collect::<Vec<&str>>()
+                .join("-"),
+            Self::Truncate(length) => value.chars().take(*length).collect(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum Part {
+    Literal(String),
+    Placeholder {
+        key: String,
+        width: usize,
+        zero_padded: bool,
+        filters: Vec<Filter>,
+    },
+}
+
+#[derive(Clone)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug)]
+pub enum TemplateError {
+    /// a `{` without its `}`, or a lone `}`
+    UnbalancedBrace(usize),
+    /// the text after `:` is not a width
+    InvalidWidth(String),
+    /// the text after `|` is not a known filter
+    InvalidFilter(String),
+    /// the key of a placeholder has no value
+    MissingValue(String),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnbalancedBrace(position) => {
+                write!(f, "{}", tr(Message::TemplateUnbalancedBrace, &[position]))
+            }
+            Self::InvalidWidth(spec) => write!(f, "{}", tr(Message::TemplateInvalidWidth, &[spec])),
+            Self::InvalidFilter(filter) => {
+                write!(f, "{}", tr(Message::TemplateInvalidFilter, &[filter]))
+            }
+            Self::MissingValue(key) => {
+                let placeholder: String = format!("{{{key}}}");
+                write!(f, "{}", tr(Message::TemplateMissingValue, &[&placeholder]))
+            }
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+impl Template {
+    pub fn parse(text: &str) -> Result<Self, TemplateError> {
+        let mut parts: Vec<Part> = Vec::new();
+        let mut literal: String = String::new();
+        let mut chars = text.char_indices().peekable();
+        while let Some((position, c)) = chars.next() {
+            match c {
+                '{' if chars.peek().is_some_and(|(_, n)| *n == '{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek().is_some_and(|(_, n)| *n == '}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '}' => return Err(TemplateError::UnbalancedBrace(position)),
+                '{' => {
+                    let mut inner: String = String::new();
+                    loop {
+                        match chars.next() {
+                            Some((_, '}')) => break,
+                            Some((_, c)) => inner.push(c),
+                            None => return Err(TemplateError::UnbalancedBrace(position)),
+                        }
+                    }
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Self::parse_placeholder(&inner)?);
+                }
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Self { parts })
+    }
+
+    /// the literal text and placeholders, in order of appearance
+    pub fn parts(&self) -> &[Part] {
+        &self.parts
+    }
+
+    /// the keys of all placeholders, in order of appearance
+    pub fn keys(&self) -> Vec<&str> {
+        self.parts
+            .iter()
+            .filter_map(|p| match p {
+                Part::Placeholder { key, .. } => Some(key.as_str()),
+                Part::Literal(_) => None,
+            })
+            .collect()
+    }
+
+    /// the placeholders with the values returned by `lookup`, as `key=value`
+    /// separated by commas, for `--explain`
+    pub fn describe<F>(&self, lookup: F) -> String
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        self.keys()
+            .into_iter()
+            .filter_map(|key| lookup(key).map(|value| format!("{key}={value}")))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    /// fill the placeholders with the values returned by `lookup`
+    pub fn render<F>(&self, lookup: F) -> Result<String, TemplateError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut rendered: String = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => rendered.push_str(text),
+                Part::Placeholder {
+                    key,
+                    width,
+                    zero_padded,
+                    filters,
+                } => {
+                    let value: String = filters.iter().fold(
+                        lookup(key).ok_or_else(|| TemplateError::MissingValue(key.clone()))?,
+                        |value, filter| filter.apply(&value),
+                    );
+                    let padding: usize = width.saturating_sub(value.chars().count());
+                    let pad: char = if *zero_padded { '0' } else { ' ' };
+                    rendered.extend(std::iter::repeat_n(pad, padding));
+                    rendered.push_str(&value);
+                }
+            }
+        }
+        Ok(rendered)
+    }
+
+    fn parse_placeholder(inner: &str) -> Result<Part, TemplateError> {
+        let mut segments = inner.split('|');
+        let head: &str = segments.next().unwrap_or_default();
+        let filters: Vec<Filter> = segments.map(Filter::parse).collect::<Result<_, _>>()?;
+        let (key, spec) = match head.split_once(':') {
+            Some((key, spec)) => (key, Some(spec)),
+            None => (head, None),
+        };
+        if KEYS_WITH_ARGUMENT.contains(&key.trim()) {
+            return Ok(Part::Placeholder {
+                key: head.trim().to_string(),
+                width: 0,
+                zero_padded: false,
+                filters,
+            });
+        }
+        let (width, zero_padded) = match spec {
+            None => (0, false),
+            Some(spec) => (
+                spec.parse::<usize>()
+                    .map_err(|_| TemplateError::InvalidWidth(spec.to_string()))?,
+                spec.starts_with('0'),
+            ),
+        };
+        Ok(Part::Placeholder {
+            key: key.trim().to_string(),
+            width,
+            zero_padded,
+            filters,
+        })
+    }
+}
+
+/// the value of `{parent}` or `{parent:N}` as `key` for the file at `path`,
+/// none for another key or a word the name lacks; the words of a name are
+/// separated by spaces, `_`, `-` and `.`
+pub fn lookup_parent(key: &str, path: &Path) -> Option<String> {
+    let (key, argument) = match key.split_once(':') {
+        Some((key, argument)) => (key, Some(argument)),
+        None => (key, None),
+    };
+    if key != "parent" {
+        return None;
+    }
+    let absolute = path::absolute(path).ok()?;
+    let name: String = absolute
+        .parent()?
+        .file_name()?
+        .to_string_lossy()
+        .into_owned();
+    let Some(argument) = argument else {
+        return Some(name);
+    };
+    let index: isize = argument.trim().parse().ok()?;
+    let words: Vec<&str> = name
+        .split([' ', '_', '-', '.'])
+        .filter(|w| !w.is_empty())
+        .collect();
+    let position: usize = match index {
+        0 => return None,
+        1.. => index as usize - 1,
+        _ => words.len().checked_sub(index.unsigned_abs())?,
+    };
+    words.get(position).map(|w| w.to_string())
+}
+
+/// the value of `{line1}` or `{head:N}` as `key` for the file at `path`: its
+/// first line that is not blank, or its first N characters with whitespace
+/// collapsed, made fit for a file name; none for another key, a file that is
+/// not text or a value left empty
+pub fn lookup_content(key: &str, path: &Path) -> Option<String> {
+    let length: Option<usize> = match key.split_once(':') {
+        Some(("head", argument)) => Some(argument.trim().parse().ok()?),
+        None if key == "line1" => None,
+        _ => return None,
+    };
+    let text: String = read_text(path).ok()??;
+    let value: String = match length {
+        Some(length) => text
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ")
+            .chars()
+            .take(length)
+            .collect(),
+        None => text.lines().find(|l| !l.trim().is_empty())?.to_string(),
+    };
+    Some(sanitize_component(&value)).filter(|v| !v.is_empty())
+}
+
+/// the value of a key standing for something of the file at `path` rather
+/// than a value of its own, by [`lookup_parent`], [`lookup_content`],
+/// [`lookup_location`] or [`lookup_camera`]
+pub fn lookup_file(key: &str, path: &Path) -> Option<String> {
+    lookup_parent(key, path)
+        .or_else(|| lookup_content(key, path))
+        .or_else(|| lookup_location(key, path))
+        .or_else(|| lookup_camera(key, path))
+}
+
+/// the start of the file at `path`, none when it is not UTF-8 text; a
+/// character cut at the end of what is read is left out
+fn read_text(path: &Path) -> io::Result<Option<String>> {
+    let mut bytes: Vec<u8> = Vec::new();
+    File::open(path)?
+        .take(CONTENT_READ_LIMIT)
+        .read_to_end(&mut bytes)?;
+    if bytes.contains(&0) {
+        return Ok(None);
+    }
+    let text: &str = match std::str::from_utf8(&bytes) {
+        Ok(text) => text,
+        Err(err) if err.error_len().is_none() => {
+            std::str::from_utf8(&bytes[..err.valid_up_to()]).unwrap()
+        }
+        Err(_) => return Ok(None),
+    };
+    Ok(Some(text.trim_start_matches('\u{feff}').to_string()))
+}
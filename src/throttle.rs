@@ -0,0 +1,183 @@
+//! Pacing the file operations of a run by `--throttle`, so that a large batch
+//! against a small NAS or a cloud-mounted file system leaves room for others:
+//! metadata reads and renames to so many a second, copies to so many bytes a
+//! second, both slowed further for as long as the operations take unusually
+//! long.
+
+use crate::{
+    i18n::{tr, Message},
+    size::parse_size,
+};
+use clap::{Arg, ArgAction, ArgMatches};
+use std::{
+    sync::{Mutex, OnceLock},
+    thread,
+    time::{Duration, Instant},
+};
+
+const THROTTLE_HELP_MESSAGE: &str = r#"Pace the file operations, comma-separated: a number of metadata reads
+and renames a second, e.g. 50/s, and a size copied a second, e.g.
+20MB/s; while an operation takes several times longer than usual, as
+on a busy share, the pace slows down, and recovers once it is back"#;
+
+/// how much longer than the usual an operation takes for the pace to slow
+const LATENCY_SPIKE_FACTOR: u32 = 4;
+
+/// the least an operation takes to count as unusually long, above the jitter
+/// of fast local file systems
+const MIN_LATENCY_SPIKE: Duration = Duration::from_millis(20);
+
+/// the most the pace slows down by
+const MAX_BACKOFF: f64 = 64.0;
+
+/// the pacing of this run, if any
+static THROTTLE: OnceLock<Option<Throttle>> = OnceLock::new();
+
+/// the rates of `--throttle`
+#[derive(Clone, Copy, Default)]
+pub struct Rates {
+    ops_per_second: Option<f64>,
+    bytes_per_second: Option<f64>,
+}
+
+struct Throttle {
+    rates: Rates,
+    state: Mutex<Pace>,
+}
+
+struct Pace {
+    /// when the next operation may start
+    next_operation: Instant,
+    /// when the next bytes may be copied
+    next_bytes: Instant,
+    /// the moving average of how long an operation takes
+    latency: Option<Duration>,
+    /// what the rates are divided by, 1 unless operations took unusually long
+    backoff: f64,
+}
+
+impl Throttle {
+    /// wait for the turn of an operation, reserving it
+    fn wait_operation(&self) {
+        let Some(rate) = self.rates.ops_per_second else {
+            return;
+        };
+        let mut pace = self.state.lock().unwrap();
+        let start: Instant = pace.next_operation.max(Instant::now());
+        pace.next_operation = start + Duration::from_secs_f64(pace.backoff / rate);
+        drop(pace);
+        thread::sleep(start.saturating_duration_since(Instant::now()));
+    }
+
+    /// wait until `count` more bytes copied keep to the rate
+    fn wait_bytes(&self, count: u64) {
+        let Some(rate) = self.rates.bytes_per_second else {
+            return;
+        };
+        let mut pace = self.state.lock().unwrap();
+        let start: Instant = pace.next_bytes.max(Instant::now());
+        pace.next_bytes = start + Duration::from_secs_f64(count as f64 * pace.backoff / rate);
+        let end: Instant = pace.next_bytes;
+        drop(pace);
+        thread::sleep(end.saturating_duration_since(Instant::now()));
+    }
+
+    /// slow down after an operation taking `elapsed`, several times the
+    /// usual, or else speed back up a little
+    fn record(&self, elapsed: Duration) {
+        let mut pace = self.state.lock().unwrap();
+        pace.backoff = match pace.latency {
+            Some(latency)
+                if elapsed > MIN_LATENCY_SPIKE && elapsed > latency * LATENCY_SPIKE_FACTOR =>
+            {
+                (pace.backoff * 2.0).min(MAX_BACKOFF)
+            }
+            _ => (pace.backoff * 0.9).max(1.0),
+        };
+        pace.latency = Some(match pace.latency {
+            Some(latency) => latency.mul_f64(0.8) + elapsed.mul_f64(0.2),
+            None => elapsed,
+        });
+    }
+}
+
+/// parse a rate like `50/s` or `50`, operations a second, or `20MB/s`, a
+/// size a second
+fn parse_rate(text: &str) -> Result<Rates, String> {
+    let amount: &str = text.trim().strip_suffix("/s").unwrap_or(text.trim());
+    if let Ok(count) = amount.parse::<f64>() {
+        return match count > 0.0 && count.is_finite() {
+            true => Ok(Rates {
+                ops_per_second: Some(count),
+                ..Rates::default()
+            }),
+            false => Err(tr(Message::InvalidRate, &[&text])),
+        };
+    }
+    match parse_size(amount) {
+        Some(size) if size > 0 => Ok(Rates {
+            bytes_per_second: Some(size as f64),
+            ..Rates::default()
+        }),
+        _ => Err(tr(Message::InvalidRateUnit, &[&text])),
+    }
+}
+
+/// the `--throttle` option of the tools changing files
+pub fn get_throttle_arg() -> Arg {
+    Arg::new("throttle")
+        .long("throttle")
+        .help(THROTTLE_HELP_MESSAGE)
+        .value_name("RATE")
+        .value_parser(parse_rate)
+        .value_delimiter(',')
+        .action(ArgAction::Append)
+}
+
+/// pace the file operations of this run by `--throttle`
+pub fn set_throttle(matches: &ArgMatches) {
+    let rates: Rates = matches
+        .try_get_many::<Rates>("throttle")
+        .ok()
+        .flatten()
+        .into_iter()
+        .flatten()
+        .fold(Rates::default(), |all, rates| Rates {
+            ops_per_second: rates.ops_per_second.or(all.ops_per_second),
+            bytes_per_second: rates.bytes_per_second.or(all.bytes_per_second),
+        });
+    let is_throttled: bool = rates.ops_per_second.is_some() || rates.bytes_per_second.is_some();
+    let _ = THROTTLE.set(is_throttled.then(|| Throttle {
+        rates,
+        state: Mutex::new(Pace {
+            next_operation: Instant::now(),
+            next_bytes: Instant::now(),
+            latency: None,
+            backoff: 1.0,
+        }),
+    }));
+}
+
+fn get_throttle() -> Option<&'static Throttle> {
+    THROTTLE.get().and_then(Option::as_ref)
+}
+
+/// run the file operation `operation` in its turn by `--throttle`, timing it
+/// to slow down while the file system is slow
+pub fn paced<T>(operation: impl FnOnce() -> T) -> T {
+    let Some(throttle) = get_throttle() else {
+        return operation();
+    };
+    throttle.wait_operation();
+    let start: Instant = Instant::now();
+    let result: T = operation();
+    throttle.record(start.elapsed());
+    result
+}
+
+/// wait after copying `count` bytes until the copy keeps to `--throttle`
+pub fn pace_bytes(count: u64) {
+    if let Some(throttle) = get_throttle() {
+        throttle.wait_bytes(count);
+    }
+}
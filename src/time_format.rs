@@ -0,0 +1,407 @@
+//! Date and time formats naming files by time: chrono's strftime, extended with
+//! placeholders in braces for what it lacks, e.g. `%Y_{yday:03}` or `{base36}`.
+
+use crate::{
+    age::parse_age,
+    i18n::{tr, Message},
+    template::{lookup_file, Part, Template, TemplateError},
+};
+use chrono::{DateTime, Datelike, TimeDelta, TimeZone, Utc, Weekday};
+use clap::{Arg, ArgAction, ArgMatches};
+use std::{fmt::Display, path::Path, sync::OnceLock};
+
+pub const WEEKDAY_NAMES: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+pub const TIME_SOURCE_NAMES: [&str; 2] = ["modified", "created"];
+
+const TIME_SOURCE_HELP_MESSAGE: &str = r#"The time of each file to name it by: modified, or created, where the
+file system keeps the creation time, as on Windows and macOS"#;
+
+/// the time of the files naming them in this run
+static TIME_SOURCE: OnceLock<TimeSource> = OnceLock::new();
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeSource {
+    #[default]
+    Modified,
+    Created,
+}
+
+impl TimeSource {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "modified" => Self::Modified,
+            "created" => Self::Created,
+            _ => panic!("Unknown time source: {name}"),
+        }
+    }
+
+    /// the time as named in explanations, with its UTC `offset`, e.g. `+08:00`
+    pub fn explain(&self, offset: &str) -> String {
+        match self {
+            Self::Modified => tr(Message::ExplainModificationTime, &[&offset]),
+            Self::Created => tr(Message::ExplainCreationTime, &[&offset]),
+        }
+    }
+}
+
+pub const TIME_TOKENS_HELP_MESSAGE: &str = r#"Besides chrono's specifiers, the format takes placeholders in braces,
+with an optional width as in {yday:03}:
+  {epoch}:    Unix time in seconds
+  {epoch_ms}: Unix time in milliseconds
+  {yday}:     day of the year, from 1
+  {week}:     week of the year, from 0 before the first --week-start day
+  {base36}:   Unix time in seconds in base 36, for very short names
+  {elapsed}:  time since --relative-to as +HH-MM-SS, or -HH-MM-SS before it
+  {elapsed_secs}: seconds since --relative-to
+  {parent}:   the name of the directory of the file, {parent:N} its Nth
+              word split at spaces, _ - and ., from the end if negative
+  {line1}:    the first line of a text file, {head:N} its first N characters
+  {location}: the city a photo was taken in by its GPS coordinates, or else
+              the country, {country} the country, offline
+  {camera}:   the make and model of the camera of a photo, {lens} its lens
+  {session}:  the number of the session of the file by --sessionize, from 1,
+              {session_seq} its number in the session
+Filters after | transform a value: lower, upper, slug, trunc:N, e.g.
+{base36|upper}; {{ and }} stand for literal braces"#;
+
+const SESSIONIZE_HELP_MESSAGE: &str = r#"Group the files into sessions, a new one starting wherever more than
+GAP passes between a file and the next by time, e.g. 2h, numbering
+them for {session} and the files of each for {session_seq}"#;
+
+#[derive(Clone)]
+pub struct TimeFormat {
+    template: Template,
+    week_start: Weekday,
+    /// the instant `{elapsed}` counts from, by `--relative-to`
+    anchor: Option<DateTime<Utc>>,
+}
+
+impl TimeFormat {
+    pub fn parse(text: &str, week_start: Weekday) -> Result<Self, TemplateError> {
+        Ok(Self {
+            template: Template::parse(text)?,
+            week_start,
+            anchor: None,
+        })
+    }
+
+    /// the format counting `{elapsed}` and `{elapsed_secs}` from `anchor`
+    pub fn with_anchor(mut self, anchor: DateTime<Utc>) -> Self {
+        self.anchor = Some(anchor);
+        self
+    }
+
+    /// `time` in this format; an unknown placeholder is an error
+    pub fn format<Tz>(&self, time: &DateTime<Tz>) -> Result<String, TemplateError>
+    where
+        Tz: TimeZone,
+        Tz::Offset: Display,
+    {
+        self.render(time, None)
+    }
+
+    /// `time` in this format for the file at `path`, with `{name}` standing for
+    /// `name`, its old name, `{parent}` for its directory and `{line1}` and
+    /// `{head:N}` for its content
+    pub fn format_named<Tz>(
+        &self,
+        time: &DateTime<Tz>,
+        name: &str,
+        path: &Path,
+    ) -> Result<String, TemplateError>
+    where
+        Tz: TimeZone,
+        Tz::Offset: Display,
+    {
+        self.format_with(time, path, &[("name", name.to_string())])
+    }
+
+    /// `time` in this format for the file at `path`, as by
+    /// [`TimeFormat::format_named`], with each key of `values` standing for
+    /// its value
+    pub fn format_with<Tz>(
+        &self,
+        time: &DateTime<Tz>,
+        path: &Path,
+        values: &[(&str, String)],
+    ) -> Result<String, TemplateError>
+    where
+        Tz: TimeZone,
+        Tz::Offset: Display,
+    {
+        self.render(time, Some((values, path)))
+    }
+
+    fn render<Tz>(
+        &self,
+        time: &DateTime<Tz>,
+        file: Option<(&[(&str, String)], &Path)>,
+    ) -> Result<String, TemplateError>
+    where
+        Tz: TimeZone,
+        Tz::Offset: Display,
+    {
+        let strftime: String = self.template.render(|key| match file {
+            // a value is no strftime format, whatever it holds
+            Some((values, path)) => match values.iter().find(|(k, _)| *k == key) {
+                Some((_, value)) => Some(value.replace('%', "%%")),
+                None => self
+                    .get_token(key, time)
+                    .or_else(|| lookup_file(key, path).map(|v| v.replace('%', "%%"))),
+            },
+            None => self.get_token(key, time),
+        })?;
+        Ok(time.format(&strftime).to_string())
+    }
+
+    /// why names in this format would not sort by time, if they would not:
+    /// coarser fields must come before finer ones, zero-padded and as numbers
+    pub fn find_unsortable(&self) -> Option<String> {
+        let mut fields: Vec<Field> = vec![];
+        for part in self.template.parts() {
+            match part {
+                Part::Literal(text) => fields.extend(parse_strftime(text)),
+                Part::Placeholder {
+                    key,
+                    width,
+                    zero_padded,
+                    ..
+                } => fields.push(match key.as_str() {
+                    "epoch" | "epoch_ms" | "base36" | "elapsed" => Field::Instant,
+                    "elapsed_secs" if *zero_padded => Field::Instant,
+                    "elapsed_secs" => Field::Unsortable(tr(
+                        Message::NotZeroPadded,
+                        &[&"{elapsed_secs}", &"{elapsed_secs:06}"],
+                    )),
+                    "yday" if *zero_padded && *width >= 3 => {
+                        Field::Unit(DAY, String::from("{yday}"))
+                    }
+                    "week" if *zero_padded && *width >= 2 => {
+                        Field::Unit(MONTH, String::from("{week}"))
+                    }
+                    "yday" | "week" => Field::Unsortable(tr(
+                        Message::NotZeroPadded,
+                        &[&format!("{{{key}}}"), &format!("{{{key}:03}}")],
+                    )),
+                    _ => continue,
+                }),
+            }
+        }
+
+        let mut finest: Option<(u8, &str)> = None;
+        for field in &fields {
+            match field {
+                // the time itself, ordered after any coarser fields before it
+                Field::Instant => return None,
+                Field::Unsortable(reason) => return Some(reason.clone()),
+                Field::Weekday(name) if finest.is_none_or(|(rank, _)| rank < DAY) => {
+                    return Some(tr(Message::BeforeTheDay, &[name]));
+                }
+                Field::Weekday(_) => (),
+                Field::Unit(rank, name) => match finest {
+                    Some((finer, finer_name)) if *rank < finer => {
+                        return Some(tr(Message::ComesAfter, &[name, &finer_name]));
+                    }
+                    _ => finest = Some((*rank, name.as_str())),
+                },
+            }
+        }
+        None
+    }
+
+    /// the time from the anchor to `time`, if there is an anchor
+    fn get_elapsed<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> Option<TimeDelta> {
+        Some(time.with_timezone(&Utc) - self.anchor?)
+    }
+
+    fn get_token<Tz: TimeZone>(&self, key: &str, time: &DateTime<Tz>) -> Option<String> {
+        match key {
+            "epoch" => Some(time.timestamp().to_string()),
+            "epoch_ms" => Some(time.timestamp_millis().to_string()),
+            "yday" => Some(time.ordinal().to_string()),
+            "week" => {
+                let from_start: u32 = time.weekday().days_since(self.week_start);
+                Some(((time.ordinal0() + 7 - from_start) / 7).to_string())
+            }
+            "base36" => Some(encode_base36(time.timestamp())),
+            "elapsed" => Some(format_elapsed(self.get_elapsed(time)?)),
+            "elapsed_secs" => Some(self.get_elapsed(time)?.num_seconds().to_string()),
+            _ => None,
+        }
+    }
+}
+
+const YEAR: u8 = 0;
+const MONTH: u8 = 1;
+const DAY: u8 = 2;
+const HOUR: u8 = 3;
+const MINUTE: u8 = 4;
+const SECOND: u8 = 5;
+const FRACTION: u8 = 6;
+
+/// what a specifier of a format contributes to the order of names
+enum Field {
+    /// a number of a unit of time, by rank from the year down, and the specifier
+    Unit(u8, String),
+    /// the whole time, as Unix time
+    Instant,
+    /// a weekday, ordered by the day before it
+    Weekday(String),
+    Unsortable(String),
+}
+
+/// the fields of the strftime specifiers in `text`, ignoring literal text,
+/// time zones and AM/PM markers
+fn parse_strftime(text: &str) -> Vec<Field> {
+    use Field::*;
+    let mut fields: Vec<Field> = vec![];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+        let padding: Option<char> = chars.next_if(|c| matches!(c, '-' | '_' | '0'));
+        // widths and modifiers, as in %.3f, %3f and %:z
+        while chars
+            .next_if(|c| c.is_ascii_digit() || matches!(c, '.' | ':' | '#'))
+            .is_some()
+        {}
+        let Some(spec) = chars.next() else {
+            break;
+        };
+        let unit = |rank: u8, name: Message| match padding {
+            Some('-') => Unsortable(tr(Message::NotPadded, &[&format!("%-{spec}")])),
+            _ => Unit(rank, tr(name, &[])),
+        };
+        let year = || Unit(YEAR, tr(Message::TheYear, &[]));
+        let month = || Unit(MONTH, tr(Message::TheMonth, &[]));
+        let day = || Unit(DAY, tr(Message::TheDay, &[]));
+        let hour = || Unit(HOUR, tr(Message::TheHour, &[]));
+        let minute = || Unit(MINUTE, tr(Message::TheMinute, &[]));
+        let second = || Unit(SECOND, tr(Message::TheSecond, &[]));
+        match spec {
+            'Y' | 'C' | 'y' | 'G' | 'g' => fields.push(unit(YEAR, Message::TheYear)),
+            'm' => fields.push(unit(MONTH, Message::TheMonth)),
+            'U' | 'W' | 'V' => fields.push(unit(MONTH, Message::TheWeek)),
+            'd' | 'e' | 'j' => fields.push(unit(DAY, Message::TheDay)),
+            'H' | 'k' => fields.push(unit(HOUR, Message::TheHour)),
+            'M' => fields.push(unit(MINUTE, Message::TheMinute)),
+            'S' => fields.push(unit(SECOND, Message::TheSecond)),
+            'f' => fields.push(unit(FRACTION, Message::TheFraction)),
+            's' => fields.push(Instant),
+            'F' => fields.extend([year(), month(), day()]),
+            'T' | 'X' => fields.extend([hour(), minute(), second()]),
+            'R' => fields.extend([hour(), minute()]),
+            'a' | 'A' | 'u' | 'w' => fields.push(Weekday(tr(Message::TheWeekday, &[]))),
+            'b' | 'B' | 'h' => {
+                fields.push(Unsortable(tr(Message::MonthByName, &[&format!("%{spec}")])))
+            }
+            'I' | 'l' | 'r' => fields.push(Unsortable(tr(
+                Message::TwelveHourClock,
+                &[&format!("%{spec}")],
+            ))),
+            'D' | 'x' | 'c' => fields.push(Unsortable(tr(
+                Message::MonthBeforeYear,
+                &[&format!("%{spec}")],
+            ))),
+            _ => (),
+        }
+    }
+    fields
+}
+
+/// `value` in lowercase base 36, with a leading `-` when negative
+fn encode_base36(value: i64) -> String {
+    const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut rest: u64 = value.unsigned_abs();
+    let mut digits: Vec<u8> = vec![];
+    loop {
+        digits.push(DIGITS[(rest % 36) as usize]);
+        rest /= 36;
+        if rest == 0 {
+            break;
+        }
+    }
+    if value < 0 {
+        digits.push(b'-');
+    }
+    digits.reverse();
+    String::from_utf8(digits).unwrap()
+}
+
+/// `elapsed` as `+HH-MM-SS`, or `-HH-MM-SS` when negative, with as many digits
+/// for the hours as they take
+fn format_elapsed(elapsed: TimeDelta) -> String {
+    let sign: char = if elapsed < TimeDelta::zero() {
+        '-'
+    } else {
+        '+'
+    };
+    let seconds: i64 = elapsed.num_seconds().abs();
+    format!(
+        "{sign}{:02}-{:02}-{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// the `--time-source` option of the tools naming files by their own time
+pub fn get_time_source_arg() -> Arg {
+    Arg::new("time_source")
+        .long("time-source")
+        .help(TIME_SOURCE_HELP_MESSAGE)
+        .value_parser(TIME_SOURCE_NAMES)
+        .default_value("modified")
+}
+
+/// name the files of this run by the time given by `--time-source`
+pub fn set_time_source(matches: &ArgMatches) {
+    let source: TimeSource = matches
+        .try_get_one::<String>("time_source")
+        .ok()
+        .flatten()
+        .map_or_else(TimeSource::default, |name| TimeSource::from(name));
+    let _ = TIME_SOURCE.set(source);
+}
+
+/// the time naming the files of this run
+pub fn get_time_source() -> TimeSource {
+    TIME_SOURCE.get().copied().unwrap_or_default()
+}
+
+/// the `--week-start` option of the tools naming files by time
+pub fn get_week_start_arg() -> Arg {
+    Arg::new("week_start")
+        .long("week-start")
+        .help("The first day of a week for {week} in the format")
+        .value_parser(WEEKDAY_NAMES)
+        .default_value("mon")
+}
+
+/// the day given by `--week-start`
+pub fn get_week_start(matches: &ArgMatches) -> Weekday {
+    matches
+        .get_one::<String>("week_start")
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+/// the `--sessionize` option of the tools naming files by time
+pub fn get_sessionize_arg() -> Arg {
+    Arg::new("sessionize")
+        .long("sessionize")
+        .help(SESSIONIZE_HELP_MESSAGE)
+        .value_name("GAP")
+        .value_parser(parse_age)
+}
+
+/// the `--strict` flag of the tools naming files by time
+pub fn get_strict_arg() -> Arg {
+    Arg::new("strict")
+        .long("strict")
+        .help("Refuse a format whose names would not sort by time, instead of warning")
+        .action(ArgAction::SetTrue)
+}
@@ -0,0 +1,64 @@
+//! Checking by `--verify` that the changes of a run hold on disk once applied,
+//! for file systems acknowledging renames and writes they then lose, as on
+//! flaky USB enclosures.
+
+use crate::{
+    filesystem::{DiskFilesystem, ReadOnly},
+    hash::hash_file,
+    i18n::{tr, Message},
+};
+use clap::{Arg, ArgAction};
+use std::{fs, path::Path};
+
+const VERIFY_HELP_MESSAGE: &str = r#"After applying, check that every new name exists and every old one is
+gone, and that copies match their sources, failing if any does not"#;
+
+/// what is wrong with the rename of `source` to `target` once done, if anything;
+/// `source` may stay when `is_reused`, as the new name of another file
+pub fn check_rename(source: &Path, target: &Path, is_reused: bool) -> Option<String> {
+    check_rename_in(ReadOnly::new(&DiskFilesystem), source, target, is_reused)
+}
+
+/// what is wrong with the rename of `source` to `target` on `filesystem`, as
+/// for [`check_rename`]
+pub fn check_rename_in(
+    filesystem: ReadOnly,
+    source: &Path,
+    target: &Path,
+    is_reused: bool,
+) -> Option<String> {
+    if !filesystem.exists(target) {
+        return Some(tr(Message::TargetGone, &[&target.display()]));
+    }
+    // a name differing only in case or normalization names the target itself
+    let is_left: bool = filesystem.exists(source) && !filesystem.is_same_file(source, target);
+    if is_left && !is_reused {
+        return Some(tr(Message::SourceLeft, &[&source.display()]));
+    }
+    None
+}
+
+/// what is wrong with the copy `target` of `source` once done, if anything:
+/// missing, or differing in size or content
+pub fn check_copy(source: &Path, target: &Path) -> Option<String> {
+    let Ok(metadata) = fs::metadata(target) else {
+        return Some(tr(Message::TargetGone, &[&target.display()]));
+    };
+    let is_same: bool = fs::metadata(source).is_ok_and(|m| m.len() == metadata.len())
+        && matches!(
+            (hash_file(source), hash_file(target)),
+            (Ok(a), Ok(b)) if a == b
+        );
+    match is_same {
+        true => None,
+        false => Some(tr(Message::CopyDiffers, &[&target.display()])),
+    }
+}
+
+/// the `--verify` flag of the tools renaming and moving files
+pub fn get_verify_arg() -> Arg {
+    Arg::new("verify")
+        .long("verify")
+        .help(VERIFY_HELP_MESSAGE)
+        .action(ArgAction::SetTrue)
+}
@@ -0,0 +1,442 @@
+//! Listing the files below a directory, optionally skipping those ignored by
+//! `.gitignore`, `.ignore` and the global git excludes, outside the sizes
+//! given by `--min-size` and `--max-size`, not of a type given by `--type`, or
+//! not holding the content given by `--mime` and `--category`; recursive walks
+//! stop at the depth given by `--max-depth` and, with `--one-file-system`, at
+//! mount points.
+
+use crate::{
+    content::{get_mime_type, matches_mime, Category, CATEGORY_NAMES},
+    expr::{get_where_arg, matches_where, set_where_filter},
+    hardlinks::handle_hard_links,
+    i18n::{tr, Message},
+    journal::is_sidecar_journal,
+    manifest::{get_since_arg, is_changed_since, set_since_manifest},
+    retry::with_retries,
+    size::parse_size,
+    sort::{compare_paths, get_sort_order},
+};
+use clap::{value_parser, Arg, ArgAction, ArgMatches};
+use ignore::WalkBuilder;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// whether the recursive walks of this run skip ignored files
+static IGNORE_RULES: OnceLock<bool> = OnceLock::new();
+
+/// the deepest level the recursive walks of this run list, 1 for the entries
+/// directly inside the root, and whether they stay on the file system of the root
+static RECURSION_LIMITS: OnceLock<(Option<usize>, bool)> = OnceLock::new();
+
+/// the smallest and largest size in bytes of the files listed in this run
+static SIZE_LIMITS: OnceLock<(Option<u64>, Option<u64>)> = OnceLock::new();
+
+/// the types of the entries listed in this run, all when empty
+static ENTRY_TYPES: OnceLock<Vec<EntryType>> = OnceLock::new();
+
+pub const ENTRY_TYPE_NAMES: [&str; 3] = ["file", "dir", "symlink"];
+
+const TYPE_HELP_MESSAGE: &str = r#"Only take entries of this type, repeated for several:
+  file:    regular files
+  dir:     directories
+  symlink: symbolic links
+By default every entry the tool works on is taken"#;
+
+/// the MIME type patterns and categories of the files listed in this run,
+/// all when both are empty
+static CONTENT_FILTER: OnceLock<(Vec<String>, Vec<Category>)> = OnceLock::new();
+
+const MIME_HELP_MESSAGE: &str = r#"Only take files whose content is of this MIME type, e.g. image/png,
+or of any type under it, e.g. image/*; repeated for several"#;
+
+const CATEGORY_HELP_MESSAGE: &str = r#"Only take files whose content is of this kind, repeated for several;
+taken with --mime, a file of any of the types given is taken"#;
+
+const MIN_SIZE_HELP_MESSAGE: &str = r#"Leave out files smaller than this size, e.g. 10K or 5MiB;
+K, M, G, T are binary (1024) and KB, MB, GB, TB decimal (1000)"#;
+
+const MAX_SIZE_HELP_MESSAGE: &str = "Leave out files larger than this size, e.g. 2G or 700MB";
+
+pub struct WalkEntry {
+    /// path including the walked root
+    pub path: PathBuf,
+    /// path relative to the walked root
+    pub relative: PathBuf,
+    /// metadata of the entry itself (symbolic links are not followed)
+    pub metadata: fs::Metadata,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum EntryType {
+    File,
+    Dir,
+    Symlink,
+}
+
+impl EntryType {
+    pub fn from(name: &str) -> Self {
+        match name {
+            "file" => Self::File,
+            "dir" => Self::Dir,
+            "symlink" => Self::Symlink,
+            _ => panic!("Unknown entry type: {name}"),
+        }
+    }
+
+    /// whether an entry with `metadata`, not following links, is of this type
+    pub fn matches(&self, metadata: &fs::Metadata) -> bool {
+        match self {
+            Self::File => metadata.is_file(),
+            Self::Dir => metadata.is_dir(),
+            Self::Symlink => metadata.is_symlink(),
+        }
+    }
+}
+
+/// the `--no-ignore` option of the tools skipping ignored files when recursing
+pub fn get_no_ignore_arg() -> Arg {
+    Arg::new("no_ignore")
+        .long("no-ignore")
+        .help("Also go into files and directories ignored by .gitignore, .ignore\nor the global git excludes when recursing")
+        .action(ArgAction::SetTrue)
+}
+
+/// skip ignored files in the recursive walks of this run, unless `--no-ignore`
+pub fn set_ignore_rules(matches: &ArgMatches) {
+    let _ = IGNORE_RULES.set(!matches.get_flag("no_ignore"));
+}
+
+fn has_ignore_rules() -> bool {
+    *IGNORE_RULES.get().unwrap_or(&false)
+}
+
+/// the `--max-depth` and `--one-file-system` options of the tools recursing
+pub fn get_recursion_args() -> [Arg; 2] {
+    [
+        Arg::new("max_depth")
+            .long("max-depth")
+            .help("Go no deeper than this many levels below each directory when\nrecursing, 1 for only the entries directly inside it")
+            .value_parser(value_parser!(u64).range(1..)),
+        Arg::new("one_file_system")
+            .long("one-file-system")
+            .help("Do not go into directories on other file systems when recursing,\nsuch as a mounted drive or a bind mount (Unix only)")
+            .action(ArgAction::SetTrue),
+    ]
+}
+
+/// stop the recursive walks of this run at `--max-depth` and, with
+/// `--one-file-system`, at mount points
+pub fn set_recursion_limits(matches: &ArgMatches) {
+    let _ = RECURSION_LIMITS.set((
+        matches
+            .get_one::<u64>("max_depth")
+            .map(|d| usize::try_from(*d).unwrap_or(usize::MAX)),
+        matches.get_flag("one_file_system"),
+    ));
+}
+
+fn get_recursion_limits() -> (Option<usize>, bool) {
+    *RECURSION_LIMITS.get().unwrap_or(&(None, false))
+}
+
+/// the device of the file system holding an entry, on Unix
+#[cfg(unix)]
+fn get_device(metadata: &fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.dev())
+}
+
+#[cfg(not(unix))]
+fn get_device(_metadata: &fs::Metadata) -> Option<u64> {
+    None
+}
+
+/// whether a recursive walk goes into the directory `relative` to its root,
+/// with `metadata`, inside a directory on `device`
+fn can_descend(relative: &Path, metadata: &fs::Metadata, device: Option<u64>) -> bool {
+    let (max_depth, one_file_system) = get_recursion_limits();
+    max_depth.is_none_or(|max| relative.components().count() < max)
+        && !(one_file_system && device.is_some() && get_device(metadata) != device)
+}
+
+/// the `--min-size` and `--max-size` options of the tools taking batches of files
+pub fn get_size_args() -> [Arg; 2] {
+    [
+        Arg::new("min_size")
+            .long("min-size")
+            .help(MIN_SIZE_HELP_MESSAGE)
+            .value_parser(parse_size_arg),
+        Arg::new("max_size")
+            .long("max-size")
+            .help(MAX_SIZE_HELP_MESSAGE)
+            .value_parser(parse_size_arg),
+    ]
+}
+
+fn parse_size_arg(text: &str) -> Result<u64, String> {
+    parse_size(text).ok_or_else(|| tr(Message::InvalidSize, &[&text]))
+}
+
+/// list only files within `--min-size` and `--max-size` in this run
+pub fn set_size_limits(matches: &ArgMatches) {
+    let _ = SIZE_LIMITS.set((
+        matches.get_one::<u64>("min_size").copied(),
+        matches.get_one::<u64>("max_size").copied(),
+    ));
+}
+
+/// whether an entry is within the size limits of this run;
+/// only regular files are limited, never directories or links
+fn is_within_size_limits(metadata: &fs::Metadata) -> bool {
+    let Some((min_size, max_size)) = SIZE_LIMITS.get() else {
+        return true;
+    };
+    !metadata.is_file()
+        || (min_size.is_none_or(|min| metadata.len() >= min)
+            && max_size.is_none_or(|max| metadata.len() <= max))
+}
+
+/// the `--type` option of the tools walking directories
+pub fn get_type_arg() -> Arg {
+    Arg::new("type")
+        .long("type")
+        .help(TYPE_HELP_MESSAGE)
+        .value_parser(ENTRY_TYPE_NAMES)
+        .action(ArgAction::Append)
+}
+
+/// list only entries of the types given by `--type` in this run
+pub fn set_entry_types(matches: &ArgMatches) {
+    let types: Vec<EntryType> = matches
+        .get_many::<String>("type")
+        .unwrap_or_default()
+        .map(|name| EntryType::from(name))
+        .collect();
+    let _ = ENTRY_TYPES.set(types);
+}
+
+/// whether an entry is of one of the types of this run
+fn has_entry_type(metadata: &fs::Metadata) -> bool {
+    match ENTRY_TYPES.get() {
+        Some(types) if !types.is_empty() => types.iter().any(|t| t.matches(metadata)),
+        _ => true,
+    }
+}
+
+/// the `--mime`, `--category`, `--where` and `--since` options of the tools
+/// taking batches of files
+pub fn get_content_args() -> [Arg; 4] {
+    [
+        Arg::new("mime")
+            .long("mime")
+            .help(MIME_HELP_MESSAGE)
+            .action(ArgAction::Append),
+        Arg::new("category")
+            .long("category")
+            .help(CATEGORY_HELP_MESSAGE)
+            .value_parser(CATEGORY_NAMES)
+            .action(ArgAction::Append),
+        get_where_arg(),
+        get_since_arg(),
+    ]
+}
+
+/// list only files holding the content given by `--mime` and `--category`,
+/// for which the expression of `--where` holds, and changed since the
+/// manifest of `--since`, in this run
+pub fn set_content_filter(matches: &ArgMatches) {
+    let patterns: Vec<String> = matches
+        .get_many::<String>("mime")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let categories: Vec<Category> = matches
+        .get_many::<String>("category")
+        .unwrap_or_default()
+        .map(|name| Category::from(name))
+        .collect();
+    let _ = CONTENT_FILTER.set((patterns, categories));
+    set_where_filter(matches);
+    set_since_manifest(matches);
+}
+
+/// whether the entry at `path` holds content of the types of this run,
+/// read from its first bytes; only regular files hold any
+fn has_content(path: &Path, metadata: &fs::Metadata) -> bool {
+    let (patterns, categories) = match CONTENT_FILTER.get() {
+        Some((patterns, categories)) if !(patterns.is_empty() && categories.is_empty()) => {
+            (patterns, categories)
+        }
+        _ => return true,
+    };
+    if !metadata.is_file() {
+        return false;
+    }
+    let has_mime: bool = !patterns.is_empty()
+        && get_mime_type(path).is_some_and(|m| patterns.iter().any(|p| matches_mime(p, m)));
+    has_mime || Category::of(path).is_some_and(|c| categories.contains(&c))
+}
+
+/// `paths` without the files outside the size limits or content types of this
+/// run, for the files named on the command line, in the order of `--sort` if
+/// given, and with their hard links handled by `--hardlinks`; a missing file is
+/// kept for the tool to report
+pub fn filter_paths(paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = paths
+        .into_iter()
+        .filter(|p| {
+            with_retries(|| fs::metadata(p)).map_or(true, |m| {
+                is_within_size_limits(&m)
+                    && has_content(p, &m)
+                    && matches_where(p, &m)
+                    && is_changed_since(p, &m)
+            })
+        })
+        .collect();
+    if get_sort_order().is_some() {
+        paths.sort_by(|a, b| compare_paths(a, b));
+    }
+    handle_hard_links(paths)
+}
+
+/// Recursively list every non-directory entry below `root`, sorted by path as by `--sort`.
+///
+/// Symbolic links are reported as entries but never descended into; with the
+/// ignore rules set for the run, ignored entries and `.git` are left out, and
+/// nothing beyond the recursion limits of the run is listed.
+pub fn walk_files(root: &Path) -> io::Result<Vec<WalkEntry>> {
+    let mut entries: Vec<WalkEntry> = Vec::new();
+    match has_ignore_rules() {
+        true => walk_dir_ignoring(root, false, &mut entries)?,
+        false => walk_dir(root, Path::new(""), true, false, &mut entries)?,
+    }
+    entries.sort_by(|a, b| compare_paths(&a.relative, &b.relative));
+    Ok(entries)
+}
+
+/// Recursively list every entry below `root`, directories included, sorted by path
+/// as by `--sort`.
+///
+/// Symbolic links are reported as entries but never descended into; with the
+/// ignore rules set for the run, ignored entries and `.git` are left out, and
+/// nothing beyond the recursion limits of the run is listed.
+pub fn walk_entries(root: &Path) -> io::Result<Vec<WalkEntry>> {
+    let mut entries: Vec<WalkEntry> = Vec::new();
+    match has_ignore_rules() {
+        true => walk_dir_ignoring(root, true, &mut entries)?,
+        false => walk_dir(root, Path::new(""), true, true, &mut entries)?,
+    }
+    entries.sort_by(|a, b| compare_paths(&a.relative, &b.relative));
+    Ok(entries)
+}
+
+/// List the non-directory entries directly inside `dir`, sorted by name as by `--sort`.
+pub fn list_files(dir: &Path) -> io::Result<Vec<WalkEntry>> {
+    let mut entries: Vec<WalkEntry> = Vec::new();
+    walk_dir(dir, Path::new(""), false, false, &mut entries)?;
+    entries.sort_by(|a, b| compare_paths(&a.relative, &b.relative));
+    Ok(entries)
+}
+
+/// List the entries directly inside `dir`, directories included, sorted by
+/// name as by `--sort`.
+pub fn list_entries(dir: &Path) -> io::Result<Vec<WalkEntry>> {
+    let mut entries: Vec<WalkEntry> = Vec::new();
+    walk_dir(dir, Path::new(""), false, true, &mut entries)?;
+    entries.sort_by(|a, b| compare_paths(&a.relative, &b.relative));
+    Ok(entries)
+}
+
+/// whether an entry passes the size limits, types, content types, `--where`
+/// filter and `--since` manifest of this run; the journal kept inside a
+/// directory is never listed
+fn is_listed(path: &Path, metadata: &fs::Metadata) -> bool {
+    !is_sidecar_journal(path)
+        && is_within_size_limits(metadata)
+        && has_entry_type(metadata)
+        && has_content(path, metadata)
+        && matches_where(path, metadata)
+        && is_changed_since(path, metadata)
+}
+
+fn walk_dir(
+    dir: &Path,
+    relative_dir: &Path,
+    recursive: bool,
+    include_dirs: bool,
+    entries: &mut Vec<WalkEntry>,
+) -> io::Result<()> {
+    let device: Option<u64> = match recursive && get_recursion_limits().1 {
+        true => get_device(&with_retries(|| fs::metadata(dir))?),
+        false => None,
+    };
+    for dir_entry in fs::read_dir(dir)? {
+        let dir_entry: fs::DirEntry = dir_entry?;
+        // keep paths below the current directory free of a leading `./`
+        let path: PathBuf = if dir == Path::new(".") {
+            PathBuf::from(dir_entry.file_name())
+        } else {
+            dir_entry.path()
+        };
+        let relative: PathBuf = relative_dir.join(dir_entry.file_name());
+        let metadata: fs::Metadata = with_retries(|| fs::symlink_metadata(&path))?;
+        let is_dir: bool = metadata.is_dir();
+        if is_dir && recursive && can_descend(&relative, &metadata, device) {
+            walk_dir(&path, &relative, recursive, include_dirs, entries)?;
+        }
+        if (!is_dir || include_dirs) && is_listed(&path, &metadata) {
+            entries.push(WalkEntry {
+                path,
+                relative,
+                metadata,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// walk below `root` like `walk_dir`, leaving out what the ignore files exclude
+/// and the `.git` directory, within the recursion limits of this run
+fn walk_dir_ignoring(
+    root: &Path,
+    include_dirs: bool,
+    entries: &mut Vec<WalkEntry>,
+) -> io::Result<()> {
+    let (max_depth, one_file_system) = get_recursion_limits();
+    let walker = WalkBuilder::new(root)
+        .max_depth(max_depth)
+        .same_file_system(one_file_system)
+        .hidden(false)
+        .require_git(false)
+        .filter_entry(|e| e.file_name() != ".git")
+        .build();
+    for dir_entry in walker {
+        let dir_entry: ignore::DirEntry = dir_entry.map_err(|err| {
+            let message: String = err.to_string();
+            err.into_io_error()
+                .unwrap_or_else(|| io::Error::other(message))
+        })?;
+        if dir_entry.depth() == 0 {
+            continue;
+        }
+        let relative: PathBuf = dir_entry.path().strip_prefix(root).unwrap().to_path_buf();
+        // keep paths below the current directory free of a leading `./`
+        let path: PathBuf = if root == Path::new(".") {
+            relative.clone()
+        } else {
+            dir_entry.into_path()
+        };
+        let metadata: fs::Metadata = with_retries(|| fs::symlink_metadata(&path))?;
+        if (!metadata.is_dir() || include_dirs) && is_listed(&path, &metadata) {
+            entries.push(WalkEntry {
+                path,
+                relative,
+                metadata,
+            });
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,117 @@
+//! Extended attributes, such as macOS Finder tags, `com.apple.quarantine` and
+//! `user.*` attributes, and snapshots of them across a tree so that they survive
+//! copies that drop them.
+
+use crate::{
+    i18n::{tr, Message},
+    paths::{decode_hex, encode_hex, resolve},
+    walk::{walk_entries, WalkEntry},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// attribute names with their values
+pub type Attributes = BTreeMap<String, Vec<u8>>;
+
+/// the attributes of one entry of a tree snapshot, with hex-encoded values
+#[derive(Serialize, Deserialize)]
+pub struct XattrEntry {
+    /// path relative to the snapshot root, empty for the root itself
+    pub name: String,
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// the extended attributes of `path` itself, not following symbolic links
+pub fn read_xattrs(path: &Path) -> io::Result<Attributes> {
+    let mut attributes: Attributes = Attributes::new();
+    for name in xattr::list(path)? {
+        if let Some(value) = xattr::get(path, &name)? {
+            attributes.insert(name.to_string_lossy().into_owned(), value);
+        }
+    }
+    Ok(attributes)
+}
+
+/// set every attribute of `attributes` on `path`, keeping its other attributes
+pub fn write_xattrs(path: &Path, attributes: &Attributes) -> io::Result<()> {
+    for (name, value) in attributes {
+        xattr::set(path, name, value)?;
+    }
+    Ok(())
+}
+
+pub fn copy_xattrs(from: &Path, to: &Path) -> io::Result<()> {
+    write_xattrs(to, &read_xattrs(from)?)
+}
+
+/// the attributes of `root` and, with `recursive`, every entry below it,
+/// leaving out entries without attributes
+pub fn snapshot_xattrs(root: &Path, recursive: bool) -> io::Result<Vec<XattrEntry>> {
+    let mut paths: Vec<(PathBuf, String)> = vec![(root.to_path_buf(), String::new())];
+    if recursive && fs::symlink_metadata(root)?.is_dir() {
+        let entries: Vec<WalkEntry> = walk_entries(root)?;
+        paths.extend(
+            entries
+                .into_iter()
+                .map(|e| (e.path, e.relative.to_string_lossy().into_owned())),
+        );
+    }
+
+    let mut snapshot: Vec<XattrEntry> = vec![];
+    for (path, name) in paths {
+        let attributes: Attributes = read_xattrs(&path)?;
+        if !attributes.is_empty() {
+            snapshot.push(XattrEntry {
+                name,
+                attributes: attributes
+                    .into_iter()
+                    .map(|(k, v)| (k, encode_hex(&v)))
+                    .collect(),
+            });
+        }
+    }
+    Ok(snapshot)
+}
+
+/// set the attributes of a snapshot on the same relative paths below `root`;
+/// return the paths that no longer exist
+pub fn restore_xattrs(root: &Path, snapshot: &[XattrEntry]) -> io::Result<Vec<PathBuf>> {
+    let mut missing: Vec<PathBuf> = vec![];
+    for entry in snapshot {
+        let path: PathBuf = if entry.name.is_empty() {
+            root.to_path_buf()
+        } else {
+            resolve(root, &entry.name)
+        };
+        if fs::symlink_metadata(&path).is_err() {
+            missing.push(path);
+            continue;
+        }
+        let mut attributes: Attributes = Attributes::new();
+        for (name, value) in &entry.attributes {
+            let value: Vec<u8> = decode_hex(value).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    tr(Message::InvalidAttributeValue, &[name, &entry.name]),
+                )
+            })?;
+            attributes.insert(name.clone(), value);
+        }
+        write_xattrs(&path, &attributes)?;
+    }
+    Ok(missing)
+}
+
+pub fn read_snapshot(path: &Path) -> io::Result<Vec<XattrEntry>> {
+    serde_json::from_str(&fs::read_to_string(path)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn write_snapshot<W: io::Write>(mut writer: W, snapshot: &[XattrEntry]) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut writer, snapshot)?;
+    writeln!(writer)
+}
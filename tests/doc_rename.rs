@@ -0,0 +1,43 @@
+//! Snapshots of the trees doc_rename leaves, naming documents after the date
+//! and title of their properties.
+
+use ray_commands::fixture::{Fixture, Run};
+use std::io::{self, Cursor, Write};
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+const PROGRAM: &str = env!("CARGO_BIN_EXE_doc_rename");
+
+/// a docx of the core properties `title` and `created`, with nothing else
+fn docx(title: &str, created: &str) -> io::Result<Vec<u8>> {
+    let core: String = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+         <cp:coreProperties \
+         xmlns:cp=\"http://schemas.openxmlformats.org/package/2006/metadata/core-properties\" \
+         xmlns:dc=\"http://purl.org/dc/elements/1.1/\" \
+         xmlns:dcterms=\"http://purl.org/dc/terms/\">\
+         <dc:title>{title}</dc:title>\
+         <dcterms:created>{created}</dcterms:created>\
+         </cp:coreProperties>"
+    );
+    let mut writer: ZipWriter<Cursor<Vec<u8>>> = ZipWriter::new(Cursor::new(Vec::new()));
+    writer
+        .start_file("docProps/core.xml", SimpleFileOptions::default())
+        .map_err(io::Error::other)?;
+    writer.write_all(core.as_bytes())?;
+    Ok(writer.finish().map_err(io::Error::other)?.into_inner())
+}
+
+#[test]
+fn document_is_named_by_its_properties() -> io::Result<()> {
+    let fixture: Fixture = Fixture::new()?
+        .with_file("Document1.docx", docx("Budget", "2024-05-01T09:00:00Z")?)
+        .and_then(|f| f.with_file("notes.txt", "bb"))?;
+    let size: u64 = fixture.path("Document1.docx").metadata()?.len();
+    let run: Run = fixture.run(PROGRAM, ["--yes", "Document1.docx"])?;
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(
+        fixture.snapshot()?,
+        format!("2024-05-01 Budget.docx {size}\nnotes.txt 2")
+    );
+    Ok(())
+}
@@ -0,0 +1,18 @@
+//! Snapshots of the trees fix_encoding leaves, for names of UTF-8 read as
+//! Latin-1.
+
+use ray_commands::fixture::{Fixture, Run};
+use std::io;
+
+const PROGRAM: &str = env!("CARGO_BIN_EXE_fix_encoding");
+
+#[test]
+fn mojibake_is_decoded() -> io::Result<()> {
+    let fixture: Fixture = Fixture::new()?
+        .with_file("caf\u{c3}\u{a9}.txt", "a")
+        .and_then(|f| f.with_file("plain.txt", "bb"))?;
+    let run: Run = fixture.run(PROGRAM, ["--yes", "."])?;
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(fixture.snapshot()?, "caf\u{e9}.txt 1\nplain.txt 2");
+    Ok(())
+}
@@ -0,0 +1,28 @@
+//! Snapshots of the trees normalize_screenshots leaves, for the times taken
+//! from the names and screenshots of the same time.
+
+use ray_commands::fixture::{Fixture, Run};
+use std::io;
+
+const PROGRAM: &str = env!("CARGO_BIN_EXE_normalize_screenshots");
+
+#[test]
+fn screenshots_are_named_by_their_time() -> io::Result<()> {
+    let fixture: Fixture = Fixture::new()?
+        .with_file("Screenshot 2024-05-01 at 10.11.12.png", "a")
+        .and_then(|f| f.with_file("Screen Shot 2024-05-01 at 10.11.12 AM.png", "bb"))?;
+    let run: Run = fixture.run(
+        PROGRAM,
+        [
+            "--yes",
+            "Screenshot 2024-05-01 at 10.11.12.png",
+            "Screen Shot 2024-05-01 at 10.11.12 AM.png",
+        ],
+    )?;
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(
+        fixture.snapshot()?,
+        "Screenshot_2024-05-01_10-11-12.png 1\nScreenshot_2024-05-01_10-11-12_1.png 2"
+    );
+    Ok(())
+}
@@ -0,0 +1,17 @@
+//! Snapshots of the trees normalize_unicode leaves, composing decomposed names.
+
+use ray_commands::fixture::{Fixture, Run};
+use std::io;
+
+const PROGRAM: &str = env!("CARGO_BIN_EXE_normalize_unicode");
+
+#[test]
+fn decomposed_name_is_composed() -> io::Result<()> {
+    let fixture: Fixture = Fixture::new()?
+        .with_file("cafe\u{301}.txt", "a")
+        .and_then(|f| f.with_file("plain.txt", "bb"))?;
+    let run: Run = fixture.run(PROGRAM, ["--yes", "."])?;
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(fixture.snapshot()?, "caf\u{e9}.txt 1\nplain.txt 2");
+    Ok(())
+}
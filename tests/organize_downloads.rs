@@ -0,0 +1,22 @@
+//! Snapshots of the trees organize_downloads leaves, for the default rules and
+//! a name taken in the folder.
+
+use ray_commands::fixture::{Fixture, Run};
+use std::io;
+
+const PROGRAM: &str = env!("CARGO_BIN_EXE_organize_downloads");
+
+#[test]
+fn files_are_moved_by_kind() -> io::Result<()> {
+    let fixture: Fixture = Fixture::new()?
+        .with_file("a.pdf", "a")
+        .and_then(|f| f.with_file("b.jpg", "bb"))
+        .and_then(|f| f.with_file("Pictures/b.jpg", "older"))?;
+    let run: Run = fixture.run(PROGRAM, ["--yes", "--on-conflict", "suffix", "."])?;
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(
+        fixture.snapshot()?,
+        "Documents/\nDocuments/a.pdf 1\nPictures/\nPictures/b.jpg 5\nPictures/b_1.jpg 2"
+    );
+    Ok(())
+}
@@ -0,0 +1,58 @@
+//! Snapshots of the trees pdf_rename leaves, naming a PDF after its Title
+//! metadata, from which generator prefixes are dropped.
+
+use lopdf::{dictionary, Document, Object};
+use ray_commands::fixture::{Fixture, Run};
+use std::io;
+
+const PROGRAM: &str = env!("CARGO_BIN_EXE_pdf_rename");
+
+/// a PDF of one empty page with `title` as its Title metadata
+fn pdf(title: &str) -> io::Result<Vec<u8>> {
+    let mut document: Document = Document::with_version("1.5");
+    let pages_id = document.new_object_id();
+    let page_id = document.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+    });
+    document.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => vec![page_id.into()],
+            "Count" => 1,
+        }),
+    );
+    let catalog_id = document.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    let info_id = document.add_object(dictionary! {
+        "Title" => Object::string_literal(title),
+    });
+    document.trailer.set("Root", catalog_id);
+    document.trailer.set("Info", info_id);
+    let mut bytes: Vec<u8> = Vec::new();
+    document.save_to(&mut bytes).map_err(io::Error::other)?;
+    Ok(bytes)
+}
+
+#[test]
+fn pdf_is_named_by_its_title() -> io::Result<()> {
+    let fixture: Fixture = Fixture::new()?
+        .with_file("scan0001.pdf", pdf("Microsoft Word - Annual Report")?)
+        .and_then(|f| f.with_file("untitled.pdf", pdf("Untitled")?))?;
+    let size = |name: &str| fixture.path(name).metadata().map(|m| m.len());
+    let sizes: [u64; 2] = [size("scan0001.pdf")?, size("untitled.pdf")?];
+    let run: Run = fixture.run(
+        PROGRAM,
+        ["--yes", "--source", "title", "scan0001.pdf", "untitled.pdf"],
+    )?;
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(
+        fixture.snapshot()?,
+        format!("Annual Report.pdf {}\nuntitled.pdf {}", sizes[0], sizes[1])
+    );
+    Ok(())
+}
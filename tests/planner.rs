@@ -0,0 +1,132 @@
+//! The planner run on trees in memory: chains, swaps and cycles of renames,
+//! names differing only in case, and a directory moved into itself.
+
+use ray_commands::{
+    conflict::ConflictStrategy, error, filesystem::MemoryFilesystem, plan::RenamePlan,
+};
+use std::{path::PathBuf, sync::Arc};
+
+/// the renames of `pairs`, by their paths
+fn renames(pairs: &[(&str, &str)]) -> Vec<(PathBuf, Result<PathBuf, String>)> {
+    pairs
+        .iter()
+        .map(|(s, t)| (PathBuf::from(s), Ok(PathBuf::from(t))))
+        .collect()
+}
+
+/// plan `pairs` against `tree` by `conflict` and apply the plan without asking
+fn apply(
+    tree: &Arc<MemoryFilesystem>,
+    pairs: &[(&str, &str)],
+    conflict: ConflictStrategy,
+) -> (RenamePlan, error::Result<bool>) {
+    let mut plan: RenamePlan = RenamePlan::with_conflict(conflict).with_filesystem(tree.clone());
+    plan.add_batch(renames(pairs));
+    let result: error::Result<bool> = plan.apply("planner", false);
+    (plan, result)
+}
+
+/// the contents of the file `path` of `tree`, as text
+fn read(tree: &MemoryFilesystem, path: &str) -> String {
+    String::from_utf8(tree.contents(&PathBuf::from(path)).unwrap_or_default()).unwrap()
+}
+
+#[test]
+fn chain_is_renamed_from_its_end() {
+    let tree: Arc<MemoryFilesystem> = Arc::new(
+        MemoryFilesystem::new()
+            .with_file("a", "a")
+            .with_file("b", "b"),
+    );
+    let (_, result) = apply(&tree, &[("a", "b"), ("b", "c")], ConflictStrategy::Skip);
+    assert!(result.unwrap());
+    assert_eq!(tree.files(), [PathBuf::from("b"), PathBuf::from("c")]);
+    assert_eq!(read(&tree, "b"), "a");
+    assert_eq!(read(&tree, "c"), "b");
+}
+
+#[test]
+fn swap_parks_one_file() {
+    // the temporary name first tried is taken in the tree, though not on disk
+    let tree: Arc<MemoryFilesystem> = Arc::new(
+        MemoryFilesystem::new()
+            .with_file("a", "a")
+            .with_file("b", "b")
+            .with_file(".a.tmp-rename-0", "parked"),
+    );
+    let (_, result) = apply(&tree, &[("a", "b"), ("b", "a")], ConflictStrategy::Skip);
+    assert!(result.unwrap());
+    assert_eq!(read(&tree, "a"), "b");
+    assert_eq!(read(&tree, "b"), "a");
+    assert_eq!(read(&tree, ".a.tmp-rename-0"), "parked");
+    assert_eq!(tree.files().len(), 3);
+}
+
+#[test]
+fn three_cycle_is_renamed() {
+    let tree: Arc<MemoryFilesystem> = Arc::new(
+        MemoryFilesystem::new()
+            .with_file("a", "a")
+            .with_file("b", "b")
+            .with_file("c", "c"),
+    );
+    let (_, result) = apply(
+        &tree,
+        &[("a", "b"), ("b", "c"), ("c", "a")],
+        ConflictStrategy::Skip,
+    );
+    assert!(result.unwrap());
+    assert_eq!(read(&tree, "a"), "c");
+    assert_eq!(read(&tree, "b"), "a");
+    assert_eq!(read(&tree, "c"), "b");
+    assert_eq!(tree.files().len(), 3);
+}
+
+#[test]
+fn case_only_collision_is_skipped() {
+    let tree: Arc<MemoryFilesystem> = Arc::new(
+        MemoryFilesystem::new()
+            .with_file("a", "a")
+            .with_file("b", "b"),
+    );
+    let (plan, result) = apply(
+        &tree,
+        &[("a", "Photo.jpg"), ("b", "photo.jpg")],
+        ConflictStrategy::Skip,
+    );
+    assert!(result.unwrap());
+    assert_eq!(plan.skipped().len(), 1);
+    assert_eq!(plan.skipped()[0].0, PathBuf::from("b"));
+    assert_eq!(
+        tree.files(),
+        [PathBuf::from("Photo.jpg"), PathBuf::from("b")]
+    );
+}
+
+#[test]
+fn case_only_collision_is_suffixed() {
+    let tree: Arc<MemoryFilesystem> = Arc::new(
+        MemoryFilesystem::new()
+            .with_file("a", "a")
+            .with_file("b", "b"),
+    );
+    let (_, result) = apply(
+        &tree,
+        &[("a", "Photo.jpg"), ("b", "photo.jpg")],
+        ConflictStrategy::Suffix,
+    );
+    assert!(result.unwrap());
+    assert_eq!(
+        tree.files(),
+        [PathBuf::from("Photo.jpg"), PathBuf::from("photo_1.jpg")]
+    );
+}
+
+#[test]
+fn dir_into_itself_fails_and_stays() {
+    let tree: Arc<MemoryFilesystem> = Arc::new(MemoryFilesystem::new().with_file("d/f", "f"));
+    let (_, result) = apply(&tree, &[("d", "d/sub/d")], ConflictStrategy::Skip);
+    assert!(result.is_err());
+    assert_eq!(tree.files(), [PathBuf::from("d/f")]);
+    assert_eq!(read(&tree, "d/f"), "f");
+}
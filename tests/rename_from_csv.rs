@@ -0,0 +1,41 @@
+//! Snapshots of the trees rename_from_csv leaves, for a mapping of old names
+//! to new ones, chained, and one taking the name of a file kept.
+
+use ray_commands::fixture::{Fixture, Run};
+use std::io;
+
+const PROGRAM: &str = env!("CARGO_BIN_EXE_rename_from_csv");
+
+#[test]
+fn mapping_is_renamed_in_order() -> io::Result<()> {
+    // b.txt moves on to c.txt before a.txt takes its name
+    let mapping: &str = "old,new\na.txt,b.txt\nb.txt,c.txt\n";
+    let fixture: Fixture = Fixture::new()?
+        .with_file("a.txt", "a")
+        .and_then(|f| f.with_file("b.txt", "bb"))
+        .and_then(|f| f.with_file("map.csv", mapping))?;
+    let run: Run = fixture.run(PROGRAM, ["--yes", "map.csv"])?;
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(
+        fixture.snapshot()?,
+        format!("b.txt 1\nc.txt 2\nmap.csv {}", mapping.len())
+    );
+    Ok(())
+}
+
+#[test]
+fn existing_file_is_skipped() -> io::Result<()> {
+    let mapping: &str = "old,new\na.txt,b.txt\n";
+    let fixture: Fixture = Fixture::new()?
+        .with_file("a.txt", "a")
+        .and_then(|f| f.with_file("b.txt", "bb"))
+        .and_then(|f| f.with_file("map.csv", mapping))?;
+    let run: Run = fixture.run(PROGRAM, ["--yes", "map.csv"])?;
+    assert_eq!(
+        fixture.snapshot()?,
+        format!("a.txt 1\nb.txt 2\nmap.csv {}", mapping.len()),
+        "{}",
+        run.stderr
+    );
+    Ok(())
+}
@@ -0,0 +1,129 @@
+//! Snapshots of the trees rename_mod_time leaves, for the renames taking names
+//! of existing files, chains and cycles of renames, and interrupted runs.
+
+use ray_commands::fixture::{Fixture, Run};
+use std::{
+    fs, io,
+    time::{Duration, SystemTime},
+};
+
+const PROGRAM: &str = env!("CARGO_BIN_EXE_rename_mod_time");
+
+/// names by the day alone, of the times in UTC the fixtures run in
+const FORMAT: [&str; 2] = ["-f", "%Y-%m-%d"];
+
+/// a dest map moving every photo into `blocker`
+const DEST_MAP: &str = "[[rule]]\nextensions = [\"jpg\"]\ndestination = \"blocker\"\n";
+
+/// noon UTC of `day` of May 2024
+fn noon(day: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(1_714_478_400 + day * 86_400)
+}
+
+/// run the tool in `fixture` without asking, naming by the day
+fn rename(fixture: &Fixture, args: &[&str]) -> io::Result<Run> {
+    let args: Vec<&str> = ["--yes"]
+        .iter()
+        .chain(&FORMAT)
+        .chain(args)
+        .copied()
+        .collect();
+    fixture.run(PROGRAM, args)
+}
+
+#[test]
+fn existing_file_is_skipped() -> io::Result<()> {
+    let fixture: Fixture = Fixture::new()?
+        .with_file("a.jpg", "new")
+        .and_then(|f| f.with_modified("a.jpg", noon(1)))
+        .and_then(|f| f.with_file("2024-05-01.jpg", "older"))?;
+    let run: Run = rename(&fixture, &["a.jpg"])?;
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert!(run.stdout.contains("already exists"), "{}", run.stdout);
+    assert_eq!(fixture.snapshot()?, "2024-05-01.jpg 5\na.jpg 3");
+    assert_eq!(fixture.read("2024-05-01.jpg")?, "older");
+    Ok(())
+}
+
+#[test]
+fn existing_file_is_kept_by_suffix() -> io::Result<()> {
+    let fixture: Fixture = Fixture::new()?
+        .with_file("a.jpg", "new")
+        .and_then(|f| f.with_modified("a.jpg", noon(1)))
+        .and_then(|f| f.with_file("2024-05-01.jpg", "older"))?;
+    let run: Run = rename(&fixture, &["--on-conflict", "suffix", "a.jpg"])?;
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(fixture.snapshot()?, "2024-05-01.jpg 5\n2024-05-01_1.jpg 3");
+    assert_eq!(fixture.read("2024-05-01.jpg")?, "older");
+    Ok(())
+}
+
+#[test]
+fn chain_is_renamed_in_order() -> io::Result<()> {
+    for chunk_size in ["0", "1"] {
+        // a.jpg takes the name of 2024-05-01.jpg, which moves on to 2024-05-02.jpg
+        let fixture: Fixture = Fixture::new()?
+            .with_file("a.jpg", "a")
+            .and_then(|f| f.with_modified("a.jpg", noon(1)))
+            .and_then(|f| f.with_file("2024-05-01.jpg", "bb"))
+            .and_then(|f| f.with_modified("2024-05-01.jpg", noon(2)))?;
+        let mut args: Vec<&str> = vec!["a.jpg", "2024-05-01.jpg"];
+        if chunk_size != "0" {
+            args.extend(["--chunk-size", chunk_size]);
+        }
+        let run: Run = rename(&fixture, &args)?;
+        assert_eq!(run.code, Some(0), "{}", run.stderr);
+        assert_eq!(fixture.snapshot()?, "2024-05-01.jpg 1\n2024-05-02.jpg 2");
+        assert_eq!(fixture.read("2024-05-01.jpg")?, "a");
+    }
+    Ok(())
+}
+
+#[test]
+fn cycle_is_renamed_in_order() -> io::Result<()> {
+    for chunk_size in ["0", "1"] {
+        // the two files swap names
+        let fixture: Fixture = Fixture::new()?
+            .with_file("2024-05-01.jpg", "x")
+            .and_then(|f| f.with_modified("2024-05-01.jpg", noon(2)))
+            .and_then(|f| f.with_file("2024-05-02.jpg", "yy"))
+            .and_then(|f| f.with_modified("2024-05-02.jpg", noon(1)))?;
+        let mut args: Vec<&str> = vec!["2024-05-01.jpg", "2024-05-02.jpg"];
+        if chunk_size != "0" {
+            args.extend(["--chunk-size", chunk_size]);
+        }
+        let run: Run = rename(&fixture, &args)?;
+        assert_eq!(run.code, Some(0), "{}", run.stderr);
+        assert_eq!(fixture.snapshot()?, "2024-05-01.jpg 2\n2024-05-02.jpg 1");
+        assert_eq!(fixture.read("2024-05-01.jpg")?, "yy");
+    }
+    Ok(())
+}
+
+#[test]
+fn failed_run_is_resumed() -> io::Result<()> {
+    // the destination of the photos is taken by a file, failing the run
+    let fixture: Fixture = Fixture::new()?
+        .with_file("a.jpg", "a")
+        .and_then(|f| f.with_modified("a.jpg", noon(1)))
+        .and_then(|f| f.with_file("b.jpg", "bb"))
+        .and_then(|f| f.with_modified("b.jpg", noon(2)))
+        .and_then(|f| f.with_file("blocker", "x"))
+        .and_then(|f| f.with_file("map.toml", DEST_MAP))?;
+    let run: Run = rename(&fixture, &["--dest-map", "map.toml", "a.jpg", "b.jpg"])?;
+    assert_ne!(run.code, Some(0));
+    let map: String = format!("map.toml {}", DEST_MAP.len());
+    assert_eq!(
+        fixture.snapshot()?,
+        format!("a.jpg 1\nb.jpg 2\nblocker 1\n{map}")
+    );
+
+    fs::remove_file(fixture.path("blocker"))?;
+    let run: Run = fixture.run(PROGRAM, ["--yes", "--resume"])?;
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(
+        fixture.snapshot()?,
+        format!("blocker/\nblocker/2024-05-01.jpg 1\nblocker/2024-05-02.jpg 2\n{map}")
+    );
+    Ok(())
+}
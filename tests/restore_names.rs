@@ -0,0 +1,23 @@
+//! Snapshots of the trees restore_names leaves, renaming files back to the
+//! names of a mapping.
+
+use ray_commands::fixture::{Fixture, Run};
+use std::io;
+
+const PROGRAM: &str = env!("CARGO_BIN_EXE_restore_names");
+
+#[test]
+fn mapping_is_reversed() -> io::Result<()> {
+    let mapping: &str = "old,new\na.txt,b.txt\nc.txt,d.txt\n";
+    let fixture: Fixture = Fixture::new()?
+        .with_file("b.txt", "a")
+        .and_then(|f| f.with_file("d.txt", "cc"))
+        .and_then(|f| f.with_file("map.csv", mapping))?;
+    let run: Run = fixture.run(PROGRAM, ["--yes", "map.csv"])?;
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(
+        fixture.snapshot()?,
+        format!("a.txt 1\nc.txt 2\nmap.csv {}", mapping.len())
+    );
+    Ok(())
+}
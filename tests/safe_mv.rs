@@ -0,0 +1,31 @@
+//! Snapshots of the trees safe_mv leaves, moving files into a directory and
+//! copying them without the duplicates.
+
+use ray_commands::fixture::{Fixture, Run};
+use std::io;
+
+const PROGRAM: &str = env!("CARGO_BIN_EXE_safe_mv");
+
+#[test]
+fn files_are_moved_into_directory() -> io::Result<()> {
+    let fixture: Fixture = Fixture::new()?
+        .with_file("a", "a")
+        .and_then(|f| f.with_file("b", "bb"))
+        .and_then(|f| f.with_dir("d"))?;
+    let run: Run = fixture.run(PROGRAM, ["a", "b", "d"])?;
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(fixture.snapshot()?, "d/\nd/a 1\nd/b 2");
+    Ok(())
+}
+
+#[test]
+fn duplicate_is_not_copied() -> io::Result<()> {
+    let fixture: Fixture = Fixture::new()?
+        .with_file("b", "bb")
+        .and_then(|f| f.with_file("c", "same"))
+        .and_then(|f| f.with_file("d/a", "same"))?;
+    let run: Run = fixture.run(PROGRAM, ["--copy", "--skip-duplicates", "b", "c", "d"])?;
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(fixture.snapshot()?, "b 2\nc 4\nd/\nd/a 4\nd/b 2");
+    Ok(())
+}
@@ -0,0 +1,19 @@
+//! Snapshots of the trees shuffle_names leaves, numbering files in an order
+//! drawn by a seed.
+
+use ray_commands::fixture::{Fixture, Run};
+use std::io;
+
+const PROGRAM: &str = env!("CARGO_BIN_EXE_shuffle_names");
+
+#[test]
+fn seed_draws_the_same_order() -> io::Result<()> {
+    let fixture: Fixture = Fixture::new()?
+        .with_file("a", "a")
+        .and_then(|f| f.with_file("b", "bb"))
+        .and_then(|f| f.with_file("c", "ccc"))?;
+    let run: Run = fixture.run(PROGRAM, ["--yes", "--seed", "7", "a", "b", "c"])?;
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(fixture.snapshot()?, "01 3\n02 2\n03 1");
+    Ok(())
+}
@@ -0,0 +1,43 @@
+//! Snapshots of the trees tag_rename leaves, filing audio by its ID3v1 tag
+//! and skipping a file missing a tag of the template.
+
+use ray_commands::fixture::{Fixture, Run};
+use std::io;
+
+const PROGRAM: &str = env!("CARGO_BIN_EXE_tag_rename");
+
+/// the audio `audio` followed by an ID3v1.1 tag of `title`, `artist`,
+/// `album` and `track`
+fn mp3(audio: &[u8], title: &str, artist: &str, album: &str, track: u8) -> Vec<u8> {
+    let field = |text: &str, size: usize| -> Vec<u8> {
+        let mut bytes: Vec<u8> = text.as_bytes().to_vec();
+        bytes.resize(size, 0);
+        bytes
+    };
+    let mut bytes: Vec<u8> = audio.to_vec();
+    bytes.extend(b"TAG");
+    bytes.extend(field(title, 30));
+    bytes.extend(field(artist, 30));
+    bytes.extend(field(album, 30));
+    bytes.extend(field("2024", 4));
+    // a comment ending with a zero byte and the track
+    bytes.extend(field("", 28));
+    bytes.extend([0, track, 255]);
+    bytes
+}
+
+#[test]
+fn audio_is_filed_by_its_tags() -> io::Result<()> {
+    let fixture: Fixture = Fixture::new()?
+        .with_file("a.mp3", mp3(b"a", "Intro", "Band", "First", 1))
+        .and_then(|f| f.with_file("b.mp3", mp3(b"bb", "Outro", "Band", "First", 12)))
+        .and_then(|f| f.with_file("c.mp3", mp3(b"ccc", "Untagged", "", "", 0)))?;
+    let run: Run = fixture.run(PROGRAM, ["--yes", "a.mp3", "b.mp3", "c.mp3"])?;
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(
+        fixture.snapshot()?,
+        "Band/\nBand/First/\nBand/First/01 - Intro.mp3 129\n\
+         Band/First/12 - Outro.mp3 130\nc.mp3 131"
+    );
+    Ok(())
+}